@@ -0,0 +1,170 @@
+//! Benchmarks for reading sync responses, to catch regressions in how long
+//! big accounts take to sync. Run them with `cargo bench`; each prints the
+//! median time over a few runs of parsing, and then storing, a full sync of a
+//! made-up account with as many tasks as a big one has, carrying every field
+//! Todoist sends, not only the ones that are kept.
+
+#![warn(clippy::all, clippy::pedantic, clippy::unwrap_used)]
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use serde_json::json;
+use todoist::{
+    storage::Store,
+    stream,
+    sync::{Item, Response},
+};
+
+/// How many tasks the made-up account has.
+const ITEMS: usize = 20_000;
+
+/// How many times each benchmark is run, to take the median of.
+const RUNS: usize = 7;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let body = serde_json::to_vec(&full_sync(ITEMS))?;
+    println!("full sync of {ITEMS} tasks, {:.1} MB", mb(body.len()));
+
+    bench("parse response", || {
+        let response: Response = serde_json::from_slice(&body)?;
+        black_box(response);
+        Ok(())
+    })?;
+    bench("parse response in batches", || {
+        let response = stream::parse(body.as_slice(), stream::BATCH_SIZE, |batch| {
+            black_box(batch);
+            true
+        })?;
+        black_box(response);
+        Ok(())
+    })?;
+
+    let response: Response = serde_json::from_slice(&body)?;
+    bench("store response", || {
+        let mut store = Store::open_in_memory()?;
+        store.apply(&response)?;
+        Ok(())
+    })?;
+
+    let mut store = Store::open_in_memory()?;
+    store.apply(&response)?;
+    bench("read stored tasks", || {
+        let items: Vec<Item> = store.items()?;
+        black_box(items);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Runs `run` a few times, printing the median time it took.
+fn bench(
+    name: &str,
+    mut run: impl FnMut() -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut times: Vec<Duration> = Vec::with_capacity(RUNS);
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        run()?;
+        times.push(start.elapsed());
+    }
+    times.sort();
+    let median = times[RUNS / 2];
+    println!(
+        "{name:<28} {:>8.2} ms {:>8.0} ns/task",
+        median.as_secs_f64() * 1000.0,
+        median.as_secs_f64() * 1e9 / f64::from(u32::try_from(ITEMS)?),
+    );
+    Ok(())
+}
+
+fn mb(bytes: usize) -> f64 {
+    f64::from(u32::try_from(bytes).unwrap_or(u32::MAX)) / 1_000_000.0
+}
+
+/// A full sync response with `items` tasks, spread over a few dozen projects,
+/// shaped like the ones Todoist sends.
+fn full_sync(items: usize) -> serde_json::Value {
+    let projects: Vec<_> = (0..40)
+        .map(|index| {
+            json!({
+                "id": format!("2203{index:06}"),
+                "v2_id": format!("6Jf8VQXxpwv56VQ{index}"),
+                "name": format!("Project {index}"),
+                "color": "berry_red",
+                "parent_id": null,
+                "v2_parent_id": null,
+                "child_order": index,
+                "collapsed": false,
+                "shared": false,
+                "can_assign_tasks": false,
+                "is_deleted": false,
+                "is_archived": false,
+                "is_favorite": index % 7 == 0,
+                "sync_id": null,
+                "inbox_project": index == 0,
+                "view_style": "list",
+                "created_at": "2023-01-01T09:00:00.000000Z",
+                "updated_at": "2024-01-01T09:00:00.000000Z",
+            })
+        })
+        .collect();
+    let items: Vec<_> = (0..items)
+        .map(|index| {
+            json!({
+                "id": format!("7000{index:07}"),
+                "v2_id": format!("6X7rM8997g3RQmvh{index}"),
+                "user_id": "13743454",
+                "project_id": format!("2203{:06}", index % 40),
+                "v2_project_id": format!("6Jf8VQXxpwv56VQ{}", index % 40),
+                "section_id": null,
+                "v2_section_id": null,
+                "parent_id": null,
+                "v2_parent_id": null,
+                "content": format!("Task number {index}, with a few words in it"),
+                "description": if index % 5 == 0 { "Some notes about the task, over a line or two." } else { "" },
+                "priority": 1 + index % 4,
+                "due": if index % 3 == 0 {
+                    json!({
+                        "date": "2024-05-01",
+                        "timezone": null,
+                        "string": "every day",
+                        "lang": "en",
+                        "is_recurring": true,
+                    })
+                } else {
+                    serde_json::Value::Null
+                },
+                "deadline": null,
+                "duration": null,
+                "labels": if index % 4 == 0 { json!(["errands", "home"]) } else { json!([]) },
+                "child_order": index,
+                "day_order": -1,
+                "collapsed": false,
+                "checked": false,
+                "is_deleted": false,
+                "sync_id": null,
+                "added_by_uid": "13743454",
+                "assigned_by_uid": null,
+                "responsible_uid": null,
+                "added_at": "2024-01-01T09:00:00.000000Z",
+                "updated_at": "2024-01-02T09:00:00.000000Z",
+                "completed_at": null,
+            })
+        })
+        .collect();
+    json!({
+        "full_sync": true,
+        "full_sync_date_utc": "2024-05-01T09:00:00Z",
+        "sync_token": "BENCH_SYNC_TOKEN",
+        "temp_id_mapping": {},
+        "projects": projects,
+        "items": items,
+        "sections": [],
+        "labels": [],
+        "day_orders": {},
+        "day_orders_timestamp": "1714554000.0",
+    })
+}
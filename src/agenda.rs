@@ -0,0 +1,89 @@
+//! Date-based views of tasks across all projects, like the "Today" and
+//! "Upcoming" views in the official apps.
+
+use chrono::{Days, NaiveDate, NaiveDateTime};
+
+use crate::{locale, sync::Item};
+
+/// Open tasks with due dates, split up by day.
+pub struct Agenda<'a> {
+    /// Tasks that were due before the first day, oldest first.
+    pub overdue: Vec<&'a Item>,
+
+    /// Every day in the agenda's range, in order, with the tasks due on it.
+    pub days: Vec<(NaiveDate, Vec<&'a Item>)>,
+}
+
+/// The date part of an item's due date, which may or may not include a time.
+#[must_use]
+pub fn due_date(item: &Item) -> Option<NaiveDate> {
+    let date = item.due.as_ref()?.date.get(..10)?;
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()
+}
+
+/// When an item is due, in the user's time, if it's due at a particular time.
+/// Times that end in `Z` are in UTC; the rest are already local.
+#[must_use]
+pub fn due_time(item: &Item) -> Option<NaiveDateTime> {
+    let date = &item.due.as_ref()?.date;
+    if let Some(utc) = date.strip_suffix('Z') {
+        let time = NaiveDateTime::parse_from_str(utc, "%Y-%m-%dT%H:%M:%S").ok()?;
+        return Some(locale::user_time(time.and_utc()));
+    }
+    NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
+#[must_use]
+pub fn is_overdue(item: &Item, today: NaiveDate) -> bool {
+    due_date(item).is_some_and(|date| date < today) && !item.checked
+}
+
+/// Builds an agenda covering `days` days, starting with `today`. Within each
+/// day, tasks with times come in order of time, and the rest by priority.
+#[must_use]
+pub fn agenda(items: &[Item], today: NaiveDate, days: u64) -> Agenda<'_> {
+    let mut open: Vec<&Item> = items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .collect();
+    sort_by_due(&mut open);
+
+    let overdue = open
+        .iter()
+        .copied()
+        .filter(|item| is_overdue(item, today))
+        .collect();
+
+    let days = (0..days)
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .map(|date| {
+            let day_items = open
+                .iter()
+                .copied()
+                .filter(|item| due_date(item) == Some(date))
+                .collect();
+            (date, day_items)
+        })
+        .collect();
+
+    Agenda { overdue, days }
+}
+
+/// Sorts items by due date and time, and then by priority. Items without due
+/// dates go first.
+pub fn sort_by_due(items: &mut [&Item]) {
+    items.sort_by_key(|item| {
+        let due = item.due.as_ref().map(|due| due.date.clone());
+        (due, std::cmp::Reverse(item.priority))
+    });
+}
+
+/// A heading for a day, relative to `today` where that reads better.
+#[must_use]
+pub fn day_heading(date: NaiveDate, today: NaiveDate) -> String {
+    match (date - today).num_days() {
+        0 => "Today".to_string(),
+        1 => "Tomorrow".to_string(),
+        _ => date.format("%a %b %-d").to_string(),
+    }
+}
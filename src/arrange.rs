@@ -0,0 +1,136 @@
+//! Reordering and regrouping the tasks in a list, as `list --sort` and
+//! `--group-by` ask, instead of the order and sections they have in Todoist.
+
+use std::cmp::Reverse;
+
+use chrono::{NaiveDate, NaiveTime};
+use clap::ValueEnum;
+
+use crate::{
+    agenda::{day_heading, due_date, due_time},
+    sync::{Item, Project},
+};
+
+/// What to sort the tasks in a list by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Sort {
+    /// Soonest first, by time on the same day, with undated tasks last.
+    Due,
+
+    /// Most urgent first, and then by due date.
+    Priority,
+
+    /// Oldest first.
+    Added,
+
+    /// By project name, keeping each project's own order.
+    Project,
+}
+
+/// What to group the tasks in a list under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum GroupBy {
+    Project,
+
+    /// Each of a task's labels, so a task with two labels is in two groups.
+    Label,
+
+    /// The day each task is due, with overdue tasks together.
+    DueDate,
+}
+
+/// Sorts `items` by `sort`. Ties are left as they were.
+pub fn sort(items: &mut [&Item], sort: Sort, projects: &[Project]) {
+    match sort {
+        Sort::Due => items.sort_by_key(|item| due_key(item)),
+        Sort::Priority => items.sort_by_key(|item| (Reverse(item.priority), due_key(item))),
+        // RFC 3339 times in UTC sort as they read
+        Sort::Added => items.sort_by_key(|item| (item.added_at.is_none(), item.added_at.clone())),
+        Sort::Project => items.sort_by_key(|item| {
+            let name = projects
+                .iter()
+                .find(|project| project.id == item.project_id)
+                .map(|project| project.name.to_lowercase());
+            (name.is_none(), name, item.child_order)
+        }),
+    }
+}
+
+/// Where an item comes when sorting by due date: by day, then by time, with
+/// the day's untimed tasks after its timed ones, and undated tasks last.
+fn due_key(item: &Item) -> (bool, NaiveDate, bool, NaiveTime) {
+    let (date, time) = (due_day(item), due_time(item).map(|time| time.time()));
+    (
+        date.is_none(),
+        date.unwrap_or_default(),
+        time.is_none(),
+        time.unwrap_or_default(),
+    )
+}
+
+/// A group's heading, along with the date it's sorted by, or `None` for the
+/// tasks that go in none of the groups.
+type GroupKey = Option<(NaiveDate, String)>;
+
+/// The day an item is due in the user's time, which a UTC time can put on
+/// another day than its date says.
+fn due_day(item: &Item) -> Option<NaiveDate> {
+    due_time(item).map_or_else(|| due_date(item), |time| Some(time.date()))
+}
+
+/// Splits `items` into headed groups by `group_by`, keeping their order
+/// within each group. Projects and labels come in the order of their first
+/// tasks, and days in order, with tasks that are in none of them last.
+#[must_use]
+pub fn group<'a>(
+    items: &[&'a Item],
+    group_by: GroupBy,
+    projects: &[Project],
+    today: NaiveDate,
+) -> Vec<(String, Vec<&'a Item>)> {
+    let mut groups: Vec<(GroupKey, Vec<&Item>)> = Vec::new();
+    let mut add = |key: GroupKey, item| {
+        if let Some((_, items)) = groups.iter_mut().find(|(other, _)| *other == key) {
+            items.push(item);
+        } else {
+            groups.push((key, vec![item]));
+        }
+    };
+    for item in items {
+        match group_by {
+            GroupBy::Project => {
+                let name = projects
+                    .iter()
+                    .find(|project| project.id == item.project_id)
+                    .map_or(item.project_id.clone(), |project| project.name.clone());
+                add(Some((NaiveDate::MIN, name)), item);
+            }
+            GroupBy::Label if item.labels.is_empty() => add(None, item),
+            GroupBy::Label => {
+                for label in &item.labels {
+                    add(Some((NaiveDate::MIN, format!("@{label}"))), item);
+                }
+            }
+            GroupBy::DueDate => match due_day(item) {
+                Some(date) if date < today => {
+                    add(Some((NaiveDate::MIN, "Overdue".to_string())), item);
+                }
+                Some(date) => add(Some((date, day_heading(date, today))), item),
+                None => add(None, item),
+            },
+        }
+    }
+    // stable, so projects and labels, which all have the same date, keep
+    // their order
+    groups.sort_by_key(|(key, _)| (key.is_none(), key.as_ref().map(|(date, _)| *date)));
+
+    let rest = match group_by {
+        GroupBy::Project => "No project",
+        GroupBy::Label => "No labels",
+        GroupBy::DueDate => "No due date",
+    };
+    groups
+        .into_iter()
+        .map(|(key, items)| (key.map_or(rest.to_string(), |(_, name)| name), items))
+        .collect()
+}
@@ -0,0 +1,65 @@
+//! Parsing for batches of actions, which are sent to the server together in a
+//! single sync request.
+//!
+//! On the command line, a batch is a sequence of actions, each followed by its
+//! argument: `add "buy milk" add "call mom" complete 3`. In a batch file, each
+//! line holds one action, like `add buy milk`; blank lines and lines starting
+//! with `#` are ignored.
+
+use crate::{Error, Result};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Add a todo, written in quick-add syntax.
+    Add(String),
+
+    /// Complete a task, given as an ID, a list index, or `last`.
+    Complete(String),
+}
+
+impl Step {
+    fn new(action: &str, argument: &str) -> Result<Self> {
+        if argument.is_empty() {
+            return Err(Error::Batch(format!("'{action}' needs an argument")));
+        }
+
+        match action.to_lowercase().as_str() {
+            "add" => Ok(Step::Add(argument.to_string())),
+            "complete" => Ok(Step::Complete(argument.to_string())),
+            _ => Err(Error::Batch(format!(
+                "unknown action '{action}', expected 'add' or 'complete'"
+            ))),
+        }
+    }
+}
+
+/// Parses a batch given as command-line arguments.
+///
+/// # Errors
+///
+/// Returns [`Error::Batch`] if an action is unknown or missing its argument.
+pub fn parse_args(args: &[String]) -> Result<Vec<Step>> {
+    args.chunks(2)
+        .map(|chunk| {
+            let argument = chunk.get(1).map_or("", |argument| argument.trim());
+            Step::new(&chunk[0], argument)
+        })
+        .collect()
+}
+
+/// Parses the contents of a batch file.
+///
+/// # Errors
+///
+/// Returns [`Error::Batch`] if a line's action is unknown or missing its
+/// argument.
+pub fn parse_file(text: &str) -> Result<Vec<Step>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let (action, argument) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            Step::new(action, argument.trim())
+        })
+        .collect()
+}
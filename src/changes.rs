@@ -0,0 +1,92 @@
+//! What a sync changed in the store, worked out by comparing the items and
+//! projects it had before with the ones in the sync response.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::sync::{Item, Project, Response};
+
+/// How many items and projects a sync added, changed, or removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Changes {
+    pub items_added: usize,
+
+    /// Items that were open before, and were completed elsewhere.
+    pub items_completed: usize,
+
+    pub items_updated: usize,
+    pub items_deleted: usize,
+    pub projects_added: usize,
+    pub projects_updated: usize,
+    pub projects_deleted: usize,
+}
+
+impl Changes {
+    /// Whether the sync changed nothing.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Compares the items and projects the store had before a sync with the ones
+/// in its response. A full sync only returns what's still there, so anything
+/// missing from it counts as deleted, except for completed items and archived
+/// projects, which a full sync leaves out.
+#[must_use]
+pub fn compare(items: &[Item], projects: &[Project], response: &Response) -> Changes {
+    let mut changes = Changes::default();
+
+    if let Some(new_items) = &response.items {
+        let old: HashMap<&str, &Item> = items.iter().map(|item| (item.id.as_str(), item)).collect();
+        for item in new_items {
+            match old.get(item.id.as_str()) {
+                Some(_) if item.is_deleted => changes.items_deleted += 1,
+                None if !item.is_deleted && !item.checked => changes.items_added += 1,
+                Some(old) if item.checked && !old.checked => changes.items_completed += 1,
+                Some(old) if differ(*old, item) => changes.items_updated += 1,
+                _ => {}
+            }
+        }
+        if response.full_sync {
+            let ids: HashSet<&str> = new_items.iter().map(|item| item.id.as_str()).collect();
+            changes.items_deleted += items
+                .iter()
+                .filter(|item| !item.checked && !ids.contains(item.id.as_str()))
+                .count();
+        }
+    }
+
+    if let Some(new_projects) = &response.projects {
+        let old: HashMap<&str, &Project> = projects
+            .iter()
+            .map(|project| (project.id.as_str(), project))
+            .collect();
+        for project in new_projects {
+            match old.get(project.id.as_str()) {
+                Some(_) if project.is_deleted => changes.projects_deleted += 1,
+                None if !project.is_deleted => changes.projects_added += 1,
+                Some(old) if differ(*old, project) => changes.projects_updated += 1,
+                _ => {}
+            }
+        }
+        if response.full_sync {
+            let ids: HashSet<&str> = new_projects
+                .iter()
+                .map(|project| project.id.as_str())
+                .collect();
+            changes.projects_deleted += projects
+                .iter()
+                .filter(|project| !project.is_archived && !ids.contains(project.id.as_str()))
+                .count();
+        }
+    }
+
+    changes
+}
+
+/// Whether two versions of a resource differ in any field.
+fn differ<T: Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
@@ -0,0 +1,336 @@
+//! Checklists of tasks to add again each time something comes up, like moving
+//! house or onboarding someone, with each task due some number of days from
+//! the day it's started. Due dates are written as offsets from that day: `D`
+//! is the day itself, `D+2` two days after, `D-14` two weeks before, and
+//! `D+1w` a week after. An offset can be followed by a time, like `D+2 9am`.
+//! Other due dates, like "every monday", are left for Todoist to read.
+//!
+//! A checklist is a CSV file, in the form [`import`](crate::import) reads, or
+//! a small YAML file, like this one:
+//!
+//! ```yaml
+//! project: Moving
+//! tasks:
+//!   - content: Book movers
+//!     due: D-14
+//!     priority: 1
+//!   - content: Pack the kitchen
+//!     due: D-2 6pm
+//!     section: Packing
+//!     labels: [home]
+//!     subtasks:
+//!       - Wrap the glasses
+//!       - Label the boxes
+//! ```
+//!
+//! `project` and `section` at the top are for the tasks that don't name their
+//! own, and can be left out; without `tasks`, the file can also be just the
+//! list of tasks. Each task has a `content`, and can have a `description`,
+//! `project`, `section`, `due`, `priority` (from `p1` to `p4`, or just the
+//! number), `labels`, and `subtasks`. A task that's only its content can be
+//! written without the `content:`, on its own.
+
+use chrono::{NaiveDate, TimeDelta};
+
+use crate::{
+    import::{self, Task},
+    quick_add, Error, Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A list of tasks, as described above.
+    Yaml,
+
+    /// One row per task, with a header row.
+    Csv,
+}
+
+impl Format {
+    /// The format a file's extension suggests: CSV for `.csv` files, and YAML
+    /// otherwise.
+    #[must_use]
+    pub fn of(path: &std::path::Path) -> Self {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension.map(str::to_lowercase).as_deref() {
+            Some("csv") => Format::Csv,
+            _ => Format::Yaml,
+        }
+    }
+}
+
+/// A line of a YAML file, without its indentation.
+struct Line<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+/// Parses the tasks in a checklist, in `format`, with their due dates still
+/// written as offsets.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if the file can't be read as a checklist.
+pub fn parse(text: &str, format: Format) -> Result<Vec<Task>> {
+    match format {
+        Format::Yaml => parse_yaml(text),
+        Format::Csv => import::parse_csv(text),
+    }
+}
+
+/// Gives the tasks whose due dates are offsets the dates they come to when the
+/// checklist is started on `start`.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if an offset is too far away to be a date.
+pub fn schedule(tasks: &mut [Task], start: NaiveDate) -> Result<()> {
+    for task in tasks {
+        let Some(due) = task.due.as_deref() else {
+            continue;
+        };
+        let Some((days, time)) = offset(due) else {
+            continue;
+        };
+        let date = start
+            .checked_add_signed(TimeDelta::try_days(days).unwrap_or(TimeDelta::MAX))
+            .ok_or(Error::Import(format!(
+                "'{}' is due too far from the start",
+                task.content
+            )))?;
+        task.due = Some(match time {
+            "" => date.to_string(),
+            time => format!("{date} {time}"),
+        });
+    }
+    Ok(())
+}
+
+/// The number of days a due date like `D+2` is from the start, and the time
+/// after it, if there's one. Due dates that aren't offsets give `None`.
+#[must_use]
+pub fn offset(due: &str) -> Option<(i64, &str)> {
+    let due = due.trim();
+    let (offset, time) = due.split_once(char::is_whitespace).unwrap_or((due, ""));
+    let offset = offset
+        .strip_prefix('D')
+        .or_else(|| offset.strip_prefix('d'))?;
+    if offset.is_empty() {
+        return Some((0, time.trim()));
+    }
+    let (sign, amount) = match offset.split_at_checked(1)? {
+        ("+", amount) => (1, amount),
+        ("-", amount) => (-1, amount),
+        _ => return None,
+    };
+    let (amount, unit) = match amount.strip_suffix(['w', 'W']) {
+        Some(weeks) => (weeks, 7),
+        None => (amount.strip_suffix(['d', 'D']).unwrap_or(amount), 1),
+    };
+    let amount: i64 = amount.parse().ok()?;
+    Some((sign * amount * unit, time.trim()))
+}
+
+/// Parses a checklist in YAML, as described in the module documentation.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if a line isn't part of a task or a setting, or
+/// a task has no content or an unknown priority.
+pub fn parse_yaml(text: &str) -> Result<Vec<Task>> {
+    let lines: Vec<Line> = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(number, line)| {
+            let text = line.trim_start();
+            Line {
+                number: number + 1,
+                indent: line.len() - text.len(),
+                text: text.trim_end(),
+            }
+        })
+        .collect();
+
+    let (mut project, mut section) = (None, None);
+    let mut tasks = Vec::new();
+    let mut index = 0;
+    while let Some(line) = lines.get(index) {
+        if line.text.starts_with('-') {
+            index = parse_list(&lines, index, line.indent, None, &mut tasks)?;
+            continue;
+        }
+        index += 1;
+        match field(line.text) {
+            Some(("project", value)) => project = Some(value.to_string()),
+            Some(("section", value)) => section = Some(value.to_string()),
+            Some(("tasks", "")) => {
+                let indent = lines.get(index).map_or(0, |next| next.indent);
+                if indent >= line.indent {
+                    index = parse_list(&lines, index, indent, None, &mut tasks)?;
+                }
+            }
+            _ => return Err(unexpected(line)),
+        }
+    }
+
+    for task in &mut tasks {
+        if task.project.is_none() {
+            task.project.clone_from(&project);
+        }
+        if task.section.is_none() {
+            task.section.clone_from(&section);
+        }
+    }
+    Ok(tasks)
+}
+
+/// Parses the list of tasks that starts at `lines[index]`, with its items at
+/// `indent`, adding them to `tasks` as children of `parent`. Returns the index
+/// of the line after the list.
+fn parse_list(
+    lines: &[Line],
+    mut index: usize,
+    indent: usize,
+    parent: Option<usize>,
+    tasks: &mut Vec<Task>,
+) -> Result<usize> {
+    while let Some(line) = lines.get(index).filter(|line| line.indent == indent) {
+        let Some(item) = line.text.strip_prefix('-') else {
+            return Err(unexpected(line));
+        };
+        let first = item.trim_start();
+        // the item's other fields line up with its first one
+        let field_indent = indent + line.text.len() - first.len();
+        let task_index = tasks.len();
+        tasks.push(Task {
+            parent,
+            ..Default::default()
+        });
+        index += 1;
+
+        let mut on_first_line = !first.is_empty();
+        loop {
+            let (line, text) = if on_first_line {
+                (line, first)
+            } else {
+                match lines
+                    .get(index)
+                    .filter(|line| line.indent == field_indent && !line.text.starts_with('-'))
+                {
+                    Some(line) => {
+                        index += 1;
+                        (line, line.text)
+                    }
+                    None => break,
+                }
+            };
+            let is_first = std::mem::take(&mut on_first_line);
+            match field(text) {
+                Some(("subtasks", "")) => {
+                    let indent = lines.get(index).map_or(0, |next| next.indent);
+                    if indent >= field_indent {
+                        index = parse_list(lines, index, indent, Some(task_index), tasks)?;
+                    }
+                }
+                Some((key, value)) => set(&mut tasks[task_index], key, value, line)?,
+                None if is_first => {
+                    tasks[task_index].content = unquote(text).to_string();
+                }
+                None => return Err(unexpected(line)),
+            }
+        }
+        if tasks[task_index].content.is_empty() {
+            return Err(Error::Import(format!(
+                "the task on line {} has no content",
+                line.number
+            )));
+        }
+    }
+    Ok(index)
+}
+
+/// Sets one of a task's fields from the YAML it was written in.
+fn set(task: &mut Task, key: &str, value: &str, line: &Line) -> Result<()> {
+    let text = Some(value.to_string()).filter(|value| !value.is_empty());
+    match key {
+        "content" => task.content = value.to_string(),
+        "description" => task.description = text,
+        "project" => task.project = text,
+        "section" => task.section = text,
+        "due" => task.due = text,
+        "priority" => {
+            task.priority = Some(
+                value
+                    .parse()
+                    .ok()
+                    .filter(|priority| (1..=4).contains(priority))
+                    .or_else(|| quick_add::parse_priority(value))
+                    .ok_or(Error::Import(format!(
+                        "line {} has an invalid priority '{value}'",
+                        line.number
+                    )))?,
+            );
+        }
+        "labels" => {
+            task.labels = value
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .split(|char: char| char == ',' || char.is_whitespace())
+                .map(|label| unquote(label).trim_start_matches('@'))
+                .filter(|label| !label.is_empty())
+                .map(ToString::to_string)
+                .collect();
+        }
+        _ => return Err(unexpected(line)),
+    }
+    Ok(())
+}
+
+/// The key and value of a line like `due: D+2`, if it's one of a checklist's
+/// keys, with the value unquoted and without a comment after it.
+fn field(text: &str) -> Option<(&str, &str)> {
+    const KEYS: [&str; 9] = [
+        "content",
+        "description",
+        "project",
+        "section",
+        "due",
+        "priority",
+        "labels",
+        "subtasks",
+        "tasks",
+    ];
+    let (key, value) = text.split_once(':')?;
+    let key = key.trim();
+    if !KEYS.contains(&key) || !(value.is_empty() || value.starts_with(' ')) {
+        return None;
+    }
+    let value = value.trim();
+    let value = if value.starts_with(['"', '\'']) {
+        unquote(value)
+    } else {
+        value
+            .split_once(" #")
+            .map_or(value, |(value, _)| value)
+            .trim()
+    };
+    Some((key, value))
+}
+
+/// The text inside a pair of quotes, or all of it, if it isn't quoted.
+fn unquote(text: &str) -> &str {
+    let text = text.trim();
+    ['"', '\'']
+        .into_iter()
+        .find_map(|quote| text.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(text)
+}
+
+fn unexpected(line: &Line) -> Error {
+    Error::Import(format!(
+        "line {} isn't part of a task: '{}'",
+        line.number, line.text
+    ))
+}
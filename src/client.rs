@@ -0,0 +1,489 @@
+//! A thin wrapper around the Todoist Sync API that retries requests which fail
+//! for transient reasons.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use reqwest::{header::RETRY_AFTER, Certificate, Proxy, RequestBuilder, StatusCode};
+use serde::de::DeserializeOwned;
+use tracing::{debug, info, trace};
+
+use crate::{
+    conflict,
+    hooks::Hooks,
+    rate_limit::Limiter,
+    rest::RestClient,
+    storage::FULL_SYNC_TOKEN,
+    stream::{self, Batch},
+    sync::{
+        ActivityPage, ActivityQuery, ArchivedPage, ArchivedQuery, CompletedPage, CompletedQuery,
+        Item, Project, Request, Response,
+    },
+    Error, Result,
+};
+
+/// The Sync API's address.
+pub const SYNC_URL: &str = "https://api.todoist.com/sync/v9";
+
+/// How many times a failed request is retried, unless configured otherwise.
+pub const DEFAULT_RETRIES: u32 = 3;
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a request can take before it's given up on, unless configured
+/// otherwise.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long an unused connection is kept open for the next request.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// How often an open connection is checked on while it's idle.
+const TCP_KEEPALIVE: Duration = Duration::from_mins(1);
+
+/// How to connect to Todoist, where the defaults won't do.
+#[derive(Debug, Default)]
+pub struct HttpOptions<'a> {
+    /// Whether to negotiate HTTP/2 with servers that support it, instead of
+    /// sticking to HTTP/1.1.
+    pub http2: bool,
+
+    /// The proxy to send every request through. Without one, the proxies named
+    /// by `HTTPS_PROXY` and `HTTP_PROXY` are used, if they're set.
+    pub proxy: Option<&'a str>,
+
+    /// A file of PEM certificates to trust on top of the system's, for proxies
+    /// that re-sign TLS connections with their own certificate authority.
+    pub ca_bundle: Option<&'a Path>,
+
+    /// How long each request (and each retry of it) can take, from connecting
+    /// to reading the whole response. Requests that take longer fail as if the
+    /// server couldn't be reached. Without one, requests can take forever.
+    pub timeout: Option<Duration>,
+}
+
+/// Builds an HTTP client to share between the sync and REST clients, so that
+/// the requests a command makes go over the same connection rather than each
+/// opening its own.
+///
+/// # Errors
+///
+/// Returns [`Error::Config`] if the proxy isn't a valid URL or the CA bundle
+/// has no valid certificates, an I/O error if the bundle can't be read, and
+/// [`Error::Network`] if the TLS backend can't be set up.
+pub fn http_client(options: &HttpOptions) -> Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE);
+    if !options.http2 {
+        builder = builder.http1_only();
+    }
+    if let Some(timeout) = options.timeout {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(proxy) = options.proxy {
+        let proxy = Proxy::all(proxy)
+            .map_err(|err| Error::Config(format!("invalid proxy '{proxy}': {err}")))?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = options.ca_bundle {
+        let invalid = |reason: String| Error::Config(format!("{}: {reason}", path.display()));
+        let bundle = fs::read_to_string(path)?;
+        let certificates = pem_certificates(&bundle);
+        if certificates.is_empty() {
+            return Err(invalid("no certificates found".to_string()));
+        }
+        for pem in certificates {
+            let certificate =
+                Certificate::from_pem(pem.as_bytes()).map_err(|err| invalid(err.to_string()))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// The certificates in a PEM bundle, each with its `BEGIN` and `END` lines.
+pub(crate) fn pem_certificates(bundle: &str) -> Vec<&str> {
+    const BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+    const END: &str = "-----END CERTIFICATE-----";
+    let mut certificates = Vec::new();
+    let mut rest = bundle;
+    while let Some(start) = rest.find(BEGIN) {
+        let Some(length) = rest[start..].find(END) else {
+            break;
+        };
+        let end = start + length + END.len();
+        certificates.push(&rest[start..end]);
+        rest = &rest[end..];
+    }
+    certificates
+}
+
+#[derive(Clone)]
+pub struct SyncClient {
+    http: reqwest::Client,
+    sync_url: String,
+    api_key: String,
+    retries: u32,
+    limiter: Limiter,
+    rest: Option<RestClient>,
+    dry_run: bool,
+    in_parts: bool,
+    on_conflict: conflict::Strategy,
+    hooks: Hooks,
+}
+
+impl SyncClient {
+    #[must_use]
+    pub fn new(sync_url: &str, api_key: &str, retries: u32) -> Self {
+        SyncClient {
+            http: reqwest::Client::new(),
+            sync_url: sync_url.to_string(),
+            api_key: api_key.to_string(),
+            retries,
+            limiter: Limiter::default(),
+            rest: None,
+            dry_run: false,
+            in_parts: false,
+            on_conflict: conflict::Strategy::default(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Sends requests through `http`, typically one made by [`http_client`]
+    /// and shared with the REST client.
+    #[must_use]
+    pub fn with_http(self, http: reqwest::Client) -> Self {
+        SyncClient { http, ..self }
+    }
+
+    /// Keeps requests to the pace `limiter` sets, instead of Todoist's
+    /// documented [limit](crate::rate_limit).
+    #[must_use]
+    pub fn with_rate_limit(self, limiter: Limiter) -> Self {
+        SyncClient { limiter, ..self }
+    }
+
+    /// Pairs the client with a REST client, for the operations that are
+    /// simpler through the REST API.
+    #[must_use]
+    pub fn with_rest(self, rest: RestClient) -> Self {
+        SyncClient {
+            rest: Some(rest),
+            ..self
+        }
+    }
+
+    /// Stops the client from sending commands: [`session`](crate::session)
+    /// returns them in an [`Error::DryRun`] instead. Requests without commands,
+    /// like plain syncs, still go out.
+    #[must_use]
+    pub fn dry_run(self, dry_run: bool) -> Self {
+        SyncClient { dry_run, ..self }
+    }
+
+    /// Whether the client is in dry-run mode.
+    #[must_use]
+    pub fn is_dry_run(&self) -> bool {
+        self.dry_run
+    }
+
+    /// Has [`session::sync`](crate::session::sync) download the store in
+    /// parts, for accounts too big to download at once: one resource type at
+    /// a time for full syncs, with each response read in batches.
+    #[must_use]
+    pub fn in_parts(self, in_parts: bool) -> Self {
+        SyncClient { in_parts, ..self }
+    }
+
+    /// Whether the client syncs in parts.
+    #[must_use]
+    pub fn syncs_in_parts(&self) -> bool {
+        self.in_parts
+    }
+
+    /// What to do with queued commands that [conflict](crate::conflict) with
+    /// changes made on the server since they were queued.
+    #[must_use]
+    pub fn on_conflict(self, on_conflict: conflict::Strategy) -> Self {
+        SyncClient {
+            on_conflict,
+            ..self
+        }
+    }
+
+    /// What the client does with conflicting queued commands.
+    #[must_use]
+    pub fn conflict_strategy(&self) -> conflict::Strategy {
+        self.on_conflict
+    }
+
+    /// Runs `hooks` as [`session`](crate::session) sends commands and syncs.
+    #[must_use]
+    pub fn with_hooks(self, hooks: Hooks) -> Self {
+        SyncClient { hooks, ..self }
+    }
+
+    /// The hooks the client runs.
+    #[must_use]
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    /// The REST client to use alongside this one, if there is one.
+    #[must_use]
+    pub fn rest(&self) -> Option<&RestClient> {
+        self.rest.as_ref()
+    }
+
+    /// Sends a request to the sync endpoint, once the [rate
+    /// limit](crate::rate_limit) allows it. Timeouts, failed connections, rate
+    /// limiting, and server errors are retried with exponential backoff (or
+    /// after as long as the server asks, for rate limiting), up to the
+    /// configured number of retries.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Auth`] if the API key is rejected, and
+    /// [`Error::Network`] if the server can't be reached, responds with an
+    /// error status once retries are exhausted, or sends back something that
+    /// isn't a sync response.
+    pub async fn sync(&self, request: &Request) -> Result<Response> {
+        debug!(
+            commands = request.commands.len(),
+            resource_types = ?request.resource_types,
+            incremental = request.sync_token != FULL_SYNC_TOKEN,
+            "sync request"
+        );
+        let response: Response = self
+            .send(|| {
+                self.http
+                    .post(format!("{}/sync", self.sync_url))
+                    .json(request)
+            })
+            .await?;
+        debug!(
+            full_sync = response.full_sync,
+            items = response.items.as_ref().map_or(0, Vec::len),
+            projects = response.projects.as_ref().map_or(0, Vec::len),
+            failed_commands = response
+                .sync_status
+                .iter()
+                .flat_map(HashMap::values)
+                .filter(|status| !status.is_ok())
+                .count(),
+            "sync response"
+        );
+        Ok(response)
+    }
+
+    /// Like [`SyncClient::sync`], but hands the resources in the response to
+    /// `on_batch` as it's [read](crate::stream::read), instead of returning
+    /// them in it.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`SyncClient::sync`], or whatever `on_batch` returns.
+    pub async fn sync_in_batches(
+        &self,
+        request: &Request,
+        on_batch: impl FnMut(Batch) -> Result<()>,
+    ) -> Result<Response> {
+        debug!(
+            resource_types = ?request.resource_types,
+            incremental = request.sync_token != FULL_SYNC_TOKEN,
+            "sync request, read in batches"
+        );
+        let response = self
+            .respond(|| {
+                self.http
+                    .post(format!("{}/sync", self.sync_url))
+                    .json(request)
+            })
+            .await?;
+        stream::read(response, stream::BATCH_SIZE, on_batch).await
+    }
+
+    /// Fetches a page of completed items, most recently completed first.
+    /// Retried like [`SyncClient::sync`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`SyncClient::sync`].
+    pub async fn completed(&self, query: &CompletedQuery) -> Result<CompletedPage> {
+        self.send(|| {
+            self.http
+                .get(format!("{}/completed/get_all", self.sync_url))
+                .query(query)
+        })
+        .await
+    }
+
+    /// Fetches the archived projects, which syncing leaves out. Retried like
+    /// [`SyncClient::sync`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`SyncClient::sync`].
+    pub async fn archived_projects(&self) -> Result<Vec<Project>> {
+        self.send(|| {
+            self.http
+                .get(format!("{}/projects/get_archived", self.sync_url))
+        })
+        .await
+    }
+
+    /// Fetches a page of a project's completed items, which syncing leaves
+    /// out, most recently completed first. Retried like [`SyncClient::sync`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`SyncClient::sync`].
+    pub async fn archived_items(&self, query: &ArchivedQuery) -> Result<ArchivedPage> {
+        self.send(|| {
+            self.http
+                .get(format!("{}/archive/items", self.sync_url))
+                .query(query)
+        })
+        .await
+    }
+
+    /// Adds a task from a line of text, letting Todoist parse it the way the
+    /// quick-add box in its apps does: dates, `#Project`, `@label`, `p1`-`p4`,
+    /// and `+assignee`. Returns the new item. Retried like
+    /// [`SyncClient::sync`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`SyncClient::sync`].
+    pub async fn quick_add(&self, text: &str) -> Result<Item> {
+        self.send(|| {
+            self.http
+                .post(format!("{}/quick/add", self.sync_url))
+                .json(&serde_json::json!({ "text": text }))
+        })
+        .await
+    }
+
+    /// Fetches a page of the activity log, most recent events first. Retried
+    /// like [`SyncClient::sync`].
+    ///
+    /// # Errors
+    ///
+    /// The same as [`SyncClient::sync`].
+    pub async fn activity(&self, query: &ActivityQuery) -> Result<ActivityPage> {
+        self.send(|| {
+            self.http
+                .get(format!("{}/activity/get", self.sync_url))
+                .query(query)
+        })
+        .await
+    }
+
+    /// Sends the request that `build` makes, retrying it as described for
+    /// [`SyncClient::sync`], and parses the response.
+    async fn send<T: DeserializeOwned>(&self, build: impl Fn() -> RequestBuilder) -> Result<T> {
+        Ok(self.respond(build).await?.json::<T>().await?)
+    }
+
+    /// Sends the request that `build` makes, retrying it as described for
+    /// [`SyncClient::sync`], and returns the response, without reading its
+    /// body.
+    async fn respond(&self, build: impl Fn() -> RequestBuilder) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            self.limiter.acquire().await;
+            let result = send_logged(build().bearer_auth(&self.api_key), &self.api_key).await;
+
+            let delay = match &result {
+                Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                    let delay = retry_after(response).unwrap_or(backoff(attempt));
+                    // other requests sent meanwhile would only be turned away too
+                    self.limiter.hold_off(Instant::now(), delay);
+                    Some(delay)
+                }
+                Ok(response) if is_transient(response.status()) => {
+                    Some(retry_after(response).unwrap_or(backoff(attempt)))
+                }
+                Err(err) if err.is_timeout() || err.is_connect() => Some(backoff(attempt)),
+                _ => None,
+            };
+
+            match delay {
+                Some(delay) if attempt < self.retries => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                _ => {
+                    let response = result?;
+                    if matches!(
+                        response.status(),
+                        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+                    ) {
+                        return Err(Error::Auth);
+                    }
+                    return Ok(response.error_for_status()?);
+                }
+            }
+        }
+    }
+}
+
+/// Sends a request, logging its status and how long it took, and at the
+/// trace level its body, with `api_key` redacted.
+pub(crate) async fn send_logged(
+    request: RequestBuilder,
+    api_key: &str,
+) -> reqwest::Result<reqwest::Response> {
+    let (http, request) = request.build_split();
+    let request = request?;
+    let (method, url) = (request.method().clone(), request.url().clone());
+    if let Some(body) = request.body().and_then(reqwest::Body::as_bytes) {
+        trace!(
+            body = redact(&String::from_utf8_lossy(body), api_key),
+            "{method} {url}"
+        );
+    }
+
+    let start = Instant::now();
+    let result = http.execute(request).await;
+    let elapsed = start.elapsed().as_millis();
+    match &result {
+        Ok(response) => info!(
+            "{method} {url}: {} over {:?} in {elapsed} ms",
+            response.status(),
+            response.version()
+        ),
+        Err(err) => info!("{method} {url}: failed after {elapsed} ms ({err})"),
+    }
+    result
+}
+
+/// Replaces every occurrence of the API key in `text`, so it stays out of logs.
+fn redact(text: &str, api_key: &str) -> String {
+    if api_key.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(api_key, "[REDACTED]")
+    }
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// The delay requested by a `Retry-After` header, if it's given in seconds.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?;
+    seconds.trim().parse().ok().map(Duration::from_secs)
+}
+
+fn backoff(attempt: u32) -> Duration {
+    BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_DELAY)
+}
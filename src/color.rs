@@ -0,0 +1,161 @@
+//! Terminal colors, using the same palette as the official Todoist apps.
+//!
+//! Colors are given as RGB values, and drawn according to the [`Theme`]: as
+//! they are on terminals with true color, or as the closest of the basic ANSI
+//! colors on the ones without it.
+
+use std::{io::IsTerminal, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+/// When to use colors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Choice {
+    /// Only when stdout is a terminal and `NO_COLOR` isn't set.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// How to draw colors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Todoist's own colors, for terminals with true color.
+    #[default]
+    Todoist,
+
+    /// The closest of the basic ANSI colors, which follow the terminal's own
+    /// color scheme.
+    Basic,
+}
+
+static CHOICE: OnceLock<Choice> = OnceLock::new();
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+/// Overrides the default choice of [`Choice::Auto`]. Only the first call has
+/// any effect.
+pub fn set_choice(choice: Choice) {
+    let _ = CHOICE.set(choice);
+}
+
+/// Overrides the default theme of [`Theme::Todoist`]. Only the first call has
+/// any effect.
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+/// Whether output to stdout should be colored. By default, that's only when
+/// it's a terminal, and only if the user hasn't opted out with `NO_COLOR`.
+#[must_use]
+pub fn enabled() -> bool {
+    match CHOICE.get().copied().unwrap_or_default() {
+        Choice::Auto => std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none(),
+        Choice::Always => true,
+        Choice::Never => false,
+    }
+}
+
+/// The RGB value of one of Todoist's named colors, like `berry_red`.
+#[must_use]
+pub fn named(name: &str) -> Option<(u8, u8, u8)> {
+    let rgb = match name {
+        "berry_red" => (0xb8, 0x25, 0x6f),
+        "red" => (0xdb, 0x40, 0x35),
+        "orange" => (0xff, 0x99, 0x33),
+        "yellow" => (0xfa, 0xd0, 0x00),
+        "olive_green" => (0xaf, 0xb8, 0x3b),
+        "lime_green" => (0x7e, 0xcc, 0x49),
+        "green" => (0x29, 0x94, 0x38),
+        "mint_green" => (0x6a, 0xcc, 0xbc),
+        "teal" => (0x15, 0x8f, 0xad),
+        "sky_blue" => (0x14, 0xaa, 0xf5),
+        "light_blue" => (0x96, 0xc3, 0xeb),
+        "blue" => (0x40, 0x73, 0xff),
+        "grape" => (0x88, 0x4d, 0xff),
+        "violet" => (0xaf, 0x38, 0xeb),
+        "lavender" => (0xeb, 0x96, 0xeb),
+        "magenta" => (0xe0, 0x51, 0x94),
+        "salmon" => (0xff, 0x8d, 0x85),
+        "charcoal" => (0x80, 0x80, 0x80),
+        "grey" => (0xb8, 0xb8, 0xb8),
+        "taupe" => (0xcc, 0xac, 0x93),
+        _ => return None,
+    };
+    Some(rgb)
+}
+
+/// The color the official apps use for a priority, given in the API's
+/// numbering (so `4` is "p1"). The lowest priority has no color.
+#[must_use]
+pub fn priority(priority: u8) -> Option<(u8, u8, u8)> {
+    match priority {
+        4 => Some((0xd1, 0x45, 0x3b)),
+        3 => Some((0xeb, 0x89, 0x09)),
+        2 => Some((0x24, 0x6f, 0xe0)),
+        _ => None,
+    }
+}
+
+/// The color for the due dates of overdue tasks.
+#[must_use]
+pub fn overdue() -> (u8, u8, u8) {
+    (0xdb, 0x40, 0x35)
+}
+
+/// The color for the parts of a task that match a search.
+#[must_use]
+pub fn highlight() -> (u8, u8, u8) {
+    (0xfa, 0xd0, 0x00)
+}
+
+/// Wraps `text` in the escape codes to draw it in the given color, according
+/// to the theme set with [`set_theme`].
+#[must_use]
+pub fn paint(text: &str, rgb: (u8, u8, u8)) -> String {
+    paint_with(THEME.get().copied().unwrap_or_default(), text, rgb)
+}
+
+/// Wraps `text` in the escape codes to draw it in the given color, according
+/// to `theme`.
+#[must_use]
+pub fn paint_with(theme: Theme, text: &str, rgb: (u8, u8, u8)) -> String {
+    match theme {
+        Theme::Todoist => {
+            let (red, green, blue) = rgb;
+            format!("\x1b[38;2;{red};{green};{blue}m{text}\x1b[0m")
+        }
+        Theme::Basic => format!("\x1b[{}m{text}\x1b[0m", closest_basic(rgb)),
+    }
+}
+
+/// The escape code for the basic ANSI color with the closest hue to `rgb`.
+/// Colors that are close to grey get the terminal's grey.
+fn closest_basic(rgb: (u8, u8, u8)) -> u8 {
+    let max = rgb.0.max(rgb.1).max(rgb.2);
+    let chroma = f32::from(max - rgb.0.min(rgb.1).min(rgb.2));
+    if chroma < 48.0 {
+        return 90;
+    }
+
+    let (red, green, blue) = (f32::from(rgb.0), f32::from(rgb.1), f32::from(rgb.2));
+    let hue = if max == rgb.0 {
+        60.0 * ((green - blue) / chroma)
+    } else if max == rgb.1 {
+        60.0 * ((blue - red) / chroma) + 120.0
+    } else {
+        60.0 * ((red - green) / chroma) + 240.0
+    }
+    .rem_euclid(360.0);
+
+    match hue {
+        hue if !(20.0..330.0).contains(&hue) => 31,
+        hue if hue < 75.0 => 33,
+        hue if hue < 165.0 => 32,
+        hue if hue < 195.0 => 36,
+        hue if hue < 250.0 => 34,
+        _ => 35,
+    }
+}
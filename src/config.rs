@@ -0,0 +1,488 @@
+//! User preferences, stored in `config.toml`. Every setting is optional, and
+//! command-line flags take precedence over them.
+//!
+//! The API token is kept separately, with the rest of the app's data, so that
+//! the config file can be shared (e.g. in a dotfiles repo) without leaking it.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{color, conflict::Strategy, dedupe, files, locale, Error, Result};
+
+/// The name of the config file, within the config directory.
+pub const FILE_NAME: &str = "config.toml";
+
+/// The settings that can be read and written with `get` and `set`.
+pub const KEYS: &[&str] = &[
+    "default_project",
+    "dedupe",
+    "output",
+    "color",
+    "theme",
+    "sync_on_start",
+    "cache_ttl",
+    "keep_completed_days",
+    "sync_in_parts",
+    "timeout",
+    "retries",
+    "data_dir",
+    "token_storage",
+    "backend",
+    "http2",
+    "log_file",
+    "proxy",
+    "ca_bundle",
+    "on_conflict",
+    "date_format",
+    "locale",
+    "track_comments",
+    "on_task_added",
+    "on_task_completed",
+    "on_sync",
+];
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The project new todos go to when they don't name one, instead of the
+    /// inbox.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_project: Option<String>,
+
+    /// What `add` does about new todos that look like open tasks already in
+    /// the same project. Defaults to [`Mode::Off`](dedupe::Mode::Off).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dedupe: Option<dedupe::Mode>,
+
+    /// How to print tasks, projects, and labels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<Output>,
+
+    /// When to use colors.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<color::Choice>,
+
+    /// How to draw colors, when they're used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<color::Theme>,
+
+    /// Whether to sync before showing tasks, instead of showing cached data.
+    /// Defaults to `true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_on_start: Option<bool>,
+
+    /// How many seconds synced data is fresh for. Commands that show tasks
+    /// only sync first once it's older than this. Defaults to `0`, so that
+    /// they always do.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cache_ttl: Option<u64>,
+
+    /// How many days to keep completed tasks in the local cache for, after
+    /// they're completed. Defaults to keeping them for as long as they're
+    /// synced. See [`Store::keep_completed_for`](crate::storage::Store::keep_completed_for).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_completed_days: Option<u32>,
+
+    /// Whether to sync in parts, for accounts too big to download all at
+    /// once. Defaults to `false`. See
+    /// [`SyncClient::in_parts`](crate::client::SyncClient::in_parts).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sync_in_parts: Option<bool>,
+
+    /// How many seconds a request to Todoist can take before it's given up on,
+    /// or `0` for no limit. Defaults to
+    /// [`DEFAULT_TIMEOUT`](crate::client::DEFAULT_TIMEOUT).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// How many times to retry requests that fail for transient reasons, like
+    /// rate limiting. Defaults to
+    /// [`DEFAULT_RETRIES`](crate::client::DEFAULT_RETRIES).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+
+    /// Where to keep the API token and synced data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_dir: Option<PathBuf>,
+
+    /// Where to keep the API token. Defaults to a file in the data directory.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_storage: Option<TokenStorage>,
+
+    /// Which of Todoist's APIs to use. Defaults to [`Backend::Auto`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<Backend>,
+
+    /// Whether to use HTTP/2 with servers that support it. Defaults to
+    /// `false`, for proxies that don't handle it well.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub http2: Option<bool>,
+
+    /// Whether to keep a log of requests to Todoist, in the `logs` directory
+    /// within the data directory. Defaults to `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_file: Option<bool>,
+
+    /// The proxy to send requests through, like `http://proxy.example:8080`.
+    /// Defaults to the one in `HTTPS_PROXY` or `HTTP_PROXY`, if either is set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+
+    /// A file of PEM certificates to trust, on top of the system's, for proxies
+    /// that inspect TLS traffic.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+
+    /// What to do with changes queued while offline that clash with changes
+    /// made elsewhere. Defaults to [`Strategy::Ask`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_conflict: Option<Strategy>,
+
+    /// How to show dates, as a `strftime`-style format like `%d.%m.%Y`.
+    /// Defaults to [`DEFAULT_DATE_FORMAT`](locale::DEFAULT_DATE_FORMAT).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+
+    /// The language to write due dates in, one of
+    /// [`LANGUAGES`](locale::LANGUAGES). Defaults to the language of the
+    /// user's Todoist account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+
+    /// Whether stopping the timer adds a comment to its task, noting the time
+    /// worked. Defaults to `false`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_comments: Option<bool>,
+
+    /// A shell command to run after adding a task, with the task as JSON on
+    /// stdin. See [`hooks`](crate::hooks).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_task_added: Option<String>,
+
+    /// A shell command to run after completing a task, with the task as JSON
+    /// on stdin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_task_completed: Option<String>,
+
+    /// A shell command to run after each sync, with what changed as JSON on
+    /// stdin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_sync: Option<String>,
+
+    /// Shell commands for `serve-webhooks` to run, by the name of the event
+    /// that sets them off, like `item:completed`. Each gets the event's JSON
+    /// on stdin. Set them as `webhook_hooks.<event>`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub webhook_hooks: BTreeMap<String, String>,
+}
+
+/// The prefix of the keys for [`Config::webhook_hooks`].
+const WEBHOOK_HOOK_PREFIX: &str = "webhook_hooks.";
+
+/// Formats for printing tasks, projects, and labels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Output {
+    /// Tables and lists for reading.
+    Table,
+
+    /// JSON, for tools like `jq`.
+    Json,
+
+    /// Tab-separated lines, for scripts.
+    Plain,
+}
+
+/// Places to keep the API token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    /// A file in the data directory, readable only by the current user.
+    File,
+
+    /// The operating system's keyring, falling back to the file if the keyring
+    /// isn't available.
+    Keyring,
+}
+
+/// Which of Todoist's APIs to talk to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    /// The Sync API for most things, and the REST API where it's simpler, like
+    /// fetching a single task.
+    Auto,
+
+    /// Only the Sync API, for proxies that don't pass the REST API through.
+    Sync,
+}
+
+impl Output {
+    /// Prints a message that isn't part of the output proper, keeping it out
+    /// of the way of anything reading JSON or plain output from stdout.
+    pub fn status(self, message: &str) {
+        if self == Output::Table {
+            println!("{message}");
+        } else {
+            eprintln!("{message}");
+        }
+    }
+}
+
+impl Config {
+    /// Reads the config file at `path`, which doesn't have to exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if the file isn't valid, or an I/O error if it
+    /// can't be read.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let config: Config = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| Error::Config(format!("{}: {}", path.display(), err.message())))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Writes the config to `path`, creating its directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let text = toml::to_string(self).map_err(|err| Error::Config(err.to_string()))?;
+        files::write(path, text.as_bytes())
+    }
+
+    /// Every key that's set or can be, in the order `config get` lists them:
+    /// [`KEYS`], then each of the [`webhook_hooks`](Config::webhook_hooks).
+    #[must_use]
+    pub fn keys(&self) -> Vec<String> {
+        let hooks = self
+            .webhook_hooks
+            .keys()
+            .map(|event| format!("{WEBHOOK_HOOK_PREFIX}{event}"));
+        KEYS.iter().map(ToString::to_string).chain(hooks).collect()
+    }
+
+    /// The value of a setting, formatted the way `set` accepts it, or `None` if
+    /// it isn't set.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `key` isn't a setting.
+    pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let value = match key {
+            "default_project" => self.default_project.clone(),
+            "output" => self.output.map(|output| value_name(&output)),
+            "color" => self.color.map(|choice| value_name(&choice)),
+            "dedupe" => self.dedupe.map(|mode| value_name(&mode)),
+            "theme" => self.theme.map(|theme| value_name(&theme)),
+            "sync_on_start" => self.sync_on_start.map(|sync| sync.to_string()),
+            "cache_ttl" => self.cache_ttl.map(|ttl| ttl.to_string()),
+            "keep_completed_days" => self.keep_completed_days.map(|days| days.to_string()),
+            "sync_in_parts" => self.sync_in_parts.map(|in_parts| in_parts.to_string()),
+            "timeout" => self.timeout.map(|timeout| timeout.to_string()),
+            "retries" => self.retries.map(|retries| retries.to_string()),
+            "data_dir" => self.data_dir.as_ref().map(|dir| dir.display().to_string()),
+            "token_storage" => self.token_storage.map(|storage| value_name(&storage)),
+            "backend" => self.backend.map(|backend| value_name(&backend)),
+            "http2" => self.http2.map(|http2| http2.to_string()),
+            "log_file" => self.log_file.map(|log_file| log_file.to_string()),
+            "proxy" => self.proxy.clone(),
+            "ca_bundle" => self
+                .ca_bundle
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            "on_conflict" => self.on_conflict.map(|strategy| value_name(&strategy)),
+            "date_format" => self.date_format.clone(),
+            "locale" => self.locale.clone(),
+            "track_comments" => self.track_comments.map(|comments| comments.to_string()),
+            "on_task_added" => self.on_task_added.clone(),
+            "on_task_completed" => self.on_task_completed.clone(),
+            "on_sync" => self.on_sync.clone(),
+            _ => match key.strip_prefix(WEBHOOK_HOOK_PREFIX) {
+                Some(event) => self.webhook_hooks.get(event).cloned(),
+                None => return Err(unknown_key(key)),
+            },
+        };
+        Ok(value)
+    }
+
+    /// Changes a setting, or clears it if `value` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if `key` isn't a setting or `value` isn't
+    /// valid for it.
+    pub fn set(&mut self, key: &str, value: Option<&str>) -> Result<()> {
+        match key {
+            "default_project" => {
+                self.default_project = value.map(str::trim).map(ToString::to_string);
+            }
+            "output" => self.output = value.map(|value| parse_value(key, value)).transpose()?,
+            "color" => self.color = value.map(|value| parse_value(key, value)).transpose()?,
+            "theme" => self.theme = value.map(|value| parse_value(key, value)).transpose()?,
+            "sync_on_start" => {
+                self.sync_on_start = value.map(|value| parse_bool(key, value)).transpose()?;
+            }
+            "cache_ttl" => {
+                self.cache_ttl = value.map(|value| parse_seconds(key, value)).transpose()?;
+            }
+            "keep_completed_days" => {
+                self.keep_completed_days = value.map(|value| parse_days(key, value)).transpose()?;
+            }
+            "sync_in_parts" => {
+                self.sync_in_parts = value.map(|value| parse_bool(key, value)).transpose()?;
+            }
+            "timeout" => {
+                self.timeout = value.map(|value| parse_seconds(key, value)).transpose()?;
+            }
+            "retries" => {
+                self.retries = value.map(|value| parse_count(key, value)).transpose()?;
+            }
+            "data_dir" => self.data_dir = value.map(PathBuf::from),
+            "token_storage" => {
+                self.token_storage = value.map(|value| parse_value(key, value)).transpose()?;
+            }
+            "backend" => self.backend = value.map(|value| parse_value(key, value)).transpose()?,
+            "dedupe" => self.dedupe = value.map(|value| parse_value(key, value)).transpose()?,
+            "http2" => self.http2 = value.map(|value| parse_bool(key, value)).transpose()?,
+            "log_file" => self.log_file = value.map(|value| parse_bool(key, value)).transpose()?,
+            "proxy" => self.proxy = value.map(str::trim).map(ToString::to_string),
+            "ca_bundle" => self.ca_bundle = value.map(PathBuf::from),
+            "on_conflict" => {
+                self.on_conflict = value.map(|value| parse_value(key, value)).transpose()?;
+            }
+            "date_format" => self.date_format = value.map(ToString::to_string),
+            "locale" => self.locale = value.map(str::trim).map(ToString::to_string),
+            "track_comments" => {
+                self.track_comments = value.map(|value| parse_bool(key, value)).transpose()?;
+            }
+            "on_task_added" => self.on_task_added = value.map(ToString::to_string),
+            "on_task_completed" => self.on_task_completed = value.map(ToString::to_string),
+            "on_sync" => self.on_sync = value.map(ToString::to_string),
+            _ => {
+                let event = key
+                    .strip_prefix(WEBHOOK_HOOK_PREFIX)
+                    .filter(|event| !event.is_empty())
+                    .ok_or_else(|| unknown_key(key))?;
+                match value {
+                    Some(command) => self
+                        .webhook_hooks
+                        .insert(event.to_string(), command.to_string()),
+                    None => self.webhook_hooks.remove(event),
+                };
+            }
+        }
+        self.validate()
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self
+            .default_project
+            .as_ref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err(Error::Config("default_project can't be empty".to_string()));
+        }
+        if let Some(dir) = self.data_dir.as_ref().filter(|dir| !dir.is_absolute()) {
+            return Err(Error::Config(format!(
+                "data_dir must be an absolute path, not '{}'",
+                dir.display()
+            )));
+        }
+        if let Some(format) = self
+            .date_format
+            .as_ref()
+            .filter(|format| !locale::is_valid_date_format(format))
+        {
+            return Err(Error::Config(format!(
+                "date_format must be a strftime format like %d.%m.%Y, not '{format}'"
+            )));
+        }
+        if let Some(lang) = self
+            .locale
+            .as_ref()
+            .filter(|lang| !locale::LANGUAGES.contains(&lang.as_str()))
+        {
+            return Err(Error::Config(format!(
+                "locale must be one of {}, not '{lang}'",
+                locale::LANGUAGES.join(", ")
+            )));
+        }
+        if let Some(path) = self.ca_bundle.as_ref().filter(|path| !path.is_absolute()) {
+            return Err(Error::Config(format!(
+                "ca_bundle must be an absolute path, not '{}'",
+                path.display()
+            )));
+        }
+        Ok(())
+    }
+}
+
+fn value_name<T: ValueEnum>(value: &T) -> String {
+    value
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn parse_value<T: ValueEnum>(key: &str, value: &str) -> Result<T> {
+    T::from_str(value, true).map_err(|_| {
+        let names: Vec<String> = T::value_variants().iter().map(value_name).collect();
+        Error::Config(format!(
+            "{key} must be one of {}, not '{value}'",
+            names.join(", ")
+        ))
+    })
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" => Ok(true),
+        "false" | "no" | "off" => Ok(false),
+        _ => Err(Error::Config(format!(
+            "{key} must be true or false, not '{value}'"
+        ))),
+    }
+}
+
+fn parse_seconds(key: &str, value: &str) -> Result<u64> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::Config(format!("{key} must be a number of seconds, not '{value}'")))
+}
+
+fn parse_days(key: &str, value: &str) -> Result<u32> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::Config(format!("{key} must be a number of days, not '{value}'")))
+}
+
+fn parse_count(key: &str, value: &str) -> Result<u32> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| Error::Config(format!("{key} must be a number, not '{value}'")))
+}
+
+fn unknown_key(key: &str) -> Error {
+    Error::Config(format!(
+        "there's no setting named '{key}' (expected one of {}, or {WEBHOOK_HOOK_PREFIX}<event>)",
+        KEYS.join(", ")
+    ))
+}
@@ -0,0 +1,180 @@
+//! Finding queued changes that clash with changes made elsewhere. When a
+//! command that changes an item is queued, the item is saved along with it, as
+//! it was when the change was made. Before the queue is sent, the store is
+//! brought up to date, and each queued change is compared with what happened
+//! to its item on the server in the meantime: it conflicts if the item was
+//! deleted, or if the same field was changed on both sides.
+//!
+//! What happens to conflicting changes depends on the client's [`Strategy`].
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::sync::{Command, CommandArgs, Item, ItemCloseArgs, ItemIdArgs};
+
+/// What to do with queued changes that conflict with changes on the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum Strategy {
+    /// Send the queued changes anyway, overwriting the server's.
+    KeepLocal,
+
+    /// Drop the queued changes, keeping the server's.
+    KeepRemote,
+
+    /// Stop, handing the conflicts back in an [`Error::Conflicts`] for them to
+    /// be resolved one by one.
+    ///
+    /// [`Error::Conflicts`]: crate::Error::Conflicts
+    #[default]
+    Ask,
+}
+
+/// How to settle a single conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Send the queued change as it is.
+    KeepLocal,
+
+    /// Drop the queued change.
+    KeepRemote,
+}
+
+/// A queued change that clashes with a change made on the server.
+#[derive(Debug)]
+pub struct Conflict {
+    /// The queued command.
+    pub command: Command,
+
+    /// The item as it was when the command was queued.
+    pub base: Item,
+
+    /// The item as it is on the server now, or `None` if it was deleted.
+    pub remote: Option<Item>,
+
+    /// The fields that were changed on both sides.
+    pub differences: Vec<Difference>,
+}
+
+/// A field that a queued change and the server changed in different ways.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Difference {
+    pub field: &'static str,
+    pub local: String,
+    pub remote: String,
+}
+
+/// The item that a command changes, if it changes exactly one that already
+/// exists.
+#[must_use]
+pub fn target_item(args: &CommandArgs) -> Option<&str> {
+    match args {
+        CommandArgs::ItemClose(ItemCloseArgs { id })
+        | CommandArgs::ItemComplete(ItemIdArgs { id })
+        | CommandArgs::ItemUpdateDateComplete(ItemIdArgs { id })
+        | CommandArgs::ItemDelete(ItemIdArgs { id })
+        | CommandArgs::ItemUncomplete(ItemIdArgs { id }) => Some(id),
+        CommandArgs::ItemUpdate(args) => Some(&args.id),
+        CommandArgs::ItemMove(args) => Some(&args.id),
+        _ => None,
+    }
+}
+
+/// Compares a queued command with what happened to its item on the server
+/// since `base` was saved, returning the conflict if there is one.
+#[must_use]
+pub fn detect(command: Command, base: Item, remote: Option<&Item>) -> Option<Conflict> {
+    let Some(remote) = remote.filter(|item| !item.is_deleted) else {
+        // deleting something that's already gone is no loss
+        if matches!(command.args, CommandArgs::ItemDelete(_)) {
+            return None;
+        }
+        return Some(Conflict {
+            command,
+            base,
+            remote: None,
+            differences: Vec::new(),
+        });
+    };
+
+    let mut differences = Vec::new();
+    let mut compare = |field, base: String, remote: String, local: Option<String>| {
+        if let Some(local) = local.filter(|local| *local != remote && base != remote) {
+            differences.push(Difference {
+                field,
+                local,
+                remote,
+            });
+        }
+    };
+    match &command.args {
+        CommandArgs::ItemUpdate(args) => {
+            compare(
+                "Content",
+                base.content.clone(),
+                remote.content.clone(),
+                args.content.clone(),
+            );
+            compare(
+                "Description",
+                base.description.clone(),
+                remote.description.clone(),
+                args.description.clone(),
+            );
+            compare(
+                "Due",
+                due_string(&base),
+                due_string(remote),
+                args.due.as_ref().map(|due| due.string.clone()),
+            );
+            compare(
+                "Priority",
+                priority_name(base.priority),
+                priority_name(remote.priority),
+                args.priority.map(priority_name),
+            );
+            compare(
+                "Labels",
+                base.labels.join(" "),
+                remote.labels.join(" "),
+                args.labels.as_ref().map(|labels| labels.join(" ")),
+            );
+        }
+        CommandArgs::ItemMove(args) => {
+            compare(
+                "Project",
+                base.project_id.clone(),
+                remote.project_id.clone(),
+                args.project_id.clone(),
+            );
+            compare(
+                "Section",
+                base.section_id.clone().unwrap_or_default(),
+                remote.section_id.clone().unwrap_or_default(),
+                args.section_id.clone(),
+            );
+        }
+        _ => {}
+    }
+    if differences.is_empty() {
+        return None;
+    }
+    Some(Conflict {
+        command,
+        base,
+        remote: Some(remote.clone()),
+        differences,
+    })
+}
+
+/// A priority the way users write it, so the API's `4` is `p1`.
+fn priority_name(priority: u8) -> String {
+    format!("p{}", 5 - priority.clamp(1, 4))
+}
+
+fn due_string(item: &Item) -> String {
+    item.due
+        .as_ref()
+        .map(|due| due.string.clone().unwrap_or(due.date.clone()))
+        .unwrap_or_default()
+}
@@ -0,0 +1,94 @@
+//! A background process that keeps the local store synced, so that other
+//! commands can read it straight away instead of syncing first.
+//!
+//! The daemon listens on a Unix socket in the data directory. Each connection
+//! sends one [`Request`] as a line of JSON, and gets a [`Status`] back the same
+//! way. Only Unix-like systems have the socket; elsewhere, [`request`] always
+//! finds that no daemon is running.
+
+use std::{path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+/// The name of the daemon's socket, within the data directory.
+pub const SOCKET_NAME: &str = "daemon.sock";
+
+/// How long to wait for the daemon to answer a status request before acting
+/// as if it weren't running.
+const STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Request {
+    /// Asks when the daemon last synced.
+    Status,
+
+    /// Asks the daemon to sync now, replying once it has.
+    Sync,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Status {
+    /// When the daemon last synced successfully, in seconds since the Unix
+    /// epoch.
+    pub last_sync: Option<i64>,
+
+    /// How often the daemon syncs, in seconds.
+    pub interval: u64,
+}
+
+impl Status {
+    /// Whether the store is fresh enough that a command can skip syncing: the
+    /// daemon synced within the last two intervals, allowing for one slow or
+    /// failed sync.
+    #[must_use]
+    pub fn is_fresh(&self, now: i64) -> bool {
+        let max_age = i64::try_from(self.interval.saturating_mul(2)).unwrap_or(i64::MAX);
+        self.last_sync
+            .is_some_and(|last_sync| now.saturating_sub(last_sync) <= max_age)
+    }
+}
+
+/// Sends a request to the daemon listening on the socket at `path`, returning
+/// `None` if no daemon is running there. Status requests give up quickly, so
+/// that a stuck daemon doesn't hold up other commands.
+///
+/// # Errors
+///
+/// Returns an error if the daemon's reply can't be parsed.
+pub async fn request(path: &Path, request: &Request) -> Result<Option<Status>> {
+    #[cfg(unix)]
+    {
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        let exchange = async {
+            let mut stream = tokio::net::UnixStream::connect(path).await?;
+            let mut line = serde_json::to_string(request)?;
+            line.push('\n');
+            stream.write_all(line.as_bytes()).await?;
+
+            let mut reply = String::new();
+            BufReader::new(stream).read_line(&mut reply).await?;
+            Ok::<_, crate::Error>(reply)
+        };
+        let reply = match request {
+            Request::Status => match tokio::time::timeout(STATUS_TIMEOUT, exchange).await {
+                Ok(reply) => reply,
+                Err(_) => return Ok(None),
+            },
+            Request::Sync => exchange.await,
+        };
+        match reply {
+            Ok(reply) => Ok(Some(serde_json::from_str(&reply)?)),
+            Err(crate::Error::Io(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = (path, request);
+        Ok(None)
+    }
+}
@@ -0,0 +1,68 @@
+//! Spotting a task that's about to be added a second time, which happens when
+//! adding it seemed to fail, say because the connection dropped, but went
+//! through anyway. A new task is a duplicate of an open task in the same
+//! project when their texts are the same apart from case, spacing, and
+//! punctuation, or differ by no more than a typo or two.
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::sync::Item;
+
+/// What to do about a new task that looks like one that's already there.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    /// Add it without checking.
+    #[default]
+    Off,
+
+    /// Add it, but say which task it looks like.
+    Warn,
+
+    /// Leave it out.
+    Skip,
+}
+
+/// The first of `items` that's open and that a new task with `content` would
+/// be a duplicate of.
+#[must_use]
+pub fn duplicate<'a>(content: &str, items: &'a [Item]) -> Option<&'a Item> {
+    let key = key(content);
+    if key.is_empty() {
+        return None;
+    }
+    // a typo for every ten characters or so, but none in short ones like
+    // "Call A" and "Call B"
+    let allowed = key.chars().count() / 10;
+    items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .find(|item| distance(&key, &self::key(&item.content)) <= allowed)
+}
+
+/// The words of `text`, lowercased and without punctuation, separated by
+/// single spaces.
+fn key(text: &str) -> String {
+    text.split(|char: char| !char.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How many characters have to be added, removed, or changed to turn `a`
+/// into `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let change = previous[j] + usize::from(a != *b);
+            current.push(change.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
@@ -0,0 +1,57 @@
+//! Tasks that wait for other tasks to be completed first. Todoist doesn't have
+//! dependencies, so they're only kept in the local store, and a task that's
+//! waiting is left out of lists until the tasks it waits for are done. Tasks
+//! that aren't cached anymore, like deleted ones, don't hold anything up.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::sync::Item;
+
+/// That one task waits for another.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dependency {
+    pub task_id: String,
+
+    /// The task that has to be completed first.
+    pub blocker_id: String,
+}
+
+/// Whether having `task_id` wait for `blocker_id` would leave tasks waiting
+/// on each other in a loop, so that none of them would ever show up.
+#[must_use]
+pub fn would_cycle(dependencies: &[Dependency], task_id: &str, blocker_id: &str) -> bool {
+    let mut seen = HashSet::new();
+    let mut waiting = vec![blocker_id];
+    while let Some(id) = waiting.pop() {
+        if id == task_id {
+            return true;
+        }
+        if !seen.insert(id) {
+            continue;
+        }
+        waiting.extend(
+            dependencies
+                .iter()
+                .filter(|dependency| dependency.task_id == id)
+                .map(|dependency| dependency.blocker_id.as_str()),
+        );
+    }
+    false
+}
+
+/// The IDs of the tasks that are still waiting for one of `items` that's open.
+#[must_use]
+pub fn blocked(dependencies: &[Dependency], items: &[Item]) -> HashSet<String> {
+    let open: HashSet<&str> = items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .map(|item| item.id.as_str())
+        .collect();
+    dependencies
+        .iter()
+        .filter(|dependency| open.contains(dependency.blocker_id.as_str()))
+        .map(|dependency| dependency.task_id.clone())
+        .collect()
+}
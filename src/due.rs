@@ -0,0 +1,285 @@
+//! Checking due dates before they're sent. Todoist turns any due string it
+//! can't make sense of into a plain, one-off date (or drops the parts it
+//! doesn't understand), so a typo like "evry day" quietly loses the
+//! recurrence. [`validate`] reads a due string the way Todoist would, word by
+//! word, and catches the mistakes that can be spotted locally: misspelled date
+//! words, times and dates that don't exist, and recurrences that don't say how
+//! often.
+//!
+//! Words that aren't date words, and don't look like misspellings of one, are
+//! left for Todoist to make sense of, since it understands more than is listed
+//! here.
+
+use chrono::NaiveDate;
+
+use crate::{Error, Result};
+
+pub(crate) const WEEKDAYS: &[&str] = &[
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Abbreviations that double as ordinary words ("sat", "sun", "wed"), so they
+/// only count as dates within a phrase.
+pub(crate) const SHORT_WEEKDAYS: &[&str] = &[
+    "mon", "tue", "tues", "wed", "thu", "thurs", "fri", "sat", "sun", "weekday", "weekend",
+];
+
+pub(crate) const MONTHS: &[&str] = &[
+    "jan",
+    "january",
+    "feb",
+    "february",
+    "mar",
+    "march",
+    "apr",
+    "april",
+    "may",
+    "jun",
+    "june",
+    "jul",
+    "july",
+    "aug",
+    "august",
+    "sep",
+    "sept",
+    "september",
+    "oct",
+    "october",
+    "nov",
+    "november",
+    "dec",
+    "december",
+];
+
+/// Words that begin a date phrase on their own.
+pub(crate) const DATE_STARTS: &[&str] = &[
+    "today", "tod", "tonight", "tomorrow", "tmr", "tmrw", "every",
+];
+
+/// Words that begin a date phrase only if the next word is part of one too,
+/// since they're common in ordinary task content.
+pub(crate) const DATE_PREFIXES: &[&str] = &["next", "this", "in", "on", "at", "by"];
+
+/// Words that can continue a date phrase.
+pub(crate) const DATE_WORDS: &[&str] = &[
+    "day",
+    "days",
+    "week",
+    "weeks",
+    "month",
+    "months",
+    "year",
+    "years",
+    "hour",
+    "hours",
+    "minute",
+    "minutes",
+    "min",
+    "mins",
+    "other",
+    "morning",
+    "afternoon",
+    "evening",
+    "night",
+    "noon",
+    "midnight",
+    "am",
+    "pm",
+    "the",
+    "and",
+    "starting",
+    "until",
+    "for",
+    "workday",
+];
+
+/// Date words that quick-add doesn't look for, but that Todoist understands in
+/// a due string.
+const OTHER_WORDS: &[&str] = &[
+    "ev", "everyday", "daily", "weekly", "monthly", "yearly", "annually", "hourly", "workdays",
+    "weekdays", "weekends", "after", "ending", "from", "of", "to", "last", "first", "second",
+    "third", "fourth", "fifth", "quarter", "end", "eod", "eow", "eom", "later", "no", "date",
+];
+
+/// Checks a due string for mistakes that Todoist would silently work around,
+/// as described in the module documentation.
+///
+/// # Errors
+///
+/// Returns [`Error::Due`] if the string is empty, has a misspelled date word,
+/// a time or date that doesn't exist, or a recurrence with nothing after it.
+pub fn validate(text: &str) -> Result<()> {
+    validate_in(text, "en")
+}
+
+/// Checks a due string in the language `lang`, one of
+/// [`LANGUAGES`](crate::locale::LANGUAGES). Only English strings are checked
+/// for misspellings; in other languages, it's just times and dates that have
+/// to exist.
+///
+/// # Errors
+///
+/// The same as [`validate`].
+pub fn validate_in(text: &str, lang: &str) -> Result<()> {
+    let words: Vec<String> = text
+        .split(|char: char| char.is_whitespace() || char == ',')
+        .map(|word| word.trim_matches(['.', '!', '?', '(', ')']).to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let Some(last) = words.last() else {
+        return Err(Error::Due("it's empty".to_string()));
+    };
+    if lang != "en" {
+        return match words
+            .iter()
+            .find_map(|word| invalid_time(word).or_else(|| invalid_date(word)))
+        {
+            Some(reason) => Err(Error::Due(reason)),
+            None => Ok(()),
+        };
+    }
+    if ["every", "ev"].contains(&last.as_str()) {
+        return Err(Error::Due(format!(
+            "'{last}' needs to say how often, like 'every day' or 'every monday'"
+        )));
+    }
+
+    for word in &words {
+        if let Some(reason) = invalid_time(word).or_else(|| invalid_date(word)) {
+            return Err(Error::Due(reason));
+        }
+        if is_known(word) {
+            continue;
+        }
+        if let Some(suggestion) = suggestion(word) {
+            return Err(Error::Due(format!(
+                "did you mean '{suggestion}' instead of '{word}', in '{}'?",
+                text.trim()
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn is_known(word: &str) -> bool {
+    let known = |word: &str| {
+        [
+            WEEKDAYS,
+            SHORT_WEEKDAYS,
+            MONTHS,
+            DATE_STARTS,
+            DATE_PREFIXES,
+            DATE_WORDS,
+            OTHER_WORDS,
+        ]
+        .iter()
+        .any(|words| words.contains(&word))
+    };
+    known(word)
+        // plurals, as in "every 2 mondays"
+        || word.strip_suffix('s').is_some_and(known)
+        || is_number(word)
+        || is_ordinal(word)
+        || word.contains(|char: char| char.is_ascii_digit())
+}
+
+/// The date word that `word` is most likely a misspelling of, if it's close
+/// enough to one to be a typo. Short words aren't checked, since too many
+/// ordinary ones are a letter away from a date word.
+fn suggestion(word: &str) -> Option<&'static str> {
+    let length = word.chars().count();
+    if length < 4 || !word.chars().all(char::is_alphabetic) {
+        return None;
+    }
+    let allowed = if length < 6 { 1 } else { 2 };
+    [WEEKDAYS, MONTHS, DATE_STARTS, DATE_WORDS, OTHER_WORDS]
+        .iter()
+        .flat_map(|words| words.iter())
+        .filter(|candidate| candidate.len() >= 4)
+        .map(|candidate| (edit_distance(word, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= allowed)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The number of single-character insertions, deletions, substitutions, and
+/// swaps of adjacent characters it takes to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = (a.chars().collect(), b.chars().collect());
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Why a word that looks like a time, like "5pm" or "17:30", isn't one.
+fn invalid_time(word: &str) -> Option<String> {
+    if !is_time(word) {
+        return None;
+    }
+    let clock = word.strip_suffix("am").or_else(|| word.strip_suffix("pm"));
+    let has_meridiem = clock.is_some();
+    let clock = clock.unwrap_or(word);
+    let (hours, minutes) = clock.split_once(':').unwrap_or((clock, "0"));
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+
+    let hours_valid = if has_meridiem {
+        (1..=12).contains(&hours)
+    } else {
+        hours <= 23
+    };
+    (!hours_valid || minutes > 59).then(|| format!("'{word}' isn't a time"))
+}
+
+/// Why a word that looks like a date, like "2023-07-01", isn't one.
+fn invalid_date(word: &str) -> Option<String> {
+    (is_iso_date(word) && NaiveDate::parse_from_str(word, "%Y-%m-%d").is_err())
+        .then(|| format!("'{word}' isn't a date"))
+}
+
+pub(crate) fn is_number(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(|char| char.is_ascii_digit())
+}
+
+pub(crate) fn is_ordinal(word: &str) -> bool {
+    ["st", "nd", "rd", "th"]
+        .iter()
+        .any(|suffix| word.strip_suffix(suffix).is_some_and(is_number))
+}
+
+/// Times like "5pm", "5:30pm", "17:00", or "9am".
+pub(crate) fn is_time(word: &str) -> bool {
+    let clock = word.strip_suffix("am").or_else(|| word.strip_suffix("pm"));
+    let has_meridiem = clock.is_some();
+    let clock = clock.unwrap_or(word);
+
+    match clock.split_once(':') {
+        Some((hours, minutes)) => is_number(hours) && minutes.len() == 2 && is_number(minutes),
+        None => has_meridiem && is_number(clock),
+    }
+}
+
+/// Dates like "2023-07-01".
+pub(crate) fn is_iso_date(word: &str) -> bool {
+    let parts: Vec<&str> = word.split('-').collect();
+    matches!(parts[..], [year, month, day]
+        if year.len() == 4 && month.len() == 2 && day.len() == 2
+            && parts.iter().all(|part| is_number(part)))
+}
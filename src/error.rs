@@ -0,0 +1,109 @@
+use std::num::TryFromIntError;
+
+use crate::{conflict::Conflict, sync::Command};
+
+/// Everything that can go wrong in the library portion of the crate.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The API rejected the token, either because it's wrong or because it
+    /// has been revoked.
+    #[error(
+        "Todoist did not accept that API token. It may have been revoked or \
+         regenerated; copy the current one from Settings > Integrations > \
+         Developer in Todoist, and run `todoist auth <token>` with it."
+    )]
+    Auth,
+
+    /// The request failed on its way to or from the server, or the server
+    /// responded with an error status.
+    #[error("Request to Todoist failed: {0}")]
+    Network(#[from] reqwest::Error),
+
+    /// The server processed the request but refused one of its commands.
+    #[error("Todoist rejected the command to {description}: {message}")]
+    CommandRejected {
+        description: String,
+        code: i64,
+        message: String,
+    },
+
+    /// The server's response was missing something it should have had.
+    #[error("Unexpected response from Todoist: {0}")]
+    UnexpectedResponse(String),
+
+    /// A filter query couldn't be parsed.
+    #[error("Invalid filter: {0}")]
+    Filter(String),
+
+    /// The config file, or a value for one of its settings, isn't valid.
+    #[error("Invalid config: {0}")]
+    Config(String),
+
+    /// A due date has a mistake that Todoist would silently work around.
+    #[error("Invalid due date: {0}")]
+    Due(String),
+
+    /// A reminder's time couldn't be parsed.
+    #[error("Invalid reminder: {0}")]
+    Reminder(String),
+
+    /// A position to move something to couldn't be parsed, or doesn't make
+    /// sense for what's being moved.
+    #[error("Invalid position: {0}")]
+    Position(String),
+
+    /// A file of tasks to import couldn't be parsed.
+    #[error("Invalid import file: {0}")]
+    Import(String),
+
+    /// A batch of actions couldn't be parsed.
+    #[error("Invalid batch: {0}")]
+    Batch(String),
+
+    /// Queued commands conflict with changes made on the server, and the
+    /// client is set to ask what to do about them. Nothing was sent; new
+    /// commands were queued behind the conflicting ones.
+    #[error(
+        "{} queued changes conflict with changes made elsewhere, so they're \
+         still queued, along with any new ones. Run this again in a terminal \
+         to choose which to keep, or with --on-conflict keep-local or \
+         keep-remote.",
+        .0.len()
+    )]
+    Conflicts(Vec<Conflict>),
+
+    /// Commands were about to be sent, but the client is in dry-run mode, so
+    /// they're handed back instead.
+    #[error("Dry run: {} commands were not sent", .0.len())]
+    DryRun(Vec<Command>),
+
+    /// A hook set in the config file failed.
+    #[error("Hook failed: {0}")]
+    Hook(String),
+
+    #[error("Could not use the system keyring: {0}")]
+    Keyring(#[from] keyring::Error),
+
+    #[error("Could not read or write the local database: {0}")]
+    Storage(#[from] rusqlite::Error),
+
+    #[error("Could not (de)serialize data: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    #[error("Value out of range: {0}")]
+    OutOfRange(#[from] TryFromIntError),
+}
+
+impl Error {
+    /// Whether the server couldn't be reached at all, as opposed to it
+    /// rejecting the request. Requests that fail this way can be retried later.
+    #[must_use]
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self, Error::Network(err) if err.is_connect() || err.is_timeout())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
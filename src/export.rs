@@ -0,0 +1,310 @@
+//! Writing tasks out in portable formats: Markdown checklists and Org outlines
+//! that keep projects, sections, and subtasks nested; CSV for spreadsheets;
+//! and JSON.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+
+use serde::Serialize;
+
+use crate::{
+    order,
+    sync::{Item, Project, Section},
+    Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A heading for each project and section, with tasks as checkboxes.
+    #[value(name = "md")]
+    Markdown,
+
+    /// One row per task.
+    Csv,
+
+    /// The projects, sections, and tasks, as Todoist describes them.
+    Json,
+
+    /// A headline for each project and section, with tasks as TODO headlines
+    /// under them.
+    Org,
+}
+
+/// What to export. Tasks in projects that aren't in `projects` are left out.
+pub struct Export<'a> {
+    pub projects: &'a [Project],
+    pub sections: &'a [Section],
+    pub items: &'a [Item],
+}
+
+/// A section's tasks, or the ones in no section.
+type SectionItems<'a> = (Option<&'a Section>, Vec<&'a Item>);
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    projects: &'a [Project],
+    sections: Vec<&'a Section>,
+    items: Vec<&'a Item>,
+}
+
+impl Export<'_> {
+    /// Renders the export in `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the export can't be serialized as JSON.
+    pub fn render(&self, format: Format) -> Result<String> {
+        match format {
+            Format::Markdown => Ok(self.markdown()),
+            Format::Csv => Ok(self.csv()),
+            Format::Org => Ok(self.org()),
+            Format::Json => Ok(serde_json::to_string_pretty(&JsonExport {
+                projects: self.projects,
+                sections: self
+                    .sections
+                    .iter()
+                    .filter(|section| self.project(&section.project_id).is_some())
+                    .collect(),
+                items: self.items().collect(),
+            })?),
+        }
+    }
+
+    /// How many tasks the export has.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items().count()
+    }
+
+    /// Whether the export has no tasks.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn items(&self) -> impl Iterator<Item = &Item> {
+        self.items
+            .iter()
+            .filter(|item| !item.is_deleted && self.project(&item.project_id).is_some())
+    }
+
+    fn project(&self, id: &str) -> Option<&Project> {
+        self.projects.iter().find(|project| project.id == id)
+    }
+
+    /// The projects that have tasks, in order, each with its tasks: first the
+    /// ones outside any section, then each section that has some, in order.
+    fn groups(&self) -> Vec<(&Project, Vec<SectionItems<'_>>)> {
+        let mut projects: Vec<&Project> = self.projects.iter().collect();
+        projects.sort_by_key(|project| project.child_order);
+
+        let mut groups = Vec::new();
+        for project in projects {
+            let items: Vec<&Item> = self
+                .items()
+                .filter(|item| item.project_id == project.id)
+                .collect();
+            if items.is_empty() {
+                continue;
+            }
+            let in_section = |section_id: Option<&str>| -> Vec<&Item> {
+                items
+                    .iter()
+                    .copied()
+                    .filter(|item| item.section_id.as_deref() == section_id)
+                    .collect()
+            };
+            let mut sections = vec![(None, in_section(None))];
+
+            let mut project_sections: Vec<&Section> = self
+                .sections
+                .iter()
+                .filter(|section| section.project_id == project.id)
+                .collect();
+            project_sections.sort_by_key(|section| section.section_order);
+            for section in project_sections {
+                let items = in_section(Some(&section.id));
+                if !items.is_empty() {
+                    sections.push((Some(section), items));
+                }
+            }
+            groups.push((project, sections));
+        }
+        groups
+    }
+
+    fn markdown(&self) -> String {
+        let mut text = String::new();
+        for (project, sections) in self.groups() {
+            let _ = writeln!(text, "# {}\n", project.name);
+            for (section, items) in sections {
+                if let Some(section) = section {
+                    let _ = writeln!(text, "## {}\n", section.name);
+                }
+                write_checklist(&mut text, &items);
+            }
+        }
+        text.trim_end().to_string() + "\n"
+    }
+
+    fn org(&self) -> String {
+        let mut text = String::new();
+        for (project, sections) in self.groups() {
+            let _ = writeln!(text, "* {}", project.name);
+            for (section, items) in sections {
+                let level = match section {
+                    Some(section) => {
+                        let _ = writeln!(text, "** {}", section.name);
+                        3
+                    }
+                    None => 2,
+                };
+                write_outline(&mut text, &items, level);
+            }
+        }
+        text
+    }
+
+    fn csv(&self) -> String {
+        let mut text =
+            "id,content,description,project,section,parent_id,due,priority,labels,completed\n"
+                .to_string();
+        for item in self.items() {
+            let project = self.project(&item.project_id).map(|project| &project.name);
+            let section = item.section_id.as_ref().and_then(|id| {
+                self.sections
+                    .iter()
+                    .find(|section| &section.id == id)
+                    .map(|section| &section.name)
+            });
+            let due = item
+                .due
+                .as_ref()
+                .map(|due| due.string.as_ref().unwrap_or(&due.date));
+            let fields = [
+                item.id.as_str(),
+                &item.content,
+                &item.description,
+                project.map_or("", String::as_str),
+                section.map_or("", String::as_str),
+                item.parent_id.as_deref().unwrap_or_default(),
+                due.map_or("", String::as_str),
+                &format!("p{}", 5 - item.priority.clamp(1, 4)),
+                &item.labels.join(" "),
+                if item.checked { "true" } else { "false" },
+            ];
+            let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+            let _ = writeln!(text, "{}", row.join(","));
+        }
+        text
+    }
+}
+
+/// Writes items as a Markdown checklist, with subtasks nested under their
+/// parents.
+fn write_checklist(text: &mut String, items: &[&Item]) {
+    if items.is_empty() {
+        return;
+    }
+    let items = order::nest_subtasks(items);
+    for (item, depth) in items.iter().zip(order::depths(&items)) {
+        let check = if item.checked { 'x' } else { ' ' };
+        let _ = write!(text, "{}- [{check}] {}", "  ".repeat(depth), item.content);
+        if let Some(due) = &item.due {
+            let _ = write!(text, " (due {})", due.string.as_ref().unwrap_or(&due.date));
+        }
+        for label in &item.labels {
+            let _ = write!(text, " @{label}");
+        }
+        text.push('\n');
+    }
+    text.push('\n');
+}
+
+/// Writes items as Org headlines at `level`, with subtasks a level deeper than
+/// their parents.
+fn write_outline(text: &mut String, items: &[&Item], level: usize) {
+    if items.is_empty() {
+        return;
+    }
+    let items = order::nest_subtasks(items);
+    for (item, depth) in items.iter().zip(order::depths(&items)) {
+        let keyword = if item.checked { "DONE" } else { "TODO" };
+        let _ = write!(text, "{} {keyword} ", "*".repeat(level + depth));
+        match item.priority {
+            4 => text.push_str("[#A] "),
+            3 => text.push_str("[#B] "),
+            2 => text.push_str("[#C] "),
+            _ => {}
+        }
+        text.push_str(&item.content);
+        if !item.labels.is_empty() {
+            let tags: Vec<String> = item.labels.iter().map(|label| org_tag(label)).collect();
+            let _ = write!(text, " :{}:", tags.join(":"));
+        }
+        text.push('\n');
+
+        let mut planning = Vec::new();
+        if let Some(timestamp) = item.due.as_ref().and_then(|due| org_timestamp(&due.date)) {
+            planning.push(format!("SCHEDULED: {timestamp}"));
+        }
+        if let Some(deadline) = &item.deadline {
+            planning.extend(org_timestamp(&deadline.date).map(|date| format!("DEADLINE: {date}")));
+        }
+        if !planning.is_empty() {
+            let _ = writeln!(text, "{}", planning.join(" "));
+        }
+        if let Some(due) = item.due.as_ref().filter(|due| due.is_recurring) {
+            if let Some(string) = &due.string {
+                let _ = writeln!(text, ":PROPERTIES:\n:DUE: {string}\n:END:");
+            }
+        }
+        for line in item.description.lines() {
+            // lines that would be read as headlines are escaped with a comma
+            let escape = if line.starts_with(['*', ',']) {
+                ","
+            } else {
+                ""
+            };
+            let _ = writeln!(text, "{escape}{line}");
+        }
+    }
+}
+
+/// An active Org timestamp, like `<2024-05-01 Wed>` or `<2024-05-01 Wed
+/// 09:00>`, for a due date or deadline. Times in UTC are shown in local time.
+fn org_timestamp(date: &str) -> Option<String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(date) {
+        let time = time.with_timezone(&Local);
+        return Some(time.format("<%Y-%m-%d %a %H:%M>").to_string());
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S") {
+        return Some(time.format("<%Y-%m-%d %a %H:%M>").to_string());
+    }
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    Some(date.format("<%Y-%m-%d %a>").to_string())
+}
+
+/// A label as an Org tag, which can only have letters, numbers, and `_@#%`.
+fn org_tag(label: &str) -> String {
+    label
+        .chars()
+        .map(|char| {
+            if char.is_alphanumeric() || "_@#%".contains(char) {
+                char
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Quotes a CSV field if it needs it, as RFC 4180 describes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,64 @@
+//! Writing files so that nothing ever sees them half-written, even if two
+//! commands (or a command and the daemon) write the same file at once, or the
+//! process is killed partway through. Each file is written to a temporary file
+//! beside it, then renamed over it, which replaces it in one step.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use crate::Result;
+
+/// Replaces the contents of the file at `path`, creating it if needed.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be written.
+pub fn write(path: &Path, contents: &[u8]) -> Result<()> {
+    write_with_mode(path, contents, None)
+}
+
+/// Like [`write`], but on Unix only the owner can read the file, for files
+/// that hold credentials.
+///
+/// # Errors
+///
+/// The same as [`write`].
+pub fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    write_with_mode(path, contents, Some(0o600))
+}
+
+fn write_with_mode(path: &Path, contents: &[u8], mode: Option<u32>) -> Result<()> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a path to a file"))?;
+    // a name of its own for each process, so that two writers don't write into
+    // the same temporary file
+    let temp_path = path.with_file_name(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+
+    let result = options.open(&temp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    Ok(result?)
+}
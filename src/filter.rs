@@ -0,0 +1,421 @@
+//! A parser and evaluator for Todoist's filter syntax, so that filters like
+//! `(today | overdue) & #Work` can be run against the local store.
+//!
+//! Supported terms:
+//!
+//! - dates: `today`, `tomorrow`, `yesterday`, `overdue` (or `od`), `no date`,
+//!   `recurring`, `N days` (or `next N days`), a date like `2023-07-01`, and
+//!   `due before: <date>` / `due after: <date>`
+//! - `p1` through `p4`
+//! - `#Project` (or `##Project` to include its subprojects), `/Section`,
+//!   `@label`, and `no labels`, where names may use `*` as a wildcard
+//! - `search: <text>`, and `all`
+//!
+//! Terms combine with `&`, `|`, `!`, and parentheses. Commas separate queries
+//! whose results are listed separately.
+
+use chrono::{Days, NaiveDate};
+
+use crate::{
+    agenda::due_date,
+    sync::{Item, Project, Section},
+    Error, Result,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Filter {
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+    Term(Term),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Term {
+    All,
+    DueOn(DateSpec),
+    DueBefore(DateSpec),
+    DueAfter(DateSpec),
+    Overdue,
+    NoDate,
+    Recurring,
+
+    /// Due within this many days, starting with today.
+    NextDays(u64),
+
+    /// A priority as written, so `1` is the most urgent.
+    Priority(u8),
+
+    Project {
+        name: String,
+        with_subprojects: bool,
+    },
+    Section(String),
+    Label(String),
+    NoLabels,
+    Search(String),
+}
+
+/// A date that may be relative to the day the filter is evaluated.
+#[derive(Debug, PartialEq, Eq)]
+pub enum DateSpec {
+    /// This many days after today, which may be negative.
+    Relative(i64),
+    Absolute(NaiveDate),
+}
+
+impl DateSpec {
+    fn resolve(&self, today: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            DateSpec::Relative(offset) => {
+                let days = Days::new(offset.unsigned_abs());
+                if *offset < 0 {
+                    today.checked_sub_days(days)
+                } else {
+                    today.checked_add_days(days)
+                }
+            }
+            DateSpec::Absolute(date) => Some(*date),
+        }
+    }
+}
+
+/// What a filter needs to know beyond the items themselves.
+pub struct Context<'a> {
+    pub today: NaiveDate,
+    pub projects: &'a [Project],
+    pub sections: &'a [Section],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+    Comma,
+    Text(String),
+}
+
+/// Parses a filter, which may hold several comma-separated queries. Returns
+/// each query's text along with its parsed form.
+///
+/// # Errors
+///
+/// Returns [`Error::Filter`] if the filter isn't valid.
+pub fn parse(text: &str) -> Result<Vec<(String, Filter)>> {
+    let mut queries = Vec::new();
+    for query in split_queries(text) {
+        let tokens = tokenize(query);
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+        };
+        let filter = parser.parse_or()?;
+        if let Some(token) = parser.tokens.get(parser.position) {
+            return Err(Error::Filter(format!(
+                "unexpected {} in '{}'",
+                token.describe(),
+                query.trim()
+            )));
+        }
+        queries.push((query.trim().to_string(), filter));
+    }
+    Ok(queries)
+}
+
+fn split_queries(text: &str) -> Vec<&str> {
+    let mut queries = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, char) in text.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                queries.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    queries.push(&text[start..]);
+    queries
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+    let flush = |word: &mut String, tokens: &mut Vec<Token>| {
+        let trimmed = word.trim();
+        if !trimmed.is_empty() {
+            tokens.push(Token::Text(trimmed.to_string()));
+        }
+        word.clear();
+    };
+
+    for char in text.chars() {
+        let token = match char {
+            '&' => Token::And,
+            '|' => Token::Or,
+            '!' => Token::Not,
+            '(' => Token::Open,
+            ')' => Token::Close,
+            ',' => Token::Comma,
+            _ => {
+                word.push(char);
+                continue;
+            }
+        };
+        flush(&mut word, &mut tokens);
+        tokens.push(token);
+    }
+    flush(&mut word, &mut tokens);
+    tokens
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::And => "'&'".to_string(),
+            Token::Or => "'|'".to_string(),
+            Token::Not => "'!'".to_string(),
+            Token::Open => "'('".to_string(),
+            Token::Close => "')'".to_string(),
+            Token::Comma => "','".to_string(),
+            Token::Text(text) => format!("'{text}'"),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn next(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.position);
+        self.position += 1;
+        token
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            filter = Filter::Or(Box::new(filter), Box::new(self.parse_and()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_and(&mut self) -> Result<Filter> {
+        let mut filter = self.parse_not()?;
+        while self.peek() == Some(&Token::And) {
+            self.position += 1;
+            filter = Filter::And(Box::new(filter), Box::new(self.parse_not()?));
+        }
+        Ok(filter)
+    }
+
+    fn parse_not(&mut self) -> Result<Filter> {
+        if self.peek() == Some(&Token::Not) {
+            self.position += 1;
+            return Ok(Filter::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Filter> {
+        match self.next() {
+            Some(Token::Open) => {
+                let filter = self.parse_or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(filter),
+                    _ => Err(Error::Filter("missing ')'".to_string())),
+                }
+            }
+            Some(Token::Text(text)) => parse_term(&text.clone()).map(Filter::Term),
+            Some(token) => Err(Error::Filter(format!("unexpected {}", token.describe()))),
+            None => Err(Error::Filter("expected a term".to_string())),
+        }
+    }
+}
+
+fn parse_term(text: &str) -> Result<Term> {
+    let lower = text.to_lowercase();
+
+    if let Some(name) = text.strip_prefix("##") {
+        return Ok(Term::Project {
+            name: name.trim().to_string(),
+            with_subprojects: true,
+        });
+    }
+    if let Some(name) = text.strip_prefix('#') {
+        return Ok(Term::Project {
+            name: name.trim().to_string(),
+            with_subprojects: false,
+        });
+    }
+    if let Some(name) = text.strip_prefix('@') {
+        return Ok(Term::Label(name.trim().to_string()));
+    }
+    if let Some(name) = text.strip_prefix('/') {
+        return Ok(Term::Section(name.trim().to_string()));
+    }
+    if let Some(query) = lower.strip_prefix("search:") {
+        return Ok(Term::Search(query.trim().to_string()));
+    }
+    if let Some(date) = lower.strip_prefix("due before:") {
+        return parse_date(date.trim()).map(Term::DueBefore);
+    }
+    if let Some(date) = lower.strip_prefix("due after:") {
+        return parse_date(date.trim()).map(Term::DueAfter);
+    }
+    if let Some(priority) = crate::quick_add::parse_priority(&lower) {
+        return Ok(Term::Priority(priority));
+    }
+
+    let days = lower
+        .strip_prefix("next ")
+        .unwrap_or(&lower)
+        .strip_suffix(" days")
+        .and_then(|count| count.trim().parse().ok());
+    if let Some(days) = days {
+        return Ok(Term::NextDays(days));
+    }
+
+    match lower.as_str() {
+        "all" => Ok(Term::All),
+        "overdue" | "od" => Ok(Term::Overdue),
+        "no date" | "no due date" => Ok(Term::NoDate),
+        "recurring" => Ok(Term::Recurring),
+        "no labels" => Ok(Term::NoLabels),
+        _ => parse_date(&lower)
+            .map(Term::DueOn)
+            .map_err(|_| Error::Filter(format!("unknown term '{text}'"))),
+    }
+}
+
+fn parse_date(text: &str) -> Result<DateSpec> {
+    match text {
+        "today" => Ok(DateSpec::Relative(0)),
+        "tomorrow" => Ok(DateSpec::Relative(1)),
+        "yesterday" => Ok(DateSpec::Relative(-1)),
+        _ => NaiveDate::parse_from_str(text, "%Y-%m-%d")
+            .map(DateSpec::Absolute)
+            .map_err(|_| Error::Filter(format!("unknown date '{text}'"))),
+    }
+}
+
+impl Filter {
+    /// Whether `item` passes the filter.
+    #[must_use]
+    pub fn matches(&self, item: &Item, context: &Context) -> bool {
+        match self {
+            Filter::And(left, right) => left.matches(item, context) && right.matches(item, context),
+            Filter::Or(left, right) => left.matches(item, context) || right.matches(item, context),
+            Filter::Not(filter) => !filter.matches(item, context),
+            Filter::Term(term) => term.matches(item, context),
+        }
+    }
+}
+
+impl Term {
+    fn matches(&self, item: &Item, context: &Context) -> bool {
+        let due = due_date(item);
+        let today = context.today;
+
+        match self {
+            Term::All => true,
+            Term::DueOn(date) => due.is_some() && due == date.resolve(today),
+            Term::DueBefore(date) => due
+                .zip(date.resolve(today))
+                .is_some_and(|(due, date)| due < date),
+            Term::DueAfter(date) => due
+                .zip(date.resolve(today))
+                .is_some_and(|(due, date)| due > date),
+            Term::Overdue => due.is_some_and(|due| due < today),
+            Term::NoDate => item.due.is_none(),
+            Term::Recurring => item.due.as_ref().is_some_and(|due| due.is_recurring),
+            Term::NextDays(days) => due.is_some_and(|due| {
+                due >= today
+                    && today
+                        .checked_add_days(Days::new(*days))
+                        .is_some_and(|end| due < end)
+            }),
+            Term::Priority(priority) => 5 - item.priority.clamp(1, 4) == *priority,
+            Term::Project {
+                name,
+                with_subprojects,
+            } => context
+                .projects
+                .iter()
+                .filter(|project| name_matches(name, &project.name))
+                .any(|project| {
+                    item.project_id == project.id
+                        || (*with_subprojects
+                            && is_descendant(&item.project_id, &project.id, context.projects))
+                }),
+            Term::Section(name) => context.sections.iter().any(|section| {
+                item.section_id.as_ref() == Some(&section.id) && name_matches(name, &section.name)
+            }),
+            Term::Label(name) => item.labels.iter().any(|label| name_matches(name, label)),
+            Term::NoLabels => item.labels.is_empty(),
+            Term::Search(query) => item.content.to_lowercase().contains(query),
+        }
+    }
+}
+
+/// Whether `project_id` is nested somewhere below `ancestor_id`.
+fn is_descendant(project_id: &str, ancestor_id: &str, projects: &[Project]) -> bool {
+    let mut current = project_id;
+    // bounded, in case the hierarchy somehow has a cycle
+    for _ in 0..projects.len() {
+        let parent = projects
+            .iter()
+            .find(|project| project.id == current)
+            .and_then(|project| project.parent_id.as_deref());
+        match parent {
+            Some(parent) if parent == ancestor_id => return true,
+            Some(parent) => current = parent,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Compares names case-insensitively, where `*` in the pattern matches any
+/// run of characters.
+fn name_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.to_lowercase();
+    let name = name.to_lowercase();
+    let mut parts = pattern.split('*');
+
+    let Some(first) = parts.next() else {
+        return false;
+    };
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    if !pattern.contains('*') {
+        return rest.is_empty();
+    }
+
+    let parts: Vec<&str> = parts.collect();
+    for (index, part) in parts.iter().enumerate() {
+        if index == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(position) => rest = &rest[position + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
@@ -0,0 +1,23 @@
+//! Focus sessions: working on one task for a set time, with a countdown, as
+//! in the Pomodoro technique. Each session is kept in the store as a
+//! [work session](crate::track::WorkSession), so that the time spent on each
+//! task adds up.
+
+/// How long a session lasts unless told otherwise, in minutes.
+pub const DEFAULT_MINUTES: u64 = 25;
+
+/// A length of time, like `1h 05m` or `25m`, to the minute.
+#[must_use]
+pub fn format_duration(seconds: u64) -> String {
+    let minutes = seconds / 60;
+    match minutes / 60 {
+        0 => format!("{minutes}m"),
+        hours => format!("{hours}h {:02}m", minutes % 60),
+    }
+}
+
+/// The comment that notes a session on its task.
+#[must_use]
+pub fn note(seconds: u64) -> String {
+    format!("Focused for {}.", format_duration(seconds))
+}
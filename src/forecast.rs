@@ -0,0 +1,161 @@
+//! How many tasks land on each of the coming days, with recurring tasks
+//! counted on every day they'll come back, for spotting the days that are
+//! already full before adding more to them. Recurrences are read the way
+//! [`ical::rrule`] reads them; ones it doesn't understand only count on the
+//! next day they're due. Tasks that repeat after they're completed, like
+//! "every! 3 days", are counted as if each one is completed on the day it's
+//! due.
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+
+use crate::{agenda, ical, sync::Item};
+
+/// The busiest a day can look, as a shade in a heat strip, from none to most.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+
+/// How many tasks are due on a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day {
+    pub date: NaiveDate,
+    pub count: usize,
+}
+
+/// How a recurring task repeats.
+#[derive(Debug, PartialEq, Eq)]
+enum Rule {
+    Days(u64),
+    Weeks(u64, Vec<Weekday>),
+    Months(u32),
+}
+
+/// Counts the open tasks due on each of `days` days starting with `today`.
+/// Overdue tasks count on today, since they still have to be done.
+#[must_use]
+pub fn forecast(items: &[Item], today: NaiveDate, days: u64) -> Vec<Day> {
+    let mut forecast: Vec<Day> = (0..days)
+        .filter_map(|offset| today.checked_add_days(Days::new(offset)))
+        .map(|date| Day { date, count: 0 })
+        .collect();
+    let Some(end) = forecast.last().map(|day| day.date) else {
+        return forecast;
+    };
+
+    for item in items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+    {
+        let Some(start) = agenda::due_date(item) else {
+            continue;
+        };
+        let rule = item
+            .due
+            .as_ref()
+            .filter(|due| due.is_recurring)
+            .and_then(|due| due.string.as_deref())
+            .and_then(|string| rule(&string.replacen("every!", "every", 1)));
+        let dates = match rule {
+            Some(rule) => occurrences(&rule, start, end),
+            None => vec![start],
+        };
+        for (index, date) in dates.into_iter().enumerate() {
+            // only the occurrence that's due now is overdue; the ones before it
+            // are done already
+            let date = match date {
+                date if date >= today => date,
+                _ if index == 0 => today,
+                _ => continue,
+            };
+            if let Some(day) = forecast.iter_mut().find(|day| day.date == date) {
+                day.count += 1;
+            }
+        }
+    }
+    forecast
+}
+
+/// The shade for a day with `count` tasks, when the busiest day has `max`.
+#[must_use]
+pub fn shade(count: usize, max: usize) -> char {
+    if count == 0 || max == 0 {
+        return SHADES[0];
+    }
+    let levels = SHADES.len() - 1;
+    SHADES[(count * levels).div_ceil(max).clamp(1, levels)]
+}
+
+/// How a recurring due date repeats, from the `RRULE` it would have in a
+/// calendar.
+fn rule(due_string: &str) -> Option<Rule> {
+    let rrule = ical::rrule(due_string)?;
+    let mut freq = None;
+    let mut interval = 1;
+    let mut weekdays = Vec::new();
+    for part in rrule.split(';') {
+        match part.split_once('=')? {
+            ("FREQ", value) => freq = Some(value),
+            ("INTERVAL", value) => interval = value.parse().ok()?,
+            ("BYDAY", value) => {
+                for day in value.split(',') {
+                    weekdays.push(weekday(day)?);
+                }
+            }
+            _ => return None,
+        }
+    }
+    match freq? {
+        "DAILY" => Some(Rule::Days(u64::from(interval))),
+        "WEEKLY" => Some(Rule::Weeks(u64::from(interval), weekdays)),
+        "MONTHLY" => Some(Rule::Months(interval)),
+        "YEARLY" => Some(Rule::Months(interval.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+fn weekday(day: &str) -> Option<Weekday> {
+    Some(match day {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// The dates a task repeats on, from `start` until `end`.
+fn occurrences(rule: &Rule, start: NaiveDate, end: NaiveDate) -> Vec<NaiveDate> {
+    let every = |days: u64| {
+        let mut dates = Vec::new();
+        let mut date = Some(start);
+        while let Some(current) = date.filter(|date| *date <= end) {
+            dates.push(current);
+            date = current.checked_add_days(Days::new(days.max(1)));
+        }
+        dates
+    };
+    match rule {
+        Rule::Days(interval) => every(*interval),
+        Rule::Weeks(interval, weekdays) if weekdays.is_empty() => every(7 * interval),
+        Rule::Weeks(interval, weekdays) => {
+            let first_week = start.week(Weekday::Mon).first_day();
+            every(1)
+                .into_iter()
+                .filter(|date| {
+                    let weeks = (date.week(Weekday::Mon).first_day() - first_week).num_weeks();
+                    weekdays.contains(&date.weekday())
+                        && u64::try_from(weeks).is_ok_and(|weeks| weeks % interval.max(&1) == 0)
+                })
+                .collect()
+        }
+        Rule::Months(interval) => (0..)
+            .map_while(|count: u32| {
+                let months = count.checked_mul(*interval)?;
+                start
+                    .checked_add_months(Months::new(months))
+                    .filter(|date| *date <= end)
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,154 @@
+//! Forgiving name matching, so that a project can be picked out with something
+//! like "home reno" instead of its full name.
+
+use std::ops::Range;
+
+/// Lowercases `text` and strips its whitespace, so that "Home Renovation" and
+/// "homerenovation" are treated the same.
+#[must_use]
+pub fn normalize(text: &str) -> String {
+    text.chars()
+        .filter(|char| !char.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// How well `query` matches `candidate`, or `None` if it doesn't match at all.
+/// Higher is better: an exact match beats a prefix, which beats a match at the
+/// start of a later word, then anywhere in the name, and finally any
+/// candidate that merely contains the query's characters in order.
+#[must_use]
+pub fn score(query: &str, candidate: &str) -> Option<u32> {
+    let query = normalize(query);
+    let normalized = normalize(candidate);
+    if query.is_empty() {
+        return None;
+    }
+
+    if normalized == query {
+        Some(1000)
+    } else if normalized.starts_with(&query) {
+        Some(800)
+    } else if candidate
+        .split_whitespace()
+        .any(|word| normalize(word).starts_with(&query))
+    {
+        Some(600)
+    } else if normalized.contains(&query) {
+        Some(400)
+    } else {
+        subsequence_gaps(&query, &normalized).map(|gaps| 200u32.saturating_sub(gaps).max(1))
+    }
+}
+
+/// The best match for `query` among `candidates`, comparing the names that
+/// `name` picks out. Ties go to the shorter name, being the more specific one.
+pub fn best_match<T>(
+    query: &str,
+    candidates: impl IntoIterator<Item = T>,
+    name: impl Fn(&T) -> &str,
+) -> Option<T> {
+    candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let score = score(query, name(&candidate))?;
+            let length = name(&candidate).chars().count();
+            Some((score, std::cmp::Reverse(length), candidate))
+        })
+        .max_by_key(|(score, length, _)| (*score, *length))
+        .map(|(_, _, candidate)| candidate)
+}
+
+/// The indices of the `candidates` that match `query`, best match first, for
+/// narrowing down a list as the query is typed. Equally good matches keep
+/// their order, and an empty query matches everything.
+#[must_use]
+pub fn rank(query: &str, candidates: &[impl AsRef<str>]) -> Vec<usize> {
+    if normalize(query).is_empty() {
+        return (0..candidates.len()).collect();
+    }
+    let mut matches: Vec<(u32, usize)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(index, candidate)| Some((score(query, candidate.as_ref())?, index)))
+        .collect();
+    matches.sort_by_key(|(score, index)| (std::cmp::Reverse(*score), *index));
+    matches.into_iter().map(|(_, index)| index).collect()
+}
+
+/// The byte ranges of `text` that `query` matches, for highlighting. Like
+/// [`score`], this ignores case and whitespace, and prefers a contiguous match
+/// to one that's spread out.
+#[must_use]
+pub fn match_ranges(query: &str, text: &str) -> Option<Vec<Range<usize>>> {
+    let query: Vec<char> = normalize(query).chars().collect();
+    // the normalized characters of `text`, along with where each one came from
+    let chars: Vec<(char, Range<usize>)> = text
+        .char_indices()
+        .filter(|(_, char)| !char.is_whitespace())
+        .flat_map(|(start, char)| {
+            let range = start..start + char.len_utf8();
+            char.to_lowercase().map(move |lower| (lower, range.clone()))
+        })
+        .collect();
+    if query.is_empty() {
+        return None;
+    }
+
+    let contiguous = (0..chars.len().saturating_sub(query.len() - 1)).find(|&start| {
+        chars[start..start + query.len()]
+            .iter()
+            .zip(&query)
+            .all(|((char, _), query_char)| char == query_char)
+    });
+    let matched: Vec<(usize, &Range<usize>)> = if let Some(start) = contiguous {
+        (start..start + query.len())
+            .map(|index| (index, &chars[index].1))
+            .collect()
+    } else {
+        let mut matched = Vec::with_capacity(query.len());
+        let mut remaining = chars.iter().enumerate();
+        for query_char in &query {
+            let (index, (_, range)) = remaining.find(|(_, (char, _))| char == query_char)?;
+            matched.push((index, range));
+        }
+        matched
+    };
+
+    // characters that are next to each other in the normalized text make one
+    // range, even if there was whitespace between them
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut previous_index = None;
+    for (index, range) in matched {
+        match ranges.last_mut() {
+            Some(last) if previous_index.is_some_and(|previous| previous + 1 >= index) => {
+                last.end = last.end.max(range.end);
+            }
+            _ => ranges.push(range.clone()),
+        }
+        previous_index = Some(index);
+    }
+    Some(ranges)
+}
+
+/// If all of `query`'s characters appear in `text` in order, returns how many
+/// characters of `text` had to be skipped between the first and last of them.
+fn subsequence_gaps(query: &str, text: &str) -> Option<u32> {
+    let mut text = text.chars();
+    let mut gaps = 0;
+    let mut started = false;
+
+    for query_char in query.chars() {
+        loop {
+            let char = text.next()?;
+            if char == query_char {
+                started = true;
+                break;
+            }
+            if started {
+                gaps += 1;
+            }
+        }
+    }
+    Some(gaps)
+}
@@ -0,0 +1,153 @@
+//! Finding the tasks that commit messages refer to, for the git hooks that
+//! `hook install` sets up. A commit refers to a task with `todoist:<id>`, or
+//! with a link to it, like `https://app.todoist.com/app/task/<id>`. After
+//! "closes", "fixes", or "resolves" (or "close", "fixed", and so on), the
+//! reference means the commit finishes the task; otherwise, the commit is
+//! noted on the task in a comment.
+
+/// A line in every hook that `hook install` writes, so that it knows which
+/// hooks it can replace.
+pub const MARKER: &str = "# Installed by `todoist hook install`.";
+
+/// The hooks that `hook install` writes.
+pub const HOOKS: &[&str] = &["commit-msg", "pre-push"];
+
+/// What to do with a task that a commit refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Close,
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    pub task_id: String,
+    pub action: Action,
+}
+
+/// A commit, as `git log` describes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    pub sha: String,
+    pub message: String,
+}
+
+impl Commit {
+    /// The first line of the commit's message.
+    #[must_use]
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    /// The commit's SHA, shortened the way git usually shows it.
+    #[must_use]
+    pub fn short_sha(&self) -> &str {
+        self.sha.get(..7).unwrap_or(&self.sha)
+    }
+}
+
+/// The words that, just before a reference, mean the commit finishes the task.
+const CLOSING_WORDS: &[&str] = &[
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+/// The tasks that a commit message refers to, each once, in the order they
+/// first appear. A task that's both closed and mentioned is closed. Lines
+/// starting with `#`, which git leaves out of messages, are skipped.
+#[must_use]
+pub fn references(message: &str) -> Vec<Reference> {
+    let mut references: Vec<Reference> = Vec::new();
+    let words = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .flat_map(str::split_whitespace);
+    let mut previous = "";
+    for word in words {
+        if let Some(task_id) = task_id(word) {
+            let closing = CLOSING_WORDS.contains(&previous.to_lowercase().as_str());
+            let action = if closing {
+                Action::Close
+            } else {
+                Action::Comment
+            };
+            match references.iter_mut().find(|known| known.task_id == task_id) {
+                Some(known) if action == Action::Close => known.action = Action::Close,
+                Some(_) => {}
+                None => references.push(Reference { task_id, action }),
+            }
+        }
+        previous = word.trim_matches(|c: char| !c.is_alphanumeric());
+    }
+    references
+}
+
+/// The ID of the task that a word refers to, if it's a reference.
+fn task_id(word: &str) -> Option<String> {
+    let word = word.trim_start_matches(['(', '[', '<']);
+    let word = word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '>']);
+    let id = if let Some(id) = word.strip_prefix("todoist:") {
+        id
+    } else if let Some((_, query)) = word.split_once("todoist.com/showTask?id=") {
+        query.split('&').next()?
+    } else {
+        let (_, path) = word.split_once("todoist.com/app/task/")?;
+        // newer links put the task's content before its ID, like
+        // `buy-milk-6Jf8VQXxpwv56VQ7`
+        let path = path.split(['/', '?', '#']).next()?;
+        path.rsplit('-').next()?
+    };
+    (!id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric())).then(|| id.to_string())
+}
+
+/// The commits in the output of `git log --format=%H%x1f%B%x1e`.
+#[must_use]
+pub fn parse_log(log: &str) -> Vec<Commit> {
+    log.split('\u{1e}')
+        .filter_map(|record| {
+            let (sha, message) = record.trim_start().split_once('\u{1f}')?;
+            Some(Commit {
+                sha: sha.to_string(),
+                message: message.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// The range of commits to look at for a line of what git hands the
+/// `pre-push` hook on stdin: `<local ref> <local sha> <remote ref> <remote
+/// sha>`. For a branch that's new to `remote`, that's the commits not on any
+/// of its branches yet. Deleting a branch pushes no commits, so it has no
+/// range.
+#[must_use]
+pub fn push_range(line: &str, remote: &str) -> Option<Vec<String>> {
+    let [_, local_sha, _, remote_sha] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    let is_zero = |sha: &str| sha.chars().all(|c| c == '0');
+    if is_zero(local_sha) {
+        return None;
+    }
+    let range = if is_zero(remote_sha) {
+        vec![
+            local_sha.to_string(),
+            "--not".to_string(),
+            format!("--remotes={remote}"),
+        ]
+    } else {
+        vec![format!("{remote_sha}..{local_sha}")]
+    };
+    Some(range)
+}
+
+/// The script for one of the [`HOOKS`], which runs `todoist` at `program`.
+/// Neither hook stops the commit or the push if `todoist` fails.
+#[must_use]
+pub fn script(hook: &str, program: &str) -> String {
+    let program = format!("'{}'", program.replace('\'', r"'\''"));
+    let args = if hook == "commit-msg" {
+        "\"$1\""
+    } else {
+        "\"$@\""
+    };
+    format!("#!/bin/sh\n{MARKER}\n{program} hook {hook} {args} || true\n")
+}
@@ -0,0 +1,191 @@
+//! Scripts that the config file sets to run when things happen: when a task is
+//! added or completed from here, and after each sync. Each gets an [`Event`] as
+//! JSON on stdin, and runs to the end before the command that set it off goes
+//! on.
+//!
+//! Only changes sent from this client count; tasks added or completed
+//! elsewhere show up in the `on_sync` hook's changes instead. A command that
+//! was queued while offline sets off its hook once it's sent.
+
+use std::process::Stdio;
+
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::{
+    changes::Changes,
+    config::Config,
+    storage::Store,
+    sync::{Command, CommandArgs, Item, ItemAddArgs, ItemCloseArgs, ItemIdArgs, Response},
+    Error, Result,
+};
+
+/// The shell command to run for each kind of [`Event`], if any.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hooks {
+    pub on_task_added: Option<String>,
+    pub on_task_completed: Option<String>,
+    pub on_sync: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A task was added, with the ID the server gave it.
+    TaskAdded { id: String, task: &'a ItemAddArgs },
+
+    /// A task was completed. The task is as it was cached, if it was.
+    TaskCompleted { id: String, task: Option<Box<Item>> },
+
+    /// The store was brought up to date with the server.
+    Sync { changes: Changes },
+}
+
+impl Hooks {
+    /// The hooks set in the config file.
+    #[must_use]
+    pub fn from_config(settings: &Config) -> Self {
+        Hooks {
+            on_task_added: settings.on_task_added.clone(),
+            on_task_completed: settings.on_task_completed.clone(),
+            on_sync: settings.on_sync.clone(),
+        }
+    }
+
+    /// The hooks to run for `events`, in order, leaving out the events that
+    /// no hook is set for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an event can't be serialized.
+    pub fn runs(&self, events: &[Event]) -> Result<Vec<Run>> {
+        let mut runs = Vec::new();
+        for event in events {
+            let (setting, command) = match event {
+                Event::TaskAdded { .. } => ("on_task_added", &self.on_task_added),
+                Event::TaskCompleted { .. } => ("on_task_completed", &self.on_task_completed),
+                Event::Sync { .. } => ("on_sync", &self.on_sync),
+            };
+            if let Some(command) = command {
+                runs.push(Run {
+                    setting,
+                    command: command.clone(),
+                    input: serde_json::to_vec(event)?,
+                });
+            }
+        }
+        Ok(runs)
+    }
+
+    /// The hooks to run for what `commands` did to tasks, once `response`
+    /// says they went through.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store can't be read.
+    pub fn task_runs(
+        &self,
+        store: &Store,
+        commands: &[Command],
+        response: &Response,
+    ) -> Result<Vec<Run>> {
+        if self.on_task_added.is_none() && self.on_task_completed.is_none() {
+            return Ok(Vec::new());
+        }
+        self.runs(&task_events(store, commands, response)?)
+    }
+}
+
+/// A hook that's ready to run for an event.
+#[derive(Debug)]
+pub struct Run {
+    /// The setting the hook is in, like `on_sync`.
+    pub setting: &'static str,
+
+    command: String,
+    input: Vec<u8>,
+}
+
+impl Run {
+    /// Runs the hook, with its event on stdin.
+    ///
+    /// # Errors
+    ///
+    /// The same as [`run`].
+    pub async fn run(&self) -> Result<()> {
+        run(&self.command, &self.input).await
+    }
+}
+
+/// The events about tasks set off by `commands`, once `response` says they
+/// went through.
+///
+/// # Errors
+///
+/// Returns an error if the store can't be read.
+pub fn task_events<'a>(
+    store: &Store,
+    commands: &'a [Command],
+    response: &Response,
+) -> Result<Vec<Event<'a>>> {
+    let mut events = Vec::new();
+    for command in commands {
+        if response
+            .status(&command.uuid)
+            .is_some_and(|status| !status.is_ok())
+        {
+            continue;
+        }
+        match &command.args {
+            CommandArgs::ItemAdd(task) => {
+                let id = command
+                    .temp_id
+                    .and_then(|temp_id| response.temp_id_mapping.get(&temp_id));
+                if let Some(id) = id {
+                    events.push(Event::TaskAdded {
+                        id: id.clone(),
+                        task,
+                    });
+                }
+            }
+            CommandArgs::ItemClose(ItemCloseArgs { id })
+            | CommandArgs::ItemComplete(ItemIdArgs { id })
+            | CommandArgs::ItemUpdateDateComplete(ItemIdArgs { id }) => {
+                events.push(Event::TaskCompleted {
+                    id: id.clone(),
+                    task: store.item(id)?.map(Box::new),
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+/// Runs `command` in the shell, with `input` on stdin, and waits for it to
+/// finish.
+///
+/// # Errors
+///
+/// Returns [`Error::Hook`] if the command exits with an error, or an I/O error
+/// if it can't be started.
+pub async fn run(command: &str, input: &[u8]) -> Result<()> {
+    let (shell, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut child = tokio::process::Command::new(shell)
+        .args([flag, command])
+        .stdin(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // a hook that doesn't read its input is fine
+        let _ = stdin.write_all(input).await;
+    }
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(Error::Hook(format!("'{command}' exited with {status}")));
+    }
+    Ok(())
+}
@@ -0,0 +1,256 @@
+//! Writing due tasks out as an iCalendar (`.ics`) feed, as RFC 5545 describes,
+//! for calendar apps to subscribe to. Each open task with a due date becomes
+//! an event: all day, unless it's due at a time, and as long as the task's
+//! duration says. Recurring tasks repeat the way their due dates say, as long
+//! as that's something [`rrule`] understands; the rest show up once, on the
+//! next date they're due.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+
+use crate::sync::{DurationUnit, Item, Project};
+
+/// Lines longer than this many bytes are folded onto the next.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// The days of the week, as recurring due dates and `BYDAY` name them.
+const WEEKDAYS: &[(&[&str], &str)] = &[
+    (&["monday", "mon"], "MO"),
+    (&["tuesday", "tue", "tues"], "TU"),
+    (&["wednesday", "wed"], "WE"),
+    (&["thursday", "thu", "thurs"], "TH"),
+    (&["friday", "fri"], "FR"),
+    (&["saturday", "sat"], "SA"),
+    (&["sunday", "sun"], "SU"),
+];
+
+/// The tasks to put in a calendar. Tasks in projects that aren't in `projects`
+/// are left out, as are completed ones and ones without due dates.
+pub struct Calendar<'a> {
+    pub projects: &'a [Project],
+    pub items: &'a [Item],
+}
+
+impl Calendar<'_> {
+    /// How many events the calendar has.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items().count()
+    }
+
+    /// Whether the calendar has no events.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn items(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().filter(|item| {
+            !item.checked
+                && !item.is_deleted
+                && item.due.is_some()
+                && self.project(&item.project_id).is_some()
+        })
+    }
+
+    fn project(&self, id: &str) -> Option<&Project> {
+        self.projects.iter().find(|project| project.id == id)
+    }
+
+    /// The calendar, stamped as made at `now`.
+    #[must_use]
+    pub fn render(&self, now: DateTime<Utc>) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//todoist-cli//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+            "X-WR-CALNAME:Todoist".to_string(),
+        ];
+        let stamp = now.format("%Y%m%dT%H%M%SZ");
+        for item in self.items() {
+            let Some(start) = item.due.as_ref().and_then(|due| Start::parse(&due.date)) else {
+                continue;
+            };
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}@todoist.com", item.id));
+            lines.push(format!("DTSTAMP:{stamp}"));
+            lines.push(format!("SUMMARY:{}", escape(&item.content)));
+            lines.extend(start.lines(item));
+            let rule = item
+                .due
+                .as_ref()
+                .filter(|due| due.is_recurring)
+                .and_then(|due| rrule(due.string.as_deref()?));
+            lines.extend(rule.map(|rule| format!("RRULE:{rule}")));
+            if !item.description.is_empty() {
+                lines.push(format!("DESCRIPTION:{}", escape(&item.description)));
+            }
+            if let Some(project) = self.project(&item.project_id) {
+                lines.push(format!("CATEGORIES:{}", escape(&project.name)));
+            }
+            lines.push(format!("URL:https://app.todoist.com/app/task/{}", item.id));
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        let mut text = String::new();
+        for line in lines {
+            let _ = write!(text, "{}\r\n", fold(&line));
+        }
+        text
+    }
+}
+
+/// When an event starts, from a task's due date.
+enum Start {
+    Day(NaiveDate),
+
+    /// A time in UTC, from due dates that end in `Z`.
+    Utc(NaiveDateTime),
+
+    /// A time wherever the calendar is.
+    Floating(NaiveDateTime),
+}
+
+impl Start {
+    fn parse(date: &str) -> Option<Self> {
+        if let Some(utc) = date.strip_suffix('Z') {
+            return NaiveDateTime::parse_from_str(utc, "%Y-%m-%dT%H:%M:%S")
+                .ok()
+                .map(Start::Utc);
+        }
+        if let Ok(time) = NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%M:%S") {
+            return Some(Start::Floating(time));
+        }
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(Start::Day)
+    }
+
+    /// The lines for when the event starts, and how long it lasts. All-day
+    /// events last a day, or as many as the task takes; events at a time end
+    /// when they start unless the task has a duration.
+    fn lines(&self, item: &Item) -> Vec<String> {
+        let duration = item.duration;
+        match self {
+            Start::Day(date) => {
+                let days = match duration {
+                    Some(duration) if duration.unit == DurationUnit::Day => duration.amount.max(1),
+                    _ => 1,
+                };
+                let end = *date + Days::new(u64::from(days));
+                vec![
+                    format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+                    format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+                ]
+            }
+            Start::Utc(time) | Start::Floating(time) => {
+                let zone = if matches!(self, Start::Utc(_)) {
+                    "Z"
+                } else {
+                    ""
+                };
+                let mut lines = vec![format!("DTSTART:{}{zone}", time.format("%Y%m%dT%H%M%S"))];
+                match duration {
+                    Some(duration) if duration.unit == DurationUnit::Minute => {
+                        lines.push(format!("DURATION:PT{}M", duration.amount));
+                    }
+                    Some(duration) => lines.push(format!("DURATION:P{}D", duration.amount)),
+                    None => {}
+                }
+                lines
+            }
+        }
+    }
+}
+
+/// The `RRULE` for a recurring due date, like `FREQ=WEEKLY;BYDAY=MO,WE` for
+/// "every monday and wednesday", or `None` if it repeats in a way that isn't
+/// understood. Dates that repeat after the task is completed, like
+/// "every! 3 days", can't be put on a calendar ahead of time, so they aren't.
+#[must_use]
+pub fn rrule(due_string: &str) -> Option<String> {
+    let text = due_string.trim().to_lowercase();
+    let text = match text.as_str() {
+        "daily" => "every day",
+        "weekly" => "every week",
+        "monthly" => "every month",
+        "yearly" | "annually" => "every year",
+        text => text,
+    };
+    let rest = text.strip_prefix("every ")?.trim();
+    // the time of day is in the event's start already
+    let rest = rest.split(" at ").next().unwrap_or(rest).trim();
+    let (interval, unit) = if let Some(unit) = rest.strip_prefix("other ") {
+        (2, unit)
+    } else {
+        let count = rest.split_once(' ').and_then(|(count, unit)| {
+            let count: u32 = count.parse().ok()?;
+            Some((count, unit.trim()))
+        });
+        count.unwrap_or((1, rest))
+    };
+
+    let freq = match unit {
+        "day" | "days" => "DAILY",
+        "week" | "weeks" => "WEEKLY",
+        "month" | "months" => "MONTHLY",
+        "year" | "years" => "YEARLY",
+        "weekday" | "workday" if interval == 1 => {
+            return Some(weekly(&["MO", "TU", "WE", "TH", "FR"]))
+        }
+        _ if interval == 1 => return weekdays(unit).map(|days| weekly(&days)),
+        _ => return None,
+    };
+    Some(if interval == 1 {
+        format!("FREQ={freq}")
+    } else {
+        format!("FREQ={freq};INTERVAL={interval}")
+    })
+}
+
+fn weekly(days: &[&str]) -> String {
+    format!("FREQ=WEEKLY;BYDAY={}", days.join(","))
+}
+
+/// The `BYDAY` names of a list of weekdays, like "mon, wed and fri".
+fn weekdays(text: &str) -> Option<Vec<&'static str>> {
+    let mut days = Vec::new();
+    let words = text
+        .split([',', ' '])
+        .filter(|word| !word.is_empty() && *word != "and");
+    for word in words {
+        let (_, day) = WEEKDAYS.iter().find(|(names, _)| names.contains(&word))?;
+        if !days.contains(day) {
+            days.push(*day);
+        }
+    }
+    (!days.is_empty()).then_some(days)
+}
+
+/// Escapes text for a property value.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Splits a line into ones of at most [`MAX_LINE_LENGTH`] bytes, each after
+/// the first starting with a space, without splitting any characters.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
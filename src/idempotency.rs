@@ -0,0 +1,41 @@
+//! Keeping a change from being made twice when it's sent again. Todoist
+//! carries out each command only once for its `uuid`, so a command whose
+//! request timed out, and that may or may not have gone through, can be sent
+//! again safely as long as it has the same `uuid` the second time.
+//!
+//! Each command's `uuid` is worked out from a digest of what it does, along
+//! with a count of the commands sent so far, which keeps commands that happen
+//! to do the same thing apart. Until Todoist answers for a command, its
+//! `uuid` is kept with its digest in the [store](crate::storage::Store), so
+//! the same command sent again soon after, like `todoist add` run again after
+//! it seemed to fail, gets the same `uuid` instead of a new one.
+
+use sha2::{Digest, Sha256};
+use uuid::{Builder, Uuid};
+
+use crate::{sync::CommandArgs, Result};
+
+/// How long after a command is sent it's still taken to be the same command
+/// when it's sent again, in seconds.
+pub const WINDOW: u64 = 60 * 60;
+
+/// A digest of what a command does, which is the same for commands that do
+/// the same thing.
+///
+/// # Errors
+///
+/// Returns an error if the command can't be converted to JSON.
+pub fn digest(args: &CommandArgs) -> Result<String> {
+    let json = serde_json::to_string(args)?;
+    Ok(format!("{:x}", Sha256::digest(json.as_bytes())))
+}
+
+/// The `uuid` for the command with `digest` that's sent after `sequence`
+/// others.
+#[must_use]
+pub fn uuid(digest: &str, sequence: u64) -> Uuid {
+    let hash = Sha256::digest(format!("{digest}:{sequence}").as_bytes());
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&hash[..16]);
+    Builder::from_random_bytes(bytes).into_uuid()
+}
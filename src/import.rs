@@ -0,0 +1,617 @@
+//! Reading tasks from Markdown checklists, CSV files, and Org files, like the
+//! ones that [`export`](crate::export) writes.
+//!
+//! In Markdown, each `- [ ]` (or `- [x]`, for a completed task) line is a task,
+//! and tasks indented under another one become its subtasks. A `#` heading
+//! names the project for the tasks below it, and deeper headings (`##`, and so
+//! on) name a section in that project. A task can end with a due date in
+//! parentheses, like `(due tomorrow)`, and with labels, like `@errand`. Other
+//! lines are ignored.
+//!
+//! A CSV file needs a header row, naming its columns. Only `content` is
+//! required; the others are optional, and can come in any order:
+//!
+//! - `description`: notes about the task
+//! - `project` and `section`: where the task goes, by name
+//! - `due`: a due date in natural language, like "every monday"
+//! - `priority`: from `p1` (most urgent) to `p4`, or just the number
+//! - `labels`: separated by spaces or commas, with or without `@`
+//! - `completed`: `true`, `yes`, `x`, or `1` for completed tasks
+//! - `id` and `parent_id`: to make a task a subtask of another row, by
+//!   giving the parent's `id` as its `parent_id`
+//!
+//! Other columns are ignored.
+//!
+//! In Org, each headline with a TODO keyword is a task: `DONE`, `CANCELLED`,
+//! and `CANCELED` ones are completed, and any other keyword, like `TODO` or
+//! `NEXT`, means the task is still open. Headlines under a task become its
+//! subtasks. Headlines without a keyword name the project (at the top level)
+//! or the section (deeper down) for the tasks below them. A task's priority
+//! cookie (`[#A]` for `p1`, down to `[#C]` for `p3`) and tags become its
+//! priority and labels, its `SCHEDULED` date its due date, and its `DEADLINE`
+//! its deadline. A repeater, like `+1w`, makes the task recurring, but a `DUE`
+//! property, which the export writes for recurring tasks, says exactly how.
+//! The text under a task is its description, leaving out drawers like
+//! `:PROPERTIES:` and `:LOGBOOK:`.
+
+use std::{collections::HashMap, path::Path};
+
+use crate::{quick_add, Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// A checklist, with headings for projects and sections.
+    #[value(name = "md")]
+    Markdown,
+
+    /// One row per task, with a header row.
+    Csv,
+
+    /// An outline of TODO headlines.
+    Org,
+}
+
+/// Other apps whose exports can be imported, for moving from them to Todoist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Source {
+    /// What `task export` writes, as described in
+    /// [`taskwarrior`](crate::taskwarrior).
+    Taskwarrior,
+}
+
+impl Format {
+    /// The format a file's extension suggests: CSV for `.csv` files, Org for
+    /// `.org` files, and Markdown otherwise.
+    #[must_use]
+    pub fn of(path: &Path) -> Self {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension.map(str::to_lowercase).as_deref() {
+            Some("csv") => Format::Csv,
+            Some("org") => Format::Org,
+            _ => Format::Markdown,
+        }
+    }
+}
+
+/// A task to be imported.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Task {
+    pub content: String,
+    pub description: Option<String>,
+
+    /// The name of the project to put the task in, if it names one.
+    pub project: Option<String>,
+
+    /// The name of the section to put the task in, within its project.
+    pub section: Option<String>,
+
+    pub due: Option<String>,
+
+    /// Like `2024-05-01`.
+    pub deadline: Option<String>,
+
+    /// From 1 (most urgent) to 4, the way users write them.
+    pub priority: Option<u8>,
+
+    pub labels: Vec<String>,
+    pub completed: bool,
+
+    /// Comments to add to the task once it's been added.
+    pub comments: Vec<String>,
+
+    /// The index of the task's parent among the imported tasks. Parents always
+    /// come before their subtasks.
+    pub parent: Option<usize>,
+}
+
+/// Parses the tasks in `text`, which is in `format`.
+///
+/// # Errors
+///
+/// The same as the parser for `format`.
+pub fn parse(text: &str, format: Format) -> Result<Vec<Task>> {
+    match format {
+        Format::Markdown => parse_markdown(text),
+        Format::Csv => parse_csv(text),
+        Format::Org => parse_org(text),
+    }
+}
+
+/// Parses the tasks in a Markdown checklist.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if a task has no text.
+pub fn parse_markdown(text: &str) -> Result<Vec<Task>> {
+    let mut tasks: Vec<Task> = Vec::new();
+    let (mut project, mut section) = (None, None);
+
+    // the indentation and index of the tasks that later ones might be nested in
+    let mut ancestors: Vec<(usize, usize)> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(heading) = trimmed.strip_prefix('#') {
+            let level = 1 + heading.chars().take_while(|char| *char == '#').count();
+            let name = heading.trim_start_matches('#').trim();
+            if level == 1 {
+                project = Some(name.to_string()).filter(|name| !name.is_empty());
+                section = None;
+            } else {
+                section = Some(name.to_string()).filter(|name| !name.is_empty());
+            }
+            ancestors.clear();
+            continue;
+        }
+
+        let Some((completed, content)) = checklist_item(trimmed) else {
+            continue;
+        };
+        if content.is_empty() {
+            return Err(Error::Import(format!("line {} has no task", number + 1)));
+        }
+
+        let indent: usize = line[..line.len() - trimmed.len()]
+            .chars()
+            .map(|char| if char == '\t' { 4 } else { 1 })
+            .sum();
+        while ancestors.last().is_some_and(|(depth, _)| *depth >= indent) {
+            ancestors.pop();
+        }
+        let (content, due, labels) = split_markdown_details(content);
+        tasks.push(Task {
+            content,
+            project: project.clone(),
+            section: section.clone(),
+            due,
+            labels,
+            completed,
+            parent: ancestors.last().map(|(_, index)| *index),
+            ..Default::default()
+        });
+        ancestors.push((indent, tasks.len() - 1));
+    }
+    Ok(tasks)
+}
+
+/// Whether a line is a checklist item, and if so, whether it's checked and
+/// what it says.
+fn checklist_item(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+    let (checked, rest) = match rest.get(..3)? {
+        "[ ]" => (false, &rest[3..]),
+        "[x]" | "[X]" => (true, &rest[3..]),
+        _ => return None,
+    };
+    Some((checked, rest.trim()))
+}
+
+/// Splits the due date and labels off the end of a task, the way the Markdown
+/// export writes them.
+fn split_markdown_details(text: &str) -> (String, Option<String>, Vec<String>) {
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+    let mut labels = Vec::new();
+    while let Some(label) = words
+        .last()
+        .and_then(|word| word.strip_prefix('@'))
+        .filter(|label| !label.is_empty())
+    {
+        labels.insert(0, label.to_string());
+        words.pop();
+    }
+
+    let mut content = words.join(" ");
+    let mut due = None;
+    if let Some(start) = content.rfind(" (due ").filter(|_| content.ends_with(')')) {
+        due = Some(content[start + 6..content.len() - 1].trim().to_string());
+        content.truncate(start);
+    }
+    (content, due, labels)
+}
+
+/// Parses the tasks in a CSV file, as described in the module documentation.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if there's no `content` column, a row has no
+/// content or an invalid priority, a quote is left open, or subtasks' parents
+/// go around in a circle.
+pub fn parse_csv(text: &str) -> Result<Vec<Task>> {
+    let mut rows = csv_records(text)?.into_iter();
+    let header: Vec<String> = rows
+        .next()
+        .ok_or(Error::Import("the file is empty".to_string()))?
+        .into_iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let column = |name: &str| header.iter().position(|column| column == name);
+    let content_column =
+        column("content").ok_or(Error::Import("there's no 'content' column".to_string()))?;
+
+    let mut tasks = Vec::new();
+    let mut row_ids = Vec::new();
+    let mut parent_ids = Vec::new();
+    for (number, row) in rows.enumerate() {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let field = |name: &str| {
+            column(name)
+                .and_then(|index| row.get(index))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let content = row.get(content_column).map_or("", |field| field.trim());
+        if content.is_empty() {
+            return Err(Error::Import(format!("row {} has no content", number + 2)));
+        }
+
+        let priority = match field("priority") {
+            Some(priority) => Some(
+                priority
+                    .parse()
+                    .ok()
+                    .filter(|priority| (1..=4).contains(priority))
+                    .or_else(|| quick_add::parse_priority(priority))
+                    .ok_or(Error::Import(format!(
+                        "row {} has an invalid priority '{priority}'",
+                        number + 2
+                    )))?,
+            ),
+            None => None,
+        };
+        let labels = field("labels")
+            .unwrap_or_default()
+            .split(|char: char| char == ',' || char.is_whitespace())
+            .map(|label| label.trim_start_matches('@'))
+            .filter(|label| !label.is_empty())
+            .map(ToString::to_string)
+            .collect();
+
+        tasks.push(Task {
+            content: content.to_string(),
+            description: field("description").map(ToString::to_string),
+            project: field("project").map(ToString::to_string),
+            section: field("section").map(ToString::to_string),
+            due: field("due").map(ToString::to_string),
+            priority,
+            labels,
+            completed: field("completed").is_some_and(|completed| {
+                matches!(
+                    completed.to_lowercase().as_str(),
+                    "true" | "yes" | "x" | "1"
+                )
+            }),
+            ..Default::default()
+        });
+        row_ids.push(field("id").map(ToString::to_string));
+        parent_ids.push(field("parent_id").map(ToString::to_string));
+    }
+
+    let index_of: HashMap<&str, usize> = row_ids
+        .iter()
+        .enumerate()
+        .filter_map(|(index, id)| Some((id.as_deref()?, index)))
+        .collect();
+    for (task, parent_id) in tasks.iter_mut().zip(&parent_ids) {
+        // parents that aren't in the file are left out
+        task.parent = parent_id
+            .as_deref()
+            .and_then(|id| index_of.get(id).copied());
+    }
+    parents_first(tasks)
+}
+
+/// Parses the tasks in an Org file, as described in the module documentation.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if a task has no text or an unknown priority.
+pub fn parse_org(text: &str) -> Result<Vec<Task>> {
+    let mut tasks: Vec<Task> = Vec::new();
+    let (mut project, mut section) = (None, None);
+
+    // the level and index of the tasks that later ones might be nested in
+    let mut ancestors: Vec<(usize, usize)> = Vec::new();
+    let mut in_drawer = false;
+    let mut description: Vec<&str> = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        if let Some((level, headline)) = org_headline(line) {
+            set_description(tasks.last_mut(), &mut description);
+            in_drawer = false;
+            let Some((completed, headline)) = org_keyword(headline) else {
+                let name = Some(headline.trim().to_string()).filter(|name| !name.is_empty());
+                if level == 1 {
+                    project = name;
+                    section = None;
+                } else {
+                    section = name;
+                }
+                ancestors.clear();
+                continue;
+            };
+
+            let (priority, content, labels) = split_org_details(headline).map_err(|cookie| {
+                Error::Import(format!(
+                    "line {} has an unknown priority '{cookie}'",
+                    number + 1
+                ))
+            })?;
+            if content.is_empty() {
+                return Err(Error::Import(format!("line {} has no task", number + 1)));
+            }
+            while ancestors.last().is_some_and(|(depth, _)| *depth >= level) {
+                ancestors.pop();
+            }
+            tasks.push(Task {
+                content,
+                project: project.clone(),
+                section: section.clone(),
+                priority,
+                labels,
+                completed,
+                parent: ancestors.last().map(|(_, index)| *index),
+                ..Default::default()
+            });
+            ancestors.push((level, tasks.len() - 1));
+            continue;
+        }
+
+        // lines before the first task, or under a project or section, aren't
+        // about any task
+        let Some(task) = tasks.last_mut().filter(|_| !ancestors.is_empty()) else {
+            continue;
+        };
+        let trimmed = line.trim();
+        if in_drawer {
+            if trimmed.eq_ignore_ascii_case(":END:") {
+                in_drawer = false;
+            } else if let Some(due) = org_property(trimmed, "DUE") {
+                task.due = Some(due.to_string());
+            }
+        } else if is_drawer_start(trimmed) {
+            in_drawer = true;
+        } else if is_planning_line(trimmed) {
+            if let Some(scheduled) = org_timestamp_after(trimmed, "SCHEDULED:") {
+                // a `DUE` property says it better, if there is one
+                task.due = task.due.take().or(Some(scheduled.due_string()));
+            }
+            if let Some(deadline) = org_timestamp_after(trimmed, "DEADLINE:") {
+                task.deadline = Some(deadline.date.to_string());
+            }
+        } else {
+            // the export escapes lines that would otherwise be headlines
+            let unescaped = line
+                .strip_prefix(',')
+                .filter(|rest| rest.starts_with(['*', ',']));
+            description.push(unescaped.unwrap_or(line));
+        }
+    }
+    set_description(tasks.last_mut(), &mut description);
+    Ok(tasks)
+}
+
+/// The keywords that mean a task has been completed. Others mean it hasn't.
+const ORG_DONE_KEYWORDS: &[&str] = &["DONE", "CANCELLED", "CANCELED"];
+
+/// Whether a line is a headline, and if so, its level and the rest of it.
+fn org_headline(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|char| *char == '*').count();
+    let rest = &line[level..];
+    (level > 0 && (rest.is_empty() || rest.starts_with([' ', '\t']))).then_some((level, rest))
+}
+
+/// Whether a headline starts with a TODO keyword, and if so, whether it's
+/// completed and the rest of the headline. Keywords are all uppercase.
+fn org_keyword(headline: &str) -> Option<(bool, &str)> {
+    let headline = headline.trim_start();
+    let (keyword, rest) = headline.split_once(' ').unwrap_or((headline, ""));
+    let is_keyword = keyword.len() > 1
+        && keyword
+            .chars()
+            .all(|char| char.is_ascii_uppercase() || char == '_');
+    is_keyword.then(|| (ORG_DONE_KEYWORDS.contains(&keyword), rest))
+}
+
+/// Splits the priority cookie off the start of a headline and the tags off its
+/// end, or returns the cookie if it isn't one of `A`, `B`, or `C`.
+fn split_org_details(
+    headline: &str,
+) -> std::result::Result<(Option<u8>, String, Vec<String>), String> {
+    let mut rest = headline.trim();
+    let mut priority = None;
+    if let Some(cookie) = rest.strip_prefix("[#").and_then(|rest| rest.get(..2)) {
+        priority = match cookie {
+            "A]" => Some(1),
+            "B]" => Some(2),
+            "C]" => Some(3),
+            _ => return Err(format!("[#{cookie}")),
+        };
+        rest = rest[4..].trim_start();
+    }
+
+    let mut labels = Vec::new();
+    let (content, tags) = rest.rsplit_once(char::is_whitespace).unwrap_or(("", rest));
+    if tags.len() > 1 && tags.starts_with(':') && tags.ends_with(':') {
+        labels = tags
+            .split(':')
+            .filter(|tag| !tag.is_empty())
+            .map(ToString::to_string)
+            .collect();
+        rest = content.trim_end();
+    }
+    Ok((priority, rest.to_string(), labels))
+}
+
+/// Whether a line starts a drawer, like `:PROPERTIES:` or `:LOGBOOK:`.
+fn is_drawer_start(line: &str) -> bool {
+    line.len() > 2
+        && line.starts_with(':')
+        && line.ends_with(':')
+        && line[1..line.len() - 1]
+            .chars()
+            .all(|char| char.is_alphanumeric() || char == '_' || char == '-')
+}
+
+/// The value of a property line like `:DUE: every monday`, if it's for
+/// `name`.
+fn org_property<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let (property, value) = line.strip_prefix(':')?.split_once(':')?;
+    let value = value.trim();
+    (property.eq_ignore_ascii_case(name) && !value.is_empty()).then_some(value)
+}
+
+/// Whether a line is all `SCHEDULED:`, `DEADLINE:`, and `CLOSED:` dates.
+fn is_planning_line(line: &str) -> bool {
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+        .iter()
+        .any(|keyword| line.starts_with(keyword))
+}
+
+/// A timestamp, like `<2024-05-01 Wed 09:00 +1w>`.
+struct OrgTimestamp<'a> {
+    date: &'a str,
+    time: Option<&'a str>,
+
+    /// Like `+1w`, for timestamps that repeat.
+    repeater: Option<&'a str>,
+}
+
+impl OrgTimestamp<'_> {
+    /// The timestamp as a due date, like `2024-05-01 09:00`, or `every 1 week
+    /// starting 2024-05-01` if it repeats. Repeaters that count from when the
+    /// task is completed (`.+1w`) become `every!` dates.
+    fn due_string(&self) -> String {
+        let start = match self.time {
+            Some(time) => format!("{} {time}", self.date),
+            None => self.date.to_string(),
+        };
+        let interval = self.repeater.and_then(|repeater| {
+            let (every, repeater) = match repeater.strip_prefix(".+") {
+                Some(repeater) => ("every!", repeater),
+                None => ("every", repeater.trim_start_matches('+')),
+            };
+            let unit = match repeater.chars().last()? {
+                'h' => "hour",
+                'd' => "day",
+                'w' => "week",
+                'm' => "month",
+                'y' => "year",
+                _ => return None,
+            };
+            let count: u32 = repeater[..repeater.len() - 1].parse().ok()?;
+            Some(if count == 1 {
+                format!("{every} {unit}")
+            } else {
+                format!("{every} {count} {unit}s")
+            })
+        });
+        match interval {
+            Some(interval) => format!("{interval} starting {start}"),
+            None => start,
+        }
+    }
+}
+
+/// The timestamp after `keyword` in a planning line.
+fn org_timestamp_after<'a>(line: &'a str, keyword: &str) -> Option<OrgTimestamp<'a>> {
+    let rest = line[line.find(keyword)? + keyword.len()..].trim_start();
+    let rest = rest.strip_prefix(['<', '['])?;
+    let inside = &rest[..rest.find(['>', ']'])?];
+    let mut parts = inside.split_whitespace();
+    let date = parts.next()?;
+    let mut timestamp = OrgTimestamp {
+        date,
+        time: None,
+        repeater: None,
+    };
+    for part in parts {
+        if part.starts_with(['+', '.']) {
+            timestamp.repeater = Some(part);
+        } else if part.starts_with(|char: char| char.is_ascii_digit()) {
+            // the start of a range like `09:00-10:00`
+            timestamp.time = part.split('-').next();
+        }
+    }
+    Some(timestamp)
+}
+
+/// Gives a task the description that's been gathered for it, if any.
+fn set_description(task: Option<&mut Task>, lines: &mut Vec<&str>) {
+    let text = lines.join("\n");
+    lines.clear();
+    let text = text.trim_matches('\n').trim_end();
+    if let Some(task) = task.filter(|_| !text.is_empty()) {
+        task.description = Some(text.to_string());
+    }
+}
+
+/// Puts tasks in an order where parents come before their subtasks, keeping
+/// the original order otherwise.
+fn parents_first(tasks: Vec<Task>) -> Result<Vec<Task>> {
+    let mut order: Vec<usize> = Vec::with_capacity(tasks.len());
+    let mut placed = vec![None; tasks.len()];
+    while order.len() < tasks.len() {
+        let before = order.len();
+        for (index, task) in tasks.iter().enumerate() {
+            let ready = task.parent.is_none_or(|parent| placed[parent].is_some());
+            if placed[index].is_none() && ready {
+                placed[index] = Some(order.len());
+                order.push(index);
+            }
+        }
+        if order.len() == before {
+            return Err(Error::Import(
+                "some tasks are each other's parents".to_string(),
+            ));
+        }
+    }
+
+    let mut tasks: Vec<Option<Task>> = tasks.into_iter().map(Some).collect();
+    Ok(order
+        .into_iter()
+        .filter_map(|index| tasks[index].take())
+        .map(|task| Task {
+            parent: task.parent.and_then(|parent| placed[parent]),
+            ..task
+        })
+        .collect())
+}
+
+/// Splits CSV text into records of fields, as RFC 4180 describes: fields are
+/// separated by commas, and quoted fields can hold commas, newlines, and
+/// doubled quotes.
+fn csv_records(text: &str) -> Result<Vec<Vec<String>>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(char) = chars.next() {
+        match (char, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => record.push(std::mem::take(&mut field)),
+            ('\n', false) => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            ('\r', false) if chars.peek() == Some(&'\n') => {}
+            (char, _) => field.push(char),
+        }
+    }
+    if in_quotes {
+        return Err(Error::Import("a quoted field is never closed".to_string()));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
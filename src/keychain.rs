@@ -0,0 +1,34 @@
+//! Storage for the API token in the operating system's keyring (Keychain on
+//! macOS, Credential Manager on Windows, and the kernel keyring on Linux), as
+//! an alternative to keeping it in a plain file.
+
+use crate::Result;
+
+const SERVICE: &str = "tuido";
+const USER: &str = "api_token";
+
+fn entry() -> Result<keyring::Entry> {
+    Ok(keyring::Entry::new(SERVICE, USER)?)
+}
+
+/// The stored token, or `None` if there isn't one.
+///
+/// # Errors
+///
+/// Returns [`crate::Error::Keyring`] if the keyring can't be read.
+pub fn load() -> Result<Option<String>> {
+    match entry()?.get_password() {
+        Ok(token) => Ok(Some(token)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Saves the token, replacing any that was already stored.
+///
+/// # Errors
+///
+/// Returns [`crate::Error::Keyring`] if the keyring can't be written.
+pub fn store(token: &str) -> Result<()> {
+    Ok(entry()?.set_password(token)?)
+}
@@ -1,3 +1,93 @@
+//! A client for Todoist's Sync API, with a local cache so that tasks can be
+//! read and changed offline.
+//!
+//! - [`client::SyncClient`] sends requests to the API, retrying the ones that
+//!   fail for transient reasons.
+//! - [`storage::Store`] caches synced resources and queued commands in SQLite.
+//! - [`session`] ties the two together: syncing the store, and sending
+//!   commands (or queueing them while offline).
+//! - [`rest::RestClient`] talks to the REST API, for the few things that are
+//!   simpler there. A sync client can be given one with
+//!   [`SyncClient::with_rest`](client::SyncClient::with_rest).
+//! - [`sync`] has the typed models for requests, responses, and commands.
+//!
+//! The rest are helpers for the command-line app: parsing quick-add text and
+//! filters, fuzzy matching, config, and so on.
+//!
+//! ```no_run
+//! use todoist::{
+//!     client::{SyncClient, DEFAULT_RETRIES, SYNC_URL},
+//!     session,
+//!     storage::Store,
+//!     sync::{Command, CommandArgs, ItemAddArgs},
+//! };
+//!
+//! # async fn example() -> todoist::Result<()> {
+//! let client = SyncClient::new(SYNC_URL, "API_TOKEN", DEFAULT_RETRIES);
+//! let mut store = Store::open_in_memory()?;
+//! session::sync(&mut store, &client).await?;
+//!
+//! let user = session::fetch_user(&client).await?;
+//! let command = Command::new(CommandArgs::ItemAdd(ItemAddArgs {
+//!     project_id: user.inbox_project_id,
+//!     content: "Buy milk".to_string(),
+//!     ..Default::default()
+//! }));
+//! session::send_command(&mut store, &client, command).await?;
+//! # Ok(())
+//! # }
+//! ```
+
 #![warn(clippy::all, clippy::pedantic, clippy::unwrap_used)]
+pub mod agenda;
+pub mod arrange;
+pub mod batch;
+pub mod changes;
+pub mod checklist;
+pub mod client;
+pub mod color;
+pub mod config;
+pub mod conflict;
+pub mod daemon;
+pub mod dedupe;
+pub mod dependency;
+pub mod due;
+mod error;
+pub mod export;
+pub mod files;
+pub mod filter;
+pub mod focus;
+pub mod forecast;
+pub mod fuzzy;
+pub mod githook;
+pub mod hooks;
+pub mod ical;
+pub mod idempotency;
+pub mod import;
+pub mod keychain;
+pub mod locale;
+pub mod notify;
+pub mod order;
+pub mod porcelain;
+pub mod quick_add;
+pub mod rate_limit;
+pub mod reminder;
+pub mod rest;
+pub mod review;
+pub mod schedule;
+pub mod search;
+pub mod session;
+pub mod stats;
+pub mod storage;
+pub mod stream;
 pub mod sync;
+pub mod taskwarrior;
+pub mod template;
 mod tests;
+pub mod track;
+pub mod trash;
+pub mod undo;
+pub mod webhook;
+pub mod widget;
+
+pub use error::{Error, Result};
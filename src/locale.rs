@@ -0,0 +1,122 @@
+//! How dates are read and shown: in the user's language, in the format they
+//! chose, and in the timezone of their Todoist account, rather than as the
+//! API's ISO dates in UTC.
+//!
+//! Like the [color](crate::color) settings, these are set once at startup.
+//! Until then, dates are shown as ISO dates in the system's timezone, and
+//! read as English.
+
+use std::sync::OnceLock;
+
+use chrono::{
+    format::{Item, StrftimeItems},
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Utc,
+};
+
+/// How dates are shown when no format is given.
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The languages that Todoist understands due dates in.
+pub const LANGUAGES: &[&str] = &[
+    "da", "de", "en", "es", "fi", "fr", "it", "ja", "ko", "nb", "nl", "pl", "pt_BR", "ru", "sv",
+    "tr", "zh_CN", "zh_TW",
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    /// A `strftime`-style format for dates, like `%d.%m.%Y`. Times are shown
+    /// after it, as `%H:%M`.
+    pub date_format: Option<String>,
+
+    /// The language due dates are written in, one of [`LANGUAGES`].
+    pub lang: Option<String>,
+
+    /// The user's offset from UTC, for showing times in.
+    pub offset: Option<FixedOffset>,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+/// Sets the locale. Only the first call has any effect.
+pub fn set(locale: Locale) {
+    let _ = LOCALE.set(locale);
+}
+
+/// Whether `format` is a date format that [`Locale::date_format`] can be.
+#[must_use]
+pub fn is_valid_date_format(format: &str) -> bool {
+    !format.is_empty() && StrftimeItems::new(format).all(|item| item != Item::Error)
+}
+
+/// Turns an offset like `+05:30` or `-03:00`, as Todoist gives it in the
+/// user's timezone info, into a [`FixedOffset`].
+#[must_use]
+pub fn parse_offset(text: &str) -> Option<FixedOffset> {
+    let (sign, rest) = match text.split_at_checked(1)? {
+        ("+", rest) => (1, rest),
+        ("-", rest) => (-1, rest),
+        _ => return None,
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let seconds = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+    FixedOffset::east_opt(sign * seconds)
+}
+
+/// The language to send along with due dates, if it isn't English.
+#[must_use]
+pub fn lang() -> Option<&'static str> {
+    LOCALE.get()?.lang.as_deref().filter(|lang| *lang != "en")
+}
+
+/// A time in UTC, as it is where the user is.
+#[must_use]
+pub fn user_time(time: DateTime<Utc>) -> NaiveDateTime {
+    match LOCALE.get().and_then(|locale| locale.offset) {
+        Some(offset) => time.with_timezone(&offset).naive_local(),
+        None => time.with_timezone(&Local).naive_local(),
+    }
+}
+
+/// A date, in the user's format.
+#[must_use]
+pub fn format_date(date: NaiveDate) -> String {
+    let format = LOCALE
+        .get()
+        .and_then(|locale| locale.date_format.as_deref())
+        .unwrap_or(DEFAULT_DATE_FORMAT);
+    date.format(format).to_string()
+}
+
+/// A date and time, in the user's format.
+#[must_use]
+pub fn format_time(time: NaiveDateTime) -> String {
+    format!("{} {}", format_date(time.date()), time.format("%H:%M"))
+}
+
+/// An RFC 3339 timestamp, like a task's completion time, in the user's format
+/// and timezone. Anything else is shown as it is.
+#[must_use]
+pub fn format_timestamp(timestamp: &str) -> String {
+    DateTime::parse_from_rfc3339(timestamp).map_or(timestamp.to_string(), |time| {
+        format_time(user_time(time.with_timezone(&Utc)))
+    })
+}
+
+/// A due date as the API gives it: a date, a local date and time, or a time in
+/// UTC (ending in `Z`), in the user's format and timezone. Anything else is
+/// shown as it is.
+#[must_use]
+pub fn format_due_date(date: &str) -> String {
+    if let Ok(date) = NaiveDate::parse_from_str(date, "%Y-%m-%d") {
+        return format_date(date);
+    }
+    let (naive, utc) = match date.strip_suffix('Z') {
+        Some(naive) => (naive, true),
+        None => (date, false),
+    };
+    match NaiveDateTime::parse_from_str(naive, "%Y-%m-%dT%H:%M:%S") {
+        Ok(time) if utc => format_time(user_time(time.and_utc())),
+        Ok(time) => format_time(time),
+        Err(_) => date.to_string(),
+    }
+}
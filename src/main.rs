@@ -1,26 +1,89 @@
 #![warn(clippy::all, clippy::pedantic, clippy::unwrap_used)]
-use clap::Parser;
-use serde::Deserialize;
+use chrono::{
+    DateTime, Datelike, Days, Local, NaiveDate, NaiveTime, TimeDelta, TimeZone, Utc, Weekday,
+};
+use clap::{builder::PossibleValuesParser, Arg, ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap, HashSet},
     error::Error,
     fs,
+    io::{IsTerminal, Write},
     path::{Path, PathBuf},
+    process::ExitCode,
     str::FromStr,
+    time::{Duration, Instant},
+};
+use todoist::{
+    agenda, arrange, batch,
+    changes::Changes,
+    checklist,
+    client::{http_client, HttpOptions, SyncClient, DEFAULT_RETRIES, DEFAULT_TIMEOUT, SYNC_URL},
+    color,
+    config::{self, Backend, Config, Output, TokenStorage},
+    conflict::{Resolution, Strategy},
+    daemon, dedupe,
+    dependency::{self, Dependency},
+    due,
+    export::{self, Export},
+    files, filter, focus, forecast, fuzzy, githook,
+    hooks::{self, Hooks},
+    ical, import, keychain,
+    locale::{self, Locale},
+    notify,
+    order::{self, Position},
+    porcelain::{self, Status},
+    quick_add::{self, QuickAdd},
+    reminder,
+    rest::{RestClient, REST_URL},
+    review, schedule, search,
+    session::{self, Outcome},
+    stats::{self, ProjectCounts, SortBy},
+    storage::{Pruned, Store, FULL_SYNC_TOKEN},
+    sync::{
+        ChildOrder, Collaborator, CollaboratorState, Command, CommandArgs, CommandStatus, Deadline,
+        DueArgs, DurationUnit, Filter, Item, ItemAddArgs, ItemCloseArgs, ItemIdArgs, ItemMoveArgs,
+        ItemReorderArgs, ItemUpdateArgs, Label, LabelAddArgs, LabelIdArgs, LabelUpdateArgs,
+        LiveNotification, LiveNotificationIdsArgs, LocationTrigger, Note, NoteAddArgs, Project,
+        ProjectAddArgs, ProjectIdArgs, ProjectReorderArgs, ProjectUpdateArgs, Reminder,
+        ReminderAddArgs, ReminderIdArgs, ReminderKind, Response, Section, SectionAddArgs,
+        ShareProjectArgs, TaskDuration, User, Workspace,
+    },
+    taskwarrior,
+    template::Template,
+    track::{self, Timer, WorkSession, WorkTotal},
+    trash::{self, TrashedItem},
+    webhook,
+    widget::{self, Summary},
 };
-use todoist::sync::{
-    AddItemCommand, AddItemRequest, AddItemRequestArgs, GetUserRequest, Item, ProjectDataRequest,
-    ProjectDataResponse, Response, User,
+
+use tracing_appender::rolling;
+use tracing_subscriber::{
+    filter::{LevelFilter, Targets},
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+    Layer,
 };
-use uuid::Uuid;
+
+mod picker;
+mod progress;
+mod tui;
 
 #[derive(Debug, Parser)]
-#[command(author)]
+#[command(author, after_help = EXIT_CODES)]
+#[allow(clippy::struct_excessive_bools)]
 struct Args {
-    /// Add a new todo to the inbox.
+    /// Add a new todo to the inbox. Supports quick-add syntax: `#Project`,
+    /// `@label`, `p1`-`p4`, `+assignee`, and dates like "tomorrow 5pm".
     #[arg(short, long = "add", name = "TODO")]
     add_todo: Option<String>,
 
-    /// List the items in the inbox.
+    #[command(flatten)]
+    add_options: AddOptions,
+
+    /// List the items in the inbox (shorthand for the `list` command).
     #[arg(short, long = "list")]
     list_inbox: bool,
 
@@ -28,169 +91,7583 @@ struct Args {
     #[arg(long = "sync-url", hide = true)]
     sync_url: Option<String>,
 
+    /// Override the URL for the Todoist REST API (mostly for testing purposes).
+    /// The REST API is only used alongside a custom sync URL if this is given.
+    #[arg(long = "rest-url", hide = true)]
+    rest_url: Option<String>,
+
     /// Override the local app storage directory (mostly for testing purposes).
     #[arg(long = "local-dir", hide = true)]
     local_dir: Option<String>,
-}
 
-#[derive(Deserialize)]
-struct Config {
-    api_key: String,
+    /// How many times to retry requests that fail for transient reasons, like
+    /// rate limiting. Overrides the `retries` setting in the config file.
+    #[arg(long, global = true)]
+    retries: Option<u32>,
+
+    /// How many seconds a request to Todoist can take before it's given up on,
+    /// or 0 for no limit. Overrides the `timeout` setting.
+    #[arg(long, global = true, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// Send requests through this proxy, like `http://proxy.example:8080`.
+    /// Overrides the `proxy` setting, and `HTTPS_PROXY` and `HTTP_PROXY`.
+    #[arg(long, global = true)]
+    proxy: Option<String>,
+
+    /// How to print tasks, projects, and labels: as a table for reading, as
+    /// JSON, or as tab-separated lines for scripts. Other messages go to
+    /// stderr unless the output is a table. Overrides the `output` setting.
+    #[arg(long, global = true, value_enum)]
+    output: Option<Output>,
+
+    /// Print tab-separated lines whose fields stay the same from one version
+    /// to the next, for scripts and status bars, from `list`, `add`, and
+    /// `complete` and the commands like them. Implies `--output plain` and
+    /// `--quiet`.
+    #[arg(long, global = true, conflicts_with = "output", long_help = PORCELAIN_HELP)]
+    porcelain: bool,
+
+    /// Don't use colors, whatever the `color` setting says.
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Log each request to Todoist, with its status and how long it took, to
+    /// stderr. Repeat for more detail: `-vv` adds summaries of what was synced,
+    /// and `-vvv` the requests' bodies.
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Don't show spinners or progress bars while waiting on Todoist. They're
+    /// only shown on terminals to begin with, and not with `--verbose`.
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Print the commands that would be sent to Todoist, as JSON, instead of
+    /// sending them, so nothing changes on the server and nothing is queued.
+    /// `import` shows what it would create instead.
+    #[arg(long, global = true)]
+    dry_run: bool,
+
+    /// Show what's in the local cache, without syncing first. Changes are
+    /// still sent, or queued if Todoist can't be reached.
+    #[arg(long, global = true)]
+    offline: bool,
+
+    /// What to do with changes queued while offline that clash with changes
+    /// made elsewhere since. By default, ask about each one, or fail if
+    /// there's no terminal to ask on.
+    #[arg(long, global = true, value_enum)]
+    on_conflict: Option<Strategy>,
+
+    /// How to show dates, as a strftime format like `%d.%m.%Y` or `%b %-d`.
+    /// Overrides the `date_format` setting.
+    #[arg(long, global = true, value_parser = parse_date_format_arg)]
+    date_format: Option<String>,
+
+    /// Read settings from this file, instead of `config.toml` in the user's
+    /// config directory.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
 }
 
-const SYNC_URL: &str = "https://api.todoist.com/sync/v9";
+/// How commands that show tasks, projects, or labels should go about it,
+/// according to the flags and the config file.
+#[derive(Debug, Clone, Copy)]
+#[allow(clippy::struct_excessive_bools)]
+struct View {
+    output: Output,
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    let args = Args::parse();
+    /// Whether to sync first, rather than showing cached data.
+    sync_first: bool,
 
-    let sync_url = args.sync_url.unwrap_or(SYNC_URL.into());
+    /// Whether to show tasks' descriptions in full, rather than just the start
+    /// of their first lines.
+    full_descriptions: bool,
 
-    let data_dir = if let Some(dir) = args.local_dir {
-        PathBuf::from_str(dir.as_str())?
-    } else if let Some(dir) = dirs::data_local_dir() {
-        dir.join("tuido")
-    } else {
-        return Err("Could not find local data directory.".into());
-    };
+    /// Whether plain output is the stable kind that `--porcelain` asks for.
+    porcelain: bool,
 
-    let api_key = get_api_key(&data_dir)?;
+    /// Whether to show tasks' reminders along with them.
+    reminders: bool,
 
-    // FIXME: probably want to split up the network/file responsibilities here
-    let stored_user = get_stored_user_data(&data_dir, &sync_url, &api_key).await?;
+    /// How to reorder listed tasks, if at all.
+    sort: Option<arrange::Sort>,
 
-    if let Some(new_todo) = args.add_todo {
-        let add_item_response = add_item(
-            &sync_url,
-            &api_key,
-            &stored_user.inbox_project_id,
-            new_todo.clone(),
-        )
-        .await;
+    /// How to regroup listed tasks, if not by section.
+    group_by: Option<arrange::GroupBy>,
+}
 
-        if add_item_response.is_ok() {
-            println!("Todo '{new_todo}' added to inbox.");
+impl View {
+    fn new(args: &Args, settings: &Config) -> Self {
+        let output = match args.output.or(settings.output) {
+            _ if args.porcelain => Output::Plain,
+            output => output.unwrap_or(Output::Table),
+        };
+        View {
+            output,
+            sync_first: settings.sync_on_start.unwrap_or(true),
+            full_descriptions: false,
+            porcelain: args.porcelain,
+            reminders: false,
+            sort: None,
+            group_by: None,
         }
     }
+}
 
-    if args.list_inbox {
-        let get_inbox_response =
-            get_inbox(&sync_url, &api_key, &stored_user.inbox_project_id).await?;
+/// How to read a file of tasks to import.
+#[derive(Debug, clap::Args)]
+struct ImportOptions {
+    /// The file's format, instead of the one its extension suggests: `.csv`
+    /// for CSV, `.org` for Org, and Markdown otherwise.
+    #[arg(long, value_enum, conflicts_with = "from")]
+    format: Option<import::Format>,
 
-        println!("Inbox: ");
-        for Item { content, .. } in get_inbox_response.items {
-            println!("- {content}");
-        }
-    }
+    /// The app the file was exported from, for moving to Todoist:
+    /// `taskwarrior`, for what `task export` writes. Shows what will be
+    /// imported, and what can't be brought over, before asking to go ahead.
+    #[arg(long, value_enum)]
+    from: Option<import::Source>,
 
-    println!("Bye!");
-    Ok(())
+    /// Import from another app without asking first.
+    #[arg(long, requires = "from")]
+    yes: bool,
 }
 
-fn get_api_key(data_dir: &PathBuf) -> Result<String, Box<dyn Error>> {
-    let auth_file_name = "client_auth.toml";
-    let auth_path = Path::new(data_dir).join(auth_file_name);
-    let file = fs::read_to_string(auth_path)?;
-    let config: Config = toml::from_str(file.as_str())?;
-    Ok(config.api_key)
+/// Settings for a new todo, which take precedence over quick-add syntax.
+#[derive(Debug, clap::Args)]
+struct AddOptions {
+    /// When the new todo is due, in natural language, like "tomorrow 5pm" or
+    /// "every month on the 1st". Dates in the todo's text are left alone.
+    #[arg(long, requires = "TODO")]
+    due: Option<String>,
+
+    /// The new todo's priority, from 1 (most urgent) to 4. Also accepts the
+    /// "p1" form.
+    #[arg(short, long, requires = "TODO", value_parser = parse_priority_arg)]
+    priority: Option<u8>,
+
+    /// Notes about the new todo, shown under it.
+    #[arg(short, long, requires = "TODO")]
+    description: Option<String>,
+
+    /// The project to add the new todo to, instead of the inbox. Partial names
+    /// work, as long as they're unambiguous enough.
+    #[arg(long, requires = "TODO")]
+    project: Option<String>,
+
+    /// A label to add to the new todo. Can be given more than once.
+    #[arg(long = "label", name = "LABEL", requires = "TODO")]
+    labels: Vec<String>,
+
+    /// The section of the project to add the new todo to.
+    #[arg(long, requires = "TODO")]
+    section: Option<String>,
+
+    /// The task to add the new todo as a subtask of: its ID, its index in the
+    /// output of the last `list`, or `last`. The subtask goes in the same
+    /// project and section as its parent.
+    #[arg(long, requires = "TODO", conflicts_with_all = ["project", "section"])]
+    parent: Option<String>,
+
+    /// The collaborator to assign the new todo to, by name or email, in shared
+    /// projects.
+    #[arg(long, requires = "TODO")]
+    assign: Option<String>,
+
+    /// How long the new todo will take, like "45m", "1h30m", or "2d".
+    #[arg(long, requires = "TODO", value_parser = parse_duration_arg)]
+    duration: Option<TaskDuration>,
+
+    /// Check for open tasks in the same project that look like the new todo
+    /// first, and warn about them, or, with `--dedupe=skip`, leave the todo
+    /// out. Overrides the `dedupe` setting.
+    #[arg(
+        long,
+        value_enum,
+        require_equals = true,
+        num_args = 0..=1,
+        default_missing_value = "warn"
+    )]
+    dedupe: Option<dedupe::Mode>,
 }
 
-async fn get_stored_user_data(
-    data_dir: &PathBuf,
-    sync_url: &String,
-    api_key: &String,
-) -> Result<User, Box<dyn Error>> {
-    let user_storage_path = Path::new(data_dir).join("data").join("user.json");
+fn parse_duration_arg(arg: &str) -> Result<TaskDuration, String> {
+    schedule::parse_duration(arg).ok_or("expected a duration like 45m, 1h30m, or 2d".to_string())
+}
 
-    if user_storage_path.exists() {
-        let file = fs::read_to_string(user_storage_path)?;
-        let user = serde_json::from_str::<User>(&file)?;
-        Ok(user)
+/// The change that `edit --duration` makes: none, if it isn't given, and
+/// clearing the duration, if it's empty.
+#[allow(clippy::option_option)]
+fn duration_change(arg: Option<&str>) -> Result<Option<Option<TaskDuration>>, String> {
+    match arg.map(str::trim) {
+        Some("") => Ok(Some(None)),
+        Some(text) => Ok(Some(Some(parse_duration_arg(text)?))),
+        None => Ok(None),
+    }
+}
+
+fn parse_priority_arg(arg: &str) -> Result<u8, String> {
+    arg.parse()
+        .ok()
+        .filter(|priority| (1..=4).contains(priority))
+        .or_else(|| quick_add::parse_priority(arg))
+        .ok_or("expected a priority from 1 to 4, or p1 to p4".to_string())
+}
+
+fn parse_date_arg(arg: &str) -> Result<NaiveDate, String> {
+    let today = Local::now().date_naive();
+    match arg.to_lowercase().as_str() {
+        "today" => Ok(today),
+        "yesterday" => today
+            .checked_sub_days(Days::new(1))
+            .ok_or("expected a date".to_string()),
+        _ => NaiveDate::parse_from_str(arg, "%Y-%m-%d")
+            .map_err(|_| "expected a date like 2024-05-01, or today or yesterday".to_string()),
+    }
+}
+
+fn parse_date_format_arg(arg: &str) -> Result<String, String> {
+    if locale::is_valid_date_format(arg) {
+        Ok(arg.to_string())
     } else {
-        let user = get_user(sync_url, api_key).await?;
-        // store in file
-        println!("Storing user data in '{}'.", user_storage_path.display());
-        fs::create_dir_all(Path::new(data_dir).join("data"))?;
-        let file = fs::File::create(user_storage_path)?;
-        serde_json::to_writer_pretty(file, &user)?;
-        Ok(user)
+        Err("expected a strftime format, like %d.%m.%Y".to_string())
     }
 }
 
-async fn add_item(
-    sync_url: &str,
-    api_key: &str,
-    project_id: &str,
-    item: String,
-) -> Result<Response, Box<dyn Error>> {
-    let request_body = AddItemRequest {
-        sync_token: "*".to_string(),
-        resource_types: vec![],
-        commands: vec![AddItemCommand {
-            request_type: "item_add".to_string(),
-            args: AddItemRequestArgs {
-                project_id: project_id.to_string(),
-                content: item,
-            },
-            temp_id: Uuid::new_v4(),
-            uuid: Uuid::new_v4(),
-        }],
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// List the tasks in the inbox, or in another project.
+    List {
+        /// The name of a favorite project, label, or saved filter to list, as
+        /// picked from the sidebar in the official apps.
+        #[arg(conflicts_with_all = ["project", "filter"])]
+        favorite: Option<String>,
+
+        /// The name of the project to list instead of the inbox.
+        #[arg(long)]
+        project: Option<String>,
+
+        /// List the tasks matching a Todoist filter, like "today & #Work" or
+        /// "@email | p1", from all projects.
+        #[arg(long, conflicts_with = "project")]
+        filter: Option<String>,
+
+        /// List the tasks matching one of the filters saved in the Todoist
+        /// account, by name.
+        #[arg(long, conflicts_with_all = ["project", "filter", "favorite"])]
+        filter_name: Option<String>,
+
+        /// Only list the tasks assigned to you, in shared projects.
+        #[arg(long)]
+        assigned_to_me: bool,
+
+        /// Show tasks' descriptions in full, instead of just the start of
+        /// them.
+        #[arg(long)]
+        long: bool,
+
+        /// Show tasks' reminders too, including the ones at places.
+        #[arg(long)]
+        with_reminders: bool,
+
+        /// Sort the tasks, instead of listing them in the order they're in
+        /// in Todoist.
+        #[arg(long, value_enum)]
+        sort: Option<arrange::Sort>,
+
+        /// Group the tasks under a heading for each project, label, or due
+        /// date, instead of by section.
+        #[arg(long, value_enum)]
+        group_by: Option<arrange::GroupBy>,
+
+        /// Only list tasks in the projects of the workspace with this name, in
+        /// business accounts. Without a filter, lists every open task in them.
+        #[arg(long, conflicts_with = "project")]
+        workspace: Option<String>,
+
+        /// Exit with code 3 if any tasks are listed, like to keep from
+        /// shutting down while there are overdue tasks.
+        #[arg(long, conflicts_with = "fail_if_empty")]
+        fail_if_nonempty: bool,
+
+        /// Exit with code 3 if no tasks are listed.
+        #[arg(long)]
+        fail_if_empty: bool,
+    },
+
+    /// List favorite projects, labels, and saved filters, like the sidebar in
+    /// the official apps does. Any of them can be listed with `list <name>`.
+    #[command(name = "fav", alias = "favorites")]
+    Favorites,
+
+    /// Set things up step by step: save an API token, download everything,
+    /// and pick where new todos go. Runs by itself the first time, if there's
+    /// no token yet and this is a terminal.
+    Setup,
+
+    /// Save a Todoist API token, after checking that it works.
+    Auth {
+        /// The API token, found under Settings > Integrations > Developer.
+        #[arg(required_unless_present = "migrate_to_keyring")]
+        token: Option<String>,
+
+        /// Move the saved token from its file to the system keyring, and use the
+        /// keyring from now on.
+        #[arg(long, conflicts_with = "token")]
+        migrate_to_keyring: bool,
+    },
+
+    /// Add a new todo, or with `--stdin`, one todo for each line of stdin.
+    /// Supports quick-add syntax, like `--add`.
+    Add {
+        #[arg(name = "TODO", required_unless_present = "stdin")]
+        todo: Option<String>,
+
+        /// Read todos from stdin, one per line, and send them in as few
+        /// requests as possible. Blank lines are skipped.
+        #[arg(long, conflicts_with = "TODO")]
+        stdin: bool,
+
+        #[command(flatten)]
+        options: AddOptions,
+    },
+
+    /// Add a new todo, letting Todoist parse it the way the quick-add box in
+    /// its apps does, as in `quick "Lunch with Sam tomorrow 1pm #Personal
+    /// @food"`. Needs a connection, unlike `add`, which parses the text itself
+    /// and can queue the todo.
+    Quick {
+        #[arg(name = "TODO")]
+        text: String,
+    },
+
+    /// Mark a task as done. Recurring tasks move on to their next occurrence.
+    Complete {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task. If it's left out, pick
+        /// the task from a list.
+        task: Option<String>,
+
+        /// Complete a recurring task for good, instead of just this occurrence.
+        #[arg(long)]
+        all_occurrences: bool,
+    },
+
+    /// Change a task's content, due date, priority, or labels.
+    #[command(group(clap::ArgGroup::new("changes").required(true).multiple(true)))]
+    Edit {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task. If it's left out, pick
+        /// the task from a list.
+        task: Option<String>,
+
+        /// The task's new text.
+        #[arg(long, group = "changes")]
+        content: Option<String>,
+
+        /// The task's new description. Pass an empty string to remove it.
+        #[arg(short, long, group = "changes")]
+        description: Option<String>,
+
+        /// When the task is due, in natural language, like "tomorrow 5pm".
+        #[arg(long, group = "changes")]
+        due: Option<String>,
+
+        /// The task's new priority, from 1 (most urgent) to 4. Also accepts the
+        /// "p1" form.
+        #[arg(short, long, group = "changes", value_parser = parse_priority_arg)]
+        priority: Option<u8>,
+
+        /// The task's labels, separated by commas, replacing its current ones.
+        /// Pass an empty string to remove them all.
+        #[arg(long, group = "changes", value_delimiter = ',')]
+        labels: Option<Vec<String>>,
+
+        /// The collaborator to assign the task to, by name or email. Pass an
+        /// empty string to unassign it.
+        #[arg(long, group = "changes")]
+        assign: Option<String>,
+
+        /// How long the task will take, like "45m", "1h30m", or "2d". Pass an
+        /// empty string to remove its duration.
+        #[arg(long, group = "changes")]
+        duration: Option<String>,
+    },
+
+    /// Move a task to another project or section, or to another position
+    /// among its siblings.
+    #[command(group(clap::ArgGroup::new("destination").required(true).multiple(true)))]
+    Move {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task. If it's left out, pick
+        /// the task from a list.
+        task: Option<String>,
+
+        /// The project to move the task to.
+        #[arg(long, group = "destination")]
+        to_project: Option<String>,
+
+        /// The section to move the task to, in the project it's moving to (or
+        /// the one it's in).
+        #[arg(long, group = "destination")]
+        to_section: Option<String>,
+
+        /// Where to put the task among its new siblings: `top`, `bottom`, or
+        /// `after <task>`.
+        #[arg(long, group = "destination", num_args = 1..=2, value_names = ["WHERE", "TASK"])]
+        position: Option<Vec<String>>,
+    },
+
+    /// Push a task's due date back, to a time like `+2h`, `+3d`, `tomorrow`,
+    /// or `next monday`. The new date replaces the old one, recurrence and
+    /// all, the way `edit --due` does.
+    Snooze {
+        /// The task and when to snooze it until. The task is its ID, its index
+        /// in the output of the last `list`, or `last` for the most recently
+        /// added task; if it's left out, pick the task from a list. Offsets
+        /// like `+2h` count from now.
+        #[arg(required = true, num_args = 1..=2, value_names = ["TASK", "WHEN"])]
+        args: Vec<String>,
+
+        /// Snooze every overdue task instead of one, until WHEN.
+        #[arg(long)]
+        all_overdue: bool,
+    },
+
+    /// Have a task wait for another: it's left out of `list`, `today`, and
+    /// `upcoming` until the other is completed. Todoist doesn't have
+    /// dependencies, so they're only kept on this computer. Without `--on`,
+    /// shows what the task waits for.
+    Depend {
+        /// The task that waits: its ID, its index in the output of the last
+        /// `list`, or `last` for the most recently added task.
+        task: String,
+
+        /// The task to complete first, given the same way.
+        #[arg(long)]
+        on: Option<String>,
+
+        /// Stop the task from waiting for the other one instead.
+        #[arg(long, requires = "on")]
+        remove: bool,
+    },
+
+    /// Permanently delete a task. A copy of it is kept in the local trash,
+    /// for `trash restore`.
+    Delete {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task. If it's left out, pick
+        /// the task from a list.
+        task: Option<String>,
+
+        /// Don't ask for confirmation.
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Reverse the latest completion, deletion, or project archiving that
+    /// hasn't been undone yet. Deleted tasks are restored as new tasks.
+    Undo,
+
+    /// Mark a completed task as not done, putting it back in its project.
+    Reopen {
+        /// The ID of the task, or its index in the output of the last
+        /// `completed`.
+        task: String,
+    },
+
+    /// See the tasks deleted from here, and add them back.
+    Trash {
+        #[command(subcommand)]
+        command: TrashCommand,
+    },
+
+    /// Open a task or project in Todoist's web app, or the desktop app.
+    Open {
+        /// The task's ID, its index in the output of the last `list`, `last`
+        /// for the most recently added task, or some of its content. Projects
+        /// go by name (with or without a leading `#`) or ID. If it's left out,
+        /// pick a task from a list.
+        target: Option<String>,
+
+        /// Open the desktop or mobile app, with a `todoist://` link, instead of
+        /// the web app.
+        #[arg(long)]
+        app: bool,
+
+        /// Print the link instead of opening it.
+        #[arg(long)]
+        print: bool,
+    },
+
+    /// Find open tasks whose content or description matches some text, best
+    /// matches first. Searches the local cache, after syncing it.
+    Search {
+        /// The text to look for. Doesn't have to be exact: "dentst" finds
+        /// "Call the dentist".
+        query: String,
+
+        /// Only search the tasks in this project.
+        #[arg(long)]
+        project: Option<String>,
+
+        /// Only search the tasks with this label.
+        #[arg(long)]
+        label: Option<String>,
+    },
+
+    /// List completed tasks, most recently completed first, with who
+    /// completed them in shared projects.
+    Completed {
+        /// Only list tasks completed on or after this date, like 2024-05-01,
+        /// or "today" or "yesterday".
+        #[arg(long, value_parser = parse_date_arg)]
+        since: Option<NaiveDate>,
+
+        /// Only list tasks from this project.
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Show the latest tasks to be added, completed, or updated, with who did
+    /// it in shared projects.
+    Activity {
+        /// How many events to show.
+        #[arg(long, default_value_t = 30)]
+        limit: usize,
+
+        /// Only show events from this project.
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// See archived projects and the completed tasks in projects, which
+    /// syncing leaves out. They're kept in the local cache once fetched.
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommand,
+    },
+
+    /// Go through the inbox one task at a time, giving each a project, a due
+    /// date, or labels, or completing or deleting it.
+    Triage,
+
+    /// List overdue tasks and tasks due today, from all projects.
+    Today {
+        /// Lay out today's tasks as a timeline instead, by their due times and
+        /// durations, with the free time between them.
+        #[arg(long)]
+        schedule: bool,
+    },
+
+    /// List overdue tasks and tasks due in the next few days, from all projects.
+    Upcoming {
+        /// How many days to show, starting with today.
+        #[arg(long, default_value_t = 7)]
+        days: u64,
+    },
+
+    /// Show how many tasks are due on each of the coming days, as a heat strip
+    /// by week, with recurring tasks counted every time they come back.
+    Forecast {
+        /// How many days to show, starting with today.
+        #[arg(long, default_value_t = 30)]
+        days: u64,
+    },
+
+    /// Review the week so far, since Monday: the tasks completed, by project,
+    /// the tasks added, the ones still overdue, and the ones that have to be
+    /// done in the next week.
+    Review {
+        /// Write the review as Markdown, for pasting into notes.
+        #[arg(long)]
+        markdown: bool,
+    },
+
+    /// Focus on a task for a while, with a countdown, and keep track of the
+    /// time spent on each task. Ctrl-c stops early. Without a task, shows the
+    /// time spent on each task so far.
+    Focus {
+        /// The task's ID, or its index in the output of the last `list`.
+        task: Option<String>,
+
+        /// How long to focus for.
+        #[arg(long, default_value_t = focus::DEFAULT_MINUTES)]
+        minutes: u64,
+
+        /// Once the time is up, add a comment to the task noting the session.
+        #[arg(long)]
+        comment: bool,
+    },
+
+    /// Track the time spent working on tasks, with a timer.
+    Track {
+        #[command(subcommand)]
+        command: TrackCommand,
+    },
+
+    /// Complete or comment on the tasks that git commits refer to, with
+    /// `todoist:<id>` or a link to the task. After "closes" or "fixes", the
+    /// task is completed once the commit is pushed; otherwise the commit is
+    /// noted on it in a comment.
+    Hook {
+        #[command(subcommand)]
+        command: HookCommand,
+    },
+
+    /// Send desktop notifications for tasks as they become due, and again when
+    /// they become overdue, once each. Checks once, for running from cron,
+    /// unless `--watch` is given.
+    Notify {
+        /// Keep running, checking every `--interval` minutes.
+        #[arg(long)]
+        watch: bool,
+
+        /// How often to check, in minutes, with `--watch`.
+        #[arg(long, default_value_t = 1, requires = "watch")]
+        interval: u64,
+    },
+
+    /// Print a one-line summary of what's due, like "3 due today, 1 overdue",
+    /// for status bars to show. Uses the local cache without syncing, so it's
+    /// quick enough to run every few seconds; `daemon` can keep it up to date.
+    Status {
+        /// The status bar to write the summary for, instead of just the text.
+        #[arg(long, value_enum)]
+        format: Option<widget::Format>,
+    },
+
+    /// Browse and edit tasks in an interactive terminal UI.
+    Tui,
+
+    /// Keep running, syncing every so often, so that other commands can show
+    /// cached data straight away instead of syncing first. Stop it with
+    /// ctrl-c.
+    Daemon {
+        /// How often to sync, in seconds.
+        #[arg(long, default_value_t = 60)]
+        interval: u64,
+    },
+
+    /// Keep running, receiving Todoist's webhooks and updating the local cache
+    /// as tasks change, and running the `webhook_hooks` in the config file for
+    /// each event. Requests must be signed with the client secret of the app
+    /// the webhooks belong to, which is read from `TODOIST_CLIENT_SECRET`.
+    /// Todoist only sends webhooks to HTTPS addresses, so this usually sits
+    /// behind a reverse proxy. Stop it with ctrl-c.
+    ServeWebhooks {
+        /// The port to listen on.
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+
+        /// The address to listen on.
+        #[arg(long, default_value = "127.0.0.1")]
+        host: std::net::IpAddr,
+    },
+
+    /// Bring the local cache up to date with Todoist now, sending any queued
+    /// changes, and say what changed.
+    Sync {
+        /// Download everything again, instead of only what changed since the
+        /// last sync.
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Look after the local cache.
+    Maintenance {
+        #[command(subcommand)]
+        command: MaintenanceCommand,
+    },
+
+    /// Send several actions to Todoist in a single request, as in
+    /// `batch add "buy milk" add "call mom" complete 3`.
+    Batch {
+        /// Actions, each followed by its argument: `add <todo>` (in quick-add
+        /// syntax) or `complete <task>`.
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        actions: Vec<String>,
+
+        /// Read the actions from a file instead, one per line, or from stdin if
+        /// the path is `-`.
+        #[arg(long, conflicts_with = "actions")]
+        file: Option<PathBuf>,
+    },
+
+    /// Work with projects.
+    Projects {
+        #[command(subcommand)]
+        command: ProjectsCommand,
+    },
+
+    /// List who a project is shared with, including anyone who's been invited
+    /// but hasn't joined yet.
+    Collaborators {
+        /// The project, by name.
+        project: String,
+    },
+
+    /// Invite someone to a project, by their email address.
+    Share {
+        /// The project, by name.
+        project: String,
+
+        email: String,
+    },
+
+    /// See what others in shared projects have sent: invitations to
+    /// projects, tasks assigned to you, and comments for you.
+    Notifications {
+        /// Only show the ones that haven't been read.
+        #[arg(long)]
+        unread: bool,
+
+        #[command(subcommand)]
+        command: Option<NotificationsCommand>,
+    },
+
+    /// Work with labels.
+    Labels {
+        #[command(subcommand)]
+        command: LabelsCommand,
+    },
+
+    /// See the filters saved in the Todoist account.
+    Filters {
+        #[command(subcommand)]
+        command: FiltersCommand,
+    },
+
+    /// Read or write comments on a task.
+    Comment {
+        #[command(subcommand)]
+        command: CommentCommand,
+    },
+
+    /// Manage a task's reminders.
+    Reminder {
+        #[command(subcommand)]
+        command: ReminderCommand,
+    },
+
+    /// Save projects as templates, and make new projects from them, like a
+    /// packing list for each trip. Templates are kept on this computer.
+    Template {
+        #[command(subcommand)]
+        command: TemplateCommand,
+    },
+
+    /// Write tasks to a file, or to stdout: as Markdown checklists, CSV, JSON,
+    /// or an Org outline. Uses the local cache, after syncing it.
+    Export {
+        #[arg(long, value_enum)]
+        format: export::Format,
+
+        /// Only export the tasks in this project.
+        #[arg(long)]
+        project: Option<String>,
+
+        /// The file to write to, instead of stdout.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Write the tasks with due dates as an iCalendar feed, for calendar apps
+    /// to subscribe to. Recurring tasks repeat in the calendar too, when their
+    /// due dates can be put that way. Uses the local cache, after syncing it.
+    Ical {
+        /// Only include the tasks in this project.
+        #[arg(long)]
+        project: Option<String>,
+
+        /// The file to write to, instead of stdout, like `tasks.ics`.
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Add the tasks in a Markdown checklist, a CSV file, or an Org file,
+    /// creating any projects and sections they name that don't exist yet.
+    /// Indented checklist items and nested headlines become subtasks. CSV
+    /// files need a header row with a `content` column, and can also have the
+    /// columns that `export --format csv` writes. In Org files, TODO headlines
+    /// are tasks, with their scheduled dates as due dates and tags as labels.
+    /// With `--from`, it reads another app's export instead.
+    Import {
+        file: PathBuf,
+
+        #[command(flatten)]
+        options: ImportOptions,
+
+        /// The project for tasks that don't name one, instead of the default
+        /// project or the inbox.
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Add the tasks from a checklist, like one for moving house, with due
+    /// dates counted from when it's started.
+    Checklist {
+        #[command(subcommand)]
+        command: ChecklistCommand,
+    },
+
+    /// Show or change settings in the config file.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Print a script that sets up tab completion for a shell.
+    ///
+    /// The names of projects and labels are taken from the local cache when
+    /// the script is printed, so loading it fresh in each shell (as in
+    /// `source <(todoist completions zsh)`) keeps them up to date. (PowerShell
+    /// only completes commands and flags.)
+    Completions { shell: Shell },
+}
+
+#[derive(Debug, Subcommand)]
+enum HookCommand {
+    /// Install the `commit-msg` and `pre-push` hooks in the current git
+    /// repository.
+    Install {
+        /// Replace hooks that something else installed.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Show which tasks a commit message refers to, and what will happen to
+    /// them. Git runs this with the file the message is in.
+    CommitMsg { file: PathBuf },
+
+    /// Complete or comment on the tasks that the commits being pushed refer
+    /// to. Git runs this with the remote's name and URL, and the refs being
+    /// pushed on stdin.
+    PrePush {
+        #[arg(default_value = "origin")]
+        remote: String,
+
+        url: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TrackCommand {
+    /// Start the timer on a task, stopping it first if it's running on
+    /// another one.
+    Start {
+        /// The task's ID, or its index in the output of the last `list`.
+        /// Without one, pick a task.
+        task: Option<String>,
+    },
+
+    /// Stop the timer, and record the time worked on its task.
+    Stop {
+        /// Add a comment to the task noting the time worked. The
+        /// `track_comments` setting does this every time.
+        #[arg(long)]
+        comment: bool,
+    },
+
+    /// Show which task the timer is running on, and for how long.
+    Status,
+
+    /// Add up the time worked on each project, and on each task in it.
+    Report {
+        /// Only count time worked this week, since Monday.
+        #[arg(long)]
+        week: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Show a setting's value, or all settings if no key is given.
+    Get { key: Option<String> },
+
+    /// Change a setting. `todoist config get` lists every key, with its value.
+    Set { key: String, value: String },
+
+    /// Go back to a setting's default.
+    Unset { key: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum LabelsCommand {
+    /// Show all labels.
+    List,
+
+    /// Create a new label.
+    Add { name: String },
+
+    /// Change a label's name, on every task that has it.
+    Rename {
+        /// The label's current name.
+        label: String,
+
+        new_name: String,
+    },
+
+    /// Delete a label, removing it from every task that has it.
+    Delete {
+        label: String,
+
+        /// Don't ask for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum CommentCommand {
+    /// Add a comment to a task.
+    Add {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task.
+        task: String,
+
+        text: String,
+    },
+
+    /// Show a task's comments, oldest first.
+    List {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task.
+        task: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ReminderCommand {
+    /// Show a task's reminders, with their IDs.
+    List {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task.
+        task: String,
+    },
+
+    /// Add a reminder to a task.
+    Add {
+        /// The ID of the task, its index in the output of the last `list`, or
+        /// `last` for the most recently added task.
+        task: String,
+
+        /// When to be reminded: either relative to when the task is due, like
+        /// "30 minutes before" or "1h before", or a time like "tomorrow 9am".
+        #[arg(required_unless_present = "location")]
+        when: Option<String>,
+
+        /// Be reminded at a place instead, written as "latitude,longitude,radius"
+        /// with the radius in meters, like "40.7128,-74.0060,100". The radius
+        /// can be left off.
+        #[arg(long, conflicts_with = "when")]
+        location: Option<String>,
+
+        /// What to call the place, instead of its coordinates.
+        #[arg(long, requires = "location")]
+        name: Option<String>,
+
+        /// Be reminded when leaving the place, instead of when arriving.
+        #[arg(long, requires = "location")]
+        leaving: bool,
+    },
+
+    /// Delete a reminder.
+    Delete {
+        /// The reminder's ID, as shown by `reminder list`.
+        id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ProjectsCommand {
+    /// Show all projects, with subprojects nested under their parents.
+    List {
+        /// Show how many open tasks, overdue tasks, and sections each project
+        /// has, according to the local cache.
+        #[arg(long)]
+        counts: bool,
+
+        /// List projects by one of the counts, largest first, instead of as a
+        /// tree.
+        #[arg(long, value_enum, requires = "counts")]
+        sort: Option<SortBy>,
+
+        /// Only show the projects of the workspace with this name, in business
+        /// accounts.
+        #[arg(long)]
+        workspace: Option<String>,
+    },
+
+    /// Create a new project.
+    Add {
+        name: String,
+
+        /// The name of an existing project to nest the new one under.
+        #[arg(long)]
+        parent: Option<String>,
+    },
+
+    /// Change a project's name.
+    Rename {
+        /// The project's current name.
+        project: String,
+
+        new_name: String,
+    },
+
+    /// Archive a project, hiding it and its tasks until it's unarchived.
+    Archive {
+        project: String,
+
+        /// Don't ask for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+
+    /// Move a project to another position among its siblings.
+    Reorder {
+        project: String,
+
+        /// Where to put the project: `top`, `bottom`, or `after <project>`.
+        #[arg(long, required = true, num_args = 1..=2, value_names = ["WHERE", "PROJECT"])]
+        position: Vec<String>,
+    },
+
+    /// Permanently delete a project, along with all of its tasks.
+    Delete {
+        project: String,
+
+        /// Don't ask for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum FiltersCommand {
+    /// Show all saved filters, with their queries. Any of them can be listed
+    /// with `list --filter-name <name>`.
+    List,
+}
+
+#[derive(Debug, Subcommand)]
+enum ArchiveCommand {
+    /// Show archived projects.
+    List,
+
+    /// Show the completed tasks in a project, archived or not, most recently
+    /// completed first.
+    Show { project: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum NotificationsCommand {
+    /// Mark notifications as read: the ones with these IDs, as shown by
+    /// `--output plain`, or else all of them.
+    MarkRead { ids: Vec<String> },
+}
+
+#[derive(Debug, Subcommand)]
+enum TrashCommand {
+    /// Show the deleted tasks, most recently deleted first.
+    List,
+
+    /// Add a deleted task back, with its content, due date, labels, and so
+    /// on. It gets a new ID. If its project is gone too, it goes to the inbox.
+    Restore {
+        /// The ID the task had, as shown by `trash list`.
+        id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ChecklistCommand {
+    /// Add a checklist's tasks, all at once, each due as many days from the
+    /// start as it says, like "D+2" or "D-14".
+    Run {
+        file: PathBuf,
+
+        /// The day the checklist starts on, like 2024-05-01, or "today".
+        #[arg(long, value_parser = parse_date_arg)]
+        start: Option<NaiveDate>,
+
+        /// The file's format, instead of the one its extension suggests:
+        /// `.csv` for CSV, and YAML otherwise.
+        #[arg(long, value_enum)]
+        format: Option<checklist::Format>,
+
+        /// The project for tasks that don't name one, instead of the default
+        /// project or the inbox.
+        #[arg(long)]
+        project: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum TemplateCommand {
+    /// Save a project's sections and open tasks as a template, replacing any
+    /// template with the same name. Due dates are left out.
+    Save {
+        /// The project to save.
+        project: String,
+
+        /// What to call the template.
+        name: String,
+    },
+
+    /// Add a template's sections and tasks to a project, creating it if it
+    /// doesn't exist.
+    Apply {
+        name: String,
+
+        /// The project to add them to, instead of one named after the
+        /// template.
+        #[arg(long)]
+        project: Option<String>,
+    },
+
+    /// Show the saved templates.
+    List,
+
+    /// Delete a saved template.
+    Delete { name: String },
+}
+
+#[derive(Debug, Subcommand)]
+enum MaintenanceCommand {
+    /// Drop completed tasks from the local cache once they've been completed
+    /// for a while, along with the comments and reminders for tasks that
+    /// aren't cached anymore, then compact the cache. Nothing changes in
+    /// Todoist.
+    Prune {
+        /// How many days to keep completed tasks for, instead of
+        /// `keep_completed_days` in the config file. Without either, only
+        /// what's left over is dropped.
+        #[arg(long)]
+        keep_completed: Option<u32>,
+    },
+}
+
+/// Where to find the API token in Todoist's web app.
+const DEVELOPER_SETTINGS_URL: &str = "https://app.todoist.com/app/settings/integrations/developer";
+
+/// What's shown when there's no token and no terminal to run `setup` in.
+const NO_TOKEN: &str =
+    "No API token found. Run `todoist setup`, or `todoist auth <token>`, to add one.";
+
+/// What `auth` says when Todoist rejects the token.
+const REJECTED_TOKEN: &str = "Todoist did not accept that API token.";
+
+#[derive(Clone, Deserialize, Serialize)]
+struct AuthConfig {
+    /// Missing if the token is kept in the system keyring instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_key: Option<String>,
+}
+
+/// The exit code after ctrl-c, the one shells use for being interrupted.
+const CANCELLED: u8 = 130;
+
+/// The exit code when a `--fail-if-*` check doesn't pass.
+const CHECK_FAILED: u8 = 3;
+
+/// The exit code when Todoist doesn't accept the API token, or there isn't
+/// one.
+const AUTH_FAILED: u8 = 4;
+
+/// The exit code when a request to Todoist fails, or can't be sent at all.
+const NETWORK_FAILED: u8 = 5;
+
+/// The exit code when Todoist refuses a command.
+const REJECTED: u8 = 6;
+
+/// The exit codes, which [`EXIT_CODES`] and [`PORCELAIN_HELP`] list.
+macro_rules! exit_codes {
+    () => {
+        "\
+Exit codes:
+  0    Success
+  1    Any other error
+  2    Invalid arguments
+  3    A --fail-if-* check didn't pass
+  4    Todoist didn't accept the API token, or there isn't one
+  5    A request to Todoist failed, or couldn't be sent
+  6    Todoist refused a command
+  130  Cancelled with ctrl-c"
     };
+}
+
+const EXIT_CODES: &str = exit_codes!();
+
+/// The long help for `--porcelain`, with the fields that
+/// [`porcelain`](todoist::porcelain) prints.
+const PORCELAIN_HELP: &str = concat!(
+    "\
+Print tab-separated lines whose fields stay the same from one version to the
+next, for scripts and status bars, from `list`, `add`, and `complete` and the
+commands like them. Implies `--output plain` and `--quiet`. New fields are only
+ever added to the ends of lines.
+
+Commands that list tasks print a line for each task with:
+  1  its ID
+  2  its content
+  3  its project's ID
+  4  its section's ID, if it's in one
+  5  its parent's ID, if it's a subtask
+  6  its due date, like 2024-05-01, 2024-05-01T09:00:00, or 2024-05-01T16:00:00Z
+  7  its priority, from p1 (most urgent) to p4
+  8  its labels, separated by commas
+  9  true if it's completed, and false otherwise
+
+Commands that add or complete tasks print a line for each task with:
+  1  added, completed, queued, or skipped
+  2  its ID, which is a temporary one while it's queued
+  3  its content
+
+Text fields have their tabs and line breaks turned into spaces, and empty
+fields are left empty. Other messages go to stderr, and errors end with a
+nonzero exit code.
 
-    let resp = reqwest::Client::new()
-        .post(format!("{sync_url}/sync"))
-        .header("Authorization", format!("Bearer {api_key}"))
-        .json(&request_body)
-        .send()
-        .await
-        .map(reqwest::Response::json)?
-        .await;
-    Ok(resp?)
+",
+    exit_codes!()
+);
+
+/// A `--fail-if-*` check on what a command found that didn't pass.
+#[derive(Debug)]
+struct CheckFailed(String);
+
+impl std::fmt::Display for CheckFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
 }
 
-async fn get_inbox(
-    sync_url: &str,
-    api_key: &str,
-    project_id: &str,
-) -> Result<ProjectDataResponse, Box<dyn Error>> {
-    let request_body = ProjectDataRequest {
-        project_id: project_id.to_owned(),
-    };
-
-    let resp = reqwest::Client::new()
-        .post(format!("{sync_url}/projects/get_data"))
-        .header("Authorization", format!("Bearer {api_key}"))
-        .json(&request_body)
-        .send()
-        .await
-        .map(reqwest::Response::json)?
-        .await;
-
-    Ok(resp?)
-}
-
-async fn get_user(sync_url: &String, api_key: &String) -> Result<User, Box<dyn Error>> {
-    print!("Fetching user data... ");
-    let request_body = GetUserRequest {
-        sync_token: "*".to_string(),
-        resource_types: vec!["user".to_string()],
-        commands: vec![],
-    };
-
-    let client = reqwest::Client::new();
-    let resp = client
-        .post(format!("{sync_url}/sync"))
-        .header("Authorization", format!("Bearer {api_key}"))
-        .json(&request_body)
-        .send()
-        .await
-        .map(reqwest::Response::json::<Response>)?
-        .await?;
+impl Error for CheckFailed {}
 
-    if let Some(user) = resp.user {
-        println!("done.");
-        Ok(user)
-    } else {
-        Err("Server response did not contain user information".into())
+/// Todoist not accepting the API token, or there not being one.
+#[derive(Debug)]
+struct AuthFailed(&'static str);
+
+impl std::fmt::Display for AuthFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.0)
     }
 }
+
+impl Error for AuthFailed {}
+
+/// The exit code for an error, as [`EXIT_CODES`] lists them.
+fn exit_code(err: &(dyn Error + 'static)) -> u8 {
+    if err.is::<CheckFailed>() {
+        return CHECK_FAILED;
+    }
+    if err.is::<AuthFailed>() {
+        return AUTH_FAILED;
+    }
+    match err.downcast_ref() {
+        Some(todoist::Error::Auth) => AUTH_FAILED,
+        Some(todoist::Error::Network(_)) => NETWORK_FAILED,
+        Some(todoist::Error::CommandRejected { .. }) => REJECTED,
+        _ => 1,
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = Args::parse();
+    // the daemon, the webhook server, the TUI, and focus sessions handle
+    // ctrl-c themselves
+    let result = if matches!(
+        args.command,
+        Some(
+            Commands::Daemon { .. }
+                | Commands::ServeWebhooks { .. }
+                | Commands::Tui
+                | Commands::Focus { .. }
+        )
+    ) {
+        run(args).await
+    } else {
+        // stopping `run` cancels whatever request is in flight. The store is
+        // only written to in between them, so it's left as it was after the
+        // last one. Queued commands that may or may not have gone out stay
+        // queued, and Todoist ignores any it sees twice, by their UUIDs.
+        tokio::select! {
+            result = run(args) => result,
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("Cancelled.");
+                return ExitCode::from(CANCELLED);
+            }
+        }
+    };
+    let Err(err) = result else {
+        return ExitCode::SUCCESS;
+    };
+    if let Some(todoist::Error::DryRun(commands)) = err.downcast_ref() {
+        return print_dry_run(commands);
+    }
+    if err.is::<CheckFailed>() {
+        eprintln!("{err}");
+    } else {
+        eprintln!("Error: {err}");
+    }
+    ExitCode::from(exit_code(err.as_ref()))
+}
+
+/// Prints the commands that a dry run stopped from being sent.
+fn print_dry_run(commands: &[Command]) -> ExitCode {
+    match serde_json::to_string_pretty(commands) {
+        Ok(json) => {
+            println!("{json}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn set_up_colors(no_color: bool, settings: &Config) {
+    color::set_choice(if no_color {
+        color::Choice::Never
+    } else {
+        settings.color.unwrap_or_default()
+    });
+    color::set_theme(settings.theme.unwrap_or_default());
+}
+
+/// Reads and shows dates the way the user wants them, in the timezone of
+/// their Todoist account.
+fn set_up_locale(date_format: Option<String>, settings: &Config, user: &User) {
+    let lang = settings.locale.clone().or_else(|| {
+        user.lang
+            .clone()
+            .filter(|lang| locale::LANGUAGES.contains(&lang.as_str()))
+    });
+    locale::set(Locale {
+        date_format: date_format.or(settings.date_format.clone()),
+        lang,
+        offset: user
+            .tz_info
+            .as_ref()
+            .and_then(|tz_info| locale::parse_offset(&tz_info.gmt_string)),
+    });
+}
+
+/// How many days of log files are kept.
+const LOG_FILES_KEPT: usize = 7;
+
+/// Logs the app's events to stderr, at a level that depends on how many times
+/// `--verbose` was given, and to a file in `log_dir` (rotated daily) if there
+/// is one, at the debug level.
+fn set_up_logging(verbosity: u8, log_dir: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let stderr_level = match verbosity {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .with_ansi(color::enabled() && std::io::stderr().is_terminal())
+        .without_time()
+        .with_target(false)
+        .with_filter(Targets::new().with_target("todoist", stderr_level));
+
+    let file = match log_dir {
+        Some(dir) => {
+            let appender = rolling::Builder::new()
+                .rotation(rolling::Rotation::DAILY)
+                .filename_prefix("todoist")
+                .filename_suffix("log")
+                .max_log_files(LOG_FILES_KEPT)
+                .build(dir)?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(appender)
+                .with_ansi(false)
+                .with_filter(Targets::new().with_target("todoist", LevelFilter::DEBUG));
+            Some(layer)
+        }
+        None => None,
+    };
+    tracing_subscriber::registry()
+        .with(stderr)
+        .with(file)
+        .try_init()?;
+    Ok(())
+}
+
+async fn run(mut args: Args) -> Result<(), Box<dyn Error>> {
+    let custom_sync_url = args.sync_url.is_some();
+    let sync_url = args.sync_url.take().unwrap_or(SYNC_URL.into());
+
+    let config_path = args
+        .config
+        .take()
+        .or(dirs::config_dir().map(|dir| dir.join("tuido").join(config::FILE_NAME)));
+    if let Some(Commands::Config { command }) = &args.command {
+        let path = config_path.ok_or("Could not find the config directory.")?;
+        return run_config_command(&path, command);
+    }
+    let settings = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    set_up_colors(args.no_color, &settings);
+    progress::set_enabled(!args.quiet && !args.porcelain && args.verbose == 0);
+    let view = View::new(&args, &settings);
+    let output = view.output;
+
+    let data_dir = find_data_dir(args.local_dir.take(), &settings)?;
+    let log_dir = settings
+        .log_file
+        .unwrap_or(false)
+        .then(|| data_dir.join("logs"));
+    set_up_logging(args.verbose, log_dir.as_deref())?;
+
+    let config = read_config(&data_dir)?;
+    let retries = args.retries.or(settings.retries).unwrap_or(DEFAULT_RETRIES);
+
+    let http = http_client(&HttpOptions {
+        http2: settings.http2.unwrap_or(false),
+        proxy: args.proxy.as_deref().or(settings.proxy.as_deref()),
+        ca_bundle: settings.ca_bundle.as_deref(),
+        timeout: match args.timeout.or(settings.timeout) {
+            Some(0) => None,
+            Some(seconds) => Some(Duration::from_secs(seconds)),
+            None => Some(DEFAULT_TIMEOUT),
+        },
+    })?;
+    let use_keyring = settings.token_storage == Some(TokenStorage::Keyring);
+    match &args.command {
+        Some(Commands::Auth {
+            token: Some(token), ..
+        }) => {
+            let client = SyncClient::new(&sync_url, token, retries)
+                .with_http(http)
+                .dry_run(args.dry_run);
+            let config = AuthConfig {
+                api_key: Some(token.clone()),
+            };
+            return authenticate(&data_dir, &client, config, use_keyring, output).await;
+        }
+        Some(Commands::Auth { .. }) => {
+            let config_path = config_path.ok_or("Could not find the config directory.")?;
+            return migrate_to_keyring(&data_dir, config, &config_path, settings);
+        }
+        Some(Commands::Completions { shell }) => return print_completions(*shell, &data_dir),
+        Some(Commands::Hook {
+            command: HookCommand::Install { force },
+        }) => return install_hooks(*force, output),
+        _ => {}
+    }
+
+    let mut settings = settings;
+    let is_setup = matches!(args.command, Some(Commands::Setup));
+    // git hooks shouldn't stop a commit to ask for a token
+    let can_set_up = picker::available() && !matches!(args.command, Some(Commands::Hook { .. }));
+    let api_key = match load_api_key(config.as_ref(), use_keyring, output) {
+        Some(api_key) if !is_setup => api_key,
+        None if !is_setup && !can_set_up => return Err(AuthFailed(NO_TOKEN).into()),
+        _ => {
+            let make_client =
+                |token: &str| SyncClient::new(&sync_url, token, retries).with_http(http.clone());
+            let auth = AuthConfig { api_key: None };
+            let paths = (data_dir.as_path(), config_path.as_deref());
+            let api_key =
+                setup(paths, &mut settings, make_client, auth, use_keyring, output).await?;
+            if is_setup {
+                return Ok(());
+            }
+            api_key
+        }
+    };
+    let on_conflict = args.on_conflict.or(settings.on_conflict);
+    let mut client = SyncClient::new(&sync_url, &api_key, retries)
+        .with_http(http.clone())
+        .dry_run(args.dry_run)
+        .in_parts(settings.sync_in_parts.unwrap_or(false))
+        .on_conflict(on_conflict.unwrap_or_default());
+    if let Some(rest) = rest_client(args.rest_url.take(), custom_sync_url, &api_key, &settings) {
+        client = client.with_rest(rest.with_http(http));
+    }
+    run_with_client(args, client, &data_dir, &settings, view).await
+}
+
+/// Runs the commands that use the local store, once there's a client to sync
+/// it with.
+async fn run_with_client(
+    args: Args,
+    client: SyncClient,
+    data_dir: &Path,
+    settings: &Config,
+    mut view: View,
+) -> Result<(), Box<dyn Error>> {
+    let output = view.output;
+    let client = client.with_hooks(Hooks::from_config(settings));
+    // FIXME: probably want to split up the network/file responsibilities here
+    let stored_user = get_stored_user_data(data_dir, &client, output).await?;
+    set_up_locale(args.date_format.clone(), settings, &stored_user);
+    let store_path = data_dir.join("data").join("todoist.db");
+    let mut store = Store::open(&store_path)?.keep_completed_for(settings.keep_completed_days);
+
+    let socket_path = data_dir.join("data").join(daemon::SOCKET_NAME);
+    if let Some(Commands::Daemon { interval }) = &args.command {
+        return run_daemon(&mut store, &client, &socket_path, *interval, output).await;
+    }
+    if let Some(Commands::Status { format }) = &args.command {
+        return print_status(&store, *format);
+    }
+    if let Some(Commands::ServeWebhooks { port, host }) = &args.command {
+        let address = std::net::SocketAddr::new(*host, *port);
+        return serve_webhooks(&mut store, address, &settings.webhook_hooks, output).await;
+    }
+    // a recent sync, or a running daemon, which keeps the store synced, means
+    // there's no need to sync again
+    let cache_ttl = Duration::from_secs(settings.cache_ttl.unwrap_or(0));
+    view.sync_first = view.sync_first
+        && !args.offline
+        && !session::is_fresh(&store, cache_ttl)?
+        && !daemon_is_fresh(&socket_path).await;
+
+    if client.conflict_strategy() == Strategy::Ask && picker::available() && !args.offline {
+        resolve_conflicts(&mut store, &client).await?;
+    }
+
+    if let Some(Commands::Tui) = &args.command {
+        return tui::run(
+            store_path,
+            client,
+            stored_user.inbox_project_id,
+            view.sync_first,
+        );
+    }
+
+    let inbox_project_id = &stored_user.inbox_project_id;
+    let default_project = settings.default_project.as_deref();
+    if let Some(new_todo) = args.add_todo {
+        let mut options = args.add_options;
+        options.dedupe = options.dedupe.or(settings.dedupe);
+        add_todo(
+            &mut store,
+            &client,
+            inbox_project_id,
+            default_project,
+            &new_todo,
+            options,
+            view,
+        )
+        .await?;
+    }
+
+    let list_inbox = args.list_inbox && !matches!(args.command, Some(Commands::List { .. }));
+    if let Some(mut command) = args.command {
+        if let Commands::Add { options, .. } = &mut command {
+            options.dedupe = options.dedupe.or(settings.dedupe);
+        }
+        run_command(&mut store, &client, &stored_user, settings, command, view).await?;
+    }
+    if list_inbox {
+        list_project(&mut store, &client, inbox_project_id, None, None, view).await?;
+    }
+
+    if output == Output::Table {
+        println!("Bye!");
+    }
+    Ok(())
+}
+
+async fn run_command(
+    store: &mut Store,
+    client: &SyncClient,
+    user: &User,
+    settings: &Config,
+    command: Commands,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let inbox_project_id = user.inbox_project_id.as_str();
+    match command {
+        Commands::Projects { command } => {
+            run_projects_command(store, client, inbox_project_id, &command, view).await?;
+        }
+        Commands::Labels { command } => run_labels_command(store, client, &command, view).await?,
+        command @ (Commands::Collaborators { .. }
+        | Commands::Notifications { .. }
+        | Commands::Share { .. }) => {
+            run_sharing_command(store, client, user, command, view).await?;
+        }
+        Commands::Filters {
+            command: FiltersCommand::List,
+        } => list_filters(store, client, view).await?,
+        Commands::Comment { command } => run_comment_command(store, client, command, view).await?,
+        Commands::Reminder { command } => {
+            run_reminder_command(store, client, command, view).await?;
+        }
+        Commands::Search {
+            query,
+            project,
+            label,
+        } => {
+            search_tasks(
+                store,
+                client,
+                &query,
+                project.as_deref(),
+                label.as_deref(),
+                view,
+            )
+            .await?;
+        }
+        command @ (Commands::Completed { .. }
+        | Commands::Activity { .. }
+        | Commands::Archive { .. }) => run_history_command(store, client, command, view).await?,
+        command @ (Commands::Export { .. } | Commands::Ical { .. }) => {
+            run_export_command(store, client, command, view).await?;
+        }
+        Commands::Notify { watch, interval } => {
+            notify_due_tasks(store, client, watch.then_some(interval), view).await?;
+        }
+        Commands::Open { target, app, print } => {
+            open_page(store, target.as_deref(), app, print, view.output)?;
+        }
+        Commands::Today { schedule: false } => show_agenda(store, client, 1, view).await?,
+        Commands::Today { schedule: true } => show_schedule(store, client, view).await?,
+        Commands::Upcoming { days } => show_agenda(store, client, days, view).await?,
+        Commands::Forecast { days } => show_forecast(store, client, days, view).await?,
+        Commands::Review { markdown } => show_review(store, client, markdown, view).await?,
+        Commands::Focus {
+            task,
+            minutes,
+            comment,
+        } => focus_on_task(store, client, task.as_deref(), minutes, comment, view).await?,
+        Commands::Hook { command } => run_hook_command(store, client, command, view).await?,
+        Commands::Track { command } => {
+            let comment = settings.track_comments.unwrap_or(false);
+            run_track_command(store, client, command, comment, view).await?;
+        }
+        Commands::Triage => triage(store, client, inbox_project_id, view).await?,
+        Commands::Favorites => list_favorites(store, client, view).await?,
+        Commands::Trash { command } => {
+            run_trash_command(store, client, inbox_project_id, command, view).await?;
+        }
+        Commands::Sync { full } => sync_now(store, client, full, view.output).await?,
+        Commands::Maintenance { command } => {
+            run_maintenance_command(store, &command, settings, view)?;
+        }
+        Commands::Template { command } => {
+            run_template_command(store, client, command, view).await?;
+        }
+        command @ Commands::List { .. } => list_tasks(store, client, user, command, view).await?,
+        Commands::Depend { task, on, remove } => {
+            depend_on(store, &task, on.as_deref(), remove, view.output)?;
+        }
+        Commands::Auth { .. }
+        | Commands::Tui
+        | Commands::Status { .. }
+        | Commands::Daemon { .. }
+        | Commands::ServeWebhooks { .. }
+        | Commands::Config { .. }
+        | Commands::Completions { .. } => {}
+        command => {
+            let default_project = settings.default_project.as_deref();
+            run_task_command(
+                store,
+                client,
+                inbox_project_id,
+                default_project,
+                command,
+                view,
+            )
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Lists the tasks in a project, a favorite, or a filter, as the `list`
+/// command asks.
+async fn list_tasks(
+    store: &mut Store,
+    client: &SyncClient,
+    user: &User,
+    command: Commands,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let Commands::List {
+        favorite,
+        mut project,
+        mut filter,
+        filter_name,
+        assigned_to_me,
+        long,
+        with_reminders,
+        sort,
+        group_by,
+        workspace,
+        fail_if_nonempty,
+        fail_if_empty,
+    } = command
+    else {
+        return Ok(());
+    };
+    let view = View {
+        full_descriptions: long,
+        reminders: with_reminders,
+        sort,
+        group_by,
+        ..view
+    };
+    let assignee = assigned_to_me
+        .then(|| current_user_id(store, user))
+        .transpose()?;
+    let assignee = assignee.as_deref();
+
+    if let Some(name) = filter_name {
+        filter = Some(
+            lookup_filter(store, client, &name, view.output)
+                .await?
+                .query,
+        );
+    }
+    if let Some(name) = favorite {
+        let favorite = lookup_favorite(store, client, &name, view.output).await?;
+        match favorite.query {
+            Some(query) => filter = Some(query),
+            None => project = Some(favorite.name),
+        }
+    }
+    if workspace.is_some() && filter.is_none() && project.is_none() {
+        filter = Some("all".to_string());
+    }
+    let listed = match filter {
+        Some(filter) => {
+            let scope = (workspace.as_deref(), assignee);
+            list_filter(store, client, &filter, scope, view).await?
+        }
+        None => {
+            list_project(
+                store,
+                client,
+                &user.inbox_project_id,
+                project,
+                assignee,
+                view,
+            )
+            .await?
+        }
+    };
+    if fail_if_nonempty && listed > 0 {
+        return Err(CheckFailed(format!("{listed} tasks were listed.")).into());
+    }
+    if fail_if_empty && listed == 0 {
+        return Err(CheckFailed("No tasks were listed.".to_string()).into());
+    }
+    Ok(())
+}
+
+/// Has a task wait for the one `on` names, or stop waiting for it, or shows
+/// what it waits for.
+fn depend_on(
+    store: &mut Store,
+    task: &str,
+    on: Option<&str>,
+    remove: bool,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    let name = |store: &Store, (id, content): (String, Option<String>)| {
+        let content = match content {
+            Some(content) => Some(content),
+            None => store.item(&id)?.map(|item| item.content),
+        };
+        Ok::<_, Box<dyn Error>>((id.clone(), task_name(&id, content)))
+    };
+    let (task_id, task) = name(store, resolve_listed_item(store, task)?)?;
+    let Some(on) = on else {
+        let blockers: Vec<Dependency> = store
+            .dependencies()?
+            .into_iter()
+            .filter(|dependency| dependency.task_id == task_id)
+            .collect();
+        return print_blockers(store, &task, &blockers, output);
+    };
+    let (blocker_id, blocker) = name(store, resolve_listed_item(store, on)?)?;
+
+    if remove {
+        if !store.remove_dependency(&task_id, &blocker_id)? {
+            return Err(format!("{task} doesn't wait for {blocker}.").into());
+        }
+        output.status(&format!("{task} no longer waits for {blocker}."));
+        return Ok(());
+    }
+    if task_id == blocker_id {
+        return Err("A task can't wait for itself.".into());
+    }
+    if dependency::would_cycle(&store.dependencies()?, &task_id, &blocker_id) {
+        return Err(format!(
+            "{blocker} already waits for {task}, so {task} can't wait for it too."
+        )
+        .into());
+    }
+    store.add_dependency(&task_id, &blocker_id)?;
+    output.status(&format!(
+        "{task} now waits for {blocker}, and is hidden until it's completed."
+    ));
+    Ok(())
+}
+
+/// Prints the tasks that a task waits for, in the requested format.
+fn print_blockers(
+    store: &Store,
+    task: &str,
+    blockers: &[Dependency],
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    match output {
+        Output::Table => {
+            println!("{task} waits for:");
+            if blockers.is_empty() {
+                println!("  (nothing)");
+            }
+            for dependency in blockers {
+                let content = store.item(&dependency.blocker_id)?.map(|item| item.content);
+                println!("  {}", task_name(&dependency.blocker_id, content));
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(blockers)?),
+        Output::Plain => {
+            for dependency in blockers {
+                println!("{}", dependency.blocker_id);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The IDs of the tasks that are waiting for others, which lists leave out.
+fn blocked_ids(store: &Store) -> Result<HashSet<String>, Box<dyn Error>> {
+    let dependencies = store.dependencies()?;
+    if dependencies.is_empty() {
+        return Ok(HashSet::new());
+    }
+    Ok(dependency::blocked(&dependencies, &store.items()?))
+}
+
+/// What a favorite is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum FavoriteKind {
+    Project,
+    Label,
+    Filter,
+}
+
+/// A project, label, or saved filter that the user marked as a favorite.
+#[derive(Debug, Serialize)]
+struct Favorite {
+    kind: FavoriteKind,
+    id: String,
+    name: String,
+
+    /// The filter that lists the favorite's tasks, for labels and filters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+}
+
+/// The user's favorites, in the order the apps' sidebar shows them: projects,
+/// then labels, then filters, each in the order the user arranged them.
+fn favorites(store: &Store) -> Result<Vec<Favorite>, Box<dyn Error>> {
+    let mut projects = store.projects()?;
+    projects.retain(|project| project.is_favorite && !project.is_archived);
+    projects.sort_by_key(|project| project.child_order);
+    let mut labels = store.labels()?;
+    labels.retain(|label| label.is_favorite);
+    labels.sort_by_key(|label| label.item_order);
+    let mut filters = store.filters()?;
+    filters.retain(|filter| filter.is_favorite);
+    filters.sort_by_key(|filter| filter.item_order);
+
+    let projects = projects.into_iter().map(|project| Favorite {
+        kind: FavoriteKind::Project,
+        id: project.id,
+        name: project.name,
+        query: None,
+    });
+    let labels = labels.into_iter().map(|label| Favorite {
+        kind: FavoriteKind::Label,
+        query: Some(format!("@{}", label.name)),
+        id: label.id,
+        name: label.name,
+    });
+    let filters = filters.into_iter().map(|filter| Favorite {
+        kind: FavoriteKind::Filter,
+        id: filter.id,
+        name: filter.name,
+        query: Some(filter.query),
+    });
+    Ok(projects.chain(labels).chain(filters).collect())
+}
+
+/// Finds the favorite whose name best matches `name`, syncing first if none
+/// does.
+async fn lookup_favorite(
+    store: &mut Store,
+    client: &SyncClient,
+    name: &str,
+    output: Output,
+) -> Result<Favorite, Box<dyn Error>> {
+    let find = |store: &Store| -> Result<Option<Favorite>, Box<dyn Error>> {
+        Ok(fuzzy::best_match(name, favorites(store)?, |favorite| {
+            &favorite.name
+        }))
+    };
+    if find(store)?.is_none() {
+        sync(store, client, output).await?;
+    }
+    find(store)?.ok_or(format!("Could not find a favorite named '{name}'.").into())
+}
+
+async fn list_filters(
+    store: &mut Store,
+    client: &SyncClient,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+    print_filters(view.output, store.filters()?)
+}
+
+async fn list_favorites(
+    store: &mut Store,
+    client: &SyncClient,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+    let favorites = favorites(store)?;
+
+    match view.output {
+        Output::Table => {
+            println!("Favorites:");
+            if favorites.is_empty() {
+                println!("  (no favorites)");
+            }
+            for favorite in &favorites {
+                match favorite.kind {
+                    FavoriteKind::Project => println!("  #{}", favorite.name),
+                    FavoriteKind::Label => println!("  @{}", favorite.name),
+                    FavoriteKind::Filter => println!(
+                        "  {}  ({})",
+                        favorite.name,
+                        favorite.query.as_deref().unwrap_or_default()
+                    ),
+                }
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&favorites)?),
+        Output::Plain => {
+            for favorite in &favorites {
+                let kind = match favorite.kind {
+                    FavoriteKind::Project => "project",
+                    FavoriteKind::Label => "label",
+                    FavoriteKind::Filter => "filter",
+                };
+                println!(
+                    "{kind}\t{}\t{}\t{}",
+                    favorite.id,
+                    favorite.name,
+                    favorite.query.as_deref().unwrap_or_default()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs the commands that look back at what's been done.
+async fn run_history_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: Commands,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Commands::Completed { since, project } => {
+            show_completed(store, client, since, project.as_deref(), view).await?;
+        }
+        Commands::Activity { limit, project } => {
+            show_activity(store, client, limit, project.as_deref(), view).await?;
+        }
+        Commands::Archive { command } => run_archive_command(store, client, command, view).await?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Runs the commands that add or change tasks.
+async fn run_task_command(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    default_project: Option<&str>,
+    command: Commands,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Commands::Add {
+            todo: Some(todo),
+            options,
+            ..
+        } => {
+            add_todo(
+                store,
+                client,
+                inbox_project_id,
+                default_project,
+                &todo,
+                options,
+                view,
+            )
+            .await?;
+        }
+        Commands::Add {
+            todo: None,
+            options,
+            ..
+        } => {
+            let text = std::io::read_to_string(std::io::stdin())?;
+            let mode = options.dedupe.unwrap_or_default();
+            let defaults = (inbox_project_id, default_project);
+            add_todos(store, client, defaults, &text, mode, view).await?;
+        }
+        Commands::Quick { text } => quick_add_todo(store, client, &text, view).await?,
+        Commands::Complete {
+            task,
+            all_occurrences,
+        } => complete_task(store, client, task.as_deref(), all_occurrences, view).await?,
+        Commands::Undo => undo_last(store, client, view).await?,
+        Commands::Reopen { task } => reopen_task(store, client, &task, view).await?,
+        Commands::Edit {
+            task,
+            content,
+            description,
+            due,
+            priority,
+            labels,
+            assign,
+            duration,
+        } => {
+            let changes = ItemUpdateArgs {
+                id: String::new(),
+                content,
+                description,
+                due: due.map(due_args).transpose()?,
+                duration: duration_change(duration.as_deref())?,
+                priority: priority.map(|priority| 5 - priority),
+                labels,
+                responsible_uid: assign.map(|name| Some(name).filter(|name| !name.is_empty())),
+            };
+            edit_task(store, client, task.as_deref(), changes, view).await?;
+        }
+        Commands::Move {
+            task,
+            to_project,
+            to_section,
+            position,
+        } => {
+            let position = position.as_deref().map(Position::parse).transpose()?;
+            let destination = (to_project.as_deref(), to_section.as_deref());
+            move_task(store, client, task.as_deref(), destination, position, view).await?;
+        }
+        Commands::Snooze { args, all_overdue } => {
+            snooze(store, client, &args, all_overdue, view).await?;
+        }
+        Commands::Delete { task, force } => {
+            delete_task(store, client, task.as_deref(), force, view).await?;
+        }
+        Commands::Import {
+            file,
+            options,
+            project,
+        } => {
+            let target = (inbox_project_id, project.as_deref().or(default_project));
+            import_tasks(store, client, (&file, options), target, view).await?;
+        }
+        Commands::Checklist { command } => {
+            let defaults = (inbox_project_id, default_project);
+            run_checklist(store, client, command, defaults, view).await?;
+        }
+        Commands::Batch { actions, file } => {
+            let defaults = (inbox_project_id, default_project);
+            run_batch(store, client, defaults, &actions, file.as_deref(), view).await?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Prunes and compacts the local cache.
+fn run_maintenance_command(
+    store: &mut Store,
+    command: &MaintenanceCommand,
+    settings: &Config,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let MaintenanceCommand::Prune { keep_completed } = command;
+    let days = keep_completed.or(settings.keep_completed_days);
+    let before = store.size()?;
+    let cutoff = days.map(|days| Utc::now() - TimeDelta::days(days.into()));
+    let pruned = store.prune(cutoff)?;
+    store.vacuum()?;
+    let after = store.size()?;
+
+    match view.output {
+        Output::Table => {
+            let Pruned {
+                items,
+                notes,
+                reminders,
+            } = pruned;
+            println!(
+                "Removed {items} completed tasks, {notes} comments, and {reminders} reminders. \
+                 The cache went from {} to {}.",
+                describe_size(before),
+                describe_size(after)
+            );
+        }
+        Output::Json => {
+            let mut json = serde_json::to_value(pruned)?;
+            json["bytes_before"] = before.into();
+            json["bytes_after"] = after.into();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Output::Plain => {
+            println!("items\t{}", pruned.items);
+            println!("notes\t{}", pruned.notes);
+            println!("reminders\t{}", pruned.reminders);
+            println!("bytes_before\t{before}");
+            println!("bytes_after\t{after}");
+        }
+    }
+    Ok(())
+}
+
+async fn run_template_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: TemplateCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        TemplateCommand::Save { project, name } => {
+            if !sync(store, client, view.output).await? {
+                view.output
+                    .status("Could not reach Todoist, saving the cached project.");
+            }
+            let project = find_project(store, &project)?
+                .ok_or(format!("Could not find a project named '{project}'."))?;
+            let template = Template::snapshot(
+                &project.name,
+                &store.sections_in_project(&project.id)?,
+                &store.open_items_in_project(&project.id)?,
+            );
+            let replaced = store.save_template(&name, &template)?;
+            let saved = if replaced { "Replaced" } else { "Saved" };
+            view.output.status(&format!(
+                "{saved} the '{name}' template, with {} sections and {} tasks from {}.",
+                template.sections.len(),
+                template.tasks.len(),
+                project.name
+            ));
+        }
+        TemplateCommand::Apply { name, project } => {
+            let template = store
+                .template(&name)?
+                .ok_or(format!("There's no template named '{name}'."))?;
+            let project = project.unwrap_or(name);
+            apply_template(store, client, &template, &project, view).await?;
+        }
+        TemplateCommand::List => {
+            let templates = store.templates()?;
+            match view.output {
+                Output::Table => {
+                    if templates.is_empty() {
+                        println!("No templates yet. Save one with `template save`.");
+                    }
+                    for (name, template) in &templates {
+                        println!(
+                            "{name}: {} sections and {} tasks, from {}",
+                            template.sections.len(),
+                            template.tasks.len(),
+                            template.project
+                        );
+                    }
+                }
+                Output::Json => {
+                    let templates: Vec<_> = templates
+                        .iter()
+                        .map(|(name, template)| {
+                            serde_json::json!({ "name": name, "template": template })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&templates)?);
+                }
+                Output::Plain => {
+                    for (name, template) in &templates {
+                        println!("{name}\t{}\t{}", template.project, template.tasks.len());
+                    }
+                }
+            }
+        }
+        TemplateCommand::Delete { name } => {
+            if !store.delete_template(&name)? {
+                return Err(format!("There's no template named '{name}'.").into());
+            }
+            view.output
+                .status(&format!("Deleted the '{name}' template."));
+        }
+    }
+    Ok(())
+}
+
+/// Adds a template's sections and tasks to the project named `project`, in
+/// one batch of commands, creating the project and any of the sections that
+/// don't exist yet.
+async fn apply_template(
+    store: &mut Store,
+    client: &SyncClient,
+    template: &Template,
+    project: &str,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    if !sync(store, client, view.output).await? {
+        view.output
+            .status("Could not reach Todoist, using cached projects and sections.");
+    }
+    let mut plan = ImportPlan::new(store)?;
+    let project_id = plan.project_id(project);
+    for section in &template.sections {
+        plan.section_id(&project_id, section);
+    }
+    let tasks = template.tasks_for(project);
+    let (items, _) = plan.add_tasks(store, &tasks, &project_id)?;
+
+    let (added, queued) = send_planned_tasks(store, client, plan, (&tasks, items), view).await?;
+    if added > 0 || tasks.is_empty() {
+        view.output
+            .status(&format!("Added {added} tasks to {project}."));
+    }
+    if queued > 0 {
+        view.output.status(&format!(
+            "Could not reach Todoist. {queued} tasks will be added to {project} on the next sync."
+        ));
+    }
+    Ok(())
+}
+
+/// Sends the commands that add `tasks`, after the ones that create the
+/// projects and sections `plan` needs for them, returning how many tasks were
+/// added and how many were queued. The tasks that couldn't be added are
+/// printed, and make it an error.
+async fn send_planned_tasks(
+    store: &mut Store,
+    client: &SyncClient,
+    plan: ImportPlan,
+    (tasks, items): (&[import::Task], Vec<Command>),
+    view: View,
+) -> Result<(usize, usize), Box<dyn Error>> {
+    let total = items.len();
+    let results: Vec<_> = items
+        .iter()
+        .map(|command| (command.uuid, command.temp_id))
+        .zip(tasks.iter().map(|task| &task.content))
+        .collect();
+    let mut commands = plan.containers;
+    commands.extend(items);
+    let response = send_chunked(store, client, commands, view.output).await?;
+
+    let mut added = 0;
+    let mut queued = 0;
+    let mut failures = 0;
+    for ((uuid, temp_id), content) in results {
+        let id = temp_id.and_then(|temp_id| response.temp_id_mapping.get(&temp_id));
+        match (response.status(&uuid), id) {
+            (Some(CommandStatus::Error { error, .. }), _) => {
+                view.output
+                    .status(&format!("Failed to add '{content}': {error}."));
+                failures += 1;
+            }
+            (_, Some(_)) => added += 1,
+            (_, None) => queued += 1,
+        }
+    }
+    if failures > 0 {
+        return Err(format!("{failures} of {total} tasks could not be added.").into());
+    }
+    Ok((added, queued))
+}
+
+/// A number of bytes, in kilobytes, like "24 KB".
+fn describe_size(bytes: u64) -> String {
+    format!("{} KB", bytes.div_ceil(1024))
+}
+
+/// Lists or restores the tasks in the trash.
+async fn run_trash_command(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    command: TrashCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        TrashCommand::List => {
+            let trashed = store.trash()?;
+            match view.output {
+                Output::Table => {
+                    println!("Deleted tasks:");
+                    if trashed.is_empty() {
+                        println!("  (no tasks)");
+                    }
+                    for TrashedItem { item, deleted_at } in &trashed {
+                        let deleted_at = locale::format_timestamp(deleted_at);
+                        println!("  {}  {}  (deleted {deleted_at})", item.id, item.content);
+                    }
+                }
+                Output::Json => println!("{}", serde_json::to_string_pretty(&trashed)?),
+                Output::Plain => {
+                    for TrashedItem { item, deleted_at } in &trashed {
+                        println!("{}\t{deleted_at}\t{}", item.id, item.content);
+                    }
+                }
+            }
+        }
+        TrashCommand::Restore { id } => {
+            let item = store
+                .trash()?
+                .into_iter()
+                .find(|trashed| trashed.item.id == id)
+                .ok_or(format!("There's no task with ID {id} in the trash."))?
+                .item;
+            let args = trash::restore_args(store, item, inbox_project_id)?;
+            let (content, project) = (args.content.clone(), project_name(store, &args.project_id)?);
+
+            let command = Command::new(CommandArgs::ItemAdd(args));
+            let temp_id = command.temp_id;
+            let response = send_command(store, client, command, view.output).await?;
+            store.remove_from_trash(&id)?;
+            if let Some(temp_id) = temp_id {
+                let new_id = response
+                    .as_ref()
+                    .and_then(|response| response.temp_id_mapping.get(&temp_id).cloned())
+                    .unwrap_or(temp_id.to_string());
+                store.set_last_added(&new_id, &content)?;
+            }
+            if response.is_some() {
+                view.output
+                    .status(&format!("Restored '{content}' to {project}."));
+            } else {
+                view.output.status(&format!(
+                    "Could not reach Todoist. '{content}' will be restored on the next sync."
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Sends the command that reverses the latest entry in the undo journal. The
+/// entry stays in the journal if the command doesn't go through.
+async fn undo_last(
+    store: &mut Store,
+    client: &SyncClient,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let entry = store.last_undo_entry()?.ok_or("There's nothing to undo.")?;
+    let sent = send_command(store, client, Command::new(entry.inverse), view.output).await?;
+    store.pop_undo_entry()?;
+    if sent.is_some() {
+        view.output.status(&format!("Undid {}.", entry.action));
+    } else {
+        view.output.status(&format!(
+            "Could not reach Todoist. Will undo {} on the next sync.",
+            entry.action
+        ));
+    }
+    Ok(())
+}
+
+/// Reopens a completed task, which can be picked by its index in the output of
+/// the last `completed`, since completed tasks aren't in `list`.
+async fn reopen_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: &str,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let listed = match task.parse::<usize>() {
+        Ok(index) => store.last_completed_entry(index)?,
+        Err(_) => None,
+    };
+    let (id, content) = if let Some((id, content)) = listed {
+        (id, Some(content))
+    } else {
+        (task.to_string(), store.item(task)?.map(|item| item.content))
+    };
+
+    let command = Command::new(CommandArgs::ItemUncomplete(ItemIdArgs { id: id.clone() }));
+    let task_name = task_name(&id, content);
+    if send_command(store, client, command, view.output)
+        .await?
+        .is_some()
+    {
+        view.output.status(&format!("Reopened {task_name}."));
+    } else {
+        view.output.status(&format!(
+            "Could not reach Todoist. {task_name} will be reopened on the next sync."
+        ));
+    }
+    Ok(())
+}
+
+/// Completes a task. If it's known to be recurring, only the current occurrence
+/// is completed, unless `all_occurrences` is set.
+async fn complete_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: Option<&str>,
+    all_occurrences: bool,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (item_id, content) = resolve_task(store, task)?;
+    let cached = store.item(&item_id)?;
+    let recurring = cached
+        .as_ref()
+        .and_then(|item| item.due.as_ref())
+        .is_some_and(|due| due.is_recurring);
+    let content = content.or(cached.map(|item| item.content));
+
+    let id = item_id.clone();
+    let (args, done, queued) = match (recurring, all_occurrences) {
+        (true, false) => (
+            CommandArgs::ItemUpdateDateComplete(ItemIdArgs { id }),
+            "Completed this occurrence of ",
+            "This occurrence of ",
+        ),
+        (true, true) => (
+            CommandArgs::ItemComplete(ItemIdArgs { id }),
+            "Completed every occurrence of ",
+            "Every occurrence of ",
+        ),
+        (false, _) => (
+            CommandArgs::ItemClose(ItemCloseArgs { id }),
+            "Completed ",
+            "",
+        ),
+    };
+    let complete_item_response =
+        send_command(store, client, Command::new(args), view.output).await?;
+
+    if view.porcelain {
+        let status = match complete_item_response {
+            Some(_) => Status::Completed,
+            None => Status::Queued,
+        };
+        let content = content.unwrap_or_default();
+        println!("{}", porcelain::result(status, &item_id, &content));
+        return Ok(());
+    }
+    let task_name = task_name(&item_id, content);
+    if complete_item_response.is_some() {
+        println!("{done}{task_name}.");
+    } else {
+        println!(
+            "Could not reach Todoist. {queued}{task_name} will be completed on the next sync."
+        );
+    }
+    Ok(())
+}
+
+async fn delete_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: Option<&str>,
+    force: bool,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (id, content) = resolve_task(store, task)?;
+    let content = match content {
+        Some(content) => content,
+        None => lookup_item(store, client, &id).await?.content,
+    };
+    if !force && !confirm(&format!("Permanently delete '{content}'?"))? {
+        view.output.status("Cancelled.");
+        return Ok(());
+    }
+
+    let command = Command::new(CommandArgs::ItemDelete(ItemIdArgs { id }));
+    if send_command(store, client, command, view.output)
+        .await?
+        .is_some()
+    {
+        view.output.status(&format!("Deleted '{content}'."));
+    } else {
+        view.output.status(&format!(
+            "Could not reach Todoist. '{content}' will be deleted on the next sync."
+        ));
+    }
+    Ok(())
+}
+
+/// A due date to send, in the user's language, once it's been checked for
+/// typos.
+fn due_args(string: String) -> Result<DueArgs, Box<dyn Error>> {
+    let lang = locale::lang();
+    due::validate_in(&string, lang.unwrap_or("en"))?;
+    Ok(DueArgs {
+        string,
+        lang: lang.map(ToString::to_string),
+    })
+}
+
+/// Works out the due date for snoozing `item` until `when`.
+fn snoozed_due(item: &Item, when: &str) -> Result<DueArgs, Box<dyn Error>> {
+    let due_time = agenda::due_time(item).map(|time| time.time());
+    let string = schedule::snoozed_due(when, due_time, Local::now().naive_local()).ok_or(
+        format!("Can't snooze until '{when}'. Offsets look like +30m, +2h, +3d, or +1w."),
+    )?;
+    due_args(string)
+}
+
+/// Snoozes the task in `args`, or every overdue task, until the time in `args`.
+async fn snooze(
+    store: &mut Store,
+    client: &SyncClient,
+    args: &[String],
+    all_overdue: bool,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match (args, all_overdue) {
+        ([when], true) => snooze_overdue(store, client, when, view).await,
+        ([when], false) => snooze_task(store, client, None, when, view).await,
+        ([task, when], false) => snooze_task(store, client, Some(task), when, view).await,
+        _ => Err("Pass only WHEN with --all-overdue, not a task.".into()),
+    }
+}
+
+async fn snooze_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: Option<&str>,
+    when: &str,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (id, _) = resolve_task(store, task)?;
+    let item = lookup_item(store, client, &id).await?;
+    let due = snoozed_due(&item, when)?;
+    let due_string = due.string.clone();
+    let changes = ItemUpdateArgs {
+        id: item.id.clone(),
+        due: Some(due),
+        ..ItemUpdateArgs::default()
+    };
+
+    let command = Command::new(CommandArgs::ItemUpdate(changes));
+    if send_command(store, client, command, view.output)
+        .await?
+        .is_some()
+    {
+        view.output
+            .status(&format!("Snoozed '{}' until {due_string}.", item.content));
+    } else {
+        view.output.status(&format!(
+            "Could not reach Todoist. '{}' will be snoozed until {due_string} on the next sync.",
+            item.content
+        ));
+    }
+    Ok(())
+}
+
+/// Snoozes every open task that was due before today.
+async fn snooze_overdue(
+    store: &mut Store,
+    client: &SyncClient,
+    when: &str,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let today = Local::now().date_naive();
+    let items: Vec<Item> = store
+        .items()?
+        .into_iter()
+        .filter(|item| !item.is_deleted && agenda::is_overdue(item, today))
+        .collect();
+    if items.is_empty() {
+        view.output.status("Nothing is overdue.");
+        return Ok(());
+    }
+
+    let mut commands = Vec::new();
+    for item in &items {
+        let changes = ItemUpdateArgs {
+            id: item.id.clone(),
+            due: Some(snoozed_due(item, when)?),
+            ..ItemUpdateArgs::default()
+        };
+        commands.push(Command::new(CommandArgs::ItemUpdate(changes)));
+    }
+    let uuids: Vec<_> = commands.iter().map(|command| command.uuid).collect();
+    let response = send_chunked(store, client, commands, view.output).await?;
+
+    let mut snoozed = 0;
+    let mut queued = 0;
+    for (item, uuid) in items.iter().zip(uuids) {
+        match response.status(&uuid) {
+            Some(CommandStatus::Error { error, .. }) => {
+                view.output
+                    .status(&format!("Failed to snooze '{}': {error}.", item.content));
+            }
+            Some(CommandStatus::Ok(_)) => snoozed += 1,
+            None => queued += 1,
+        }
+    }
+    if snoozed > 0 {
+        view.output
+            .status(&format!("Snoozed {snoozed} overdue tasks until {when}."));
+    }
+    if queued > 0 {
+        view.output.status(&format!(
+            "Could not reach Todoist. {queued} overdue tasks will be snoozed until {when} on the \
+             next sync."
+        ));
+    }
+    Ok(())
+}
+
+/// Moves a task to a project, a section, or a position among its siblings,
+/// sending whichever of `item_move` and `item_reorder` it takes together.
+async fn move_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: Option<&str>,
+    (to_project, to_section): (Option<&str>, Option<&str>),
+    position: Option<Position>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (id, _) = resolve_task(store, task)?;
+    let item = lookup_item(store, client, &id).await?;
+
+    let mut commands = Vec::new();
+    let mut places = Vec::new();
+    // where the task will be, and so who its siblings will be
+    let (mut project_id, mut section_id, mut parent_id) = (
+        item.project_id.clone(),
+        item.section_id.clone(),
+        item.parent_id.clone(),
+    );
+    if to_project.is_some() || to_section.is_some() {
+        let mut place = match to_project {
+            Some(name) => {
+                let project = lookup_project(store, client, name, view.output).await?;
+                project_id = project.id;
+                project.name
+            }
+            None => project_name(store, &project_id)?,
+        };
+        section_id = match to_section {
+            Some(name) => {
+                let sections = store.sections_in_project(&project_id)?;
+                let section = fuzzy::best_match(name, sections, |section| &section.name).ok_or(
+                    format!("Could not find a section named '{name}' in {place}."),
+                )?;
+                place = format!("{place} / {}", section.name);
+                Some(section.id)
+            }
+            None => None,
+        };
+        parent_id = None;
+        commands.push(Command::new(CommandArgs::ItemMove(ItemMoveArgs {
+            id: item.id.clone(),
+            project_id: section_id.is_none().then(|| project_id.clone()),
+            section_id: section_id.clone(),
+            parent_id: None,
+        })));
+        places.push(format!("to {place}"));
+    }
+
+    if let Some(position) = position {
+        let position = match position {
+            Position::After(sibling) => Position::After(resolve_listed_item(store, &sibling)?.0),
+            position => position,
+        };
+        let mut siblings: Vec<Item> = store
+            .open_items_in_project(&project_id)?
+            .into_iter()
+            .filter(|other| other.section_id == section_id && other.parent_id == parent_id)
+            .collect();
+        siblings.sort_by_key(|other| other.child_order);
+        let siblings: Vec<&str> = siblings.iter().map(|other| other.id.as_str()).collect();
+        let order = order::reorder(&siblings, &item.id, &position)?;
+        places.push(match &position {
+            Position::Top => "to the top".to_string(),
+            Position::Bottom => "to the bottom".to_string(),
+            Position::After(sibling) => {
+                let sibling = store
+                    .item(sibling)?
+                    .map_or(sibling.clone(), |other| other.content);
+                format!("after '{sibling}'")
+            }
+        });
+        commands.push(Command::new(CommandArgs::ItemReorder(ItemReorderArgs {
+            items: child_orders(order)?,
+        })));
+    }
+
+    let uuids: Vec<_> = commands.iter().map(|command| command.uuid).collect();
+    let Some(response) =
+        send_request(store, client, FULL_SYNC_TOKEN, &[], commands, view.output).await?
+    else {
+        view.output.status(&format!(
+            "Could not reach Todoist. '{}' will be moved on the next sync.",
+            item.content
+        ));
+        return Ok(());
+    };
+    let statuses = response.sync_status.unwrap_or_default();
+    if let Some(CommandStatus::Error { error, .. }) = uuids
+        .iter()
+        .find_map(|uuid| statuses.get(uuid).filter(|status| !status.is_ok()))
+    {
+        return Err(format!("Todoist could not move '{}': {error}.", item.content).into());
+    }
+    view.output
+        .status(&format!("Moved '{}' {}.", item.content, places.join(", ")));
+    Ok(())
+}
+
+/// The name of the project with the given ID, or the ID itself if the project
+/// isn't cached.
+fn project_name(store: &Store, id: &str) -> Result<String, Box<dyn Error>> {
+    Ok(store
+        .projects()?
+        .into_iter()
+        .find(|project| project.id == id)
+        .map_or(id.to_string(), |project| project.name))
+}
+
+/// Positions for everything in `order`, counting from 1.
+fn child_orders(order: Vec<String>) -> Result<Vec<ChildOrder>, Box<dyn Error>> {
+    order
+        .into_iter()
+        .enumerate()
+        .map(|(index, id)| {
+            Ok(ChildOrder {
+                id,
+                child_order: i64::try_from(index)? + 1,
+            })
+        })
+        .collect()
+}
+
+/// Updates a task, then shows what changed. `changes` holds the new values of
+/// the fields to change; its `id` is filled in from `task`.
+async fn edit_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: Option<&str>,
+    mut changes: ItemUpdateArgs,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (id, _) = resolve_task(store, task)?;
+    let item = lookup_item(store, client, &id).await?;
+    changes.id.clone_from(&item.id);
+    if let Some(labels) = changes.labels.take() {
+        let labels = labels.into_iter().filter(|label| !label.is_empty());
+        changes.labels = Some(match_known_labels(store, labels)?);
+    }
+    if let Some(Some(name)) = &changes.responsible_uid {
+        let collaborator = lookup_collaborator(store, client, name, view.output).await?;
+        changes.responsible_uid = Some(Some(collaborator.id));
+    }
+
+    let format_due = |due: Option<&str>| due.unwrap_or("(none)").to_string();
+    let format_labels = |labels: &[String]| match labels {
+        [] => "(none)".to_string(),
+        labels => labels
+            .iter()
+            .map(|label| format!("@{label}"))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+    let mut diff = Vec::new();
+    if let Some(content) = &changes.content {
+        diff.push(("Content", item.content.clone(), content.clone()));
+    }
+    if let Some(description) = &changes.description {
+        let format_description = |description: &str| match description_preview(description) {
+            preview if preview.is_empty() => "(none)".to_string(),
+            preview => preview,
+        };
+        diff.push((
+            "Description",
+            format_description(&item.description),
+            format_description(description),
+        ));
+    }
+    if let Some(due) = &changes.due {
+        let before = item
+            .due
+            .as_ref()
+            .map(|due| due.string.as_ref().unwrap_or(&due.date));
+        diff.push((
+            "Due",
+            format_due(before.map(String::as_str)),
+            due.string.clone(),
+        ));
+    }
+    if let Some(duration) = &changes.duration {
+        let format_duration = |duration: Option<&TaskDuration>| {
+            duration.map_or("(none)".to_string(), ToString::to_string)
+        };
+        diff.push((
+            "Duration",
+            format_duration(item.duration.as_ref()),
+            format_duration(duration.as_ref()),
+        ));
+    }
+    if let Some(priority) = changes.priority {
+        let before = format!("p{}", 5 - item.priority.clamp(1, 4));
+        diff.push(("Priority", before, format!("p{}", 5 - priority)));
+    }
+    if let Some(labels) = &changes.labels {
+        diff.push(("Labels", format_labels(&item.labels), format_labels(labels)));
+    }
+    if let Some(responsible_uid) = &changes.responsible_uid {
+        let before = collaborator_name(store, item.responsible_uid.as_deref())?;
+        let after = collaborator_name(store, responsible_uid.as_deref())?;
+        diff.push(("Assignee", before, after));
+    }
+
+    let response = send_command(
+        store,
+        client,
+        Command::new(CommandArgs::ItemUpdate(changes)),
+        view.output,
+    )
+    .await?;
+    if response.is_some() {
+        view.output.status(&format!("Updated '{}':", item.content));
+    } else {
+        view.output.status(&format!(
+            "Could not reach Todoist. '{}' will be updated on the next sync:",
+            item.content
+        ));
+    }
+    for (field, before, after) in diff {
+        view.output
+            .status(&format!("  {field}: {before} → {after}"));
+    }
+    Ok(())
+}
+
+/// Finds a task by ID, fetching it if it isn't in the local store.
+async fn lookup_item(
+    store: &mut Store,
+    client: &SyncClient,
+    id: &str,
+) -> Result<Item, Box<dyn Error>> {
+    session::fetch_item(store, client, id)
+        .await?
+        .ok_or(format!("Could not find a task with ID {id}.").into())
+}
+
+/// How to refer to a task in messages: by its content if it's known, and by
+/// its ID otherwise.
+fn task_name(id: &str, content: Option<String>) -> String {
+    content.map_or(format!("task {id}"), |content| format!("'{content}'"))
+}
+
+/// Sends a batch of actions in a single request, then reports how each one
+/// went. Fails if any of them were rejected.
+async fn run_batch(
+    store: &mut Store,
+    client: &SyncClient,
+    (inbox_project_id, default_project): (&str, Option<&str>),
+    actions: &[String],
+    file: Option<&Path>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let steps = match file {
+        Some(path) if path == Path::new("-") => {
+            batch::parse_file(&std::io::read_to_string(std::io::stdin())?)?
+        }
+        Some(path) => batch::parse_file(&fs::read_to_string(path)?)?,
+        None => batch::parse_args(actions)?,
+    };
+    if steps.is_empty() {
+        return Err("No actions to send.".into());
+    }
+
+    let mut commands = Vec::new();
+    let mut summaries = Vec::new();
+    for step in steps {
+        let (args, summary) = match step {
+            batch::Step::Add(text) => {
+                let mut quick_add = quick_add::parse(&text);
+                if quick_add.project.is_none() {
+                    quick_add.project = default_project.map(ToString::to_string);
+                }
+                let (args, project_name) =
+                    resolve_quick_add(store, client, inbox_project_id, quick_add, view.output)
+                        .await?;
+                let summary = format!("Todo '{}' added to {project_name}", args.content);
+                (CommandArgs::ItemAdd(args), summary)
+            }
+            batch::Step::Complete(task) => {
+                let (id, content) = resolve_listed_item(store, &task)?;
+                let summary = format!("Completed {}", task_name(&id, content));
+                (CommandArgs::ItemClose(ItemCloseArgs { id }), summary)
+            }
+        };
+        let command = Command::new(args);
+
+        // later steps can refer to this todo as `last`, and since they're in
+        // the same request, the server will resolve its temp ID
+        if let (CommandArgs::ItemAdd(args), Some(temp_id)) = (&command.args, command.temp_id) {
+            store.set_last_added(&temp_id.to_string(), &args.content)?;
+        }
+        summaries.push((command.uuid, command.args.description(), summary));
+        commands.push(command);
+    }
+
+    let total = commands.len();
+    let Some(response) =
+        send_request(store, client, FULL_SYNC_TOKEN, &[], commands, view.output).await?
+    else {
+        view.output.status(&format!(
+            "Could not reach Todoist. {total} commands will be sent on the next sync."
+        ));
+        return Ok(());
+    };
+
+    let mut failures = 0;
+    for (uuid, description, summary) in summaries {
+        let status = response
+            .sync_status
+            .as_ref()
+            .and_then(|statuses| statuses.get(&uuid));
+        if let Some(CommandStatus::Error { error, .. }) = status {
+            failures += 1;
+            view.output
+                .status(&format!("Failed to {description}: {error}."));
+        } else {
+            view.output.status(&format!("{summary}."));
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} of {total} commands failed.").into());
+    }
+    Ok(())
+}
+
+/// Adds a todo for each non-blank line of `text`, in quick-add syntax, sending
+/// them in as few requests as the server allows. Prints the IDs of the new
+/// tasks.
+async fn add_todos(
+    store: &mut Store,
+    client: &SyncClient,
+    (inbox_project_id, default_project): (&str, Option<&str>),
+    text: &str,
+    mode: dedupe::Mode,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let mut commands: Vec<Command> = Vec::new();
+    let mut skipped = 0;
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let mut quick_add = quick_add::parse(line);
+        if quick_add.project.is_none() {
+            quick_add.project = default_project.map(ToString::to_string);
+        }
+        let (args, project_name) =
+            resolve_quick_add(store, client, inbox_project_id, quick_add, view.output).await?;
+        // the todos before this one count too, in case the same one is in
+        // the list twice
+        let adding: Vec<Item> = commands.iter().filter_map(queued_item).collect();
+        if let Some(duplicate) = find_duplicate(store, &args, &adding, mode)? {
+            if warn_about_duplicate(&args.content, &duplicate, &project_name, mode, view) {
+                skipped += 1;
+                continue;
+            }
+        }
+        commands.push(Command::new(CommandArgs::ItemAdd(args)));
+    }
+    if commands.is_empty() && skipped > 0 {
+        return Ok(());
+    }
+    if commands.is_empty() {
+        return Err("No todos to add.".into());
+    }
+
+    let total = commands.len();
+    let results: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match &command.args {
+            CommandArgs::ItemAdd(args) => {
+                Some((command.uuid, command.temp_id, args.content.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    let response = send_chunked(store, client, commands, view.output).await?;
+
+    let mut added = Vec::new();
+    let mut queued = Vec::new();
+    let mut failures = Vec::new();
+    for (uuid, temp_id, content) in results {
+        let id = temp_id.and_then(|temp_id| response.temp_id_mapping.get(&temp_id));
+        match (response.status(&uuid), id) {
+            (Some(CommandStatus::Error { error, .. }), _) => {
+                failures.push(format!("Failed to add '{content}': {error}."));
+            }
+            (_, Some(id)) => added.push((id.clone(), content)),
+            (_, None) => queued.push((temp_id.map(|id| id.to_string()), content)),
+        }
+    }
+    if let Some((id, content)) = added.last() {
+        store.set_last_added(id, content)?;
+    }
+
+    match view.output {
+        Output::Table => {
+            if !added.is_empty() {
+                println!("Added {} todos:", added.len());
+            }
+            for (id, content) in &added {
+                println!("  {id}  {content}");
+            }
+        }
+        Output::Json => {
+            let added: Vec<_> = added
+                .iter()
+                .map(|(id, content)| serde_json::json!({ "id": id, "content": content }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&added)?);
+        }
+        Output::Plain if view.porcelain => {
+            for (id, content) in &added {
+                println!("{}", porcelain::result(Status::Added, id, content));
+            }
+            for (id, content) in &queued {
+                let id = id.as_deref().unwrap_or_default();
+                println!("{}", porcelain::result(Status::Queued, id, content));
+            }
+        }
+        Output::Plain => {
+            for (id, content) in &added {
+                println!("{id}\t{content}");
+            }
+        }
+    }
+    if !queued.is_empty() {
+        view.output.status(&format!(
+            "Could not reach Todoist. {} todos will be added on the next sync.",
+            queued.len()
+        ));
+    }
+    for failure in &failures {
+        view.output.status(failure);
+    }
+    if !failures.is_empty() {
+        return Err(format!("{} of {total} todos could not be added.", failures.len()).into());
+    }
+    Ok(())
+}
+
+/// Adds a todo with Todoist's quick-add parsing, and says where it went and
+/// when it's due.
+async fn quick_add_todo(
+    store: &mut Store,
+    client: &SyncClient,
+    text: &str,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    if text.trim().is_empty() {
+        return Err("No todo to add.".into());
+    }
+    if client.is_dry_run() {
+        view.output.status(&format!("Would quick-add '{text}'."));
+        return Ok(());
+    }
+    let item = match session::quick_add(store, client, text).await {
+        Ok(item) => item,
+        Err(err) if err.is_unreachable() => {
+            return Err(
+                "Could not reach Todoist. Use `add` to have the todo added on the next sync."
+                    .into(),
+            );
+        }
+        Err(err) => return Err(err.into()),
+    };
+    store.set_last_added(&item.id, &item.content)?;
+
+    match view.output {
+        Output::Table => {
+            let project = project_name(store, &item.project_id)?;
+            let due = due_text(&item);
+            let due = if due.is_empty() {
+                String::new()
+            } else {
+                format!(", due {due}")
+            };
+            println!("Todo '{}' added to {project}{due}.", item.content);
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&item)?),
+        Output::Plain if view.porcelain => {
+            println!(
+                "{}",
+                porcelain::result(Status::Added, &item.id, &item.content)
+            );
+        }
+        Output::Plain => println!("{}\t{}", item.id, item.content),
+    }
+    Ok(())
+}
+
+/// Adds the tasks in a file, in the format `options` give or the one its name
+/// suggests, along with the projects and sections they name that don't exist
+/// yet. `target` holds the inbox's ID and the name of the project for tasks
+/// that don't name one, if there is one.
+///
+/// Imports from other apps are shown first, to be looked over before going
+/// ahead, and leave out the tasks that seem to have been imported already.
+async fn import_tasks(
+    store: &mut Store,
+    client: &SyncClient,
+    (file, options): (&Path, ImportOptions),
+    target: (&str, Option<&str>),
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let text = fs::read_to_string(file)?;
+    let (mut tasks, mut notes) = match options.from {
+        Some(import::Source::Taskwarrior) => {
+            let migration = taskwarrior::parse(&text)?;
+            (migration.tasks, migration.notes)
+        }
+        None => {
+            let format = options.format.unwrap_or_else(|| import::Format::of(file));
+            (import::parse(&text, format)?, Vec::new())
+        }
+    };
+    if tasks.is_empty() {
+        return Err(format!("No tasks to import in {}.", file.display()).into());
+    }
+    if !sync(store, client, view.output).await? {
+        view.output
+            .status("Could not reach Todoist, using cached projects and sections.");
+    }
+
+    let (inbox_project_id, fallback_project) = target;
+    let fallback_project_id = match fallback_project {
+        Some(name) => find_project_id(store, name)?,
+        None => inbox_project_id.to_string(),
+    };
+    let mut plan = ImportPlan::new(store)?;
+    if options.from.is_some() {
+        let already_imported;
+        (tasks, already_imported) = plan.already_imported(store, tasks, &fallback_project_id)?;
+        for content in already_imported {
+            notes.push(format!(
+                "'{content}' is already in Todoist, so it's left out."
+            ));
+        }
+    }
+    let (items, follow_ups) = plan.add_tasks(store, &tasks, &fallback_project_id)?;
+
+    if options.from.is_some() || client.is_dry_run() {
+        print_import_preview(&plan.containers, &tasks);
+        for note in &notes {
+            println!("{note}");
+        }
+    }
+    if client.is_dry_run() || items.is_empty() {
+        return Ok(());
+    }
+    if options.from.is_some()
+        && !options.yes
+        && !confirm(&format!("Import {} tasks?", items.len()))?
+    {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let (created, total) = (plan.containers.len(), items.len());
+    let results: Vec<_> = items
+        .iter()
+        .map(|command| (command.uuid, command.temp_id))
+        .zip(tasks.iter().map(|task| &task.content))
+        .collect();
+    let mut commands = plan.containers;
+    commands.extend(items);
+    commands.extend(follow_ups);
+    let response = send_chunked(store, client, commands, view.output).await?;
+
+    let mut imported = 0;
+    let mut queued = 0;
+    let mut failures = Vec::new();
+    for ((uuid, temp_id), content) in results {
+        let id = temp_id.and_then(|temp_id| response.temp_id_mapping.get(&temp_id));
+        match (response.status(&uuid), id) {
+            (Some(CommandStatus::Error { error, .. }), _) => {
+                failures.push(format!("Failed to add '{content}': {error}."));
+            }
+            (_, Some(_)) => imported += 1,
+            (_, None) => queued += 1,
+        }
+    }
+
+    if imported > 0 {
+        let created = if created > 0 {
+            format!(", creating {created} projects and sections")
+        } else {
+            String::new()
+        };
+        view.output
+            .status(&format!("Imported {imported} tasks{created}."));
+    }
+    if queued > 0 {
+        view.output.status(&format!(
+            "Could not reach Todoist. {queued} tasks will be imported on the next sync."
+        ));
+    }
+    for failure in &failures {
+        view.output.status(failure);
+    }
+    if !failures.is_empty() {
+        return Err(format!("{} of {total} tasks could not be imported.", failures.len()).into());
+    }
+    Ok(())
+}
+
+/// Adds the tasks in a checklist, due the days they come to from its start, in
+/// one batch of commands.
+async fn run_checklist(
+    store: &mut Store,
+    client: &SyncClient,
+    command: ChecklistCommand,
+    (inbox_project_id, default_project): (&str, Option<&str>),
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let ChecklistCommand::Run {
+        file,
+        start,
+        format,
+        project,
+    } = command;
+    let start = start.unwrap_or_else(|| Local::now().date_naive());
+    let text = fs::read_to_string(&file)?;
+    let format = format.unwrap_or_else(|| checklist::Format::of(&file));
+    let mut tasks = checklist::parse(&text, format)?;
+    if tasks.is_empty() {
+        return Err(format!("No tasks in {}.", file.display()).into());
+    }
+    checklist::schedule(&mut tasks, start)?;
+    if !sync(store, client, view.output).await? {
+        view.output
+            .status("Could not reach Todoist, using cached projects and sections.");
+    }
+
+    let fallback_project_id = match project.as_deref().or(default_project) {
+        Some(name) => find_project_id(store, name)?,
+        None => inbox_project_id.to_string(),
+    };
+    let mut plan = ImportPlan::new(store)?;
+    let (items, _) = plan.add_tasks(store, &tasks, &fallback_project_id)?;
+    if client.is_dry_run() {
+        print_import_preview(&plan.containers, &tasks);
+        return Ok(());
+    }
+
+    let (added, queued) = send_planned_tasks(store, client, plan, (&tasks, items), view).await?;
+    if added > 0 {
+        view.output.status(&format!(
+            "Added {added} tasks from the checklist, starting {start}."
+        ));
+    }
+    if queued > 0 {
+        view.output.status(&format!(
+            "Could not reach Todoist. {queued} tasks from the checklist will be added on the \
+             next sync."
+        ));
+    }
+    Ok(())
+}
+
+/// Prints what an import would create, with subtasks indented under their
+/// parents.
+fn print_import_preview(containers: &[Command], tasks: &[import::Task]) {
+    for command in containers {
+        match &command.args {
+            CommandArgs::ProjectAdd(args) => println!("Would create project '{}'.", args.name),
+            CommandArgs::SectionAdd(args) => println!("Would create section '{}'.", args.name),
+            _ => {}
+        }
+    }
+    println!("Would add {} tasks:", tasks.len());
+    let mut depths: Vec<usize> = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let depth = task.parent.map_or(0, |parent| depths[parent] + 1);
+        depths.push(depth);
+        let check = if task.completed { "x" } else { " " };
+        let due = task
+            .due
+            .as_ref()
+            .map(|due| format!(" (due {due})"))
+            .unwrap_or_default();
+        println!("  {}- [{check}] {}{due}", "  ".repeat(depth), task.content);
+    }
+}
+
+/// The projects and sections that imported tasks can go in: the existing
+/// ones, and the ones that need to be created first.
+struct ImportPlan {
+    /// Each project's name and ID.
+    projects: Vec<(String, String)>,
+
+    /// Each section's project ID, name, and ID.
+    sections: Vec<(String, String, String)>,
+
+    /// The commands that create the missing projects and sections.
+    containers: Vec<Command>,
+}
+
+impl ImportPlan {
+    fn new(store: &Store) -> Result<Self, Box<dyn Error>> {
+        Ok(ImportPlan {
+            projects: store
+                .projects()?
+                .into_iter()
+                .map(|project| (project.name, project.id))
+                .collect(),
+            sections: store
+                .sections()?
+                .into_iter()
+                .map(|section| (section.project_id, section.name, section.id))
+                .collect(),
+            containers: Vec::new(),
+        })
+    }
+
+    /// The commands that add the tasks, and the ones that then add their
+    /// comments and complete the tasks that are marked as completed.
+    fn add_tasks(
+        &mut self,
+        store: &Store,
+        tasks: &[import::Task],
+        fallback_project_id: &str,
+    ) -> Result<(Vec<Command>, Vec<Command>), Box<dyn Error>> {
+        let mut items: Vec<Command> = Vec::with_capacity(tasks.len());
+        let mut follow_ups = Vec::new();
+        for task in tasks {
+            let project_id = match &task.project {
+                Some(name) => self.project_id(name),
+                None => fallback_project_id.to_string(),
+            };
+            let section_id = task
+                .section
+                .as_ref()
+                .map(|name| self.section_id(&project_id, name));
+            let parent_id = task.parent.map(|parent| temp_id_of(&items[parent]));
+            let command = Command::new(CommandArgs::ItemAdd(ItemAddArgs {
+                project_id,
+                content: task.content.clone(),
+                description: task.description.clone(),
+                due: task
+                    .due
+                    .clone()
+                    .map(due_args)
+                    .transpose()
+                    .map_err(|err| format!("'{}': {err}", task.content))?,
+                deadline: task.deadline.clone().map(|date| Deadline { date }),
+                duration: None,
+                priority: task.priority.map(|priority| 5 - priority),
+                labels: match_known_labels(store, task.labels.clone())?,
+                responsible_uid: None,
+                section_id,
+                parent_id,
+            }));
+            for comment in &task.comments {
+                follow_ups.push(Command::new(CommandArgs::NoteAdd(NoteAddArgs {
+                    item_id: temp_id_of(&command),
+                    content: comment.clone(),
+                })));
+            }
+            if task.completed {
+                let id = temp_id_of(&command);
+                follow_ups.push(Command::new(CommandArgs::ItemClose(ItemCloseArgs { id })));
+            }
+            items.push(command);
+        }
+        Ok((items, follow_ups))
+    }
+
+    /// Splits off the tasks that are already open in the project they'd go
+    /// in, by their content, ignoring case, returning the others and the
+    /// content of the ones split off. Only tasks without parents can be
+    /// split off, so that the others' parents stay where they are.
+    fn already_imported(
+        &self,
+        store: &Store,
+        tasks: Vec<import::Task>,
+        fallback_project_id: &str,
+    ) -> Result<(Vec<import::Task>, Vec<String>), Box<dyn Error>> {
+        let items = store.items()?;
+        let (mut kept, mut left_out) = (Vec::new(), Vec::new());
+        for task in tasks {
+            let project_id = match &task.project {
+                Some(name) => self
+                    .projects
+                    .iter()
+                    .find(|(project, _)| project.eq_ignore_ascii_case(name))
+                    .map(|(_, id)| id.as_str()),
+                None => Some(fallback_project_id),
+            };
+            let is_imported = task.parent.is_none()
+                && items.iter().any(|item| {
+                    Some(item.project_id.as_str()) == project_id
+                        && !item.checked
+                        && !item.is_deleted
+                        && item.content.eq_ignore_ascii_case(&task.content)
+                });
+            if is_imported {
+                left_out.push(task.content);
+            } else {
+                kept.push(task);
+            }
+        }
+        Ok((kept, left_out))
+    }
+
+    /// The ID of the project with this name, ignoring case, which is a temp ID
+    /// if the project needs to be created.
+    fn project_id(&mut self, name: &str) -> String {
+        if let Some((_, id)) = self
+            .projects
+            .iter()
+            .find(|(project, _)| project.eq_ignore_ascii_case(name))
+        {
+            return id.clone();
+        }
+        let command = Command::new(CommandArgs::ProjectAdd(ProjectAddArgs {
+            name: name.to_string(),
+            parent_id: None,
+        }));
+        let id = temp_id_of(&command);
+        self.projects.push((name.to_string(), id.clone()));
+        self.containers.push(command);
+        id
+    }
+
+    /// The ID of the section with this name in a project, like
+    /// [`ImportPlan::project_id`].
+    fn section_id(&mut self, project_id: &str, name: &str) -> String {
+        if let Some((_, _, id)) = self.sections.iter().find(|(project, section, _)| {
+            project == project_id && section.eq_ignore_ascii_case(name)
+        }) {
+            return id.clone();
+        }
+        let command = Command::new(CommandArgs::SectionAdd(SectionAddArgs {
+            name: name.to_string(),
+            project_id: project_id.to_string(),
+        }));
+        let id = temp_id_of(&command);
+        self.sections
+            .push((project_id.to_string(), name.to_string(), id.clone()));
+        self.containers.push(command);
+        id
+    }
+}
+
+/// The temp ID of a command that creates something, which later commands in
+/// the same request can use to refer to it.
+fn temp_id_of(command: &Command) -> String {
+    command.temp_id.map(|id| id.to_string()).unwrap_or_default()
+}
+
+/// Prints what's due today and what's overdue, in one line, from the cache.
+fn print_status(store: &Store, format: Option<widget::Format>) -> Result<(), Box<dyn Error>> {
+    let items = store.items()?;
+    let summary = Summary::new(&items, Local::now().date_naive());
+    match format {
+        Some(format) => println!("{}", summary.render(format)?),
+        None => println!("{}", summary.text()),
+    }
+    Ok(())
+}
+
+/// Lists the open tasks in a project (the inbox if `project_name` isn't given),
+/// grouped by section, and returns how many there are.
+async fn list_project(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    project_name: Option<String>,
+    assignee: Option<&str>,
+    view: View,
+) -> Result<usize, Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let (project_id, project_title) = if let Some(name) = project_name {
+        let project = store
+            .projects()?
+            .into_iter()
+            .find(|project| project.name.eq_ignore_ascii_case(&name))
+            .ok_or(format!("Could not find a project named '{name}'."))?;
+        (project.id, project.name)
+    } else {
+        (inbox_project_id.to_string(), "Inbox".to_string())
+    };
+
+    let mut items = store.open_items_in_project(&project_id)?;
+    if let Some(assignee) = assignee {
+        items.retain(|item| item.responsible_uid.as_deref() == Some(assignee));
+    }
+    let blocked = blocked_ids(store)?;
+    items.retain(|item| !blocked.contains(&item.id));
+    let groups = group_by_section(&items, &store.sections_in_project(&project_id)?);
+    let groups = arrange_groups(store, groups, view)?;
+
+    print_items(store, view, Some(&project_title), &groups)?;
+    let listed_items: Vec<&Item> = groups.into_iter().flat_map(|(_, items)| items).collect();
+    store.set_last_list(&listed_items)?;
+    Ok(listed_items.len())
+}
+
+/// Lists the open tasks that match a filter, and returns how many there are.
+/// Each of the filter's comma-separated queries gets its own group.
+async fn list_filter(
+    store: &mut Store,
+    client: &SyncClient,
+    text: &str,
+    (workspace, assignee): (Option<&str>, Option<&str>),
+    view: View,
+) -> Result<usize, Box<dyn Error>> {
+    let queries = filter::parse(text)?;
+    sync_or_warn(store, client, view).await?;
+
+    let mut items = store.items()?;
+    let blocked = blocked_ids(store)?;
+    items.retain(|item| !blocked.contains(&item.id));
+    let projects = store.projects()?;
+    let title = match workspace {
+        Some(name) => {
+            let workspace = find_workspace(store, name)?;
+            let in_workspace: HashSet<&str> = projects
+                .iter()
+                .filter(|project| project.workspace_id.as_ref() == Some(&workspace.id))
+                .map(|project| project.id.as_str())
+                .collect();
+            items.retain(|item| in_workspace.contains(item.project_id.as_str()));
+            match text {
+                "all" => workspace.name,
+                text => format!("{text} in {}", workspace.name),
+            }
+        }
+        None => text.to_string(),
+    };
+    let sections = store.sections()?;
+    let context = filter::Context {
+        today: Local::now().date_naive(),
+        projects: &projects,
+        sections: &sections,
+    };
+
+    let has_headings = queries.len() > 1;
+    let groups: Vec<(Option<String>, Vec<&Item>)> = queries
+        .into_iter()
+        .map(|(text, query)| {
+            let mut matches: Vec<&Item> = items
+                .iter()
+                .filter(|item| !item.checked && query.matches(item, &context))
+                .filter(|item| {
+                    assignee.is_none_or(|id| item.responsible_uid.as_deref() == Some(id))
+                })
+                .collect();
+            agenda::sort_by_due(&mut matches);
+            (Some(text).filter(|_| has_headings), matches)
+        })
+        .collect();
+    let groups = arrange_groups(store, groups, view)?;
+
+    print_items(store, view, Some(&title), &groups)?;
+    let listed_items: Vec<&Item> = groups.into_iter().flat_map(|(_, items)| items).collect();
+    store.set_last_list(&listed_items)?;
+    Ok(listed_items.len())
+}
+
+/// Lists the open tasks that match a search, optionally narrowed down to a
+/// project or label.
+async fn search_tasks(
+    store: &mut Store,
+    client: &SyncClient,
+    query: &str,
+    project: Option<&str>,
+    label: Option<&str>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let project_id = match project {
+        Some(name) => Some(
+            find_project(store, name)?
+                .ok_or(format!("Could not find a project named '{name}'."))?
+                .id,
+        ),
+        None => None,
+    };
+    let mut items = store.items()?;
+    items.retain(|item| {
+        project_id.as_ref().is_none_or(|id| &item.project_id == id)
+            && label.is_none_or(|label| {
+                item.labels
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(label.trim_start_matches('@')))
+            })
+    });
+    let hits = search::search(query, &items);
+
+    match view.output {
+        Output::Table => print_search_hits(query, &hits),
+        Output::Json => println!("{}", serde_json::to_string_pretty(&hits)?),
+        Output::Plain => {
+            for (index, hit) in hits.iter().enumerate() {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    index + 1,
+                    hit.item.id,
+                    hit.item.content,
+                    hit.score
+                );
+            }
+        }
+    }
+    let listed_items: Vec<&Item> = hits.iter().map(|hit| hit.item).collect();
+    store.set_last_list(&listed_items)?;
+    Ok(())
+}
+
+/// Lists the tasks completed since `since`, optionally only from `project`,
+/// numbered so that `reopen` can refer to them by index.
+async fn show_completed(
+    store: &mut Store,
+    client: &SyncClient,
+    since: Option<NaiveDate>,
+    project: Option<&str>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let project_id = project
+        .map(|name| find_project_id(store, name))
+        .transpose()?;
+    let since = since.and_then(utc_start_of);
+    let items = session::completed_items(client, project_id.as_deref(), since.as_deref()).await?;
+    store.set_last_completed(&items)?;
+
+    match view.output {
+        Output::Table => {
+            let attribution = Attribution::new(store)?;
+            println!("Completed tasks:");
+            if items.is_empty() {
+                println!("  (no tasks)");
+            }
+            for (index, item) in (1..).zip(&items) {
+                println!(
+                    "  {index:>2}  {}  {}{}",
+                    locale::format_timestamp(&item.completed_at),
+                    item.content,
+                    attribution.describe(Some(&item.project_id), item.user_id.as_deref())
+                );
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&items)?),
+        Output::Plain => {
+            for item in &items {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    item.task_id,
+                    item.completed_at,
+                    item.content,
+                    item.user_id.as_deref().unwrap_or_default()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Counts down a focus session on a task, then records it, notes it on the
+/// task if asked to, and shows the time spent on the task in total. Ctrl-c
+/// ends the session early, and it's recorded as far as it got. Without a task,
+/// shows the time spent on each task instead.
+async fn focus_on_task(
+    store: &mut Store,
+    client: &SyncClient,
+    task: Option<&str>,
+    minutes: u64,
+    comment: bool,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let Some(task) = task else {
+        return print_work_totals(view.output, &store.work_totals()?);
+    };
+    if minutes == 0 {
+        return Err("A focus session has to last at least a minute.".into());
+    }
+    sync_or_warn(store, client, view).await?;
+    let (item_id, _) = resolve_listed_item(store, task)?;
+    let item = lookup_item(store, client, &item_id).await?;
+
+    let length = Duration::from_mins(minutes);
+    view.output.status(&format!(
+        "Focusing on '{}' for {}. Press ctrl-c to stop early.",
+        item.content,
+        focus::format_duration(length.as_secs())
+    ));
+    let (started_at, start) = (Utc::now(), Instant::now());
+    let timer = progress::countdown(length.as_secs(), "Focusing");
+    let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    let finished = loop {
+        tokio::select! {
+            _ = ticks.tick() => {
+                let elapsed = start.elapsed();
+                if elapsed >= length {
+                    break true;
+                }
+                let left = length.saturating_sub(elapsed).as_secs();
+                timer.set_position(elapsed.as_secs());
+                timer.set_prefix(format!("{:02}:{:02}", left / 60, left % 60));
+            }
+            _ = tokio::signal::ctrl_c() => break false,
+        }
+    };
+    timer.finish_and_clear();
+    let seconds = start.elapsed().min(length).as_secs();
+    store.record_work(&WorkSession::new(&item, started_at, seconds))?;
+
+    let total = store
+        .work_totals()?
+        .into_iter()
+        .find(|total| total.item_id == item.id);
+    let total = total.ok_or("The focus session could not be recorded.")?;
+    match view.output {
+        Output::Table => {
+            let sessions = if total.sessions == 1 {
+                "session"
+            } else {
+                "sessions"
+            };
+            println!(
+                "{} on '{}' for {}. That's {} in total, over {} {sessions}.",
+                if finished {
+                    "Focused"
+                } else {
+                    "Stopped early, after focusing"
+                },
+                item.content,
+                focus::format_duration(seconds),
+                focus::format_duration(total.seconds),
+                total.sessions
+            );
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&total)?),
+        Output::Plain => println!("{}\t{seconds}\t{}", item.id, total.seconds),
+    }
+    if !finished {
+        return Ok(());
+    }
+    send_notifications_about(&[("Time's up".to_string(), item.content.clone())]);
+    if comment {
+        let args = CommandArgs::NoteAdd(NoteAddArgs {
+            item_id: item.id.clone(),
+            content: focus::note(seconds),
+        });
+        if send_command(store, client, Command::new(args), view.output)
+            .await?
+            .is_none()
+        {
+            view.output
+                .status("Could not reach Todoist. The comment will be added on the next sync.");
+        }
+    }
+    Ok(())
+}
+
+fn print_work_totals(output: Output, totals: &[WorkTotal]) -> Result<(), Box<dyn Error>> {
+    match output {
+        Output::Table => {
+            println!("Time spent on each task:");
+            if totals.is_empty() {
+                println!("  (nothing tracked yet)");
+            }
+            for total in totals {
+                let sessions = if total.sessions == 1 {
+                    "session"
+                } else {
+                    "sessions"
+                };
+                println!(
+                    "  {:>7}  {}  ({} {sessions})",
+                    focus::format_duration(total.seconds),
+                    total.content,
+                    total.sessions
+                );
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(totals)?),
+        Output::Plain => {
+            for total in totals {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    total.item_id, total.seconds, total.sessions, total.content
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_track_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: TrackCommand,
+    always_comment: bool,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        TrackCommand::Start { task } => {
+            let (item_id, _) = resolve_task(store, task.as_deref())?;
+            let item = lookup_item(store, client, &item_id).await?;
+            stop_timer(store, client, always_comment, view.output).await?;
+            store.set_timer(Some(&Timer::start(&item, Utc::now())))?;
+            view.output
+                .status(&format!("Started tracking time on '{}'.", item.content));
+        }
+        TrackCommand::Stop { comment } => {
+            if !stop_timer(store, client, comment || always_comment, view.output).await? {
+                return Err("The timer isn't running.".into());
+            }
+        }
+        TrackCommand::Status => {
+            let timer = store.timer()?;
+            let session = timer.clone().map(|timer| timer.stop(Utc::now()));
+            match view.output {
+                Output::Table => match &session {
+                    Some(session) => println!(
+                        "Tracking time on '{}', for {} so far.",
+                        session.content,
+                        focus::format_duration(session.seconds)
+                    ),
+                    None => println!("The timer isn't running."),
+                },
+                Output::Json => println!("{}", serde_json::to_string_pretty(&session)?),
+                Output::Plain => {
+                    if let Some(session) = &session {
+                        println!(
+                            "{}\t{}\t{}",
+                            session.item_id, session.seconds, session.content
+                        );
+                    }
+                }
+            }
+        }
+        TrackCommand::Report { week } => {
+            let since = if week {
+                let start = review::week_start(Local::now().date_naive());
+                let start = Local.from_local_datetime(&start.and_time(NaiveTime::MIN));
+                start
+                    .earliest()
+                    .map_or(DateTime::UNIX_EPOCH, |start| start.to_utc())
+            } else {
+                DateTime::UNIX_EPOCH
+            };
+            let totals = track::project_totals(&store.work_sessions(since)?);
+            print_project_totals(store, view.output, week, &totals)?;
+        }
+    }
+    Ok(())
+}
+
+/// Stops the timer, if it's running, and records the time worked, noting it
+/// on the task too if `comment` is set. Returns whether it was running.
+async fn stop_timer(
+    store: &mut Store,
+    client: &SyncClient,
+    comment: bool,
+    output: Output,
+) -> Result<bool, Box<dyn Error>> {
+    let Some(timer) = store.timer()? else {
+        return Ok(false);
+    };
+    let session = timer.stop(Utc::now());
+    store.record_work(&session)?;
+    store.set_timer(None)?;
+    output.status(&format!(
+        "Tracked {} on '{}'.",
+        focus::format_duration(session.seconds),
+        session.content
+    ));
+    if comment {
+        let args = CommandArgs::NoteAdd(NoteAddArgs {
+            item_id: session.item_id.clone(),
+            content: track::note(session.seconds),
+        });
+        if send_command(store, client, Command::new(args), output)
+            .await?
+            .is_none()
+        {
+            output.status("Could not reach Todoist. The comment will be added on the next sync.");
+        }
+    }
+    Ok(true)
+}
+
+fn print_project_totals(
+    store: &Store,
+    output: Output,
+    week: bool,
+    totals: &[(String, u64, Vec<WorkTotal>)],
+) -> Result<(), Box<dyn Error>> {
+    let projects = store.projects()?;
+    let name = |id: &str| match projects.iter().find(|project| project.id == id) {
+        Some(project) => project.name.clone(),
+        None if id.is_empty() => "(no project)".to_string(),
+        None => id.to_string(),
+    };
+    match output {
+        Output::Table => {
+            println!("Time tracked{}:", if week { " this week" } else { "" });
+            if totals.is_empty() {
+                println!("  (nothing tracked)");
+            }
+            for (project_id, seconds, items) in totals {
+                println!(
+                    "  {:>7}  {}",
+                    focus::format_duration(*seconds),
+                    name(project_id)
+                );
+                for item in items {
+                    println!(
+                        "    {:>7}  {}",
+                        focus::format_duration(item.seconds),
+                        item.content
+                    );
+                }
+            }
+            let total: u64 = totals.iter().map(|(_, seconds, _)| seconds).sum();
+            println!("  {:>7}  in total", focus::format_duration(total));
+        }
+        Output::Json => {
+            let json: Vec<_> = totals
+                .iter()
+                .map(|(project_id, seconds, items)| {
+                    serde_json::json!({
+                        "project_id": project_id,
+                        "project": name(project_id),
+                        "seconds": seconds,
+                        "items": items,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Output::Plain => {
+            for (project_id, _, items) in totals {
+                for item in items {
+                    println!(
+                        "{project_id}\t{}\t{}\t{}",
+                        item.item_id, item.seconds, item.content
+                    );
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the git hooks into the current repository, replacing the ones that
+/// an earlier `hook install` wrote, but no others unless `force` is set.
+fn install_hooks(force: bool, output: Output) -> Result<(), Box<dyn Error>> {
+    let git = std::process::Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .map_err(|err| format!("Could not run git: {err}"))?;
+    if !git.status.success() {
+        return Err("Not in a git repository.".into());
+    }
+    let dir = PathBuf::from(String::from_utf8(git.stdout)?.trim());
+    for hook in githook::HOOKS {
+        let path = dir.join(hook);
+        let ours = fs::read_to_string(&path).map(|script| script.contains(githook::MARKER));
+        if !force && ours.is_ok_and(|ours| !ours) {
+            return Err(format!(
+                "There's already a {hook} hook at '{}'. Use `--force` to replace it.",
+                path.display()
+            )
+            .into());
+        }
+    }
+
+    let program = std::env::current_exe()?;
+    fs::create_dir_all(&dir)?;
+    for hook in githook::HOOKS {
+        let path = dir.join(hook);
+        let script = githook::script(hook, &program.to_string_lossy());
+        files::write(&path, script.as_bytes())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755))?;
+        }
+    }
+    output.status(&format!(
+        "Installed the {} hooks in '{}'.",
+        githook::HOOKS.join(" and "),
+        dir.display()
+    ));
+    Ok(())
+}
+
+async fn run_hook_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: HookCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        // handled before there's a store
+        HookCommand::Install { .. } => {}
+        HookCommand::CommitMsg { file } => {
+            // the commit is waiting, so this only looks at the cache
+            for reference in githook::references(&fs::read_to_string(file)?) {
+                let Some(item) = store.item(&reference.task_id)? else {
+                    view.output.status(&format!(
+                        "There's no task with ID {} in the cache.",
+                        reference.task_id
+                    ));
+                    continue;
+                };
+                let action = match reference.action {
+                    githook::Action::Close => "completed",
+                    githook::Action::Comment => "noted on",
+                };
+                view.output.status(&format!(
+                    "'{}' will be {action} once this commit is pushed.",
+                    item.content
+                ));
+            }
+        }
+        HookCommand::PrePush { remote, .. } => {
+            let mut commits = Vec::new();
+            for line in std::io::stdin().lines() {
+                let Some(range) = githook::push_range(&line?, &remote) else {
+                    continue;
+                };
+                let log = std::process::Command::new("git")
+                    .args(["log", "--reverse", "--format=%H%x1f%B%x1e"])
+                    .args(range)
+                    .output()?;
+                commits.extend(githook::parse_log(&String::from_utf8(log.stdout)?));
+            }
+            for commit in commits {
+                if store.mark_pushed(&commit.sha)? {
+                    handle_pushed_commit(store, client, &commit).await?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Completes or comments on the tasks a pushed commit refers to. Everything it
+/// says goes to stderr, so that it stays out of git's output.
+async fn handle_pushed_commit(
+    store: &mut Store,
+    client: &SyncClient,
+    commit: &githook::Commit,
+) -> Result<(), Box<dyn Error>> {
+    for reference in githook::references(&commit.message) {
+        let id = reference.task_id;
+        let task_name = task_name(&id, store.item(&id)?.map(|item| item.content));
+        let (args, done) = match reference.action {
+            githook::Action::Close => (
+                CommandArgs::ItemClose(ItemCloseArgs { id }),
+                format!("Completed {task_name}"),
+            ),
+            githook::Action::Comment => (
+                CommandArgs::NoteAdd(NoteAddArgs {
+                    item_id: id,
+                    content: format!(
+                        "Mentioned in commit {}: {}",
+                        commit.short_sha(),
+                        commit.subject()
+                    ),
+                }),
+                format!("Noted the commit on {task_name}"),
+            ),
+        };
+        match send_command(store, client, Command::new(args), Output::Plain).await {
+            Ok(Some(_)) => eprintln!("{done} ({}).", commit.short_sha()),
+            Ok(None) => eprintln!("Could not reach Todoist. {done} on the next sync."),
+            Err(err) => eprintln!("Could not update {task_name}: {err}"),
+        }
+    }
+    Ok(())
+}
+
+/// Midnight at the start of `date`, in local time, as the UTC time that the
+/// server takes.
+fn utc_start_of(date: NaiveDate) -> Option<String> {
+    let start = Local
+        .from_local_datetime(&date.and_time(NaiveTime::MIN))
+        .earliest()?;
+    Some(
+        start
+            .with_timezone(&Utc)
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string(),
+    )
+}
+
+/// Shows the review of the week so far. Without a connection, the review
+/// leaves out completed tasks, since they aren't all cached.
+async fn show_review(
+    store: &mut Store,
+    client: &SyncClient,
+    markdown: bool,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    // keep messages out of the Markdown, like with plain output
+    let view = if markdown {
+        View {
+            output: Output::Plain,
+            ..view
+        }
+    } else {
+        view
+    };
+    sync_or_warn(store, client, view).await?;
+
+    let today = Local::now().date_naive();
+    let since = utc_start_of(review::week_start(today));
+    let completed = match session::completed_items(client, None, since.as_deref()).await {
+        Ok(completed) => completed,
+        Err(err) if err.is_unreachable() => {
+            view.output
+                .status("Could not reach Todoist, so completed tasks are left out.");
+            Vec::new()
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let (items, projects) = (store.items()?, store.projects()?);
+    let review = review::review(&completed, &items, &projects, today);
+    let name = |id: &str| {
+        projects
+            .iter()
+            .find(|project| project.id == id)
+            .map_or(id.to_string(), |project| project.name.clone())
+    };
+
+    if markdown {
+        print!("{}", review.markdown(name));
+        return Ok(());
+    }
+    match view.output {
+        Output::Table => print_review(&review, name),
+        Output::Json => {
+            let completed: Vec<_> = review
+                .completed
+                .iter()
+                .map(|(project_id, items)| {
+                    serde_json::json!({
+                        "project_id": project_id,
+                        "project": name(project_id),
+                        "items": items,
+                    })
+                })
+                .collect();
+            let upcoming: Vec<_> = review
+                .upcoming
+                .iter()
+                .map(|(date, item)| serde_json::json!({ "date": date.to_string(), "item": item }))
+                .collect();
+            let json = serde_json::json!({
+                "start": review.start.to_string(),
+                "completed": completed,
+                "added": review.added,
+                "overdue": review.overdue,
+                "upcoming": upcoming,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Output::Plain => {
+            for item in review.completed.iter().flat_map(|(_, items)| items) {
+                println!(
+                    "completed\t{}\t{}\t{}",
+                    item.task_id, item.completed_at, item.content
+                );
+            }
+            for item in &review.added {
+                let added_at = item.added_at.as_deref().unwrap_or_default();
+                println!("added\t{}\t{added_at}\t{}", item.id, item.content);
+            }
+            for item in &review.overdue {
+                let due = item.due.as_ref().map(|due| due.date.as_str());
+                println!(
+                    "overdue\t{}\t{}\t{}",
+                    item.id,
+                    due.unwrap_or_default(),
+                    item.content
+                );
+            }
+            for (date, item) in &review.upcoming {
+                println!("upcoming\t{}\t{date}\t{}", item.id, item.content);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_review(review: &review::Review, project_name: impl Fn(&str) -> String) {
+    println!("Week of {}", locale::format_date(review.start));
+    let count: usize = review.completed.iter().map(|(_, items)| items.len()).sum();
+    println!("\nCompleted ({count}):");
+    for (project_id, items) in &review.completed {
+        println!("  {}", project_name(project_id));
+        for item in items {
+            println!("    {}", item.content);
+        }
+    }
+    println!("\nAdded ({}):", review.added.len());
+    for item in &review.added {
+        println!("  {} ({})", item.content, project_name(&item.project_id));
+    }
+    println!("\nOverdue ({}):", review.overdue.len());
+    for item in &review.overdue {
+        println!("  {}  {}", due_text(item), item.content);
+    }
+    println!("\nComing up ({}):", review.upcoming.len());
+    for (date, item) in &review.upcoming {
+        println!("  {}  {}", locale::format_date(*date), item.content);
+    }
+}
+
+async fn run_archive_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: ArchiveCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let cached = "Could not reach Todoist, showing cached data.";
+    match command {
+        ArchiveCommand::List => {
+            let projects = match session::archived_projects(store, client).await {
+                Ok(projects) => projects,
+                Err(err) if err.is_unreachable() => {
+                    view.output.status(cached);
+                    let mut projects = store.projects()?;
+                    projects.retain(|project| project.is_archived);
+                    projects
+                }
+                Err(err) => return Err(err.into()),
+            };
+            match view.output {
+                Output::Table => {
+                    println!("Archived projects:");
+                    if projects.is_empty() {
+                        println!("  (no projects)");
+                    }
+                    for project in &projects {
+                        println!("  {}", project.name);
+                    }
+                }
+                Output::Json => println!("{}", serde_json::to_string_pretty(&projects)?),
+                Output::Plain => {
+                    for project in &projects {
+                        println!("{}\t{}", project.id, project.name);
+                    }
+                }
+            }
+        }
+        ArchiveCommand::Show { project } => {
+            // the project might be archived, and so not known yet
+            if find_project(store, &project)?.is_none() {
+                match session::archived_projects(store, client).await {
+                    Ok(_) => {}
+                    Err(err) if err.is_unreachable() => {}
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            let project = lookup_project(store, client, &project, view.output).await?;
+            let items = match session::archived_items(store, client, &project.id).await {
+                Ok(items) => items,
+                Err(err) if err.is_unreachable() => {
+                    view.output.status(cached);
+                    let mut items = store.items()?;
+                    items.retain(|item| item.project_id == project.id && item.checked);
+                    items
+                }
+                Err(err) => return Err(err.into()),
+            };
+            print_archived_items(view.output, &project, &items)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_archived_items(
+    output: Output,
+    project: &Project,
+    items: &[Item],
+) -> Result<(), Box<dyn Error>> {
+    match output {
+        Output::Table => {
+            println!("Completed in {}:", project.name);
+            if items.is_empty() {
+                println!("  (no tasks)");
+            }
+            for item in items {
+                match item.completed_at.as_deref() {
+                    Some(completed_at) => {
+                        println!(
+                            "  {}  {}",
+                            locale::format_timestamp(completed_at),
+                            item.content
+                        );
+                    }
+                    None => println!("  {}", item.content),
+                }
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(items)?),
+        Output::Plain => {
+            for item in items {
+                println!(
+                    "{}\t{}\t{}",
+                    item.id,
+                    item.completed_at.as_deref().unwrap_or_default(),
+                    item.content
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn show_activity(
+    store: &mut Store,
+    client: &SyncClient,
+    limit: usize,
+    project: Option<&str>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let project_id = project
+        .map(|name| find_project_id(store, name))
+        .transpose()?;
+    let events = session::item_activity(client, project_id.as_deref(), limit).await?;
+
+    match view.output {
+        Output::Table => {
+            let attribution = Attribution::new(store)?;
+            println!("Activity:");
+            if events.is_empty() {
+                println!("  (no activity)");
+            }
+            for event in &events {
+                let action = match event.event_type.as_str() {
+                    "added" => "Added",
+                    "completed" => "Completed",
+                    _ => "Updated",
+                };
+                let content = event.extra_data.content.as_deref().unwrap_or_default();
+                println!(
+                    "  {}  {action} '{content}'{}",
+                    locale::format_timestamp(&event.event_date),
+                    attribution.describe(
+                        event.parent_project_id.as_deref(),
+                        event.initiator_id.as_deref()
+                    )
+                );
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&events)?),
+        Output::Plain => {
+            for event in &events {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    event.event_date,
+                    event.event_type,
+                    event.object_id,
+                    event.extra_data.content.as_deref().unwrap_or_default(),
+                    event.initiator_id.as_deref().unwrap_or_default()
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes tasks out as the `export` or `ical` command asks.
+async fn run_export_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: Commands,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Commands::Export {
+            format,
+            project,
+            out,
+        } => {
+            export_tasks(
+                store,
+                client,
+                format,
+                project.as_deref(),
+                out.as_deref(),
+                view,
+            )
+            .await
+        }
+        Commands::Ical { project, out } => {
+            export_calendar(store, client, project.as_deref(), out.as_deref(), view).await
+        }
+        _ => Ok(()),
+    }
+}
+
+async fn export_tasks(
+    store: &mut Store,
+    client: &SyncClient,
+    format: export::Format,
+    project: Option<&str>,
+    out: Option<&Path>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let mut projects = store.projects()?;
+    if let Some(name) = project {
+        let id = find_project_id(store, name)?;
+        projects.retain(|project| project.id == id);
+    }
+    let (sections, items) = (store.sections()?, store.items()?);
+    let export = Export {
+        projects: &projects,
+        sections: &sections,
+        items: &items,
+    };
+    let text = export.render(format)?;
+
+    match out {
+        Some(path) => {
+            files::write(path, text.as_bytes())?;
+            view.output.status(&format!(
+                "Exported {} tasks to {}.",
+                export.len(),
+                path.display()
+            ));
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+/// Writes the tasks with due dates as an iCalendar feed, to `out` or stdout.
+async fn export_calendar(
+    store: &mut Store,
+    client: &SyncClient,
+    project: Option<&str>,
+    out: Option<&Path>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let mut projects = store.projects()?;
+    if let Some(name) = project {
+        let id = find_project_id(store, name)?;
+        projects.retain(|project| project.id == id);
+    }
+    let items = store.items()?;
+    let calendar = ical::Calendar {
+        projects: &projects,
+        items: &items,
+    };
+    let text = calendar.render(Utc::now());
+
+    match out {
+        Some(path) => {
+            files::write(path, text.as_bytes())?;
+            view.output.status(&format!(
+                "Wrote {} tasks to {}.",
+                calendar.len(),
+                path.display()
+            ));
+        }
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+/// Names for the projects and people in the history of tasks, from the local
+/// cache.
+struct Attribution {
+    projects: HashMap<String, String>,
+    collaborators: HashMap<String, String>,
+}
+
+impl Attribution {
+    fn new(store: &Store) -> Result<Self, Box<dyn Error>> {
+        Ok(Attribution {
+            projects: store
+                .projects()?
+                .into_iter()
+                .map(|project| (project.id, project.name))
+                .collect(),
+            collaborators: store
+                .collaborators()?
+                .into_iter()
+                .map(|collaborator| (collaborator.id, collaborator.full_name))
+                .collect(),
+        })
+    }
+
+    /// Where something happened and who did it, like " (Home, by Sam)", as
+    /// far as they're known. Only shared projects have collaborators.
+    fn describe(&self, project_id: Option<&str>, user_id: Option<&str>) -> String {
+        let project = project_id.and_then(|id| self.projects.get(id));
+        let user = user_id.and_then(|id| self.collaborators.get(id));
+        match (project, user) {
+            (Some(project), Some(user)) => format!(" ({project}, by {user})"),
+            (Some(project), None) => format!(" ({project})"),
+            (None, Some(user)) => format!(" (by {user})"),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// Prints search results as a numbered list, so that later commands can refer
+/// to them by index, with the parts that matched highlighted. Matching
+/// descriptions are shown under their tasks.
+fn print_search_hits(query: &str, hits: &[search::Hit]) {
+    println!("Results for '{query}':");
+    if hits.is_empty() {
+        println!("  (no tasks)");
+        return;
+    }
+
+    let use_color = color::enabled();
+    let width = hits.len().to_string().len();
+    for (index, hit) in hits.iter().enumerate() {
+        let content = highlight(&hit.item.content, &hit.content_matches, use_color);
+        let due = due_text(hit.item);
+        let due = if due.is_empty() {
+            due
+        } else {
+            format!("  ({due})")
+        };
+        println!("  {:>width$}  {content}{due}", index + 1);
+
+        if !hit.description_matches.is_empty() {
+            // newlines and spaces are the same length, so the ranges still line up
+            let description = hit.item.description.replace('\n', " ");
+            println!(
+                "  {:width$}    {}",
+                "",
+                highlight(&description, &hit.description_matches, use_color)
+            );
+        }
+    }
+}
+
+/// Colors the given byte ranges of `text`.
+fn highlight(text: &str, ranges: &[std::ops::Range<usize>], use_color: bool) -> String {
+    if !use_color {
+        return text.to_string();
+    }
+    let mut highlighted = String::new();
+    let mut end = 0;
+    for range in ranges {
+        highlighted.push_str(&text[end..range.start]);
+        highlighted.push_str(&color::paint(&text[range.clone()], color::highlight()));
+        end = range.end;
+    }
+    highlighted.push_str(&text[end..]);
+    highlighted
+}
+
+/// Whether a daemon is running and has synced recently.
+async fn daemon_is_fresh(socket_path: &Path) -> bool {
+    let status = daemon::request(socket_path, &daemon::Request::Status).await;
+    status
+        .ok()
+        .flatten()
+        .is_some_and(|status| status.is_fresh(Utc::now().timestamp()))
+}
+
+/// Syncs every `interval` seconds until interrupted, answering requests on the
+/// daemon's socket in between. See [`daemon`] for how they're made.
+#[cfg(unix)]
+async fn run_daemon(
+    store: &mut Store,
+    client: &SyncClient,
+    socket_path: &Path,
+    interval: u64,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    if daemon::request(socket_path, &daemon::Request::Status)
+        .await?
+        .is_some()
+    {
+        return Err("A daemon is already running.".into());
+    }
+    // a socket left behind by a daemon that didn't shut down cleanly
+    if socket_path.exists() {
+        fs::remove_file(socket_path)?;
+    }
+    let listener = tokio::net::UnixListener::bind(socket_path)?;
+    output.status(&format!(
+        "Syncing every {interval} seconds. Press ctrl-c to stop."
+    ));
+
+    let mut status = daemon::Status {
+        last_sync: None,
+        interval,
+    };
+    let mut ticks = tokio::time::interval(Duration::from_secs(interval.max(1)));
+    let result = loop {
+        let synced = tokio::select! {
+            _ = ticks.tick() => daemon_sync(store, client, &mut status, output).await,
+            connection = listener.accept() => match connection {
+                Ok((stream, _)) => {
+                    answer_daemon_request(store, client, &mut status, stream, output).await
+                }
+                Err(_) => Ok(()),
+            },
+            _ = tokio::signal::ctrl_c() => break Ok(()),
+        };
+        if let Err(err) = synced {
+            break Err(err);
+        }
+    };
+    fs::remove_file(socket_path)?;
+    result
+}
+
+#[cfg(not(unix))]
+async fn run_daemon(
+    _store: &mut Store,
+    _client: &SyncClient,
+    _socket_path: &Path,
+    _interval: u64,
+    _output: Output,
+) -> Result<(), Box<dyn Error>> {
+    Err("The daemon needs Unix sockets, which this system doesn't have.".into())
+}
+
+/// Syncs on the daemon's behalf. Failures are only reported, so that the
+/// daemon keeps going until the network comes back, except for a rejected API
+/// token, which is returned since trying again won't help.
+#[cfg(unix)]
+async fn daemon_sync(
+    store: &mut Store,
+    client: &SyncClient,
+    status: &mut daemon::Status,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    match sync(store, client, output).await {
+        Ok(true) => status.last_sync = Some(Utc::now().timestamp()),
+        Ok(false) => eprintln!("Could not reach Todoist; will try again."),
+        Err(err) if matches!(err.downcast_ref(), Some(todoist::Error::Auth)) => return Err(err),
+        Err(err) => eprintln!("Sync failed: {err}"),
+    }
+    Ok(())
+}
+
+/// Reads one request from a connection to the daemon's socket and replies with
+/// the daemon's status, after syncing if asked to.
+#[cfg(unix)]
+async fn answer_daemon_request(
+    store: &mut Store,
+    client: &SyncClient,
+    status: &mut daemon::Status,
+    stream: tokio::net::UnixStream,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    // a client that never finishes its request shouldn't hold up syncing
+    let mut reader = BufReader::new(reader);
+    let read = reader.read_line(&mut line);
+    if !matches!(
+        tokio::time::timeout(Duration::from_secs(1), read).await,
+        Ok(Ok(_))
+    ) {
+        return Ok(());
+    }
+    match serde_json::from_str(&line) {
+        Ok(daemon::Request::Status) => {}
+        Ok(daemon::Request::Sync) => daemon_sync(store, client, status, output).await?,
+        Err(_) => return Ok(()),
+    }
+    if let Ok(mut reply) = serde_json::to_string(status) {
+        reply.push('\n');
+        // the client may have given up waiting, which is fine
+        let _ = writer.write_all(reply.as_bytes()).await;
+    }
+    Ok(())
+}
+
+/// A webhook request, as the server's connections pass it on to be handled:
+/// its signature, its body, and where to send the status to answer with.
+type Delivery = (
+    Option<String>,
+    hyper::body::Bytes,
+    tokio::sync::oneshot::Sender<hyper::StatusCode>,
+);
+
+/// Webhook requests bigger than this are turned away unread.
+const MAX_WEBHOOK_SIZE: u64 = 1 << 20;
+
+/// Receives Todoist's webhooks at `address` until ctrl-c. Connections are
+/// served on their own, but requests are handled one at a time, in the order
+/// they arrive, since they all write to the store.
+async fn serve_webhooks(
+    store: &mut Store,
+    address: std::net::SocketAddr,
+    hooks: &BTreeMap<String, String>,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    let secret = std::env::var("TODOIST_CLIENT_SECRET").map_err(|_| {
+        "Set TODOIST_CLIENT_SECRET to the client secret of the app that the webhooks belong to."
+    })?;
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    output.status(&format!(
+        "Listening for webhooks on {}. Press ctrl-c to stop.",
+        listener.local_addr()?
+    ));
+
+    let (sender, mut deliveries) = tokio::sync::mpsc::channel::<Delivery>(16);
+    loop {
+        tokio::select! {
+            connection = listener.accept() => {
+                let Ok((stream, _)) = connection else {
+                    continue;
+                };
+                let sender = sender.clone();
+                let service = hyper::service::service_fn(move |request| {
+                    receive_webhook(request, sender.clone())
+                });
+                let connection = hyper::server::conn::Http::new()
+                    .http1_only(true)
+                    .serve_connection(stream, service);
+                tokio::spawn(connection);
+            }
+            Some((signature, body, reply)) = deliveries.recv() => {
+                let status = handle_webhook(store, &secret, hooks, signature.as_deref(), &body, output)?;
+                // the connection may have closed already, which is fine
+                let _ = reply.send(status);
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+    Ok(())
+}
+
+/// Reads a webhook request and passes it on to [`serve_webhooks`], answering
+/// with the status it gets back.
+async fn receive_webhook(
+    request: hyper::Request<hyper::Body>,
+    deliveries: tokio::sync::mpsc::Sender<Delivery>,
+) -> Result<hyper::Response<hyper::Body>, hyper::Error> {
+    use hyper::{body::HttpBody, StatusCode};
+
+    let size = request.body().size_hint().upper();
+    let status = if request.method() != hyper::Method::POST {
+        StatusCode::METHOD_NOT_ALLOWED
+    } else if size.is_none() {
+        StatusCode::LENGTH_REQUIRED
+    } else if size.is_some_and(|size| size > MAX_WEBHOOK_SIZE) {
+        StatusCode::PAYLOAD_TOO_LARGE
+    } else {
+        let signature = request
+            .headers()
+            .get(webhook::SIGNATURE_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(ToString::to_string);
+        let body = hyper::body::to_bytes(request.into_body()).await?;
+        let (reply, status) = tokio::sync::oneshot::channel();
+        if deliveries.send((signature, body, reply)).await.is_ok() {
+            status.await.unwrap_or(StatusCode::SERVICE_UNAVAILABLE)
+        } else {
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    };
+    let mut response = hyper::Response::new(hyper::Body::empty());
+    *response.status_mut() = status;
+    Ok(response)
+}
+
+/// Handles a webhook request that's been read in full: checks its signature,
+/// caches the task it's about, if any, and starts the event's hook.
+fn handle_webhook(
+    store: &mut Store,
+    secret: &str,
+    hooks: &BTreeMap<String, String>,
+    signature: Option<&str>,
+    body: &[u8],
+    output: Output,
+) -> Result<hyper::StatusCode, Box<dyn Error>> {
+    use hyper::StatusCode;
+
+    if !signature.is_some_and(|signature| webhook::verify(secret, body, signature)) {
+        eprintln!("Turned away a webhook without a valid signature.");
+        return Ok(StatusCode::UNAUTHORIZED);
+    }
+    let Ok(event) = serde_json::from_slice::<webhook::Event>(body) else {
+        eprintln!("Turned away a webhook that isn't a Todoist event.");
+        return Ok(StatusCode::BAD_REQUEST);
+    };
+    match event.item() {
+        Some(item) => {
+            output.status(&format!("{}: {}", event.event_name, item.content));
+            store.put_items(&[item])?;
+        }
+        None => output.status(&event.event_name),
+    }
+    if let Some(command) = hooks.get(&event.event_name) {
+        let (command, event_name, body) = (command.clone(), event.event_name, body.to_vec());
+        // Todoist gives up on webhooks that take too long to answer, so hooks
+        // run on their own
+        tokio::spawn(async move {
+            if let Err(err) = hooks::run(&command, &body).await {
+                eprintln!("The hook for {event_name} failed: {err}");
+            }
+        });
+    }
+    Ok(StatusCode::OK)
+}
+
+/// At most this many notifications are sent at once; any more are rolled into
+/// a single one, so that a long list of overdue tasks doesn't flood the screen.
+const MAX_NOTIFICATIONS: usize = 3;
+
+/// Sends notifications for the tasks that have become due or overdue since the
+/// last check, syncing first unless that's turned off. With an interval (in
+/// minutes), keeps checking instead of returning.
+async fn notify_due_tasks(
+    store: &mut Store,
+    client: &SyncClient,
+    interval: Option<u64>,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    loop {
+        // being offline is fine; the cached due dates are checked regardless
+        if view.sync_first {
+            sync(store, client, view.output).await?;
+        }
+        let items = store.items()?;
+        let mut alerts = Vec::new();
+        for alert in notify::alerts(&items, Local::now().naive_local()) {
+            if store.mark_notified(&alert.key())? {
+                alerts.push(alert);
+            }
+        }
+        send_notifications(&alerts, view.output);
+
+        let Some(interval) = interval else {
+            return Ok(());
+        };
+        tokio::time::sleep(Duration::from_secs(interval.max(1) * 60)).await;
+    }
+}
+
+fn send_notifications(alerts: &[notify::Alert], output: Output) {
+    for alert in alerts {
+        output.status(&format!("{}: {}", alert.summary(), alert.item.content));
+    }
+    let notifications = if alerts.len() > MAX_NOTIFICATIONS {
+        let contents: Vec<&str> = alerts
+            .iter()
+            .map(|alert| alert.item.content.as_str())
+            .collect();
+        vec![(notify::batch_summary(alerts), contents.join("\n"))]
+    } else {
+        alerts
+            .iter()
+            .map(|alert| (alert.summary().to_string(), alert.item.content.clone()))
+            .collect()
+    };
+    send_notifications_about(&notifications);
+}
+
+/// Shows a desktop notification for each summary and body.
+fn send_notifications_about(notifications: &[(String, String)]) {
+    for (summary, body) in notifications {
+        let shown = notify_rust::Notification::new()
+            .appname("todoist")
+            .summary(summary)
+            .body(body)
+            .show();
+        if let Err(err) = shown {
+            eprintln!("Could not show a notification: {err}");
+        }
+    }
+}
+
+/// Lists overdue tasks, then the tasks due on each of the next `days` days.
+async fn show_agenda(
+    store: &mut Store,
+    client: &SyncClient,
+    days: u64,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let mut items = store.items()?;
+    let blocked = blocked_ids(store)?;
+    items.retain(|item| !blocked.contains(&item.id));
+    let today = Local::now().date_naive();
+    let agenda = agenda::agenda(&items, today, days);
+
+    let mut groups = vec![(Some("Overdue".to_string()), agenda.overdue)];
+    for (date, day_items) in agenda.days {
+        groups.push((Some(agenda::day_heading(date, today)), day_items));
+    }
+    print_items(store, view, None, &groups)?;
+    let listed_items: Vec<&Item> = groups.into_iter().flat_map(|(_, items)| items).collect();
+    store.set_last_list(&listed_items)?;
+    Ok(())
+}
+
+/// Shows how many tasks are due on each of the next `days` days.
+async fn show_forecast(
+    store: &mut Store,
+    client: &SyncClient,
+    days: u64,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let items = store.items()?;
+    let forecast = forecast::forecast(&items, Local::now().date_naive(), days);
+    match view.output {
+        Output::Table => print_forecast(&forecast),
+        Output::Json => {
+            let days: Vec<_> = forecast
+                .iter()
+                .map(|day| serde_json::json!({ "date": day.date.to_string(), "count": day.count }))
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&days)?);
+        }
+        Output::Plain => {
+            for day in &forecast {
+                println!("{}\t{}", day.date, day.count);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints a forecast with a line for each week, Monday first, that shades
+/// each day by how busy it is and ends with the week's total.
+fn print_forecast(forecast: &[forecast::Day]) {
+    let Some(first) = forecast.first() else {
+        return;
+    };
+    println!("Tasks due over the next {} days:", forecast.len());
+    println!("          M T W T F S S");
+    let max = forecast.iter().map(|day| day.count).max().unwrap_or(0);
+    let offset = first.date.weekday().num_days_from_monday() as usize;
+    let mut days = forecast.iter().peekable();
+    let mut blanks = offset;
+    while let Some(day) = days.peek() {
+        let week = day.date.week(Weekday::Mon).first_day();
+        let mut cells = "  ".repeat(blanks);
+        let mut total = 0;
+        while let Some(day) = days.next_if(|day| day.date.week(Weekday::Mon).first_day() == week) {
+            cells.push(forecast::shade(day.count, max));
+            cells.push(' ');
+            total += day.count;
+        }
+        println!("  {:<6}  {cells:<14}  {total}", week.format("%b %-d"));
+        blanks = 0;
+    }
+
+    if let Some(busiest) = forecast
+        .iter()
+        .filter(|day| day.count > 0)
+        .max_by_key(|day| {
+            // the earliest of the busiest days
+            (day.count, Reverse(day.date))
+        })
+    {
+        println!();
+        println!(
+            "Busiest: {}, with {} tasks. Shades go from ░ for fewest to █ for most.",
+            busiest.date.format("%a %b %-d"),
+            busiest.count
+        );
+    }
+}
+
+/// Shows today's tasks on a timeline, with the free time between them, then
+/// the ones that aren't due at a particular time.
+async fn show_schedule(
+    store: &mut Store,
+    client: &SyncClient,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+
+    let mut items = store.items()?;
+    let blocked = blocked_ids(store)?;
+    items.retain(|item| !blocked.contains(&item.id));
+    let schedule = schedule::schedule(&items, Local::now().date_naive());
+    let listed_items: Vec<&Item> = schedule
+        .blocks
+        .iter()
+        .map(|block| block.item)
+        .chain(schedule.unscheduled.iter().copied())
+        .collect();
+    match view.output {
+        Output::Table => print_schedule(&schedule),
+        Output::Json => {
+            let blocks: Vec<_> = schedule
+                .blocks
+                .iter()
+                .map(|block| {
+                    serde_json::json!({
+                        "start": block.start.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                        "end": block.end.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                        "overlaps": block.overlaps,
+                        "item": block.item,
+                    })
+                })
+                .collect();
+            let json = serde_json::json!({
+                "blocks": blocks,
+                "unscheduled": schedule.unscheduled,
+            });
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Output::Plain => {
+            for (index, block) in schedule.blocks.iter().enumerate() {
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    index + 1,
+                    block.item.id,
+                    block.start.format("%H:%M"),
+                    block.end.format("%H:%M"),
+                    block.item.content
+                );
+            }
+            for (index, item) in schedule.unscheduled.iter().enumerate() {
+                let index = schedule.blocks.len() + index + 1;
+                println!("{index}\t{}\t\t\t{}", item.id, item.content);
+            }
+        }
+    }
+    store.set_last_list(&listed_items)?;
+    Ok(())
+}
+
+/// Gaps shorter than this aren't shown as free time.
+const MIN_FREE_MINUTES: i64 = 15;
+
+fn print_schedule(schedule: &schedule::Schedule) {
+    if schedule.blocks.is_empty() && schedule.unscheduled.is_empty() {
+        println!("  (no tasks)");
+        return;
+    }
+
+    let mut busy_until: Option<chrono::NaiveDateTime> = None;
+    for (index, block) in schedule.blocks.iter().enumerate() {
+        if let Some(end) =
+            busy_until.filter(|end| (block.start - *end).num_minutes() >= MIN_FREE_MINUTES)
+        {
+            let free = TaskDuration {
+                amount: u32::try_from((block.start - end).num_minutes()).unwrap_or(u32::MAX),
+                unit: DurationUnit::Minute,
+            };
+            println!(
+                "      {}–{}  (free, {free})",
+                end.format("%H:%M"),
+                block.start.format("%H:%M")
+            );
+        }
+        let times = if block.end > block.start {
+            format!(
+                "{}–{}",
+                block.start.format("%H:%M"),
+                block.end.format("%H:%M")
+            )
+        } else {
+            format!("{}      ", block.start.format("%H:%M"))
+        };
+        let overlap = if block.overlaps { "  (overlaps)" } else { "" };
+        println!(
+            "  {:>2}  {times}  {}{overlap}",
+            index + 1,
+            block.item.content
+        );
+        busy_until = busy_until.max(Some(block.end));
+    }
+
+    if !schedule.unscheduled.is_empty() {
+        println!();
+        println!("  Any time today:");
+        for (index, item) in schedule.unscheduled.iter().enumerate() {
+            let index = schedule.blocks.len() + index + 1;
+            let duration = item
+                .duration
+                .map(|duration| format!(" ({duration})"))
+                .unwrap_or_default();
+            println!("  {index:>2}  {}{duration}", item.content);
+        }
+    }
+}
+
+fn run_config_command(path: &Path, command: &ConfigCommand) -> Result<(), Box<dyn Error>> {
+    let mut settings = Config::load(path)?;
+    match command {
+        ConfigCommand::Get { key: Some(key) } => {
+            println!("{}", settings.get(key)?.unwrap_or("(not set)".to_string()));
+        }
+        ConfigCommand::Get { key: None } => {
+            for key in settings.keys() {
+                let value = settings.get(&key)?.unwrap_or("(not set)".to_string());
+                println!("{key} = {value}");
+            }
+        }
+        ConfigCommand::Set { key, value } => {
+            settings.set(key, Some(value))?;
+            settings.save(path)?;
+            println!("Set {key} to {value}.");
+        }
+        ConfigCommand::Unset { key } => {
+            settings.set(key, None)?;
+            settings.save(path)?;
+            println!("Unset {key}.");
+        }
+    }
+    Ok(())
+}
+
+/// Generates a completion script for `shell`, which offers the names of cached
+/// projects and labels wherever one is expected.
+fn print_completions(shell: Shell, data_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let store_path = data_dir.join("data").join("todoist.db");
+    let (projects, labels) = if store_path.exists() {
+        let store = Store::open(&store_path)?;
+        let projects = store
+            .projects()?
+            .into_iter()
+            .filter(|project| !project.is_archived)
+            .map(|project| project.name);
+        let labels = store.labels()?.into_iter().map(|label| label.name);
+        (projects.collect(), labels.collect())
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    let names = |names: &[String]| {
+        let names = names.to_vec();
+        move |arg: Arg| {
+            if names.is_empty() {
+                arg
+            } else {
+                arg.value_parser(PossibleValuesParser::new(names))
+            }
+        }
+    };
+    let mut command = Args::command()
+        .mut_arg("project", names(&projects))
+        .mut_arg("LABEL", names(&labels))
+        .mut_subcommand("list", |list| list.mut_arg("project", names(&projects)))
+        .mut_subcommand("edit", |edit| edit.mut_arg("labels", names(&labels)))
+        .mut_subcommand("projects", |command| {
+            command
+                .mut_subcommand("add", |add| add.mut_arg("parent", names(&projects)))
+                .mut_subcommand("rename", |rename| {
+                    rename.mut_arg("project", names(&projects))
+                })
+                .mut_subcommand("archive", |archive| {
+                    archive.mut_arg("project", names(&projects))
+                })
+                .mut_subcommand("delete", |delete| {
+                    delete.mut_arg("project", names(&projects))
+                })
+        })
+        .mut_subcommand("labels", |command| {
+            command
+                .mut_subcommand("rename", |rename| rename.mut_arg("label", names(&labels)))
+                .mut_subcommand("delete", |delete| delete.mut_arg("label", names(&labels)))
+        });
+
+    clap_complete::generate(shell, &mut command, "todoist", &mut std::io::stdout());
+    Ok(())
+}
+
+/// The REST client to pair with the sync client, unless the config says to stick
+/// to the Sync API. A custom sync URL (as in testing) is only paired with a
+/// custom REST URL, since the default one wouldn't be on the same server.
+fn rest_client(
+    rest_url: Option<String>,
+    custom_sync_url: bool,
+    api_key: &str,
+    settings: &Config,
+) -> Option<RestClient> {
+    if settings.backend == Some(Backend::Sync) {
+        return None;
+    }
+    match (rest_url, custom_sync_url) {
+        (Some(url), _) => Some(RestClient::new(&url, api_key)),
+        (None, false) => Some(RestClient::new(REST_URL, api_key)),
+        (None, true) => None,
+    }
+}
+
+/// The directory for the API token and synced data: the one given on the
+/// command line, or in the config, or else the platform's usual place.
+fn find_data_dir(local_dir: Option<String>, settings: &Config) -> Result<PathBuf, Box<dyn Error>> {
+    if let Some(dir) = local_dir {
+        Ok(PathBuf::from_str(dir.as_str())?)
+    } else if let Some(dir) = &settings.data_dir {
+        Ok(dir.clone())
+    } else if let Some(dir) = dirs::data_local_dir() {
+        Ok(dir.join("tuido"))
+    } else {
+        Err("Could not find local data directory.".into())
+    }
+}
+
+const AUTH_FILE_NAME: &str = "client_auth.toml";
+
+fn read_config(data_dir: &Path) -> Result<Option<AuthConfig>, Box<dyn Error>> {
+    let auth_path = data_dir.join(AUTH_FILE_NAME);
+    if !auth_path.exists() {
+        return Ok(None);
+    }
+    let file = fs::read_to_string(auth_path)?;
+    Ok(Some(toml::from_str(file.as_str())?))
+}
+
+/// Finds the API token, looking in the system keyring first if that's where
+/// it's supposed to be, and in the token file otherwise (or if the keyring
+/// can't be read).
+fn load_api_key(config: Option<&AuthConfig>, use_keyring: bool, output: Output) -> Option<String> {
+    if use_keyring {
+        match keychain::load() {
+            Ok(Some(token)) => return Some(token),
+            Ok(None) => {}
+            Err(err) => output.status(&format!("{err}. Looking for a token file instead.")),
+        }
+    }
+    config.and_then(|config| config.api_key.clone())
+}
+
+/// Moves the API token from the token file to the system keyring, and switches
+/// the config over to using the keyring.
+fn migrate_to_keyring(
+    data_dir: &Path,
+    config: Option<AuthConfig>,
+    config_path: &Path,
+    mut settings: Config,
+) -> Result<(), Box<dyn Error>> {
+    let mut config = config
+        .filter(|config| config.api_key.is_some())
+        .ok_or("There's no API token file to migrate.")?;
+    let token = config.api_key.take().unwrap_or_default();
+
+    keychain::store(&token)?;
+    if keychain::load()?.as_ref() != Some(&token) {
+        return Err("The system keyring didn't keep the token, so it was left in its file.".into());
+    }
+
+    settings.token_storage = Some(TokenStorage::Keyring);
+    settings.save(config_path)?;
+    files::write_private(
+        &data_dir.join(AUTH_FILE_NAME),
+        toml::to_string(&config)?.as_bytes(),
+    )?;
+
+    println!("Moved the API token to the system keyring.");
+    Ok(())
+}
+
+/// Walks through setting things up: asks for an API token until Todoist
+/// accepts one, saves it, downloads everything, and if there's no config file
+/// yet, asks which project new todos should go to and writes one. Returns the
+/// token.
+async fn setup(
+    (data_dir, config_path): (&Path, Option<&Path>),
+    settings: &mut Config,
+    make_client: impl Fn(&str) -> SyncClient,
+    mut auth: AuthConfig,
+    use_keyring: bool,
+    output: Output,
+) -> Result<String, Box<dyn Error>> {
+    println!("Welcome! To get started, this needs your Todoist API token.");
+    println!("You'll find it in Todoist under Settings > Integrations > Developer:");
+    println!("  {DEVELOPER_SETTINGS_URL}");
+    let (token, client) = loop {
+        let Some(token) = prompt("API token:")? else {
+            return Err("Setup cancelled.".into());
+        };
+        let token = token.trim().to_string();
+        if token.is_empty() {
+            continue;
+        }
+        let client = make_client(&token);
+        auth.api_key = Some(token.clone());
+        match authenticate(data_dir, &client, auth.clone(), use_keyring, output).await {
+            Ok(()) => break (token, client),
+            Err(err) if err.is::<AuthFailed>() => {
+                println!("Todoist did not accept that token. Check it and try again.");
+            }
+            Err(err) => return Err(err),
+        }
+    };
+
+    let mut store = Store::open(&data_dir.join("data").join("todoist.db"))?;
+    let spinner = progress::spinner("Downloading your tasks...");
+    let outcome = session::sync(&mut store, &client).await;
+    spinner.finish_and_clear();
+    if outcome?.response.is_some() {
+        let (items, projects) = (store.items()?, store.projects()?);
+        let open = items.iter().filter(|item| !item.checked).count();
+        println!(
+            "Downloaded {open} tasks in {} projects.",
+            projects
+                .iter()
+                .filter(|project| !project.is_archived)
+                .count()
+        );
+    } else {
+        println!("Could not reach Todoist. Your tasks will be downloaded next time.");
+    }
+
+    if let Some(path) = config_path.filter(|path| !path.exists()) {
+        let answer = prompt("Which project should new todos go to? (leave empty for the inbox)")?;
+        if let Some(name) = answer
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            match find_project(&store, name)? {
+                Some(project) => settings.default_project = Some(project.name),
+                None => {
+                    println!(
+                        "Could not find a project named '{name}', so they'll go to the inbox."
+                    );
+                }
+            }
+        }
+        settings.save(path)?;
+        println!(
+            "Saved settings to '{}'. Change them any time with `todoist config set`.",
+            path.display()
+        );
+    }
+    println!("All set! Try `todoist add \"Buy milk tomorrow\"`, or `todoist today`.");
+    Ok(token)
+}
+
+/// Checks the config's API key against the API (using `client`, which should
+/// be set up with that key), then saves the config (readable only by the
+/// current user) along with the account's user data. If `use_keyring` is set,
+/// the key goes in the system keyring instead, if it's available.
+async fn authenticate(
+    data_dir: &Path,
+    client: &SyncClient,
+    mut config: AuthConfig,
+    use_keyring: bool,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    let user = match get_user(client).await {
+        Ok(user) => user,
+        Err(err) => {
+            return match err.downcast_ref::<todoist::Error>() {
+                Some(todoist::Error::Auth) => Err(AuthFailed(REJECTED_TOKEN).into()),
+                _ => Err(err),
+            };
+        }
+    };
+
+    if let Some(token) = config.api_key.as_ref().filter(|_| use_keyring) {
+        match keychain::store(token) {
+            Ok(()) => config.api_key = None,
+            Err(err) => output.status(&format!("{err}. Saving the token to a file instead.")),
+        }
+    }
+
+    fs::create_dir_all(data_dir)?;
+    files::write_private(
+        &data_dir.join(AUTH_FILE_NAME),
+        toml::to_string(&config)?.as_bytes(),
+    )?;
+    store_user_data(data_dir, &user, output)?;
+
+    println!("Authenticated as {}.", user.email);
+    Ok(())
+}
+
+fn store_user_data(data_dir: &Path, user: &User, output: Output) -> Result<(), Box<dyn Error>> {
+    let user_storage_path = data_dir.join("data").join("user.json");
+    output.status(&format!(
+        "Storing user data in '{}'.",
+        user_storage_path.display()
+    ));
+    fs::create_dir_all(data_dir.join("data"))?;
+    files::write(&user_storage_path, &serde_json::to_vec_pretty(user)?)?;
+    Ok(())
+}
+
+async fn get_stored_user_data(
+    data_dir: &Path,
+    client: &SyncClient,
+    output: Output,
+) -> Result<User, Box<dyn Error>> {
+    let user_storage_path = data_dir.join("data").join("user.json");
+
+    if user_storage_path.exists() {
+        let file = fs::read_to_string(user_storage_path)?;
+        let user = serde_json::from_str::<User>(&file)?;
+        Ok(user)
+    } else {
+        let user = get_user(client).await?;
+        store_user_data(data_dir, &user, output)?;
+        Ok(user)
+    }
+}
+
+/// Like [`resolve_listed_item`], but when no task is given, lets the user pick
+/// one of the open tasks in the local store.
+fn resolve_task(
+    store: &Store,
+    task: Option<&str>,
+) -> Result<(String, Option<String>), Box<dyn Error>> {
+    if let Some(task) = task {
+        return resolve_listed_item(store, task);
+    }
+    if !picker::available() {
+        return Err(
+            "No task given. Pass its ID, or its index in the output of the last `list`.".into(),
+        );
+    }
+
+    let project_names: HashMap<String, String> = store
+        .projects()?
+        .into_iter()
+        .map(|project| (project.id, project.name))
+        .collect();
+    let items: Vec<Item> = store
+        .items()?
+        .into_iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .collect();
+    if items.is_empty() {
+        return Err("There are no tasks to pick from.".into());
+    }
+    let choices: Vec<String> = items
+        .iter()
+        .map(|item| match project_names.get(&item.project_id) {
+            Some(name) => format!("{}  #{name}", item.content),
+            None => item.content.clone(),
+        })
+        .collect();
+
+    let index = picker::pick("Task:", &choices)?.ok_or("No task picked.")?;
+    let item = &items[index];
+    Ok((item.id.clone(), Some(item.content.clone())))
+}
+
+/// Interprets `task` as an index into the output of the last `list` if it can,
+/// `last` as the most recently added task, and as a task ID otherwise. Returns
+/// the task's ID, and its content if known.
+fn resolve_listed_item(
+    store: &Store,
+    task: &str,
+) -> Result<(String, Option<String>), Box<dyn Error>> {
+    if task == "last" {
+        let (id, content) = store
+            .last_added()?
+            .ok_or("No todo has been added yet, so there's no `last` one.")?;
+        return Ok((id, Some(content)));
+    }
+    if let Ok(index) = task.parse::<usize>() {
+        if let Some((id, content)) = store.last_list_entry(index)? {
+            return Ok((id, Some(content)));
+        }
+    }
+
+    Ok((task.to_string(), None))
+}
+
+/// Opens a task or project with the system's opener, or prints a link to it.
+fn open_page(
+    store: &Store,
+    target: Option<&str>,
+    app: bool,
+    print: bool,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    let (page, name) = resolve_page(store, target)?;
+    let link = page.link(app);
+    if print {
+        println!("{link}");
+    } else {
+        output.status(&format!("Opening {name}..."));
+        open::that_detached(&link).map_err(|err| format!("Could not open {link}: {err}"))?;
+    }
+    Ok(())
+}
+
+/// A task or project, as a place to go in Todoist's apps.
+enum Page {
+    Task(String),
+    Project(String),
+}
+
+impl Page {
+    /// A link to the page in the web app, or with `app`, a deep link for the
+    /// desktop and mobile apps.
+    fn link(&self, app: bool) -> String {
+        match (self, app) {
+            (Page::Task(id), false) => format!("https://app.todoist.com/app/task/{id}"),
+            (Page::Project(id), false) => format!("https://app.todoist.com/app/project/{id}"),
+            (Page::Task(id), true) => format!("todoist://task?id={id}"),
+            (Page::Project(id), true) => format!("todoist://project?id={id}"),
+        }
+    }
+}
+
+/// Works out which task or project `target` means, from the local cache, along
+/// with a name to call it by. Projects are matched by name if `target` starts
+/// with `#`; otherwise tasks are tried first, then projects.
+fn resolve_page(store: &Store, target: Option<&str>) -> Result<(Page, String), Box<dyn Error>> {
+    let Some(target) = target else {
+        let (id, content) = resolve_task(store, None)?;
+        let name = task_name(&id, content);
+        return Ok((Page::Task(id), name));
+    };
+    let project = |name: &str| -> Result<Option<(Page, String)>, Box<dyn Error>> {
+        let mut projects = store.projects()?;
+        let project = match projects.iter().position(|project| project.id == name) {
+            Some(index) => Some(projects.swap_remove(index)),
+            None => fuzzy::best_match(name, projects, |project| &project.name),
+        };
+        Ok(project.map(|project| (Page::Project(project.id), format!("#{}", project.name))))
+    };
+    if let Some(name) = target.strip_prefix('#') {
+        return project(name)?.ok_or(format!("Could not find a project named '{name}'.").into());
+    }
+
+    if let (id, Some(content)) = resolve_listed_item(store, target)? {
+        return Ok((Page::Task(id), format!("'{content}'")));
+    }
+    let mut items: Vec<Item> = store
+        .items()?
+        .into_iter()
+        .filter(|item| !item.is_deleted)
+        .collect();
+    let item = if let Some(index) = items.iter().position(|item| item.id == target) {
+        Some(items.swap_remove(index))
+    } else {
+        let open_items = items.into_iter().filter(|item| !item.checked);
+        fuzzy::best_match(target, open_items, |item| &item.content)
+    };
+    if let Some(item) = item {
+        return Ok((Page::Task(item.id), format!("'{}'", item.content)));
+    }
+    project(target)?.ok_or(format!("Could not find a task or project matching '{target}'.").into())
+}
+
+/// Adds a todo written in quick-add syntax. It goes to `default_project` (or
+/// the inbox) unless the text or `options` name another project.
+async fn add_todo(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    default_project: Option<&str>,
+    text: &str,
+    options: AddOptions,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let mut quick_add = match options.due {
+        Some(due) => QuickAdd {
+            due: Some(due),
+            ..quick_add::parse_without_dates(text)
+        },
+        None => quick_add::parse(text),
+    };
+    if let Some(priority) = options.priority {
+        quick_add.priority = Some(priority);
+    }
+    if let Some(project) = options.project {
+        quick_add.project = Some(project);
+    }
+    if quick_add.project.is_none() && options.parent.is_none() {
+        quick_add.project = default_project.map(ToString::to_string);
+    }
+    quick_add.labels.extend(options.labels);
+    if let Some(assignee) = options.assign {
+        quick_add.assignee = Some(assignee);
+    }
+    let (mut item_add_args, mut project_name) =
+        resolve_quick_add(store, client, inbox_project_id, quick_add, view.output).await?;
+    item_add_args.description = options.description;
+    item_add_args.duration = options.duration;
+
+    if let Some(task) = options.parent {
+        let (parent_id, _) = resolve_listed_item(store, &task)?;
+        let parent = lookup_item(store, client, &parent_id).await?;
+        item_add_args.project_id = parent.project_id;
+        item_add_args.section_id = parent.section_id;
+        item_add_args.parent_id = Some(parent.id);
+        project_name = format!("'{}' as a subtask", parent.content);
+    }
+
+    if let Some(name) = options.section {
+        let find = |store: &Store| -> Result<Option<Section>, Box<dyn Error>> {
+            let sections = store.sections_in_project(&item_add_args.project_id)?;
+            Ok(fuzzy::best_match(&name, sections, |section| &section.name))
+        };
+        if find(store)?.is_none() {
+            sync(store, client, view.output).await?;
+        }
+        let section = find(store)?.ok_or(format!(
+            "Could not find a section named '{name}' in {project_name}."
+        ))?;
+        item_add_args.section_id = Some(section.id);
+        project_name = format!("{project_name} / {}", section.name);
+    }
+    let content = item_add_args.content.clone();
+    let mode = options.dedupe.unwrap_or_default();
+    if let Some(duplicate) = find_duplicate(store, &item_add_args, &[], mode)? {
+        if warn_about_duplicate(&content, &duplicate, &project_name, mode, view) {
+            return Ok(());
+        }
+    }
+
+    let command = Command::new(CommandArgs::ItemAdd(item_add_args));
+    let temp_id = command.temp_id;
+    let add_item_response = send_command(store, client, command, view.output).await?;
+
+    // until the server assigns a real ID, the temp ID stands in for it; the
+    // server resolves it in any commands that are sent in the same batch
+    let id = temp_id.map(|temp_id| {
+        add_item_response
+            .as_ref()
+            .and_then(|response| response.temp_id_mapping.get(&temp_id).cloned())
+            .unwrap_or(temp_id.to_string())
+    });
+    if let Some(id) = &id {
+        store.set_last_added(id, &content)?;
+    }
+
+    if view.porcelain {
+        let status = match add_item_response {
+            Some(_) => Status::Added,
+            None => Status::Queued,
+        };
+        let id = id.unwrap_or_default();
+        println!("{}", porcelain::result(status, &id, &content));
+    } else if add_item_response.is_some() {
+        println!("Todo '{content}' added to {project_name}.");
+    } else {
+        println!("Could not reach Todoist. Todo '{content}' will be added on the next sync.");
+    }
+    Ok(())
+}
+
+/// The open task in a new todo's project that the todo looks like a duplicate
+/// of, when `mode` says to check: one that's cached, one that's queued to be
+/// added, or one of `adding`.
+fn find_duplicate(
+    store: &Store,
+    args: &ItemAddArgs,
+    adding: &[Item],
+    mode: dedupe::Mode,
+) -> Result<Option<Item>, Box<dyn Error>> {
+    if mode == dedupe::Mode::Off {
+        return Ok(None);
+    }
+    let mut items = store.open_items_in_project(&args.project_id)?;
+    items.extend(store.queued_commands()?.iter().filter_map(queued_item));
+    items.extend(adding.iter().cloned());
+    items.retain(|item| item.project_id == args.project_id);
+    Ok(dedupe::duplicate(&args.content, &items).cloned())
+}
+
+/// The task that an `item_add` command will add, with its temp ID.
+fn queued_item(command: &Command) -> Option<Item> {
+    let CommandArgs::ItemAdd(args) = &command.args else {
+        return None;
+    };
+    Some(Item {
+        id: temp_id_of(command),
+        content: args.content.clone(),
+        project_id: args.project_id.clone(),
+        ..Default::default()
+    })
+}
+
+/// Says that a new todo looks like `duplicate`, returning whether it's to be
+/// left out.
+fn warn_about_duplicate(
+    content: &str,
+    duplicate: &Item,
+    project_name: &str,
+    mode: dedupe::Mode,
+    view: View,
+) -> bool {
+    let skip = mode == dedupe::Mode::Skip;
+    if skip && view.porcelain {
+        let result = porcelain::result(Status::Skipped, &duplicate.id, &duplicate.content);
+        println!("{result}");
+    } else if skip {
+        view.output.status(&format!(
+            "Skipped '{content}', since '{}' is already in {project_name}.",
+            duplicate.content
+        ));
+    } else {
+        view.output.status(&format!(
+            "Warning: '{}' is already in {project_name}, and looks the same.",
+            duplicate.content
+        ));
+    }
+    skip
+}
+
+/// Turns parsed quick-add text into the arguments for an `item_add` command,
+/// looking up the project and assignee by name. If a name isn't in the local
+/// store, the store is synced once before giving up on it. Also returns the
+/// name of the project the task will be added to.
+async fn resolve_quick_add(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    quick_add: QuickAdd,
+    output: Output,
+) -> Result<(ItemAddArgs, String), Box<dyn Error>> {
+    let needs_lookup = |store: &Store| -> Result<bool, Box<dyn Error>> {
+        let project_missing = match &quick_add.project {
+            Some(name) => find_project(store, name)?.is_none(),
+            None => false,
+        };
+        let assignee_missing = match &quick_add.assignee {
+            Some(name) => find_collaborator(store, name)?.is_none(),
+            None => false,
+        };
+        Ok(project_missing || assignee_missing)
+    };
+    if needs_lookup(store)? {
+        sync(store, client, output).await?;
+    }
+
+    let (project_id, project_name) = match &quick_add.project {
+        Some(name) => {
+            let project = find_project(store, name)?
+                .ok_or(format!("Could not find a project named '{name}'."))?;
+            (project.id, project.name)
+        }
+        None => (inbox_project_id.to_string(), "inbox".to_string()),
+    };
+
+    let responsible_uid = match &quick_add.assignee {
+        Some(name) => Some(
+            find_collaborator(store, name)?
+                .ok_or(format!("Could not find a collaborator named '{name}'."))?
+                .id,
+        ),
+        None => None,
+    };
+
+    let labels = match_known_labels(store, quick_add.labels)?;
+
+    let args = ItemAddArgs {
+        project_id,
+        content: quick_add.content,
+        description: None,
+        due: quick_add.due.map(due_args).transpose()?,
+        deadline: None,
+        duration: None,
+        priority: quick_add.priority.map(|priority| 5 - priority),
+        labels,
+        responsible_uid,
+        section_id: None,
+        parent_id: None,
+    };
+    Ok((args, project_name))
+}
+
+/// Uses the existing spelling of any labels that are already known, so that
+/// differently-cased names don't create duplicate labels.
+fn match_known_labels(
+    store: &Store,
+    names: impl IntoIterator<Item = String>,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let known_labels = store.labels()?;
+    Ok(names
+        .into_iter()
+        .map(|name| {
+            known_labels
+                .iter()
+                .find(|label| label.name.eq_ignore_ascii_case(&name))
+                .map_or(name, |label| label.name.clone())
+        })
+        .collect())
+}
+
+/// The ID of the project named `name`, as [`find_project`] finds it.
+fn find_project_id(store: &Store, name: &str) -> Result<String, Box<dyn Error>> {
+    Ok(find_project(store, name)?
+        .ok_or(format!("Could not find a project named '{name}'."))?
+        .id)
+}
+
+/// Finds the project whose name best matches `name`, ignoring case and spaces
+/// (since quick-add names can't contain spaces) and allowing for partial names.
+fn find_project(store: &Store, name: &str) -> Result<Option<Project>, Box<dyn Error>> {
+    Ok(fuzzy::best_match(name, store.projects()?, |project| {
+        &project.name
+    }))
+}
+
+/// Finds a workspace by name, the way [`find_project`] finds projects.
+fn find_workspace(store: &Store, name: &str) -> Result<Workspace, Box<dyn Error>> {
+    fuzzy::best_match(name, store.workspaces()?, |workspace| &workspace.name)
+        .ok_or(format!("Could not find a workspace named '{name}'.").into())
+}
+
+/// Finds a collaborator by email, full name, or first name, ignoring case and
+/// spaces.
+fn find_collaborator(store: &Store, name: &str) -> Result<Option<Collaborator>, Box<dyn Error>> {
+    let name = fuzzy::normalize(name);
+    Ok(store.collaborators()?.into_iter().find(|collaborator| {
+        let first_name = collaborator.full_name.split_whitespace().next();
+        fuzzy::normalize(&collaborator.email) == name
+            || fuzzy::normalize(&collaborator.full_name) == name
+            || first_name.is_some_and(|first_name| fuzzy::normalize(first_name) == name)
+    }))
+}
+
+/// Finds a collaborator like [`find_collaborator`] does, syncing first if they
+/// aren't known yet.
+async fn lookup_collaborator(
+    store: &mut Store,
+    client: &SyncClient,
+    name: &str,
+    output: Output,
+) -> Result<Collaborator, Box<dyn Error>> {
+    if find_collaborator(store, name)?.is_none() {
+        sync(store, client, output).await?;
+    }
+    find_collaborator(store, name)?
+        .ok_or(format!("Could not find a collaborator named '{name}'.").into())
+}
+
+/// The full name of the collaborator with this ID, the ID itself if they
+/// aren't known, or "(none)".
+fn collaborator_name(store: &Store, id: Option<&str>) -> Result<String, Box<dyn Error>> {
+    let Some(id) = id else {
+        return Ok("(none)".to_string());
+    };
+    Ok(store
+        .collaborators()?
+        .into_iter()
+        .find(|collaborator| collaborator.id == id)
+        .map_or(id.to_string(), |collaborator| collaborator.full_name))
+}
+
+/// The current user's ID, for finding the tasks assigned to them. User data
+/// saved by older versions doesn't have it, so then the user is looked up
+/// among the collaborators by email.
+fn current_user_id(store: &Store, user: &User) -> Result<String, Box<dyn Error>> {
+    if !user.id.is_empty() {
+        return Ok(user.id.clone());
+    }
+    store
+        .collaborators()?
+        .into_iter()
+        .find(|collaborator| {
+            !user.email.is_empty() && collaborator.email.eq_ignore_ascii_case(&user.email)
+        })
+        .map(|collaborator| collaborator.id)
+        .ok_or("Could not tell which collaborator you are.".into())
+}
+
+/// Sends a sync request carrying `commands`, returning `None` if the server
+/// couldn't be reached and they were queued. See [`session::send_request`].
+async fn send_request(
+    store: &mut Store,
+    client: &SyncClient,
+    sync_token: &str,
+    resource_types: &[&str],
+    commands: Vec<Command>,
+    output: Output,
+) -> Result<Option<Response>, Box<dyn Error>> {
+    let outcome =
+        session::send_request(store, client, sync_token, resource_types, commands).await?;
+    Ok(report_queued_commands(outcome, output))
+}
+
+/// Reports how any previously queued commands went, then hands back the
+/// response.
+fn report_queued_commands(outcome: Outcome, output: Output) -> Option<Response> {
+    for conflict in &outcome.dropped {
+        output.status(&format!(
+            "Dropped queued command: {}, since it conflicts with changes made elsewhere.",
+            conflict.command.args.description()
+        ));
+    }
+    for command in &outcome.flushed {
+        let description = command.args.description();
+        match outcome.status(&command.uuid) {
+            Some(CommandStatus::Error { error, .. }) => {
+                output.status(&format!("Queued command failed: {description} ({error})."));
+            }
+            _ => output.status(&format!("Queued command succeeded: {description}.")),
+        }
+    }
+    for (setting, err) in &outcome.hook_errors {
+        eprintln!("The {setting} hook failed: {err}");
+    }
+    outcome.response
+}
+
+/// Shows each queued command that clashes with changes made elsewhere, and
+/// asks whether to send it anyway or drop it.
+async fn resolve_conflicts(store: &mut Store, client: &SyncClient) -> Result<(), Box<dyn Error>> {
+    for conflict in session::find_conflicts(store, client).await? {
+        println!(
+            "This queued command conflicts with changes made elsewhere: {}.",
+            conflict.command.args.description()
+        );
+        if conflict.remote.is_none() {
+            println!("  '{}' was deleted.", conflict.base.content);
+        }
+        for difference in &conflict.differences {
+            println!("  {}", difference.field);
+            println!("    local:  {}", difference.local);
+            println!("    remote: {}", difference.remote);
+        }
+
+        let resolution = loop {
+            print!("Keep the [l]ocal change, or the [r]emote one? ");
+            std::io::stdout().flush()?;
+            let mut answer = String::new();
+            if std::io::stdin().read_line(&mut answer)? == 0 {
+                return Err("No answer given.".into());
+            }
+            match answer.trim().to_lowercase().as_str() {
+                "l" | "local" => break Resolution::KeepLocal,
+                "r" | "remote" => break Resolution::KeepRemote,
+                _ => {}
+            }
+        };
+        session::resolve_conflict(store, &conflict, resolution)?;
+    }
+    Ok(())
+}
+
+/// Sends commands in as many requests as it takes, and merges the responses.
+/// Commands that have neither a status nor a temp ID mapping in the merged
+/// response were queued because the server couldn't be reached.
+async fn send_chunked(
+    store: &mut Store,
+    client: &SyncClient,
+    commands: Vec<Command>,
+    output: Output,
+) -> Result<Response, Box<dyn Error>> {
+    let bar = progress::bar(commands.len(), "Sending changes");
+    let outcomes =
+        session::send_chunked_with_progress(store, client, commands, |sent| bar.inc(sent as u64))
+            .await;
+    bar.finish_and_clear();
+
+    let mut merged = Response::default();
+    for outcome in outcomes? {
+        if let Some(response) = report_queued_commands(outcome, output) {
+            merged
+                .sync_status
+                .get_or_insert_default()
+                .extend(response.sync_status.unwrap_or_default());
+            merged.temp_id_mapping.extend(response.temp_id_mapping);
+        }
+    }
+    Ok(merged)
+}
+
+/// Sends a single command, returning `None` if it was queued because the
+/// server couldn't be reached. Fails if the server rejects the command.
+async fn send_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: Command,
+    output: Output,
+) -> Result<Option<Response>, Box<dyn Error>> {
+    let outcome = session::send_command(store, client, command).await?;
+    Ok(report_queued_commands(outcome, output))
+}
+
+/// Brings the local store up to date with the server. If the server can't be
+/// reached, the store is left as-is and `false` is returned.
+async fn sync(
+    store: &mut Store,
+    client: &SyncClient,
+    output: Output,
+) -> Result<bool, Box<dyn Error>> {
+    let spinner = progress::spinner("Syncing...");
+    let outcome = session::sync(store, client).await;
+    spinner.finish_and_clear();
+    Ok(report_queued_commands(outcome?, output).is_some())
+}
+
+/// Syncs as the `sync` command asks, and reports what changed.
+async fn sync_now(
+    store: &mut Store,
+    client: &SyncClient,
+    full: bool,
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    let spinner = progress::spinner(if full {
+        "Syncing everything..."
+    } else {
+        "Syncing..."
+    });
+    let result = session::sync_with_changes(store, client, full).await;
+    spinner.finish_and_clear();
+    let (outcome, changes) = result?;
+    report_queued_commands(outcome, output);
+    let changes = changes.ok_or("Could not reach Todoist. The local cache is unchanged.")?;
+
+    match output {
+        Output::Table if changes.is_empty() => println!("Already up to date."),
+        Output::Table => println!("Synced: {}.", describe_changes(&changes)),
+        Output::Json => println!("{}", serde_json::to_string_pretty(&changes)?),
+        Output::Plain => {
+            let counts = serde_json::to_value(changes)?;
+            for (name, count) in counts.as_object().into_iter().flatten() {
+                println!("{name}\t{count}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A summary of what a sync changed, like "2 items added, 1 completed
+/// remotely; 1 project updated".
+fn describe_changes(changes: &Changes) -> String {
+    let mut parts = Vec::new();
+    let mut describe = |noun: &str, counts: &[(usize, &str)]| {
+        let mut counts = counts.iter().filter(|(count, _)| *count > 0);
+        if let Some((count, what)) = counts.next() {
+            let plural = if *count == 1 { "" } else { "s" };
+            let rest = counts.map(|(count, what)| format!(", {count} {what}"));
+            parts.push(format!(
+                "{count} {noun}{plural} {what}{}",
+                rest.collect::<String>()
+            ));
+        }
+    };
+    describe(
+        "item",
+        &[
+            (changes.items_added, "added"),
+            (changes.items_completed, "completed remotely"),
+            (changes.items_updated, "updated"),
+            (changes.items_deleted, "deleted"),
+        ],
+    );
+    describe(
+        "project",
+        &[
+            (changes.projects_added, "added"),
+            (changes.projects_updated, "updated"),
+            (changes.projects_deleted, "deleted"),
+        ],
+    );
+    parts.join("; ")
+}
+
+/// Syncs before showing something, unless the config says to use the cache.
+/// If the server can't be reached, notes that the cached data will be shown
+/// instead.
+async fn sync_or_warn(
+    store: &mut Store,
+    client: &SyncClient,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    if view.sync_first && !sync(store, client, view.output).await? {
+        view.output
+            .status("Could not reach Todoist, showing cached data.");
+    }
+    Ok(())
+}
+
+async fn get_user(client: &SyncClient) -> Result<User, Box<dyn Error>> {
+    let spinner = progress::spinner("Fetching user data...");
+    let user = session::fetch_user(client).await;
+    spinner.finish_and_clear();
+    Ok(user?)
+}
+
+/// Splits a project's items into groups for each of its sections, in order,
+/// after the items that aren't in a section.
+fn group_by_section<'a>(
+    items: &'a [Item],
+    sections: &[Section],
+) -> Vec<(Option<String>, Vec<&'a Item>)> {
+    let in_section = |item: &Item, section_id: Option<&str>| {
+        let item_section = item
+            .section_id
+            .as_deref()
+            .filter(|id| sections.iter().any(|section| section.id == *id));
+        item_section == section_id
+    };
+
+    let mut groups: Vec<(Option<String>, Vec<&Item>)> = vec![(
+        None,
+        items.iter().filter(|item| in_section(item, None)).collect(),
+    )];
+    for section in sections {
+        let section_items = items
+            .iter()
+            .filter(|item| in_section(item, Some(&section.id)))
+            .collect();
+        groups.push((Some(section.name.clone()), section_items));
+    }
+    for (_, items) in &mut groups {
+        *items = order::nest_subtasks(items);
+    }
+    groups
+}
+
+/// Listed items under a heading, like the name of their section, if they have
+/// one.
+type ItemGroup<'a> = (Option<String>, Vec<&'a Item>);
+
+/// Sorts and regroups listed items as `--sort` and `--group-by` ask. Sorting
+/// alone keeps the groups and sorts within each of them.
+fn arrange_groups<'a>(
+    store: &Store,
+    mut groups: Vec<ItemGroup<'a>>,
+    view: View,
+) -> Result<Vec<ItemGroup<'a>>, Box<dyn Error>> {
+    if view.sort.is_none() && view.group_by.is_none() {
+        return Ok(groups);
+    }
+    let projects = store.projects()?;
+    if let Some(group_by) = view.group_by {
+        let mut items: Vec<&Item> = groups.into_iter().flat_map(|(_, items)| items).collect();
+        if let Some(sort) = view.sort {
+            arrange::sort(&mut items, sort, &projects);
+        }
+        let today = Local::now().date_naive();
+        return Ok(arrange::group(&items, group_by, &projects, today)
+            .into_iter()
+            .map(|(heading, items)| (Some(heading), items))
+            .collect());
+    }
+    if let Some(sort) = view.sort {
+        for (_, items) in &mut groups {
+            arrange::sort(items, sort, &projects);
+        }
+    }
+    Ok(groups)
+}
+
+/// Prints items in the requested format, under `title` if it's a table.
+fn print_items(
+    store: &Store,
+    view: View,
+    title: Option<&str>,
+    groups: &[(Option<String>, Vec<&Item>)],
+) -> Result<(), Box<dyn Error>> {
+    let items = groups.iter().flat_map(|(_, items)| items.iter().copied());
+    let mut reminders: HashMap<String, Vec<Reminder>> = HashMap::new();
+    if view.reminders {
+        for reminder in store.reminders()? {
+            reminders
+                .entry(reminder.item_id.clone())
+                .or_default()
+                .push(reminder);
+        }
+    }
+    match view.output {
+        Output::Table => {
+            if let Some(title) = title {
+                println!("{title}:");
+            }
+            let first_names = store
+                .collaborators()?
+                .into_iter()
+                .map(|collaborator| {
+                    let first_name = collaborator.full_name.split_whitespace().next();
+                    let first_name = first_name.unwrap_or(&collaborator.email).to_string();
+                    (collaborator.id, first_name)
+                })
+                .collect();
+            let details = TableDetails {
+                note_counts: &store.note_counts()?,
+                first_names: &first_names,
+                full_descriptions: view.full_descriptions,
+                reminders: &reminders,
+            };
+            print_items_table(groups, &details);
+        }
+        Output::Json if view.reminders => {
+            let items: Vec<_> = items
+                .map(|item| ItemWithReminders {
+                    item,
+                    reminders: reminders.get(&item.id).map_or(&[], Vec::as_slice),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&items)?);
+        }
+        Output::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&items.collect::<Vec<_>>())?
+        ),
+        Output::Plain if view.porcelain => {
+            for item in items {
+                println!("{}", porcelain::task(item));
+            }
+        }
+        Output::Plain => {
+            for (index, item) in items.enumerate() {
+                let mut line = format!(
+                    "{}\t{}\t{}\t{}\tp{}",
+                    index + 1,
+                    item.id,
+                    item.content,
+                    due_text(item),
+                    5 - item.priority.clamp(1, 4)
+                );
+                if view.reminders {
+                    let texts: Vec<String> = reminders
+                        .get(&item.id)
+                        .into_iter()
+                        .flatten()
+                        .map(reminder_text)
+                        .collect();
+                    line = format!("{line}\t{}", texts.join("; "));
+                }
+                println!("{line}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// How an item's due date is shown: as it was written, if possible, and
+/// otherwise in the user's date format.
+fn due_text(item: &Item) -> String {
+    item.due
+        .as_ref()
+        .map(|due| {
+            due.string
+                .clone()
+                .unwrap_or_else(|| locale::format_due_date(&due.date))
+        })
+        .unwrap_or_default()
+}
+
+/// How long a description can be before it's cut short in a table.
+const DESCRIPTION_PREVIEW_LENGTH: usize = 60;
+
+/// The start of a description, for showing it on one line: its first line,
+/// cut short if it's long, with an ellipsis if anything was left out.
+fn description_preview(description: &str) -> String {
+    let description = description.trim();
+    let first_line = description.lines().next().unwrap_or_default();
+    let mut preview: String = first_line
+        .chars()
+        .take(DESCRIPTION_PREVIEW_LENGTH)
+        .collect();
+    if preview.len() < description.len() {
+        preview = format!("{}…", preview.trim_end());
+    }
+    preview
+}
+
+/// Prints a description under its item, in full or as a preview.
+fn print_description(description: &str, indent: &str, full: bool) {
+    if full {
+        for line in description.trim().lines() {
+            println!("{indent}{}", line.trim_end());
+        }
+    } else if !description.trim().is_empty() {
+        println!("{indent}{}", description_preview(description));
+    }
+}
+
+/// An item in JSON output, along with its reminders.
+#[derive(Serialize)]
+struct ItemWithReminders<'a> {
+    #[serde(flatten)]
+    item: &'a Item,
+    reminders: &'a [Reminder],
+}
+
+/// What else to show about items in a table, besides their content.
+struct TableDetails<'a> {
+    note_counts: &'a HashMap<String, usize>,
+    first_names: &'a HashMap<String, String>,
+    full_descriptions: bool,
+    reminders: &'a HashMap<String, Vec<Reminder>>,
+}
+
+/// An item's due date in a table, marked if it recurs, and with its duration.
+fn due_cell(item: &Item) -> String {
+    let mut cell = due_text(item);
+    if item.due.as_ref().is_some_and(|due| due.is_recurring) {
+        cell.push_str(" ↻");
+    }
+    if let Some(duration) = item.duration {
+        cell = format!("{cell} ({duration})").trim_start().to_string();
+    }
+    cell
+}
+
+/// Prints items as a numbered table, so that later commands can refer to them
+/// by index. Groups with names get a heading; empty groups are skipped.
+/// Assigned items show their assignee's first name, in quick-add syntax, and
+/// descriptions go under their items' content.
+fn print_items_table(groups: &[(Option<String>, Vec<&Item>)], details: &TableDetails) {
+    let TableDetails {
+        note_counts,
+        first_names,
+        full_descriptions,
+        reminders,
+    } = *details;
+    let items: Vec<&Item> = groups
+        .iter()
+        .flat_map(|(_, items)| items.iter().copied())
+        .collect();
+    if items.is_empty() {
+        println!("  (no tasks)");
+        return;
+    }
+
+    // subtasks are indented when they come right after their parent or its
+    // other subtasks, which is how projects are listed
+    let depths = order::depths(&items);
+    let rows: Vec<[String; 4]> = items
+        .iter()
+        .zip(&depths)
+        .enumerate()
+        .map(|(index, (item, depth))| {
+            let indent = "  ".repeat(*depth);
+            let comments = match note_counts.get(&item.id) {
+                Some(1) => " [1 comment]".to_string(),
+                Some(count) => format!(" [{count} comments]"),
+                None => String::new(),
+            };
+            let assignee = item
+                .responsible_uid
+                .as_ref()
+                .and_then(|id| first_names.get(id))
+                .map(|name| format!(" +{name}"))
+                .unwrap_or_default();
+            [
+                (index + 1).to_string(),
+                format!("{indent}{}{assignee}{comments}", item.content),
+                due_cell(item),
+                format!("p{}", 5 - item.priority.clamp(1, 4)),
+            ]
+        })
+        .collect();
+
+    let headers = ["#", "Content", "Due", "Priority"];
+    let mut widths = headers.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    // cells are padded before they're colored, so that the escape codes don't
+    // count towards their widths
+    let format_row = |cells: [&str; 4], colors: [Option<(u8, u8, u8)>; 4]| {
+        cells
+            .iter()
+            .zip(colors)
+            .zip(widths)
+            .map(|((cell, rgb), width)| {
+                let padding = " ".repeat(width - cell.chars().count());
+                match rgb {
+                    Some(rgb) => format!("{}{padding}", color::paint(cell, rgb)),
+                    None => format!("{cell}{padding}"),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let use_color = color::enabled();
+    let today = Local::now().date_naive();
+    println!("  {}", format_row(headers, [None; 4]));
+    let mut rows = rows.iter().zip(&items).zip(&depths);
+    for (name, group) in groups {
+        if group.is_empty() {
+            continue;
+        }
+        if let Some(name) = name {
+            println!();
+            println!("  {name}:");
+        }
+
+        for ((row, item), depth) in rows.by_ref().take(group.len()) {
+            let mut colors = [None; 4];
+            if use_color {
+                if agenda::is_overdue(item, today) {
+                    colors[2] = Some(color::overdue());
+                }
+                colors[3] = color::priority(item.priority);
+            }
+            println!(
+                "  {}",
+                format_row(row.each_ref().map(String::as_str), colors)
+            );
+
+            // lined up with the content, a little further in
+            let indent = " ".repeat(2 + widths[0] + 2 + 2 * depth + 2);
+            print_description(&item.description, &indent, full_descriptions);
+            for reminder in reminders.get(&item.id).into_iter().flatten() {
+                println!("{indent}⏰ {}", reminder_text(reminder));
+            }
+        }
+    }
+}
+
+async fn run_projects_command(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    command: &ProjectsCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (args, name, done, queued) = match command {
+        ProjectsCommand::List {
+            counts,
+            sort,
+            workspace,
+        } => {
+            sync_or_warn(store, client, view).await?;
+
+            let mut projects = active_projects(store.projects()?, inbox_project_id);
+            if let Some(name) = workspace {
+                let workspace = find_workspace(store, name)?;
+                projects.retain(|project| project.workspace_id.as_ref() == Some(&workspace.id));
+            }
+            if *counts {
+                let counts = stats::project_counts(
+                    &store.items()?,
+                    &store.sections()?,
+                    Local::now().date_naive(),
+                );
+                print_project_counts(view.output, &projects, &counts, *sort)?;
+            } else {
+                print_projects(view.output, &projects, &store.workspaces()?)?;
+            }
+            return Ok(());
+        }
+        ProjectsCommand::Add { name, parent } => {
+            let parent_id = match parent {
+                Some(parent) => Some(lookup_project(store, client, parent, view.output).await?.id),
+                None => None,
+            };
+            let args = CommandArgs::ProjectAdd(ProjectAddArgs {
+                name: name.clone(),
+                parent_id,
+            });
+            (args, name.clone(), "added", "will be added")
+        }
+        ProjectsCommand::Rename { project, new_name } => {
+            let project = lookup_project(store, client, project, view.output).await?;
+            let args = CommandArgs::ProjectUpdate(ProjectUpdateArgs {
+                id: project.id,
+                name: Some(new_name.clone()),
+            });
+            (args, project.name, "renamed", "will be renamed")
+        }
+        ProjectsCommand::Archive { project, yes } => {
+            let project = lookup_project(store, client, project, view.output).await?;
+            if !yes && !confirm(&format!("Archive project '{}'?", project.name))? {
+                println!("Cancelled.");
+                return Ok(());
+            }
+            let args = CommandArgs::ProjectArchive(ProjectIdArgs { id: project.id });
+            (args, project.name, "archived", "will be archived")
+        }
+        ProjectsCommand::Reorder { project, position } => {
+            let project = lookup_project(store, client, project, view.output).await?;
+            let position = match Position::parse(position)? {
+                Position::After(sibling) => Position::After(
+                    lookup_project(store, client, &sibling, view.output)
+                        .await?
+                        .id,
+                ),
+                position => position,
+            };
+            let mut siblings: Vec<Project> = store
+                .projects()?
+                .into_iter()
+                .filter(|other| !other.is_archived && other.parent_id == project.parent_id)
+                .collect();
+            siblings.sort_by_key(|other| other.child_order);
+            let siblings: Vec<&str> = siblings.iter().map(|other| other.id.as_str()).collect();
+            let args = CommandArgs::ProjectReorder(ProjectReorderArgs {
+                projects: child_orders(order::reorder(&siblings, &project.id, &position)?)?,
+            });
+            (args, project.name, "moved", "will be moved")
+        }
+        ProjectsCommand::Delete { project, yes } => {
+            let project = lookup_project(store, client, project, view.output).await?;
+            let prompt = format!(
+                "Permanently delete project '{}' and all of its tasks?",
+                project.name
+            );
+            if !yes && !confirm(&prompt)? {
+                println!("Cancelled.");
+                return Ok(());
+            }
+            let args = CommandArgs::ProjectDelete(ProjectIdArgs { id: project.id });
+            (args, project.name, "deleted", "will be deleted")
+        }
+    };
+
+    if send_command(store, client, Command::new(args), view.output)
+        .await?
+        .is_some()
+    {
+        println!("Project '{name}' {done}.");
+    } else {
+        println!("Could not reach Todoist. Project '{name}' {queued} on the next sync.");
+    }
+    Ok(())
+}
+
+/// Runs the commands for working with other people in shared projects.
+async fn run_sharing_command(
+    store: &mut Store,
+    client: &SyncClient,
+    user: &User,
+    command: Commands,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        Commands::Collaborators { project } => {
+            sync_or_warn(store, client, view).await?;
+
+            let project = lookup_project(store, client, &project, view.output).await?;
+            let states = store.collaborator_states(&project.id)?;
+            print_collaborators(
+                view.output,
+                &project,
+                &states,
+                &store.collaborators()?,
+                user,
+            )
+        }
+        Commands::Share { project, email } => {
+            if !email.contains('@') {
+                return Err(format!("'{email}' isn't an email address.").into());
+            }
+            let project = lookup_project(store, client, &project, view.output).await?;
+            let args = CommandArgs::ShareProject(ShareProjectArgs {
+                project_id: project.id,
+                email: email.clone(),
+            });
+            if send_command(store, client, Command::new(args), view.output)
+                .await?
+                .is_some()
+            {
+                view.output
+                    .status(&format!("Invited {email} to '{}'.", project.name));
+            } else {
+                view.output.status(&format!(
+                    "Could not reach Todoist. {email} will be invited to '{}' on the next sync.",
+                    project.name
+                ));
+            }
+            Ok(())
+        }
+        Commands::Notifications {
+            command: Some(NotificationsCommand::MarkRead { ids }),
+            ..
+        } => mark_notifications_read(store, client, &ids, view.output).await,
+        Commands::Notifications {
+            unread,
+            command: None,
+        } => {
+            sync_or_warn(store, client, view).await?;
+
+            let notifications: Vec<LiveNotification> = store
+                .live_notifications()?
+                .into_iter()
+                .filter(|notification| {
+                    NOTIFICATION_TYPES.contains(&notification.notification_type.as_str())
+                        && (notification.is_unread || !unread)
+                })
+                .collect();
+            print_notifications(store, view.output, &notifications)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The kinds of notifications that are shown: the ones about something for
+/// the user to do, rather than, say, someone else finishing a task.
+const NOTIFICATION_TYPES: [&str; 3] = ["share_invitation_sent", "item_assigned", "note_added"];
+
+/// Marks the notifications with `ids` as read, or all the unread ones.
+async fn mark_notifications_read(
+    store: &mut Store,
+    client: &SyncClient,
+    ids: &[String],
+    output: Output,
+) -> Result<(), Box<dyn Error>> {
+    let mut notifications = store.live_notifications()?;
+    if let Some(id) = ids.iter().find(|id| {
+        !notifications
+            .iter()
+            .any(|notification| &notification.id == *id)
+    }) {
+        return Err(format!("Could not find a notification with ID '{id}'.").into());
+    }
+    notifications.retain(|notification| {
+        notification.is_unread && (ids.is_empty() || ids.contains(&notification.id))
+    });
+    if notifications.is_empty() {
+        output.status("No unread notifications.");
+        return Ok(());
+    }
+
+    let args = CommandArgs::LiveNotificationsMarkRead(LiveNotificationIdsArgs {
+        ids: notifications
+            .iter()
+            .map(|notification| notification.id.clone())
+            .collect(),
+    });
+    let count = match notifications.len() {
+        1 => "1 notification".to_string(),
+        count => format!("{count} notifications"),
+    };
+    if send_command(store, client, Command::new(args), output)
+        .await?
+        .is_some()
+    {
+        for notification in &mut notifications {
+            notification.is_unread = false;
+        }
+        store.put_live_notifications(&notifications)?;
+        output.status(&format!("Marked {count} as read."));
+    } else {
+        output.status(&format!(
+            "Could not reach Todoist. {count} will be marked as read on the next sync."
+        ));
+    }
+    Ok(())
+}
+
+async fn run_labels_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: &LabelsCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (args, name, done, queued) = match command {
+        LabelsCommand::List => {
+            sync_or_warn(store, client, view).await?;
+
+            print_labels(view.output, store.labels()?)?;
+            return Ok(());
+        }
+        LabelsCommand::Add { name } => {
+            let args = CommandArgs::LabelAdd(LabelAddArgs { name: name.clone() });
+            (args, name.clone(), "added", "will be added")
+        }
+        LabelsCommand::Rename { label, new_name } => {
+            let label = lookup_label(store, client, label, view.output).await?;
+            let args = CommandArgs::LabelUpdate(LabelUpdateArgs {
+                id: label.id,
+                name: Some(new_name.clone()),
+            });
+            (args, label.name, "renamed", "will be renamed")
+        }
+        LabelsCommand::Delete { label, yes } => {
+            let label = lookup_label(store, client, label, view.output).await?;
+            let prompt = format!(
+                "Delete label '{}' and remove it from all of its tasks?",
+                label.name
+            );
+            if !yes && !confirm(&prompt)? {
+                println!("Cancelled.");
+                return Ok(());
+            }
+            let args = CommandArgs::LabelDelete(LabelIdArgs { id: label.id });
+            (args, label.name, "deleted", "will be deleted")
+        }
+    };
+
+    if send_command(store, client, Command::new(args), view.output)
+        .await?
+        .is_some()
+    {
+        println!("Label '{name}' {done}.");
+    } else {
+        println!("Could not reach Todoist. Label '{name}' {queued} on the next sync.");
+    }
+    Ok(())
+}
+
+async fn run_comment_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: CommentCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    match command {
+        CommentCommand::Add { task, text } => {
+            let (item_id, content) = resolve_listed_item(store, &task)?;
+            let content = content.or(store.item(&item_id)?.map(|item| item.content));
+            let task_name = task_name(&item_id, content);
+
+            let args = CommandArgs::NoteAdd(NoteAddArgs {
+                item_id,
+                content: text,
+            });
+            if send_command(store, client, Command::new(args), view.output)
+                .await?
+                .is_some()
+            {
+                println!("Comment added to {task_name}.");
+            } else {
+                println!(
+                    "Could not reach Todoist. The comment on {task_name} will be added on the next sync."
+                );
+            }
+        }
+        CommentCommand::List { task } => {
+            sync_or_warn(store, client, view).await?;
+
+            let (item_id, _) = resolve_listed_item(store, &task)?;
+            let item = lookup_item(store, client, &item_id).await?;
+            print_notes(view.output, &item, &store.notes_for_item(&item.id)?)?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints a task's comments in the requested format.
+fn print_notes(output: Output, item: &Item, notes: &[Note]) -> Result<(), Box<dyn Error>> {
+    match output {
+        Output::Table => {
+            println!("Comments on '{}':", item.content);
+            if notes.is_empty() {
+                println!("  (no comments)");
+            }
+            for note in notes {
+                println!(
+                    "  {}  {}",
+                    locale::format_timestamp(&note.posted_at),
+                    note.content
+                );
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(notes)?),
+        Output::Plain => {
+            for note in notes {
+                println!("{}\t{}\t{}", note.id, note.posted_at, note.content);
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn run_reminder_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: ReminderCommand,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    let (args, done, queued) = match command {
+        ReminderCommand::List { task } => {
+            sync_or_warn(store, client, view).await?;
+
+            let (item_id, _) = resolve_listed_item(store, &task)?;
+            let item = lookup_item(store, client, &item_id).await?;
+            print_reminders(view.output, &item, &store.reminders_for_item(&item.id)?)?;
+            return Ok(());
+        }
+        ReminderCommand::Add {
+            task,
+            when,
+            location,
+            name,
+            leaving,
+        } => {
+            let when = when.as_deref().map(reminder::parse).transpose()?;
+            let place = location.as_deref().map(reminder::parse_place).transpose()?;
+            let (item_id, content) = resolve_listed_item(store, &task)?;
+            let cached = store.item(&item_id)?;
+            if matches!(when, Some(reminder::When::Before(_)))
+                && cached.as_ref().is_some_and(|item| item.due.is_none())
+            {
+                return Err("Relative reminders only work on tasks with a due date.".into());
+            }
+            let task_name = task_name(&item_id, content.or(cached.map(|item| item.content)));
+
+            let mut args = ReminderAddArgs {
+                item_id,
+                kind: ReminderKind::Relative,
+                due: None,
+                minute_offset: None,
+                name: None,
+                loc_lat: None,
+                loc_long: None,
+                loc_trigger: None,
+                radius: None,
+            };
+            match (when, place) {
+                (Some(reminder::When::Before(minutes)), _) => args.minute_offset = Some(minutes),
+                (Some(reminder::When::At(string)), _) => {
+                    args.kind = ReminderKind::Absolute;
+                    args.due = Some(due_args(string)?);
+                }
+                (None, Some(place)) => {
+                    args.kind = ReminderKind::Location;
+                    args.name = Some(name.unwrap_or(format!("{},{}", place.lat, place.long)));
+                    args.loc_lat = Some(place.lat);
+                    args.loc_long = Some(place.long);
+                    args.loc_trigger = Some(if leaving {
+                        LocationTrigger::OnLeave
+                    } else {
+                        LocationTrigger::OnEnter
+                    });
+                    args.radius = place.radius;
+                }
+                (None, None) => return Err("A reminder needs a time or a place.".into()),
+            }
+            let args = CommandArgs::ReminderAdd(args);
+            (
+                args,
+                format!("Reminder added to {task_name}."),
+                format!("The reminder on {task_name} will be added"),
+            )
+        }
+        ReminderCommand::Delete { id } => (
+            CommandArgs::ReminderDelete(ReminderIdArgs { id: id.clone() }),
+            format!("Reminder {id} deleted."),
+            format!("Reminder {id} will be deleted"),
+        ),
+    };
+
+    if send_command(store, client, Command::new(args), view.output)
+        .await?
+        .is_some()
+    {
+        println!("{done}");
+    } else {
+        println!("Could not reach Todoist. {queued} on the next sync.");
+    }
+    Ok(())
+}
+
+/// Prints a task's reminders in the requested format.
+fn print_reminders(
+    output: Output,
+    item: &Item,
+    reminders: &[Reminder],
+) -> Result<(), Box<dyn Error>> {
+    match output {
+        Output::Table => {
+            println!("Reminders for '{}':", item.content);
+            if reminders.is_empty() {
+                println!("  (no reminders)");
+            }
+            for reminder in reminders {
+                println!("  {}  {}", reminder.id, reminder_text(reminder));
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(reminders)?),
+        Output::Plain => {
+            for reminder in reminders {
+                println!("{}\t{}", reminder.id, reminder_text(reminder));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// When a reminder goes off, as it would be written.
+fn reminder_text(reminder: &Reminder) -> String {
+    match reminder.kind {
+        ReminderKind::Relative => reminder::describe_offset(reminder.minute_offset.unwrap_or(0)),
+        ReminderKind::Absolute => reminder
+            .due
+            .as_ref()
+            .map(|due| due.string.clone().unwrap_or(due.date.clone()))
+            .unwrap_or_default(),
+        ReminderKind::Location => {
+            let place = match (&reminder.name, &reminder.loc_lat, &reminder.loc_long) {
+                (Some(name), _, _) => name.clone(),
+                (None, Some(lat), Some(long)) => format!("{lat},{long}"),
+                _ => "a location".to_string(),
+            };
+            let within = reminder
+                .radius
+                .map(|radius| format!(" (within {radius} m)"))
+                .unwrap_or_default();
+            match reminder.loc_trigger {
+                Some(LocationTrigger::OnLeave) => format!("when leaving {place}{within}"),
+                _ => format!("when arriving at {place}{within}"),
+            }
+        }
+    }
+}
+
+/// Finds a label by name, syncing once if it isn't in the local store.
+async fn lookup_label(
+    store: &mut Store,
+    client: &SyncClient,
+    name: &str,
+    output: Output,
+) -> Result<Label, Box<dyn Error>> {
+    let find = |store: &Store| -> Result<Option<Label>, Box<dyn Error>> {
+        Ok(fuzzy::best_match(name, store.labels()?, |label| {
+            &label.name
+        }))
+    };
+    if find(store)?.is_none() {
+        sync(store, client, output).await?;
+    }
+    find(store)?.ok_or(format!("Could not find a label named '{name}'.").into())
+}
+
+/// Prints labels in the order the user arranged them, in the requested format.
+fn print_labels(output: Output, mut labels: Vec<Label>) -> Result<(), Box<dyn Error>> {
+    labels.sort_by_key(|label| label.item_order);
+    match output {
+        Output::Table => {
+            println!("Labels:");
+            print_labels_list(&labels);
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&labels)?),
+        Output::Plain => {
+            for label in labels {
+                println!("{}\t{}", label.id, label.name);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints labels as a list. Favorites are starred.
+fn print_labels_list(labels: &[Label]) {
+    if labels.is_empty() {
+        println!("  (no labels)");
+        return;
+    }
+
+    let use_color = color::enabled();
+    for label in labels {
+        let name = format!("@{}", label.name);
+        let name = match color::named(&label.color) {
+            Some(rgb) if use_color => color::paint(&name, rgb),
+            _ => name,
+        };
+        let star = if label.is_favorite { " ★" } else { "" };
+        println!("  {name}{star}");
+    }
+}
+
+fn print_filters(output: Output, mut filters: Vec<Filter>) -> Result<(), Box<dyn Error>> {
+    filters.sort_by_key(|filter| filter.item_order);
+    match output {
+        Output::Table => {
+            println!("Filters:");
+            if filters.is_empty() {
+                println!("  (no filters)");
+            }
+            let use_color = color::enabled();
+            for filter in &filters {
+                let name = match color::named(&filter.color) {
+                    Some(rgb) if use_color => color::paint(&filter.name, rgb),
+                    _ => filter.name.clone(),
+                };
+                let star = if filter.is_favorite { " ★" } else { "" };
+                println!("  {name}{star}  ({})", filter.query);
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&filters)?),
+        Output::Plain => {
+            for filter in filters {
+                println!("{}\t{}\t{}", filter.id, filter.name, filter.query);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Finds a saved filter by name, syncing once if it isn't in the local store.
+async fn lookup_filter(
+    store: &mut Store,
+    client: &SyncClient,
+    name: &str,
+    output: Output,
+) -> Result<Filter, Box<dyn Error>> {
+    let find = |store: &Store| -> Result<Option<Filter>, Box<dyn Error>> {
+        Ok(fuzzy::best_match(name, store.filters()?, |filter| {
+            &filter.name
+        }))
+    };
+    if find(store)?.is_none() {
+        sync(store, client, output).await?;
+    }
+    find(store)?.ok_or(format!("Could not find a saved filter named '{name}'.").into())
+}
+
+/// Finds a project by name, syncing once if it isn't in the local store.
+async fn lookup_project(
+    store: &mut Store,
+    client: &SyncClient,
+    name: &str,
+    output: Output,
+) -> Result<Project, Box<dyn Error>> {
+    if find_project(store, name)?.is_none() {
+        sync(store, client, output).await?;
+    }
+    find_project(store, name)?.ok_or(format!("Could not find a project named '{name}'.").into())
+}
+
+/// What to do after a step of triaging a task.
+enum TriageStep {
+    /// Ask what else to do with the same task.
+    Stay,
+
+    /// Go on to the next task, counting this one as triaged.
+    Next,
+
+    /// Go on to the next task, leaving this one in the inbox.
+    Skip,
+
+    Quit,
+}
+
+/// Goes through the tasks in the inbox one by one, asking what to do with
+/// each, until they've all been seen or the user quits.
+async fn triage(
+    store: &mut Store,
+    client: &SyncClient,
+    inbox_project_id: &str,
+    view: View,
+) -> Result<(), Box<dyn Error>> {
+    sync_or_warn(store, client, view).await?;
+    let items: Vec<Item> = store
+        .open_items_in_project(inbox_project_id)?
+        .into_iter()
+        .filter(|item| item.parent_id.is_none())
+        .collect();
+    if items.is_empty() {
+        println!("The inbox is empty.");
+        return Ok(());
+    }
+
+    let mut triaged = 0;
+    'items: for (index, item) in items.iter().enumerate() {
+        println!();
+        println!("({}/{}) {}", index + 1, items.len(), item.content);
+        if let Some(due) = &item.due {
+            println!("  Due: {}", due.string.as_ref().unwrap_or(&due.date));
+        }
+        if !item.labels.is_empty() {
+            let labels: Vec<String> = item
+                .labels
+                .iter()
+                .map(|label| format!("@{label}"))
+                .collect();
+            println!("  Labels: {}", labels.join(" "));
+        }
+
+        loop {
+            let Some(action) = prompt(
+                "[p]roject, [d]ue date, [l]abels, [c]omplete, [x] delete, [s]kip, or [q]uit?",
+            )?
+            else {
+                break 'items;
+            };
+            match triage_step(store, client, item, &action, view.output).await {
+                Ok(TriageStep::Stay) => {}
+                Ok(TriageStep::Next) => {
+                    triaged += 1;
+                    continue 'items;
+                }
+                Ok(TriageStep::Skip) => continue 'items,
+                Ok(TriageStep::Quit) => break 'items,
+                Err(err) => println!("  {err}"),
+            }
+        }
+    }
+    println!();
+    println!("Triaged {triaged} of {} inbox tasks.", items.len());
+    Ok(())
+}
+
+/// Does what the user asked for with a task that's being triaged.
+async fn triage_step(
+    store: &mut Store,
+    client: &SyncClient,
+    item: &Item,
+    action: &str,
+    output: Output,
+) -> Result<TriageStep, Box<dyn Error>> {
+    let id = item.id.clone();
+    let (args, done, step) = match action.trim().to_lowercase().as_str() {
+        "p" | "project" => {
+            let Some(name) = prompt("  Project:")?.filter(|name| !name.trim().is_empty()) else {
+                return Ok(TriageStep::Stay);
+            };
+            let project = lookup_project(store, client, name.trim(), output).await?;
+            let args = CommandArgs::ItemMove(ItemMoveArgs {
+                id,
+                project_id: Some(project.id),
+                section_id: None,
+                parent_id: None,
+            });
+            (args, format!("Moved to {}", project.name), TriageStep::Next)
+        }
+        "d" | "due" => {
+            let Some(due) = prompt("  Due:")?.filter(|due| !due.trim().is_empty()) else {
+                return Ok(TriageStep::Stay);
+            };
+            let args = CommandArgs::ItemUpdate(ItemUpdateArgs {
+                id,
+                due: Some(due_args(due.trim().to_string())?),
+                ..ItemUpdateArgs::default()
+            });
+            (args, format!("Due {}", due.trim()), TriageStep::Stay)
+        }
+        "l" | "labels" => {
+            let Some(labels) = prompt("  Labels:")? else {
+                return Ok(TriageStep::Stay);
+            };
+            let labels = labels
+                .split_whitespace()
+                .map(|label| label.trim_start_matches('@').to_string())
+                .filter(|label| !label.is_empty());
+            let labels = match_known_labels(store, labels)?;
+            let done = if labels.is_empty() {
+                "Removed its labels".to_string()
+            } else {
+                format!("Labeled @{}", labels.join(" @"))
+            };
+            let args = CommandArgs::ItemUpdate(ItemUpdateArgs {
+                id,
+                labels: Some(labels),
+                ..ItemUpdateArgs::default()
+            });
+            (args, done, TriageStep::Stay)
+        }
+        "c" | "complete" => {
+            let args = CommandArgs::ItemClose(ItemCloseArgs { id });
+            (args, "Completed".to_string(), TriageStep::Next)
+        }
+        "x" | "delete" => {
+            if !confirm(&format!("  Permanently delete '{}'?", item.content))? {
+                return Ok(TriageStep::Stay);
+            }
+            let args = CommandArgs::ItemDelete(ItemIdArgs { id });
+            (args, "Deleted".to_string(), TriageStep::Next)
+        }
+        "s" | "skip" | "" => return Ok(TriageStep::Skip),
+        "q" | "quit" => return Ok(TriageStep::Quit),
+        _ => return Err(format!("'{}' isn't one of the choices.", action.trim()).into()),
+    };
+
+    if send_command(store, client, Command::new(args), output)
+        .await?
+        .is_some()
+    {
+        println!("  {done}.");
+    } else {
+        println!("  {done}, once Todoist can be reached.");
+    }
+    Ok(step)
+}
+
+/// Asks for a line of input on the terminal, returning `None` once there's
+/// no more input.
+fn prompt(prompt: &str) -> Result<Option<String>, Box<dyn Error>> {
+    print!("{prompt} ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    if std::io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(answer.trim_end_matches(['\n', '\r']).to_string()))
+}
+
+/// Asks a yes-or-no question on the terminal, defaulting to no.
+fn confirm(prompt: &str) -> Result<bool, Box<dyn Error>> {
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// The projects that aren't archived, with the inbox first and the rest in
+/// the order the user arranged them.
+fn active_projects(projects: Vec<Project>, inbox_project_id: &str) -> Vec<Project> {
+    let mut projects: Vec<Project> = projects
+        .into_iter()
+        .filter(|project| !project.is_archived)
+        .collect();
+    projects.sort_by_key(|project| (project.id != inbox_project_id, project.child_order));
+    projects
+}
+
+/// Prints sorted projects in the requested format, with the ones in each of
+/// `workspaces` apart from the personal ones.
+fn print_projects(
+    output: Output,
+    projects: &[Project],
+    workspaces: &[Workspace],
+) -> Result<(), Box<dyn Error>> {
+    let workspace_of = |project: &Project| {
+        let id = project.workspace_id.as_ref()?;
+        workspaces.iter().find(|workspace| &workspace.id == id)
+    };
+    match output {
+        Output::Table => {
+            let in_workspace = |workspace: Option<&Workspace>| -> Vec<Project> {
+                projects
+                    .iter()
+                    .filter(|project| {
+                        workspace_of(project).map(|w| &w.id) == workspace.map(|w| &w.id)
+                    })
+                    .cloned()
+                    .collect()
+            };
+            let groups: Vec<(&Workspace, Vec<Project>)> = workspaces
+                .iter()
+                .map(|workspace| (workspace, in_workspace(Some(workspace))))
+                .filter(|(_, projects)| !projects.is_empty())
+                .collect();
+            let personal = in_workspace(None);
+            if !personal.is_empty() || groups.is_empty() {
+                println!("Projects:");
+                print_projects_tree(&personal);
+            }
+            for (workspace, projects) in groups {
+                println!("Projects in {}:", workspace.name);
+                print_projects_tree(&projects);
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&projects)?),
+        Output::Plain => {
+            for project in projects {
+                match workspace_of(project) {
+                    Some(workspace) => {
+                        println!("{}\t{}\t{}", project.id, project.name, workspace.name);
+                    }
+                    None => println!("{}\t{}", project.id, project.name),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints notifications, newest first, with who they're from and what they're
+/// about, as far as the local cache knows.
+fn print_notifications(
+    store: &Store,
+    output: Output,
+    notifications: &[LiveNotification],
+) -> Result<(), Box<dyn Error>> {
+    match output {
+        Output::Table => {
+            println!("Notifications:");
+            if notifications.is_empty() {
+                println!("  (no notifications)");
+            }
+            for notification in notifications {
+                let unread = if notification.is_unread {
+                    " (unread)"
+                } else {
+                    ""
+                };
+                println!(
+                    "  {}  {}{unread}",
+                    locale::format_timestamp(&notification.created_at),
+                    describe_notification(store, notification)?
+                );
+            }
+        }
+        Output::Json => println!("{}", serde_json::to_string_pretty(&notifications)?),
+        Output::Plain => {
+            for notification in notifications {
+                println!(
+                    "{}\t{}\t{}\t{}",
+                    notification.id,
+                    notification.created_at,
+                    notification.notification_type,
+                    describe_notification(store, notification)?
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What a notification says, like "Sam assigned you 'Book flights'".
+fn describe_notification(
+    store: &Store,
+    notification: &LiveNotification,
+) -> Result<String, Box<dyn Error>> {
+    let from = match (&notification.from_user, &notification.from_uid) {
+        (Some(user), _) => user.full_name.clone(),
+        (None, Some(id)) => store
+            .collaborators()?
+            .into_iter()
+            .find(|collaborator| &collaborator.id == id)
+            .map_or_else(
+                || "Someone".to_string(),
+                |collaborator| collaborator.full_name,
+            ),
+        (None, None) => "Someone".to_string(),
+    };
+    let project = match (&notification.project_name, &notification.project_id) {
+        (Some(name), _) => name.clone(),
+        (None, Some(id)) => project_name(store, id)?,
+        (None, None) => "a project".to_string(),
+    };
+    let item = match (&notification.item_content, &notification.item_id) {
+        (Some(content), _) => content.clone(),
+        (None, Some(id)) => store
+            .item(id)?
+            .map_or_else(|| id.clone(), |item| item.content),
+        (None, None) => "a task".to_string(),
+    };
+    Ok(match notification.notification_type.as_str() {
+        "share_invitation_sent" => format!("{from} invited you to '{project}'"),
+        "item_assigned" => format!("{from} assigned you '{item}'"),
+        "note_added" => format!("{from} commented on '{item}'"),
+        other => format!("{from}: {other}"),
+    })
+}
+
+/// Prints who's in `project`, from its collaborator `states`, along with who's
+/// only been invited so far.
+fn print_collaborators(
+    output: Output,
+    project: &Project,
+    states: &[CollaboratorState],
+    collaborators: &[Collaborator],
+    user: &User,
+) -> Result<(), Box<dyn Error>> {
+    // the user is in their shared projects too, but isn't a collaborator
+    let people: Vec<(&str, &str, &str, &str)> = states
+        .iter()
+        .map(|state| {
+            let (name, email) = match collaborators.iter().find(|c| c.id == state.user_id) {
+                Some(collaborator) => {
+                    (collaborator.full_name.as_str(), collaborator.email.as_str())
+                }
+                None if state.user_id == user.id => (user.full_name.as_str(), user.email.as_str()),
+                None => (state.user_id.as_str(), ""),
+            };
+            (state.user_id.as_str(), name, email, state.state.as_str())
+        })
+        .collect();
+    match output {
+        Output::Table => {
+            println!("Collaborators in {}:", project.name);
+            if people.is_empty() {
+                println!("  (not shared)");
+            }
+            for (_, name, email, state) in people {
+                let email = if email.is_empty() {
+                    String::new()
+                } else {
+                    format!(" <{email}>")
+                };
+                let invited = if state == "invited" { " (invited)" } else { "" };
+                println!("  {name}{email}{invited}");
+            }
+        }
+        Output::Json => {
+            let people: Vec<_> = people
+                .into_iter()
+                .map(|(id, name, email, state)| {
+                    serde_json::json!({ "id": id, "full_name": name, "email": email, "state": state })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&people)?);
+        }
+        Output::Plain => {
+            for (id, name, email, state) in people {
+                println!("{id}\t{name}\t{email}\t{state}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Prints sorted projects as an indented tree, with subprojects under their
+/// parents. Favorites are starred.
+fn print_projects_tree(projects: &[Project]) {
+    let tree = project_tree(projects);
+    if tree.is_empty() {
+        println!("  (no projects)");
+        return;
+    }
+    for (project, depth) in tree {
+        println!("  {}{}", "  ".repeat(depth), project_label(project));
+    }
+}
+
+/// Sorted projects in the order they appear in the tree, each with how deeply
+/// it's nested.
+fn project_tree(projects: &[Project]) -> Vec<(&Project, usize)> {
+    // projects whose parent isn't around (e.g. it's archived) go at the top level
+    let is_root = |project: &Project| {
+        project
+            .parent_id
+            .as_ref()
+            .is_none_or(|parent_id| !projects.iter().any(|parent| &parent.id == parent_id))
+    };
+    let mut stack: Vec<(&Project, usize)> = projects
+        .iter()
+        .filter(|project| is_root(project))
+        .rev()
+        .map(|root| (root, 0))
+        .collect();
+    let mut tree = Vec::new();
+    while let Some((project, depth)) = stack.pop() {
+        tree.push((project, depth));
+        let children = projects
+            .iter()
+            .filter(|child| child.parent_id.as_ref() == Some(&project.id));
+        stack.extend(children.rev().map(|child| (child, depth + 1)));
+    }
+    tree
+}
+
+/// A project's name in its color, starred if it's a favorite.
+fn project_label(project: &Project) -> String {
+    let name = match color::named(&project.color) {
+        Some(rgb) if color::enabled() => color::paint(&project.name, rgb),
+        _ => project.name.clone(),
+    };
+    let star = if project.is_favorite { " ★" } else { "" };
+    format!("{name}{star}")
+}
+
+/// A project and its counts, for JSON output.
+#[derive(Serialize)]
+struct ProjectWithCounts<'a> {
+    id: &'a str,
+    name: &'a str,
+    #[serde(flatten)]
+    counts: ProjectCounts,
+}
+
+/// Prints sorted projects with their counts: as a tree, or as a flat list in
+/// order of `sort` if it's given.
+fn print_project_counts(
+    output: Output,
+    projects: &[Project],
+    counts: &HashMap<String, ProjectCounts>,
+    sort: Option<SortBy>,
+) -> Result<(), Box<dyn Error>> {
+    let counts_of = |project: &Project| counts.get(&project.id).copied().unwrap_or_default();
+    let mut rows = project_tree(projects);
+    if let Some(sort) = sort {
+        rows.sort_by_key(|(project, _)| Reverse(counts_of(project).get(sort)));
+        for (_, depth) in &mut rows {
+            *depth = 0;
+        }
+    }
+
+    match output {
+        Output::Table => {
+            let plain_name = |(project, depth): &(&Project, usize)| {
+                let star = if project.is_favorite { 2 } else { 0 };
+                depth * 2 + project.name.chars().count() + star
+            };
+            let width = rows.iter().map(plain_name).max().unwrap_or(0).max(8);
+            println!(
+                "{:<width$}  {:>5}  {:>7}  {:>8}",
+                "Projects:",
+                "Open",
+                "Overdue",
+                "Sections",
+                width = width + 2
+            );
+            for row in &rows {
+                let (project, depth) = row;
+                let counts = counts_of(project);
+                let padding = " ".repeat(width - plain_name(row));
+                println!(
+                    "  {}{}{padding}  {:>5}  {:>7}  {:>8}",
+                    "  ".repeat(*depth),
+                    project_label(project),
+                    counts.open,
+                    counts.overdue,
+                    counts.sections
+                );
+            }
+        }
+        Output::Json => {
+            let rows: Vec<ProjectWithCounts> = rows
+                .iter()
+                .map(|(project, _)| ProjectWithCounts {
+                    id: &project.id,
+                    name: &project.name,
+                    counts: counts_of(project),
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&rows)?);
+        }
+        Output::Plain => {
+            for (project, _) in rows {
+                let counts = counts_of(project);
+                println!(
+                    "{}\t{}\t{}\t{}\t{}",
+                    project.id, project.name, counts.open, counts.overdue, counts.sections
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Deciding which tasks to send desktop notifications about, as their due
+//! dates and times arrive. Each task is announced once when it becomes due,
+//! and once more when it becomes overdue.
+
+use chrono::NaiveDateTime;
+
+use crate::{agenda, sync::Item};
+
+/// A notification to send about a task.
+#[derive(Debug)]
+pub struct Alert<'a> {
+    pub item: &'a Item,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// The task's due time has come, or, for a task with only a date, its day
+    /// has.
+    Due,
+
+    /// The day the task was due on has passed.
+    Overdue,
+}
+
+impl Alert<'_> {
+    /// Identifies the notification, so that it's only sent once. A recurring
+    /// task gets a new key each time its due date moves.
+    #[must_use]
+    pub fn key(&self) -> String {
+        let date = self.item.due.as_ref().map_or("", |due| due.date.as_str());
+        let kind = match self.kind {
+            Kind::Due => "due",
+            Kind::Overdue => "overdue",
+        };
+        format!("{}@{date}@{kind}", self.item.id)
+    }
+
+    /// The notification's title.
+    #[must_use]
+    pub fn summary(&self) -> &'static str {
+        match self.kind {
+            Kind::Due => "Task due",
+            Kind::Overdue => "Task overdue",
+        }
+    }
+}
+
+/// The open tasks that call for a notification at `now`, in order of when they
+/// were due.
+#[must_use]
+pub fn alerts(items: &[Item], now: NaiveDateTime) -> Vec<Alert<'_>> {
+    let mut open: Vec<&Item> = items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .collect();
+    agenda::sort_by_due(&mut open);
+
+    let today = now.date();
+    open.into_iter()
+        .filter_map(|item| {
+            let date = agenda::due_date(item)?;
+            let kind = if date < today {
+                Kind::Overdue
+            } else if date == today && agenda::due_time(item).is_none_or(|time| time <= now) {
+                Kind::Due
+            } else {
+                return None;
+            };
+            Some(Alert { item, kind })
+        })
+        .collect()
+}
+
+/// A heading for a batch of notifications, when there are too many to send
+/// one by one.
+#[must_use]
+pub fn batch_summary(alerts: &[Alert]) -> String {
+    let overdue = alerts
+        .iter()
+        .filter(|alert| alert.kind == Kind::Overdue)
+        .count();
+    let due = alerts.len() - overdue;
+    match (due, overdue) {
+        (due, 0) => format!("{due} tasks due"),
+        (0, overdue) => format!("{overdue} tasks overdue"),
+        (due, overdue) => format!("{due} tasks due, {overdue} overdue"),
+    }
+}
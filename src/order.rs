@@ -0,0 +1,126 @@
+//! Working out the order of tasks and projects: where they go when they're
+//! moved among their siblings, and how subtasks nest under their parents.
+
+use crate::{sync::Item, Error, Result};
+
+/// Where to move something, among its siblings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Position {
+    Top,
+    Bottom,
+
+    /// Right after a sibling, given the way the user referred to it (e.g. as
+    /// an ID or a list index).
+    After(String),
+}
+
+impl Position {
+    /// Parses a position given as words: `top`, `bottom`, or `after <sibling>`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Position`] if the words aren't one of those.
+    pub fn parse(words: &[String]) -> Result<Self> {
+        let words: Vec<&str> = words.iter().map(|word| word.trim()).collect();
+        match words.as_slice() {
+            [word] if word.eq_ignore_ascii_case("top") => Ok(Position::Top),
+            [word] if word.eq_ignore_ascii_case("bottom") => Ok(Position::Bottom),
+            [word, sibling] if word.eq_ignore_ascii_case("after") && !sibling.is_empty() => {
+                Ok(Position::After((*sibling).to_string()))
+            }
+            [word] if word.eq_ignore_ascii_case("after") => Err(Error::Position(
+                "'after' needs something to go after".to_string(),
+            )),
+            _ => Err(Error::Position(format!(
+                "expected top, bottom, or after <id>, not '{}'",
+                words.join(" ")
+            ))),
+        }
+    }
+}
+
+/// The IDs of `siblings` (given in their current order) with `id` put in its
+/// new place. `id` doesn't have to be among the siblings already, and for
+/// [`Position::After`] the sibling must already have been resolved to an ID.
+///
+/// # Errors
+///
+/// Returns [`Error::Position`] if the sibling to go after isn't one of
+/// `siblings`, or is `id` itself.
+pub fn reorder(siblings: &[&str], id: &str, position: &Position) -> Result<Vec<String>> {
+    let mut order: Vec<String> = siblings
+        .iter()
+        .filter(|sibling| **sibling != id)
+        .map(ToString::to_string)
+        .collect();
+    let index = match position {
+        Position::Top => 0,
+        Position::Bottom => order.len(),
+        Position::After(sibling) if sibling == id => {
+            return Err(Error::Position(
+                "can't move something after itself".to_string(),
+            ));
+        }
+        Position::After(sibling) => {
+            order
+                .iter()
+                .position(|other| other == sibling)
+                .ok_or(Error::Position(format!(
+                    "{sibling} isn't in the same place, so there's nothing to go after"
+                )))?
+                + 1
+        }
+    };
+    order.insert(index, id.to_string());
+    Ok(order)
+}
+
+/// Orders items so that subtasks come right after their parents, with siblings
+/// in their `child_order`. Subtasks whose parent isn't among `items` are
+/// treated as top-level tasks.
+#[must_use]
+pub fn nest_subtasks<'a>(items: &[&'a Item]) -> Vec<&'a Item> {
+    let is_root = |item: &Item| {
+        item.parent_id
+            .as_ref()
+            .is_none_or(|parent_id| !items.iter().any(|parent| &parent.id == parent_id))
+    };
+    let mut roots: Vec<&Item> = items.iter().copied().filter(|item| is_root(item)).collect();
+    roots.sort_by_key(|item| item.child_order);
+
+    let mut nested = Vec::with_capacity(items.len());
+    let mut stack: Vec<&Item> = roots.into_iter().rev().collect();
+    while let Some(item) = stack.pop() {
+        nested.push(item);
+
+        let mut children: Vec<&Item> = items
+            .iter()
+            .copied()
+            .filter(|child| child.parent_id.as_ref() == Some(&item.id))
+            .collect();
+        children.sort_by_key(|child| child.child_order);
+        stack.extend(children.into_iter().rev());
+    }
+    nested
+}
+
+/// How deeply each item is nested, for items in the order [`nest_subtasks`]
+/// puts them in. A subtask only counts as nested when it comes right after its
+/// parent or its parent's other subtasks.
+#[must_use]
+pub fn depths(items: &[&Item]) -> Vec<usize> {
+    let mut ancestors: Vec<&str> = Vec::new();
+    items
+        .iter()
+        .map(|item| {
+            while ancestors
+                .last()
+                .is_some_and(|ancestor| item.parent_id.as_deref() != Some(*ancestor))
+            {
+                ancestors.pop();
+            }
+            ancestors.push(&item.id);
+            ancestors.len() - 1
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+//! An inline fuzzy finder, for picking a task when a command needs one and
+//! none was given. It takes up a few lines below the prompt rather than the
+//! whole screen, and clears them again once something is picked.
+
+use std::{
+    error::Error,
+    io::{stdin, stdout, IsTerminal},
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{List, ListItem, ListState, Paragraph},
+    DefaultTerminal, TerminalOptions, Viewport,
+};
+use todoist::fuzzy;
+
+/// How many choices are shown at once.
+const VISIBLE_CHOICES: usize = 10;
+
+/// Whether there's a terminal to show the picker on.
+pub fn available() -> bool {
+    stdin().is_terminal() && stdout().is_terminal()
+}
+
+/// Lets the user narrow `choices` down by typing, and pick one with the arrow
+/// keys and enter. Returns the index of the picked choice, or `None` if the
+/// user cancels with escape or ctrl-c.
+pub fn pick(prompt: &str, choices: &[String]) -> Result<Option<usize>, Box<dyn Error>> {
+    let height = choices.len().min(VISIBLE_CHOICES) + 1;
+    let mut terminal = ratatui::try_init_with_options(TerminalOptions {
+        viewport: Viewport::Inline(u16::try_from(height)?),
+    })?;
+    let mut picker = Picker {
+        prompt,
+        choices,
+        query: String::new(),
+        matches: fuzzy::rank("", choices),
+        list_state: ListState::default().with_selected(Some(0)),
+    };
+    let result = picker.run(&mut terminal);
+    let cleared = terminal.clear();
+    ratatui::restore();
+    cleared?;
+    result
+}
+
+struct Picker<'a> {
+    prompt: &'a str,
+    choices: &'a [String],
+    query: String,
+
+    /// The indices of the choices that match the query, best first.
+    matches: Vec<usize>,
+    list_state: ListState,
+}
+
+enum Outcome {
+    Picked(usize),
+    Cancelled,
+}
+
+impl Picker<'_> {
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<Option<usize>, Box<dyn Error>> {
+        loop {
+            terminal.draw(|frame| {
+                let [prompt_area, list_area] =
+                    Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+                        .areas(frame.area());
+                let prompt = Line::from(vec![
+                    Span::from(format!("{} ", self.prompt)).bold(),
+                    Span::from(self.query.as_str()),
+                    Span::from(format!("  ({}/{})", self.matches.len(), self.choices.len())).dim(),
+                ]);
+                frame.render_widget(Paragraph::new(prompt), prompt_area);
+
+                let items: Vec<ListItem> = self
+                    .matches
+                    .iter()
+                    .map(|&index| ListItem::new(self.highlight(&self.choices[index])))
+                    .collect();
+                let list = List::new(items)
+                    .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+                    .highlight_symbol("> ");
+                frame.render_stateful_widget(list, list_area, &mut self.list_state);
+            })?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.handle_key(key) {
+                Some(Outcome::Picked(index)) => return Ok(Some(index)),
+                Some(Outcome::Cancelled) => return Ok(None),
+                None => {}
+            }
+        }
+    }
+
+    /// Handles a key press, returning the outcome once the user is done.
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Outcome> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return Some(Outcome::Cancelled),
+            KeyCode::Char('c') if ctrl => return Some(Outcome::Cancelled),
+            KeyCode::Enter => {
+                let selected = self.list_state.selected()?;
+                return self.matches.get(selected).copied().map(Outcome::Picked);
+            }
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char('p') if ctrl => self.list_state.select_previous(),
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Char('n') if ctrl => self.list_state.select_next(),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.update_matches();
+            }
+            KeyCode::Char(char) if !ctrl => {
+                self.query.push(char);
+                self.update_matches();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn update_matches(&mut self) {
+        self.matches = fuzzy::rank(&self.query, self.choices);
+        self.list_state.select(Some(0));
+    }
+
+    /// A choice, with the parts that match the query in bold.
+    fn highlight<'a>(&self, choice: &'a str) -> Line<'a> {
+        let Some(ranges) = fuzzy::match_ranges(&self.query, choice) else {
+            return Line::from(choice);
+        };
+        let mut spans = Vec::new();
+        let mut end = 0;
+        for range in ranges {
+            spans.push(Span::from(&choice[end..range.start]));
+            spans.push(Span::from(&choice[range.clone()]).bold());
+            end = range.end;
+        }
+        spans.push(Span::from(&choice[end..]));
+        Line::from(spans)
+    }
+}
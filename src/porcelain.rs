@@ -0,0 +1,92 @@
+//! The output that `--porcelain` asks for, for scripts and status bars to
+//! read. Each line is a record of tab-separated fields, in a fixed order.
+//! Unlike the other formats, it only ever changes by adding fields to the ends
+//! of lines, so scripts can split lines on tabs and rely on the fields they
+//! know about.
+//!
+//! Commands that list tasks print a [`task`] line for each one:
+//!
+//! 1. the task's ID
+//! 2. its content
+//! 3. its project's ID
+//! 4. its section's ID, if it's in one
+//! 5. its parent's ID, if it's a subtask
+//! 6. its due date as Todoist gives it, like `2024-05-01`,
+//!    `2024-05-01T09:00:00` for a time wherever the user is, or
+//!    `2024-05-01T16:00:00Z` for a time in UTC
+//! 7. its priority, from `p1` (most urgent) to `p4`
+//! 8. its labels, separated by commas
+//! 9. `true` if it's completed, and `false` otherwise
+//!
+//! Commands that add or complete tasks print a [`result`] line for each task:
+//! a [`Status`], the task's ID, and its content. Tasks that are queued to be
+//! added on the next sync have a temporary ID until then, which the `complete`
+//! command and the others take as well.
+//!
+//! Text fields have their tabs and line breaks turned into spaces, and fields
+//! with nothing in them are empty. Messages that aren't records, like
+//! warnings, go to stderr, and errors end with a nonzero exit code:
+//!
+//! - `1` for errors without codes of their own
+//! - `2` for invalid arguments
+//! - `3` when a check like `list --fail-if-nonempty` doesn't pass
+//! - `4` when Todoist doesn't accept the API token, or there isn't one
+//! - `5` when a request to Todoist fails, or can't be sent
+//! - `6` when Todoist refuses a command
+//! - `130` after ctrl-c
+
+use crate::sync::Item;
+
+/// What happened to a task, as the first field of a [`result`] line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Added,
+    Completed,
+
+    /// Todoist couldn't be reached, so the change will be sent on the next
+    /// sync.
+    Queued,
+
+    /// The task wasn't added, because it looks like one that's already there,
+    /// whose ID and content the line has instead.
+    Skipped,
+}
+
+impl Status {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Added => "added",
+            Status::Completed => "completed",
+            Status::Queued => "queued",
+            Status::Skipped => "skipped",
+        }
+    }
+}
+
+/// The line for a task in a list.
+#[must_use]
+pub fn task(item: &Item) -> String {
+    let fields = [
+        field(&item.id),
+        field(&item.content),
+        field(&item.project_id),
+        field(item.section_id.as_deref().unwrap_or_default()),
+        field(item.parent_id.as_deref().unwrap_or_default()),
+        field(item.due.as_ref().map_or("", |due| due.date.as_str())),
+        format!("p{}", 5 - item.priority.clamp(1, 4)),
+        field(&item.labels.join(",")),
+        item.checked.to_string(),
+    ];
+    fields.join("\t")
+}
+
+/// The line for a task that was added or completed.
+#[must_use]
+pub fn result(status: Status, id: &str, content: &str) -> String {
+    format!("{}\t{}\t{}", status.as_str(), field(id), field(content))
+}
+
+fn field(text: &str) -> String {
+    text.replace(['\t', '\r', '\n'], " ")
+}
@@ -0,0 +1,67 @@
+//! Spinners and progress bars, for the requests that keep the user waiting.
+//! They're drawn on stderr, and only when both stdout and stderr are
+//! terminals, so they never end up in piped output or in log files. Otherwise,
+//! and when turned off with `--quiet`, they're hidden and cost nothing.
+
+use std::{
+    io::{stderr, stdout, IsTerminal},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// How often spinners move.
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// Turns spinners and progress bars off, e.g. for `--quiet`. Only the first
+/// call has any effect.
+pub fn set_enabled(enabled: bool) {
+    let _ = ENABLED.set(enabled);
+}
+
+fn enabled() -> bool {
+    ENABLED.get().copied().unwrap_or(true) && stdout().is_terminal() && stderr().is_terminal()
+}
+
+/// A spinner showing `message`, until it's finished or dropped.
+pub fn spinner(message: &'static str) -> ProgressBar {
+    if !enabled() {
+        return ProgressBar::hidden();
+    }
+    let spinner = ProgressBar::new_spinner().with_message(message);
+    spinner.enable_steady_tick(TICK_INTERVAL);
+    spinner
+}
+
+/// A bar that fills up over `seconds` seconds, showing `message` and the time
+/// left, for counting down. Its position is in seconds.
+pub fn countdown(seconds: u64, message: &'static str) -> ProgressBar {
+    if !enabled() {
+        return ProgressBar::hidden();
+    }
+    let style = ProgressStyle::with_template("{msg} [{bar:30}] {prefix} left").map_or_else(
+        |_| ProgressStyle::default_bar(),
+        |style| style.progress_chars("=> "),
+    );
+    ProgressBar::new(seconds)
+        .with_style(style)
+        .with_message(message)
+}
+
+/// A progress bar for `total` things, showing `message`, until it's finished
+/// or dropped.
+pub fn bar(total: usize, message: &'static str) -> ProgressBar {
+    if !enabled() {
+        return ProgressBar::hidden();
+    }
+    let style = ProgressStyle::with_template("{msg} [{bar:30}] {pos}/{len}").map_or_else(
+        |_| ProgressStyle::default_bar(),
+        |style| style.progress_chars("=> "),
+    );
+    ProgressBar::new(total as u64)
+        .with_style(style)
+        .with_message(message)
+}
@@ -0,0 +1,151 @@
+//! Parsing for Todoist's quick-add syntax, which lets a task's attributes be
+//! written inline with its content:
+//!
+//! - `#Project` to pick a project
+//! - `@label` to add a label (can be repeated)
+//! - `p1` through `p4` to set a priority
+//! - `+name` to assign the task to a collaborator
+//! - a natural-language date, like "tomorrow 5pm" or "every monday"
+//!
+//! Parsing only splits the text up; resolving project and collaborator names
+//! is up to the caller.
+
+use crate::due::{
+    is_iso_date, is_number, is_ordinal, is_time, DATE_PREFIXES, DATE_STARTS, DATE_WORDS, MONTHS,
+    SHORT_WEEKDAYS, WEEKDAYS,
+};
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct QuickAdd {
+    pub content: String,
+    pub project: Option<String>,
+    pub labels: Vec<String>,
+
+    /// The priority as written, so `1` is the most urgent. Note that this is
+    /// the reverse of the API's numbering.
+    pub priority: Option<u8>,
+
+    pub assignee: Option<String>,
+
+    /// The date phrase, passed along to the API as a due date string.
+    pub due: Option<String>,
+}
+
+/// Splits quick-add text into content and attributes.
+#[must_use]
+pub fn parse(text: &str) -> QuickAdd {
+    parse_with_dates(text, true)
+}
+
+/// Like [`parse`], but leaves anything that looks like a date in the content.
+/// Useful when the due date is given separately.
+#[must_use]
+pub fn parse_without_dates(text: &str) -> QuickAdd {
+    parse_with_dates(text, false)
+}
+
+fn parse_with_dates(text: &str, find_dates: bool) -> QuickAdd {
+    let mut quick_add = QuickAdd::default();
+    let mut words: Vec<&str> = Vec::new();
+
+    for token in text.split_whitespace() {
+        if let Some(project) = token.strip_prefix('#').filter(|name| !name.is_empty()) {
+            quick_add.project = Some(project.to_string());
+        } else if let Some(label) = token.strip_prefix('@').filter(|name| !name.is_empty()) {
+            quick_add.labels.push(label.to_string());
+        } else if let Some(assignee) = token.strip_prefix('+').filter(|name| !name.is_empty()) {
+            quick_add.assignee = Some(assignee.to_string());
+        } else if let Some(priority) = parse_priority(token) {
+            quick_add.priority = Some(priority);
+        } else {
+            words.push(token);
+        }
+    }
+
+    if let Some((start, end, phrase)) = find_date_phrase(&words).filter(|_| find_dates) {
+        quick_add.due = Some(phrase);
+        words.drain(start..end);
+    }
+
+    quick_add.content = words.join(" ");
+    quick_add
+}
+
+/// Parses a priority written like "p1", returning its number.
+#[must_use]
+pub fn parse_priority(token: &str) -> Option<u8> {
+    let digit = token
+        .strip_prefix('p')
+        .or_else(|| token.strip_prefix('P'))?;
+    match digit.parse() {
+        Ok(priority @ 1..=4) => Some(priority),
+        _ => None,
+    }
+}
+
+/// Finds the first run of words that looks like a date, returning its bounds
+/// along with the phrase itself, minus any trailing punctuation.
+fn find_date_phrase(words: &[&str]) -> Option<(usize, usize, String)> {
+    let normalized: Vec<String> = words.iter().map(|word| normalize(word)).collect();
+
+    for start in 0..normalized.len() {
+        let word = normalized[start].as_str();
+        let next = normalized.get(start + 1).map(String::as_str);
+
+        let starts_phrase = DATE_STARTS.contains(&word)
+            || is_date_anchor(word)
+            || (MONTHS.contains(&word) && next.is_some_and(is_day_of_month))
+            || (DATE_PREFIXES.contains(&word) && next.is_some_and(is_date_word));
+        if !starts_phrase {
+            continue;
+        }
+
+        let mut end = start + 1;
+        while end < normalized.len()
+            && (is_date_word(&normalized[end]) || DATE_PREFIXES.contains(&normalized[end].as_str()))
+        {
+            end += 1;
+        }
+        // don't swallow a trailing preposition, as in "call mom tomorrow at"
+        while end > start + 1 && DATE_PREFIXES.contains(&normalized[end - 1].as_str()) {
+            end -= 1;
+        }
+
+        // phrases like "in 2" or "at the" aren't dates on their own
+        if normalized[start..end]
+            .iter()
+            .any(|word| is_substantive_date_word(word))
+        {
+            return Some((start, end, normalized[start..end].join(" ")));
+        }
+    }
+
+    None
+}
+
+fn normalize(word: &str) -> String {
+    word.trim_end_matches([',', '.', '!', '?']).to_lowercase()
+}
+
+/// Words that are unambiguously dates or times, and so can start a phrase.
+fn is_date_anchor(word: &str) -> bool {
+    WEEKDAYS.contains(&word) || is_time(word) || is_iso_date(word)
+}
+
+fn is_date_word(word: &str) -> bool {
+    is_substantive_date_word(word) || is_number(word) || DATE_WORDS.contains(&word)
+}
+
+/// Date words that carry meaning, as opposed to numbers and filler like "the".
+fn is_substantive_date_word(word: &str) -> bool {
+    is_date_anchor(word)
+        || DATE_STARTS.contains(&word)
+        || MONTHS.contains(&word)
+        || SHORT_WEEKDAYS.contains(&word)
+        || is_ordinal(word)
+        || (DATE_WORDS.contains(&word) && !["the", "and", "other", "for"].contains(&word))
+}
+
+fn is_day_of_month(word: &str) -> bool {
+    is_number(word) || is_ordinal(word)
+}
@@ -0,0 +1,94 @@
+//! Keeping to Todoist's limit on how many requests can be sent in a while, so
+//! that a big import or a long-running daemon slows down before Todoist starts
+//! turning requests away, rather than after.
+//!
+//! The limit is a token bucket: a request takes a token, and tokens come back
+//! at a steady rate, up to a small burst. The bucket is kept as the time it'll
+//! next be full, which is all that's needed to tell how long a request has to
+//! wait. The burst and the rate add up to no more than Todoist's limit in any
+//! window, however the requests are spread out. How many commands go in each
+//! request is kept to Todoist's other limit by
+//! [`session::send_chunked`](crate::session::send_chunked), and by
+//! [`session::send_request`](crate::session::send_request) for the queue.
+
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// How many requests Todoist takes in each [`WINDOW`].
+pub const REQUESTS_PER_WINDOW: u32 = 450;
+
+/// The window Todoist counts requests over.
+pub const WINDOW: Duration = Duration::from_mins(15);
+
+/// How many requests can be sent at once, before they have to wait for tokens
+/// to come back.
+pub const BURST: u32 = 50;
+
+/// A token bucket, shared by the clones of the client it's given to.
+#[derive(Debug, Clone)]
+pub struct Limiter {
+    /// How long it takes for a token to come back.
+    interval: Duration,
+
+    /// How long it takes to fill the bucket from empty, less one token.
+    tolerance: Duration,
+
+    /// When the bucket will next be full, if it isn't already.
+    full_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl Default for Limiter {
+    /// Todoist's documented limit.
+    fn default() -> Self {
+        Limiter::new(REQUESTS_PER_WINDOW, WINDOW, BURST)
+    }
+}
+
+impl Limiter {
+    /// A limiter that lets no more than `limit` requests through in any
+    /// `window`, the first `burst` of them without waiting.
+    #[must_use]
+    pub fn new(limit: u32, window: Duration, burst: u32) -> Self {
+        let burst = burst.clamp(1, limit.max(1));
+        let interval = window / limit.saturating_sub(burst).max(1);
+        Limiter {
+            interval,
+            tolerance: interval * (burst - 1),
+            full_at: Arc::default(),
+        }
+    }
+
+    /// Waits for a token, then takes it.
+    pub async fn acquire(&self) {
+        let wait = self.take(Instant::now());
+        if !wait.is_zero() {
+            tracing::debug!(?wait, "waiting for the rate limit");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token at `now`, returning how long to wait before using it.
+    /// Tokens taken while the bucket is empty are owed, so requests waiting
+    /// at the same time go out one interval apart.
+    pub(crate) fn take(&self, now: Instant) -> Duration {
+        let mut full_at = self.full_at.lock().unwrap_or_else(PoisonError::into_inner);
+        let start = full_at.filter(|full_at| *full_at > now).unwrap_or(now);
+        let wait = start
+            .checked_sub(self.tolerance)
+            .map_or(Duration::ZERO, |ready| ready.saturating_duration_since(now));
+        *full_at = Some(start + self.interval);
+        wait
+    }
+
+    /// Empties the bucket for `delay` after `now`, for when Todoist says it's
+    /// had too many requests anyway, like when other apps share the limit.
+    pub(crate) fn hold_off(&self, now: Instant, delay: Duration) {
+        let mut full_at = self.full_at.lock().unwrap_or_else(PoisonError::into_inner);
+        let empty_until = now + delay + self.tolerance;
+        if full_at.is_none_or(|full_at| full_at < empty_until) {
+            *full_at = Some(empty_until);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+//! Parsing for when a reminder should go off. A reminder is either relative to
+//! its task's due time, written like "30 minutes before" or "1h before", or at
+//! an absolute time, written in natural language like "tomorrow 9am" and left
+//! for the API to interpret. Location reminders go off at a place instead,
+//! written as its coordinates and how close counts as there.
+
+use crate::{Error, Result};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum When {
+    /// This many minutes before the task is due.
+    Before(u32),
+
+    /// A date phrase, passed along to the API as a due date string.
+    At(String),
+}
+
+/// A place for a location reminder.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Place {
+    /// The latitude and longitude, as decimal degrees, the way they were
+    /// written.
+    pub lat: String,
+    pub long: String,
+
+    /// How close to the place, in meters, counts as being there, if not the
+    /// app's default.
+    pub radius: Option<u32>,
+}
+
+/// Parses a place written as "latitude,longitude,radius", like
+/// "40.7128,-74.0060,100", with the radius in meters and optional.
+///
+/// # Errors
+///
+/// Returns [`Error::Reminder`] if there aren't two or three parts, or if they
+/// aren't coordinates and a number of meters.
+pub fn parse_place(text: &str) -> Result<Place> {
+    let error = || {
+        Error::Reminder(format!(
+            "expected a place like '40.7128,-74.0060,100', not '{text}'"
+        ))
+    };
+    let parts: Vec<&str> = text.split(',').map(str::trim).collect();
+    let (lat, long, radius) = match parts[..] {
+        [lat, long] => (lat, long, None),
+        [lat, long, radius] => (lat, long, Some(radius)),
+        _ => return Err(error()),
+    };
+    let degrees = |text: &str, max: f64| {
+        text.parse::<f64>()
+            .ok()
+            .filter(|degrees| degrees.abs() <= max)
+            .ok_or_else(error)
+    };
+    degrees(lat, 90.0)?;
+    degrees(long, 180.0)?;
+    let radius = radius
+        .map(|radius| radius.parse::<u32>().ok().filter(|radius| *radius > 0))
+        .map(|radius| radius.ok_or_else(error))
+        .transpose()?;
+    Ok(Place {
+        lat: lat.to_string(),
+        long: long.to_string(),
+        radius,
+    })
+}
+
+/// Parses a reminder time.
+///
+/// # Errors
+///
+/// Returns [`Error::Reminder`] if the text is empty, or if it ends in "before"
+/// but doesn't start with an amount of time.
+pub fn parse(text: &str) -> Result<When> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(Error::Reminder("a reminder needs a time".to_string()));
+    }
+
+    let lowercase = text.to_lowercase();
+    let Some(offset) = lowercase.strip_suffix("before") else {
+        return Ok(When::At(text.to_string()));
+    };
+    parse_offset(offset.trim())
+        .map(When::Before)
+        .ok_or(Error::Reminder(format!(
+            "expected an amount of time like '30 minutes before', not '{text}'"
+        )))
+}
+
+/// Parses an amount of time like "90 minutes", "1 hour", or "2d" into minutes.
+fn parse_offset(text: &str) -> Option<u32> {
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = text.split_at(split);
+    let amount: u32 = amount.parse().ok()?;
+    let minutes_per_unit = match unit.trim() {
+        "m" | "min" | "mins" | "minute" | "minutes" => 1,
+        "h" | "hr" | "hrs" | "hour" | "hours" => 60,
+        "d" | "day" | "days" => 60 * 24,
+        _ => return None,
+    };
+    amount.checked_mul(minutes_per_unit)
+}
+
+/// Describes a relative reminder the way it would be written, like "1 hour
+/// before".
+#[must_use]
+pub fn describe_offset(minutes: u32) -> String {
+    let plural = |amount: u32, unit: &str| {
+        if amount == 1 {
+            format!("1 {unit}")
+        } else {
+            format!("{amount} {unit}s")
+        }
+    };
+    let amount = match minutes {
+        0 => return "when due".to_string(),
+        m if m % (60 * 24) == 0 => plural(m / (60 * 24), "day"),
+        m if m % 60 == 0 => plural(m / 60, "hour"),
+        m => plural(m, "minute"),
+    };
+    format!("{amount} before")
+}
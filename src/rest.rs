@@ -0,0 +1,138 @@
+//! A client for Todoist's REST API (v2), for the few things that are simpler
+//! there than through the Sync API, like fetching a single task without
+//! syncing everything else.
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+
+use crate::{
+    client,
+    sync::{Due, Item, TaskDuration},
+    Error, Result,
+};
+
+/// The REST API's address.
+pub const REST_URL: &str = "https://api.todoist.com/rest/v2";
+
+#[derive(Clone)]
+pub struct RestClient {
+    http: reqwest::Client,
+    rest_url: String,
+    api_key: String,
+}
+
+/// A task, as the REST API describes it.
+#[derive(Debug, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub content: String,
+
+    #[serde(default)]
+    pub description: String,
+
+    pub project_id: String,
+    pub section_id: Option<String>,
+    pub parent_id: Option<String>,
+
+    /// The task's position among its siblings.
+    #[serde(default)]
+    pub order: i64,
+
+    /// In the same numbering as the Sync API, where `4` is the most urgent.
+    pub priority: u8,
+
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    pub due: Option<TaskDue>,
+
+    #[serde(default)]
+    pub duration: Option<TaskDuration>,
+
+    /// The collaborator the task is assigned to, which the Sync API calls
+    /// `responsible_uid`.
+    pub assignee_id: Option<String>,
+
+    #[serde(default)]
+    pub is_completed: bool,
+
+    /// When the task was added, which the Sync API calls `added_at`.
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaskDue {
+    pub date: String,
+    pub string: Option<String>,
+
+    #[serde(default)]
+    pub is_recurring: bool,
+}
+
+impl From<Task> for Item {
+    fn from(task: Task) -> Self {
+        Item {
+            id: task.id,
+            content: task.content,
+            description: task.description,
+            project_id: task.project_id,
+            section_id: task.section_id,
+            parent_id: task.parent_id,
+            child_order: task.order,
+            due: task.due.map(|due| Due {
+                date: due.date,
+                string: due.string,
+                is_recurring: due.is_recurring,
+            }),
+            deadline: None,
+            duration: task.duration,
+            labels: task.labels,
+            priority: task.priority,
+            responsible_uid: task.assignee_id,
+            checked: task.is_completed,
+            completed_at: None,
+            added_at: task.created_at,
+            is_deleted: false,
+        }
+    }
+}
+
+impl RestClient {
+    #[must_use]
+    pub fn new(rest_url: &str, api_key: &str) -> Self {
+        RestClient {
+            http: reqwest::Client::new(),
+            rest_url: rest_url.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Sends requests through `http`, typically the one the sync client uses
+    /// too.
+    #[must_use]
+    pub fn with_http(self, http: reqwest::Client) -> Self {
+        RestClient { http, ..self }
+    }
+
+    /// Fetches a single task, or `None` if there's no task with that ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Auth`] if the API key is rejected, and
+    /// [`Error::Network`] if the server can't be reached or responds with an
+    /// error.
+    pub async fn task(&self, id: &str) -> Result<Option<Task>> {
+        let request = self
+            .http
+            .get(format!("{}/tasks/{id}", self.rest_url))
+            .bearer_auth(&self.api_key);
+        let response = client::send_logged(request, &self.api_key).await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(None),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Err(Error::Auth),
+            _ => Ok(Some(response.error_for_status()?.json().await?)),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! A weekly review: what got done this week, by project, what was added, what
+//! is still overdue, and what has to be done soon, like the review that some
+//! productivity methods suggest doing at the end of every week.
+
+use std::fmt::Write;
+
+use chrono::{DateTime, Datelike, Days, NaiveDate};
+
+use crate::{
+    agenda::{self, is_overdue},
+    locale,
+    sync::{CompletedItem, Item, Project},
+};
+
+/// How many days ahead the review looks for tasks that have to be done soon.
+pub const UPCOMING_DAYS: u64 = 7;
+
+pub struct Review<'a> {
+    /// The Monday the week started on.
+    pub start: NaiveDate,
+
+    /// The tasks completed since `start`, by project, in the order of the
+    /// projects in the sidebar. Each project's tasks are in the order they
+    /// were completed.
+    pub completed: Vec<(String, Vec<&'a CompletedItem>)>,
+
+    /// Tasks that were added since `start`, whether done yet or not, oldest
+    /// first.
+    pub added: Vec<&'a Item>,
+
+    /// Open tasks that were due before today, oldest first.
+    pub overdue: Vec<&'a Item>,
+
+    /// Open tasks with deadlines in the next [`UPCOMING_DAYS`] days, counting
+    /// today, soonest first. Tasks without deadlines count their due dates.
+    pub upcoming: Vec<(NaiveDate, &'a Item)>,
+}
+
+/// The Monday of the week that `today` is in.
+#[must_use]
+pub fn week_start(today: NaiveDate) -> NaiveDate {
+    today - Days::new(u64::from(today.weekday().num_days_from_monday()))
+}
+
+/// The date a task has to be done by: its deadline, or else the day it's due.
+#[must_use]
+pub fn deadline(item: &Item) -> Option<NaiveDate> {
+    match &item.deadline {
+        Some(deadline) => NaiveDate::parse_from_str(&deadline.date, "%Y-%m-%d").ok(),
+        None => agenda::due_date(item),
+    }
+}
+
+/// Puts together the review of the week that `today` is in, from the tasks
+/// completed in it and the ones in the store.
+#[must_use]
+pub fn review<'a>(
+    completed: &'a [CompletedItem],
+    items: &'a [Item],
+    projects: &[Project],
+    today: NaiveDate,
+) -> Review<'a> {
+    let start = week_start(today);
+    let since_start = |timestamp: &str| {
+        DateTime::parse_from_rfc3339(timestamp)
+            .is_ok_and(|time| locale::user_time(time.to_utc()).date() >= start)
+    };
+
+    let mut projects: Vec<&Project> = projects.iter().collect();
+    projects.sort_by_key(|project| (project.is_archived, project.child_order));
+    let project_index = |id: &str| {
+        projects
+            .iter()
+            .position(|project| project.id == id)
+            .unwrap_or(usize::MAX)
+    };
+    let mut done: Vec<&CompletedItem> = completed
+        .iter()
+        .filter(|item| since_start(&item.completed_at))
+        .collect();
+    done.sort_by_key(|item| (project_index(&item.project_id), item.completed_at.clone()));
+    let mut by_project: Vec<(String, Vec<&CompletedItem>)> = Vec::new();
+    for item in done {
+        match by_project.last_mut() {
+            Some((project_id, items)) if *project_id == item.project_id => items.push(item),
+            _ => by_project.push((item.project_id.clone(), vec![item])),
+        }
+    }
+
+    let mut added: Vec<&Item> = items
+        .iter()
+        .filter(|item| !item.is_deleted)
+        .filter(|item| item.added_at.as_deref().is_some_and(since_start))
+        .collect();
+    added.sort_by_key(|item| item.added_at.clone());
+
+    let open: Vec<&Item> = items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .collect();
+    let mut overdue: Vec<&Item> = open
+        .iter()
+        .copied()
+        .filter(|item| is_overdue(item, today))
+        .collect();
+    agenda::sort_by_due(&mut overdue);
+
+    let last_day = today + Days::new(UPCOMING_DAYS - 1);
+    let mut upcoming: Vec<(NaiveDate, &Item)> = open
+        .iter()
+        .filter_map(|item| Some((deadline(item)?, *item)))
+        .filter(|(date, _)| (today..=last_day).contains(date))
+        .collect();
+    upcoming.sort_by_key(|(date, item)| (*date, std::cmp::Reverse(item.priority)));
+
+    Review {
+        start,
+        completed: by_project,
+        added,
+        overdue,
+        upcoming,
+    }
+}
+
+impl Review<'_> {
+    /// The review as Markdown, for pasting into notes. `project_name` names
+    /// projects by their IDs.
+    #[must_use]
+    pub fn markdown(&self, project_name: impl Fn(&str) -> String) -> String {
+        let mut text = format!("# Week of {}\n\n", locale::format_date(self.start));
+
+        let count: usize = self.completed.iter().map(|(_, items)| items.len()).sum();
+        let _ = writeln!(text, "## Completed ({count})\n");
+        for (project_id, items) in &self.completed {
+            let _ = writeln!(text, "### {}\n", project_name(project_id));
+            for item in items {
+                let _ = writeln!(text, "- [x] {}", item.content);
+            }
+            text.push('\n');
+        }
+
+        let _ = writeln!(text, "## Added ({})\n", self.added.len());
+        for item in &self.added {
+            let check = if item.checked { 'x' } else { ' ' };
+            let _ = writeln!(
+                text,
+                "- [{check}] {} ({})",
+                item.content,
+                project_name(&item.project_id)
+            );
+        }
+        if !self.added.is_empty() {
+            text.push('\n');
+        }
+
+        let _ = writeln!(text, "## Overdue ({})\n", self.overdue.len());
+        for item in &self.overdue {
+            let due = agenda::due_date(item).map(locale::format_date);
+            let _ = writeln!(
+                text,
+                "- [ ] {} (due {})",
+                item.content,
+                due.unwrap_or_default()
+            );
+        }
+        if !self.overdue.is_empty() {
+            text.push('\n');
+        }
+
+        let _ = writeln!(text, "## Coming up ({})\n", self.upcoming.len());
+        for (date, item) in &self.upcoming {
+            let _ = writeln!(
+                text,
+                "- [ ] {} (by {})",
+                item.content,
+                locale::format_date(*date)
+            );
+        }
+        text.trim_end().to_string() + "\n"
+    }
+}
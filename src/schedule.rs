@@ -0,0 +1,154 @@
+//! Time blocking: reading how long tasks will take, laying out a day's tasks
+//! as a timeline, from their due times and durations, and working out when
+//! snoozed tasks are due again.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+use crate::{
+    agenda,
+    sync::{DurationUnit, Item, TaskDuration},
+};
+
+/// Parses a duration like `45m`, `1h30m`, `1.5h`, `2 hours`, or `1d`. A bare
+/// number is taken as minutes.
+#[must_use]
+pub fn parse_duration(text: &str) -> Option<TaskDuration> {
+    let text = text.trim().to_lowercase();
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut minutes = 0.0;
+    let mut days = 0.0;
+    let mut rest = text.as_str();
+    while !rest.is_empty() {
+        let number_length = rest
+            .find(|char: char| !char.is_ascii_digit() && char != '.')
+            .unwrap_or(rest.len());
+        let number: f64 = rest[..number_length].parse().ok()?;
+        rest = rest[number_length..].trim_start();
+
+        let unit_length = rest
+            .find(|char: char| !char.is_alphabetic())
+            .unwrap_or(rest.len());
+        match &rest[..unit_length] {
+            "" | "m" | "min" | "mins" | "minute" | "minutes" => minutes += number,
+            "h" | "hr" | "hrs" | "hour" | "hours" => minutes += number * 60.0,
+            "d" | "day" | "days" => days += number,
+            _ => return None,
+        }
+        rest = rest[unit_length..].trim_start_matches([' ', ',']);
+    }
+
+    // the API only takes whole minutes or whole days, not both
+    let (amount, unit) = match (minutes, days) {
+        (minutes, 0.0) => (minutes.round(), DurationUnit::Minute),
+        (0.0, days) if days.fract() == 0.0 => (days, DurationUnit::Day),
+        (minutes, days) => ((minutes + days * 24.0 * 60.0).round(), DurationUnit::Minute),
+    };
+    if amount < 1.0 || amount > f64::from(u32::MAX) {
+        return None;
+    }
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let amount = amount as u32;
+    Some(TaskDuration { amount, unit })
+}
+
+/// The due date for a task snoozed until `when`, as a string for Todoist to
+/// parse. An offset like `+30m`, `+2h`, `+3d`, or `+1w` counts from `now`;
+/// offsets in whole days keep the time the task was due at, `due_time`, if it
+/// had one. Anything else, like `tomorrow` or `next monday`, is passed along
+/// as it is. Returns `None` if an offset isn't valid.
+#[must_use]
+pub fn snoozed_due(when: &str, due_time: Option<NaiveTime>, now: NaiveDateTime) -> Option<String> {
+    let when = when.trim();
+    let Some(offset) = when.strip_prefix('+') else {
+        return Some(when.to_string()).filter(|when| !when.is_empty());
+    };
+    let duration = match offset.trim().strip_suffix('w') {
+        Some(weeks) => TaskDuration {
+            amount: weeks.trim().parse::<u32>().ok()?.checked_mul(7)?,
+            unit: DurationUnit::Day,
+        },
+        None => parse_duration(offset)?,
+    };
+
+    if duration.unit == DurationUnit::Day {
+        let date = now.date() + TimeDelta::days(i64::from(duration.amount));
+        return Some(match due_time {
+            Some(time) => date.and_time(time).format("%Y-%m-%d %H:%M").to_string(),
+            None => date.format("%Y-%m-%d").to_string(),
+        });
+    }
+    let until = now + TimeDelta::minutes(i64::from(duration.amount));
+    Some(until.format("%Y-%m-%d %H:%M").to_string())
+}
+
+/// A day's tasks, laid out in time.
+pub struct Schedule<'a> {
+    /// The tasks due at particular times, in order of when they start.
+    pub blocks: Vec<Block<'a>>,
+
+    /// The tasks due that day but not at a particular time (or that take whole
+    /// days), in order of priority.
+    pub unscheduled: Vec<&'a Item>,
+}
+
+/// A task's place on a timeline.
+pub struct Block<'a> {
+    pub item: &'a Item,
+    pub start: NaiveDateTime,
+
+    /// When the task is done, which is when it starts if it has no duration.
+    pub end: NaiveDateTime,
+
+    /// Whether the task starts before an earlier one is done.
+    pub overlaps: bool,
+}
+
+/// Lays out the open tasks that are due on `date`.
+#[must_use]
+pub fn schedule(items: &[Item], date: NaiveDate) -> Schedule<'_> {
+    let mut day_items: Vec<&Item> = items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+        .filter(|item| agenda::due_date(item) == Some(date))
+        .collect();
+    agenda::sort_by_due(&mut day_items);
+
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut unscheduled = Vec::new();
+    for item in day_items {
+        let start = agenda::due_time(item).filter(|start| start.date() == date);
+        let length = match item.duration {
+            Some(TaskDuration {
+                unit: DurationUnit::Day,
+                ..
+            }) => None,
+            Some(duration) => Some(TimeDelta::minutes(i64::from(duration.amount))),
+            None => Some(TimeDelta::zero()),
+        };
+        let (Some(start), Some(length)) = (start, length) else {
+            unscheduled.push(item);
+            continue;
+        };
+        blocks.push(Block {
+            item,
+            start,
+            end: start + length,
+            overlaps: false,
+        });
+    }
+
+    // times in UTC can come out of order once they're made local
+    blocks.sort_by_key(|block| block.start);
+    let mut busy_until = None;
+    for block in &mut blocks {
+        block.overlaps = busy_until.is_some_and(|end| block.start < end);
+        busy_until = busy_until.max(Some(block.end));
+    }
+    Schedule {
+        blocks,
+        unscheduled,
+    }
+}
@@ -0,0 +1,61 @@
+//! Full-text search over cached tasks, using the same forgiving matching as
+//! project names, so that "dentst" still finds "Call the dentist".
+
+use std::{cmp::Reverse, ops::Range};
+
+use serde::Serialize;
+
+use crate::{fuzzy, sync::Item};
+
+/// Descriptions can be long enough to contain almost anything as a scattered
+/// subsequence, so they only count when they contain the query outright.
+const MIN_DESCRIPTION_SCORE: u32 = 400;
+
+#[derive(Debug, Serialize)]
+pub struct Hit<'a> {
+    pub item: &'a Item,
+
+    /// How well the item matched. Higher is better.
+    pub score: u32,
+
+    /// The byte ranges of the item's content that matched, for highlighting.
+    pub content_matches: Vec<Range<usize>>,
+
+    /// The byte ranges of the item's description that matched, if it was the
+    /// description that matched.
+    pub description_matches: Vec<Range<usize>>,
+}
+
+/// Finds the open items whose content or description matches `query`, best
+/// matches first. Matches in the content count for more than matches in the
+/// description.
+#[must_use]
+pub fn search<'a>(query: &str, items: &'a [Item]) -> Vec<Hit<'a>> {
+    let mut hits: Vec<Hit> = items
+        .iter()
+        .filter(|item| !item.checked)
+        .filter_map(|item| {
+            let content_score = fuzzy::score(query, &item.content);
+            let description_score = fuzzy::score(query, &item.description)
+                .filter(|score| *score >= MIN_DESCRIPTION_SCORE)
+                .map(|score| score / 2);
+            let score = content_score.max(description_score)?;
+
+            let content_matches = content_score
+                .and_then(|_| fuzzy::match_ranges(query, &item.content))
+                .unwrap_or_default();
+            let description_matches = description_score
+                .and_then(|_| fuzzy::match_ranges(query, &item.description))
+                .unwrap_or_default();
+            Some(Hit {
+                item,
+                score,
+                content_matches,
+                description_matches,
+            })
+        })
+        .collect();
+
+    hits.sort_by_key(|hit| (Reverse(hit.score), hit.item.content.chars().count()));
+    hits
+}
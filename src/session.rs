@@ -0,0 +1,896 @@
+//! Keeps a [`Store`] in step with the server: syncing, sending commands, and
+//! queueing commands while the server can't be reached, to be sent along with
+//! the next request that gets through.
+//!
+//! Queued commands are checked for [conflicts](crate::conflict) with changes
+//! made on the server before they're sent.
+//!
+//! Nothing here prints anything. Commands that were queued earlier come back
+//! in [`Outcome::flushed`] once they've been sent, so that callers can report
+//! how they went.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    changes::{self, Changes},
+    client::SyncClient,
+    conflict::{self, Conflict, Resolution, Strategy},
+    hooks, idempotency,
+    storage::{Store, FULL_SYNC_TOKEN},
+    sync::{
+        ActivityQuery, ArchivedQuery, Command, CommandArgs, CommandStatus, CompletedItem,
+        CompletedQuery, Event, Item, ItemIdArgs, Project, Request, Response, User,
+    },
+    undo, Error, Result,
+};
+
+/// The resources that are kept in the store.
+pub const RESOURCE_TYPES: &[&str] = &[
+    "items",
+    "projects",
+    "sections",
+    "labels",
+    "collaborators",
+    "notes",
+    "reminders",
+    "filters",
+    "workspaces",
+    "live_notifications",
+];
+
+/// The most commands the server accepts in a single request.
+pub const MAX_COMMANDS_PER_REQUEST: usize = 100;
+
+/// How many completed items to ask for at a time, which is as many as the
+/// server allows.
+pub const COMPLETED_PAGE_SIZE: u32 = 200;
+
+/// How many archived items to ask for at a time, which is as many as the
+/// server allows.
+pub const ARCHIVED_PAGE_SIZE: u32 = 100;
+
+/// How many events to ask for at a time, which is as many as the server
+/// allows.
+pub const ACTIVITY_PAGE_SIZE: u32 = 100;
+
+/// The kinds of item events that [`item_activity`] returns.
+pub const ITEM_EVENT_TYPES: &[&str] = &["added", "completed", "updated"];
+
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// The server's response, or `None` if it couldn't be reached and the new
+    /// commands were queued.
+    pub response: Option<Response>,
+
+    /// Previously queued commands that went out with this request. Their
+    /// statuses are in the response's `sync_status`.
+    pub flushed: Vec<Command>,
+
+    /// Previously queued commands that were dropped instead of being sent,
+    /// because they conflicted with changes on the server and the client keeps
+    /// the server's changes.
+    pub dropped: Vec<Conflict>,
+
+    /// The [hooks](crate::hooks) that failed, by the settings they're in.
+    pub hook_errors: Vec<(&'static str, Error)>,
+}
+
+impl Outcome {
+    /// The server's verdict on a command that was sent, if it gave one.
+    #[must_use]
+    pub fn status(&self, uuid: &Uuid) -> Option<&CommandStatus> {
+        self.response
+            .as_ref()
+            .and_then(|response| response.status(uuid))
+    }
+}
+
+/// Sends a sync request carrying `commands`, along with any commands that were
+/// previously queued. A queue too long to go out with `commands` is sent ahead
+/// of them, [`MAX_COMMANDS_PER_REQUEST`] at a time. If the server can't be
+/// reached, `commands` are added to the queue instead. Commands that can be
+/// undone are added to the undo journal once they've been sent or queued, and
+/// the tasks that commands delete are put in the [trash](crate::trash).
+///
+/// Queued commands that conflict with changes on the server are handled the
+/// way the client's [`Strategy`] says.
+///
+/// # Errors
+///
+/// Returns [`Error::DryRun`] with `commands` if there are any and the client is
+/// in dry-run mode, leaving the store alone. Returns [`Error::Conflicts`] if
+/// queued commands conflict and the client is set to ask about them, after
+/// queueing `commands`. Otherwise returns an error if the server responds with
+/// an error, or if the store can't be read or written.
+pub async fn send_request(
+    store: &mut Store,
+    client: &SyncClient,
+    sync_token: &str,
+    resource_types: &[&str],
+    mut commands: Vec<Command>,
+) -> Result<Outcome> {
+    if client.is_dry_run() && !commands.is_empty() {
+        return Err(Error::DryRun(commands));
+    }
+    // the queue is read before the request and written after it, so no one
+    // else can touch it in between
+    let lock = store.lock()?;
+    let created_as = stamp(store, &mut commands)?;
+    // what's saved about the new commands has to be worked out before they
+    // change anything
+    let saved = save(store, &commands)?;
+
+    let conflicts = find_conflicts(store, client).await?;
+    let mut dropped = Vec::new();
+    match client.conflict_strategy() {
+        _ if conflicts.is_empty() => {}
+        Strategy::Ask => {
+            let mut queued_commands = store.queued_commands()?;
+            queued_commands.extend(commands);
+            queue(store, &queued_commands, saved)?;
+            return Err(Error::Conflicts(conflicts));
+        }
+        Strategy::KeepLocal => {
+            for conflict in &conflicts {
+                resolve_conflict(store, conflict, Resolution::KeepLocal)?;
+            }
+        }
+        Strategy::KeepRemote => {
+            for conflict in &conflicts {
+                resolve_conflict(store, conflict, Resolution::KeepRemote)?;
+            }
+            dropped = conflicts;
+        }
+    }
+
+    let mut queued_commands = store.queued_commands()?;
+    let mut flushed = flush_overflow(store, client, &mut queued_commands, &mut commands).await?;
+    if flushed.unreachable {
+        queued_commands.extend(commands);
+        queue(store, &queued_commands, saved)?;
+        drop(lock);
+        return Ok(Outcome {
+            dropped,
+            hook_errors: run_hooks(flushed.hook_runs).await,
+            ..Outcome::default()
+        });
+    }
+    let queued_count = queued_commands.len();
+
+    let mut request_body = Request {
+        sync_token: sync_token.to_string(),
+        resource_types: resource_types.iter().map(ToString::to_string).collect(),
+        commands: queued_commands.into_iter().chain(commands).collect(),
+    };
+
+    match client.sync(&request_body).await {
+        Ok(mut response) => {
+            store.set_queued_commands(&[])?;
+            acknowledge(store, &request_body.commands, &created_as, &mut response)?;
+            store.resolve_temp_ids(&response.temp_id_mapping)?;
+            flushed.hook_runs.extend(client.hooks().task_runs(
+                store,
+                &request_body.commands,
+                &response,
+            )?);
+            flushed
+                .commands
+                .extend(request_body.commands.drain(..queued_count));
+            flushed.answer(&mut response);
+            let mut outcome = Outcome {
+                response: Some(response),
+                flushed: flushed.commands,
+                dropped,
+                ..Outcome::default()
+            };
+            let sent = |uuid: &Uuid| outcome.status(uuid).is_none_or(CommandStatus::is_ok);
+            for (uuid, entry) in saved.undo_entries {
+                if sent(&uuid) {
+                    store.push_undo_entry(&entry)?;
+                }
+            }
+            for (uuid, item) in saved.trashed {
+                if sent(&uuid) {
+                    store.put_in_trash(&item)?;
+                }
+            }
+            // hooks can run `todoist` too, which would wait for the lock
+            drop(lock);
+            outcome.hook_errors = run_hooks(flushed.hook_runs).await;
+            Ok(outcome)
+        }
+        Err(err) if err.is_unreachable() => {
+            queue(store, &request_body.commands, saved)?;
+            drop(lock);
+            Ok(Outcome {
+                dropped,
+                hook_errors: run_hooks(flushed.hook_runs).await,
+                ..Outcome::default()
+            })
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// What came of queued commands that went out ahead of a request.
+#[derive(Default)]
+struct Flushed {
+    commands: Vec<Command>,
+    sync_status: HashMap<Uuid, CommandStatus>,
+    temp_id_mapping: HashMap<Uuid, String>,
+    hook_runs: Vec<hooks::Run>,
+
+    /// Whether the server couldn't be reached, so that the rest have to be
+    /// queued.
+    unreachable: bool,
+}
+
+impl Flushed {
+    /// Adds the statuses and temp IDs that Todoist gave the commands that went
+    /// out ahead to `response`, so they're all in one place.
+    fn answer(&mut self, response: &mut Response) {
+        let sync_status = response.sync_status.get_or_insert_default();
+        sync_status.extend(std::mem::take(&mut self.sync_status));
+        response
+            .temp_id_mapping
+            .extend(std::mem::take(&mut self.temp_id_mapping));
+    }
+}
+
+/// Sends the queue on its own, [`MAX_COMMANDS_PER_REQUEST`] at a time, for as
+/// long as it's too big to go out with `commands`. Each request waits its turn
+/// with the client's rate limiter, like any other. Temp IDs that a request
+/// resolves are replaced in the commands still to be sent. What's left to send
+/// of the queue stays in `queued`.
+async fn flush_overflow(
+    store: &mut Store,
+    client: &SyncClient,
+    queued: &mut Vec<Command>,
+    commands: &mut [Command],
+) -> Result<Flushed> {
+    let mut flushed = Flushed::default();
+    while !queued.is_empty() && queued.len() + commands.len() > MAX_COMMANDS_PER_REQUEST {
+        let rest = queued.split_off(queued.len().min(MAX_COMMANDS_PER_REQUEST));
+        let request_body = Request {
+            sync_token: FULL_SYNC_TOKEN.to_string(),
+            resource_types: vec![],
+            commands: std::mem::replace(queued, rest),
+        };
+        let mut response = match client.sync(&request_body).await {
+            Ok(response) => response,
+            Err(err) if err.is_unreachable() => {
+                queued.splice(0..0, request_body.commands);
+                flushed.unreachable = true;
+                break;
+            }
+            Err(err) => return Err(err),
+        };
+        for command in queued.iter_mut().chain(commands.iter_mut()) {
+            command.resolve_temp_ids(&response.temp_id_mapping)?;
+        }
+        store.set_queued_commands(queued)?;
+        acknowledge(store, &request_body.commands, &[], &mut response)?;
+        store.resolve_temp_ids(&response.temp_id_mapping)?;
+        flushed.hook_runs.extend(client.hooks().task_runs(
+            store,
+            &request_body.commands,
+            &response,
+        )?);
+        flushed
+            .sync_status
+            .extend(response.sync_status.unwrap_or_default());
+        flushed.temp_id_mapping.extend(response.temp_id_mapping);
+        flushed.commands.extend(request_body.commands);
+    }
+    Ok(flushed)
+}
+
+/// Gives new commands the `uuid`s that [`idempotency`] works out for them, so
+/// that ones sent again keep the `uuid`s they were sent with before. Also
+/// returns each command's new `uuid` with the one it was made with.
+fn stamp(store: &mut Store, commands: &mut [Command]) -> Result<Vec<(Uuid, Uuid)>> {
+    // queued commands go out in the same request, so a new command that does
+    // the same thing as one of them is one of its own too
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for command in store.queued_commands()? {
+        *seen.entry(idempotency::digest(&command.args)?).or_default() += 1;
+    }
+    let mut created_as = Vec::with_capacity(commands.len());
+    for command in commands {
+        // commands in the same request that do the same thing are each one
+        // of their own
+        let digest = idempotency::digest(&command.args)?;
+        let count = seen.entry(digest.clone()).or_default();
+        let uuid = store.command_uuid(&format!("{digest}#{count}"))?;
+        *count += 1;
+        created_as.push((uuid, std::mem::replace(&mut command.uuid, uuid)));
+    }
+    Ok(created_as)
+}
+
+/// Forgets the `uuid`s of the commands that Todoist has answered for, and
+/// gives the new ones' statuses under the `uuid`s they were made with as
+/// well, which callers look them up by.
+fn acknowledge(
+    store: &mut Store,
+    sent: &[Command],
+    created_as: &[(Uuid, Uuid)],
+    response: &mut Response,
+) -> Result<()> {
+    let uuids: Vec<Uuid> = sent.iter().map(|command| command.uuid).collect();
+    store.acknowledge_commands(&uuids)?;
+    if let Some(statuses) = &mut response.sync_status {
+        for (uuid, created_as) in created_as {
+            if let Some(status) = statuses.get(uuid).cloned() {
+                statuses.insert(*created_as, status);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Runs hooks one after another, returning the errors of any that fail.
+async fn run_hooks(runs: Vec<hooks::Run>) -> Vec<(&'static str, Error)> {
+    let mut errors = Vec::new();
+    for run in runs {
+        if let Err(err) = run.run().await {
+            errors.push((run.setting, err));
+        }
+    }
+    errors
+}
+
+/// What's saved about new commands once they're sent or queued, by the UUID
+/// of the command each is for.
+struct Saved {
+    /// How to undo them.
+    undo_entries: Vec<(Uuid, undo::Entry)>,
+
+    /// The items they change, as they were.
+    conflict_bases: Vec<(Uuid, Item)>,
+
+    /// The items they delete, for the trash.
+    trashed: Vec<(Uuid, Item)>,
+}
+
+/// Works out how to undo new commands, the items they change, and the items
+/// they delete.
+fn save(store: &Store, commands: &[Command]) -> Result<Saved> {
+    let mut undo_entries = Vec::new();
+    let mut conflict_bases = Vec::new();
+    let mut trashed = Vec::new();
+    for command in commands {
+        if let Some(entry) = undo::entry_for(store, &command.args)? {
+            undo_entries.push((command.uuid, entry));
+        }
+        if let CommandArgs::ItemDelete(ItemIdArgs { id }) = &command.args {
+            trashed.extend(store.item(id)?.map(|item| (command.uuid, item)));
+        }
+        if let Some(item) = conflict::target_item(&command.args)
+            .map(|id| store.item(id))
+            .transpose()?
+            .flatten()
+        {
+            conflict_bases.push((command.uuid, item));
+        }
+    }
+    Ok(Saved {
+        undo_entries,
+        conflict_bases,
+        trashed,
+    })
+}
+
+/// Replaces the queue with `commands`, saving what there is to save about the
+/// new ones.
+fn queue(store: &mut Store, commands: &[Command], saved: Saved) -> Result<()> {
+    store.set_queued_commands(commands)?;
+    for (_, entry) in saved.undo_entries {
+        store.push_undo_entry(&entry)?;
+    }
+    for (uuid, item) in saved.conflict_bases {
+        store.set_conflict_base(&uuid, &item)?;
+    }
+    for (_, item) in saved.trashed {
+        store.put_in_trash(&item)?;
+    }
+    Ok(())
+}
+
+/// Brings the store up to date, then checks the queued commands that change
+/// items for conflicts with what happened to those items on the server. If
+/// nothing queued needs checking, or the server can't be reached, there are no
+/// conflicts to find.
+///
+/// # Errors
+///
+/// Returns an error if the server responds with an error, or if the store
+/// can't be read or written.
+pub async fn find_conflicts(store: &mut Store, client: &SyncClient) -> Result<Vec<Conflict>> {
+    let bases = store.conflict_bases()?;
+    if bases.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let request_body = Request {
+        sync_token: store.sync_token()?,
+        resource_types: RESOURCE_TYPES.iter().map(ToString::to_string).collect(),
+        commands: vec![],
+    };
+    match client.sync(&request_body).await {
+        Ok(response) => store.apply(&response)?,
+        Err(err) if err.is_unreachable() => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    }
+
+    let mut bases = bases;
+    let mut conflicts = Vec::new();
+    for command in store.queued_commands()? {
+        let Some(base) = bases.remove(&command.uuid) else {
+            continue;
+        };
+        let remote = conflict::target_item(&command.args)
+            .map(|id| store.item(id))
+            .transpose()?
+            .flatten();
+        conflicts.extend(conflict::detect(command, base, remote.as_ref()));
+    }
+    Ok(conflicts)
+}
+
+/// Settles a conflict. Keeping the local change sends it as it is with the
+/// next request; keeping the remote one drops it from the queue.
+///
+/// # Errors
+///
+/// Returns an error if the store can't be read or written.
+pub fn resolve_conflict(
+    store: &mut Store,
+    conflict: &Conflict,
+    resolution: Resolution,
+) -> Result<()> {
+    let uuid = conflict.command.uuid;
+    match resolution {
+        Resolution::KeepLocal => store.forget_conflict_base(&uuid),
+        Resolution::KeepRemote => {
+            let mut commands = store.queued_commands()?;
+            commands.retain(|command| command.uuid != uuid);
+            store.set_queued_commands(&commands)
+        }
+    }
+}
+
+/// Sends `commands` in as many requests as it takes to stay within
+/// [`MAX_COMMANDS_PER_REQUEST`], with an outcome for each request. Temp IDs
+/// that earlier requests resolved are replaced in later ones, so commands can
+/// refer to resources created by commands in earlier requests.
+///
+/// # Errors
+///
+/// The same as [`send_request`]. In dry-run mode, every chunk's commands are
+/// in the [`Error::DryRun`].
+pub async fn send_chunked(
+    store: &mut Store,
+    client: &SyncClient,
+    commands: Vec<Command>,
+) -> Result<Vec<Outcome>> {
+    send_chunked_with_progress(store, client, commands, |_| {}).await
+}
+
+/// The same as [`send_chunked`], calling `on_sent` with the number of commands
+/// in each request once it's done.
+///
+/// # Errors
+///
+/// The same as [`send_chunked`].
+pub async fn send_chunked_with_progress(
+    store: &mut Store,
+    client: &SyncClient,
+    commands: Vec<Command>,
+    mut on_sent: impl FnMut(usize),
+) -> Result<Vec<Outcome>> {
+    if client.is_dry_run() && !commands.is_empty() {
+        return Err(Error::DryRun(commands));
+    }
+    let mut outcomes = Vec::new();
+    let mut temp_id_mapping = HashMap::new();
+    let mut commands = commands.into_iter().peekable();
+    while commands.peek().is_some() {
+        let mut chunk: Vec<Command> = commands.by_ref().take(MAX_COMMANDS_PER_REQUEST).collect();
+        for command in &mut chunk {
+            command.resolve_temp_ids(&temp_id_mapping)?;
+        }
+        let sent = chunk.len();
+        let outcome = send_request(store, client, FULL_SYNC_TOKEN, &[], chunk).await?;
+        if let Some(response) = &outcome.response {
+            temp_id_mapping.extend(response.temp_id_mapping.clone());
+        }
+        outcomes.push(outcome);
+        on_sent(sent);
+    }
+    Ok(outcomes)
+}
+
+/// Sends a single command. The outcome's response is `None` if the command was
+/// queued because the server couldn't be reached.
+///
+/// # Errors
+///
+/// Returns [`Error::CommandRejected`] if the server refuses the command, and
+/// otherwise the same errors as [`send_request`].
+pub async fn send_command(
+    store: &mut Store,
+    client: &SyncClient,
+    command: Command,
+) -> Result<Outcome> {
+    let description = command.args.description();
+    let uuid = command.uuid;
+    let outcome = send_request(store, client, FULL_SYNC_TOKEN, &[], vec![command]).await?;
+
+    if let Some(CommandStatus::Error { error_code, error }) = outcome.status(&uuid) {
+        return Err(Error::CommandRejected {
+            description,
+            code: *error_code,
+            message: error.clone(),
+        });
+    }
+    Ok(outcome)
+}
+
+/// Brings the store up to date with the server, using the stored sync token so
+/// that only changes since the last sync are downloaded. If the server can't be
+/// reached, the store is left as-is and the outcome's response is `None`.
+///
+/// # Errors
+///
+/// The same as [`send_request`].
+pub async fn sync(store: &mut Store, client: &SyncClient) -> Result<Outcome> {
+    // the hook needs to know what changed
+    if client.hooks().on_sync.is_some() {
+        let (outcome, _) = sync_with_changes(store, client, false).await?;
+        return Ok(outcome);
+    }
+    if in_parts(store, client)? {
+        return sync_in_parts(store, client, false).await;
+    }
+    let sync_token = store.sync_token()?;
+    let outcome = send_request(store, client, &sync_token, RESOURCE_TYPES, vec![]).await?;
+    if let Some(response) = &outcome.response {
+        store.apply(response)?;
+    }
+    Ok(outcome)
+}
+
+/// Whether the store was synced less than `ttl` ago, so that it can be read
+/// without syncing first.
+///
+/// # Errors
+///
+/// Returns an error if the store can't be read.
+pub fn is_fresh(store: &Store, ttl: Duration) -> Result<bool> {
+    let Some(last_synced_at) = store.last_synced_at()? else {
+        return Ok(false);
+    };
+    Ok(Utc::now()
+        .signed_duration_since(last_synced_at)
+        .to_std()
+        .is_ok_and(|age| age < ttl))
+}
+
+/// Like [`sync`], but also works out what the sync changed in the store. With
+/// `full`, everything is downloaded again, instead of only what changed since
+/// the last sync. The changes are `None` if the server couldn't be reached.
+///
+/// # Errors
+///
+/// The same as [`send_request`].
+pub async fn sync_with_changes(
+    store: &mut Store,
+    client: &SyncClient,
+    full: bool,
+) -> Result<(Outcome, Option<Changes>)> {
+    let in_parts = in_parts(store, client)?;
+    let (items, projects) = (store.items()?, store.projects()?);
+    let outcome = if in_parts {
+        sync_in_parts(store, client, full).await?
+    } else {
+        let sync_token = if full {
+            FULL_SYNC_TOKEN.to_string()
+        } else {
+            store.sync_token()?
+        };
+        send_request(store, client, &sync_token, RESOURCE_TYPES, vec![]).await?
+    };
+    let Some(response) = &outcome.response else {
+        return Ok((outcome, None));
+    };
+    let changes = if in_parts {
+        // the response's resources are already in the store, so it's compared
+        // as if all of them had been downloaded again
+        let synced = Response {
+            full_sync: true,
+            items: Some(store.items()?),
+            projects: Some(store.projects()?),
+            ..Response::default()
+        };
+        changes::compare(&items, &projects, &synced)
+    } else {
+        let changes = changes::compare(&items, &projects, response);
+        store.apply(response)?;
+        changes
+    };
+    let mut outcome = outcome;
+    let runs = client.hooks().runs(&[hooks::Event::Sync { changes }])?;
+    outcome.hook_errors.extend(run_hooks(runs).await);
+    Ok((outcome, Some(changes)))
+}
+
+/// Whether to sync in parts: when the client is set to, as long as there are no
+/// queued commands, which go out with an ordinary sync instead.
+fn in_parts(store: &Store, client: &SyncClient) -> Result<bool> {
+    Ok(client.syncs_in_parts() && store.queued_commands()?.is_empty())
+}
+
+/// Brings the store up to date like [`sync`] does, but in parts, for accounts
+/// too big to download at once (see [`SyncClient::in_parts`]). A full sync
+/// downloads one resource type at a time, and each response is read in
+/// [batches](crate::stream) that go into the store as they come in, so only a
+/// batch of resources is held in memory at a time. The outcome's response has
+/// the sync token and such, but none of the resources. With `full`,
+/// everything is downloaded again. Queued commands aren't sent.
+///
+/// # Errors
+///
+/// Returns an error if the server responds with an error, or if the store
+/// can't be read or written.
+pub async fn sync_in_parts(store: &mut Store, client: &SyncClient, full: bool) -> Result<Outcome> {
+    let _lock = store.lock()?;
+    match download_in_parts(store, client, full).await {
+        Ok(response) => {
+            store.finish_sync(&response.sync_token)?;
+            Ok(Outcome {
+                response: Some(response),
+                ..Outcome::default()
+            })
+        }
+        Err(err) if err.is_unreachable() => Ok(Outcome::default()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Downloads what's changed since the last sync, or everything, one resource
+/// type at a time, returning the response to take the sync token from.
+async fn download_in_parts(store: &mut Store, client: &SyncClient, full: bool) -> Result<Response> {
+    let sync_token = store.sync_token()?;
+    if !full && sync_token != FULL_SYNC_TOKEN {
+        let response = download(store, client, &sync_token, RESOURCE_TYPES).await?;
+        // a token that's too old gets a full sync back, which can't tell what
+        // was deleted when it's read as an incremental one
+        if !response.full_sync {
+            return Ok(response);
+        }
+    }
+    let mut first = None;
+    for resource_type in RESOURCE_TYPES {
+        let part = download(store, client, FULL_SYNC_TOKEN, &[resource_type]).await?;
+        // changes made after the first part was downloaded are caught by the
+        // next sync, since it starts from the first part's token
+        first.get_or_insert(part);
+    }
+    first.ok_or(Error::UnexpectedResponse("nothing to sync".to_string()))
+}
+
+/// Sends a sync request for `resource_types` without any commands, putting
+/// the resources in the response into the store as they're read.
+async fn download(
+    store: &mut Store,
+    client: &SyncClient,
+    sync_token: &str,
+    resource_types: &[&str],
+) -> Result<Response> {
+    let full = sync_token == FULL_SYNC_TOKEN;
+    let request = Request {
+        sync_token: sync_token.to_string(),
+        resource_types: resource_types.iter().map(ToString::to_string).collect(),
+        commands: vec![],
+    };
+    let mut kinds = Vec::new();
+    let result = client
+        .sync_in_batches(&request, |batch| {
+            if !kinds.contains(&batch.kind()) {
+                kinds.push(batch.kind());
+            }
+            store.apply_batch(&batch, full)
+        })
+        .await;
+    if full {
+        // what was seen of a download that failed is forgotten, not removed
+        let kinds = if result.is_ok() {
+            kinds.as_slice()
+        } else {
+            &[]
+        };
+        store.remove_unsynced(kinds)?;
+    }
+    result
+}
+
+/// Finds an item by ID, looking in the store first. If it isn't there, it's
+/// fetched on its own from the REST API, if the client has a REST client, and
+/// otherwise by syncing the store.
+///
+/// # Errors
+///
+/// Returns an error if the server responds with an error, or if the store
+/// can't be read or written.
+pub async fn fetch_item(store: &mut Store, client: &SyncClient, id: &str) -> Result<Option<Item>> {
+    if let Some(item) = store.item(id)? {
+        return Ok(Some(item));
+    }
+
+    if let Some(rest) = client.rest() {
+        match rest.task(id).await {
+            Ok(task) => {
+                let item = task.map(Item::from);
+                if let Some(item) = &item {
+                    store.put_items(std::slice::from_ref(item))?;
+                }
+                return Ok(item);
+            }
+            // the Sync API might still get through, e.g. from behind a proxy
+            Err(err) if err.is_unreachable() => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    sync(store, client).await?;
+    store.item(id)
+}
+
+/// Fetches every item completed since `since` (a UTC time like
+/// `2024-05-01T00:00:00`), or ever if it's `None`, going through as many pages
+/// as it takes. The most recently completed items come first.
+///
+/// # Errors
+///
+/// The same as [`SyncClient::completed`].
+pub async fn completed_items(
+    client: &SyncClient,
+    project_id: Option<&str>,
+    since: Option<&str>,
+) -> Result<Vec<CompletedItem>> {
+    let mut query = CompletedQuery {
+        project_id: project_id.map(ToString::to_string),
+        since: since.map(ToString::to_string),
+        limit: COMPLETED_PAGE_SIZE,
+        offset: 0,
+    };
+    let mut items = Vec::new();
+    loop {
+        let page = client.completed(&query).await?;
+        let count = page.items.len();
+        items.extend(page.items);
+        if count < COMPLETED_PAGE_SIZE as usize {
+            return Ok(items);
+        }
+        query.offset += COMPLETED_PAGE_SIZE;
+    }
+}
+
+/// Adds a task with Todoist's own quick-add parsing, storing it as the server
+/// made it.
+///
+/// # Errors
+///
+/// The same as [`SyncClient::quick_add`], or an error if the store can't be
+/// written.
+pub async fn quick_add(store: &mut Store, client: &SyncClient, text: &str) -> Result<Item> {
+    let item = client.quick_add(text).await?;
+    store.put_items(std::slice::from_ref(&item))?;
+    Ok(item)
+}
+
+/// Fetches the archived projects into the store, since syncing leaves them
+/// out, and returns them.
+///
+/// # Errors
+///
+/// The same as [`SyncClient::archived_projects`], or an error if the store
+/// can't be written.
+pub async fn archived_projects(store: &mut Store, client: &SyncClient) -> Result<Vec<Project>> {
+    let projects = client.archived_projects().await?;
+    store.put_projects(&projects)?;
+    Ok(projects)
+}
+
+/// Fetches all of a project's completed items into the store, since syncing
+/// leaves them out, going through as many pages as it takes. The most recently
+/// completed items come first.
+///
+/// # Errors
+///
+/// The same as [`SyncClient::archived_items`], or an error if the store can't
+/// be written.
+pub async fn archived_items(
+    store: &mut Store,
+    client: &SyncClient,
+    project_id: &str,
+) -> Result<Vec<Item>> {
+    let mut query = ArchivedQuery {
+        project_id: project_id.to_string(),
+        limit: ARCHIVED_PAGE_SIZE,
+        cursor: None,
+    };
+    let mut items = Vec::new();
+    loop {
+        let page = client.archived_items(&query).await?;
+        items.extend(page.items);
+        match page.next_cursor.filter(|_| page.has_more) {
+            Some(cursor) => query.cursor = Some(cursor),
+            None => break,
+        }
+    }
+    store.put_items(&items)?;
+    Ok(items)
+}
+
+/// Fetches up to `limit` of the latest events where items were added,
+/// completed, or updated, optionally only in one project. The most recent
+/// events come first.
+///
+/// # Errors
+///
+/// The same as [`SyncClient::activity`].
+pub async fn item_activity(
+    client: &SyncClient,
+    project_id: Option<&str>,
+    limit: usize,
+) -> Result<Vec<Event>> {
+    let mut query = ActivityQuery {
+        object_type: Some("item".to_string()),
+        parent_project_id: project_id.map(ToString::to_string),
+        limit: ACTIVITY_PAGE_SIZE,
+        offset: 0,
+    };
+    let mut events = Vec::new();
+    while events.len() < limit {
+        let page = client.activity(&query).await?;
+        let count = page.events.len();
+        events.extend(
+            page.events
+                .into_iter()
+                .filter(|event| ITEM_EVENT_TYPES.contains(&event.event_type.as_str())),
+        );
+        if count < ACTIVITY_PAGE_SIZE as usize {
+            break;
+        }
+        query.offset += ACTIVITY_PAGE_SIZE;
+    }
+    events.truncate(limit);
+    Ok(events)
+}
+
+/// Fetches the user that the client's API token belongs to.
+///
+/// # Errors
+///
+/// Returns [`Error::UnexpectedResponse`] if the server doesn't send the user
+/// back, and otherwise the same errors as [`SyncClient::sync`].
+pub async fn fetch_user(client: &SyncClient) -> Result<User> {
+    let request_body = Request {
+        sync_token: FULL_SYNC_TOKEN.to_string(),
+        resource_types: vec!["user".to_string()],
+        commands: vec![],
+    };
+
+    client
+        .sync(&request_body)
+        .await?
+        .user
+        .ok_or(Error::UnexpectedResponse(
+            "it did not contain user information".to_string(),
+        ))
+}
@@ -0,0 +1,75 @@
+//! Counts of what's in each project, worked out from the local cache.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{
+    agenda::is_overdue,
+    sync::{Item, Section},
+};
+
+/// How much is in a project. Subprojects' tasks aren't included.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectCounts {
+    /// Uncompleted tasks, including subtasks.
+    pub open: usize,
+
+    /// Uncompleted tasks that were due before today.
+    pub overdue: usize,
+
+    /// Sections that aren't archived.
+    pub sections: usize,
+}
+
+/// Which count to sort projects by, largest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SortBy {
+    Open,
+    Overdue,
+    Sections,
+}
+
+impl ProjectCounts {
+    #[must_use]
+    pub fn get(&self, sort_by: SortBy) -> usize {
+        match sort_by {
+            SortBy::Open => self.open,
+            SortBy::Overdue => self.overdue,
+            SortBy::Sections => self.sections,
+        }
+    }
+}
+
+/// Counts the open tasks, overdue tasks, and sections in every project that
+/// has any, by project ID.
+#[must_use]
+pub fn project_counts(
+    items: &[Item],
+    sections: &[Section],
+    today: NaiveDate,
+) -> HashMap<String, ProjectCounts> {
+    let mut counts: HashMap<String, ProjectCounts> = HashMap::new();
+    for item in items
+        .iter()
+        .filter(|item| !item.checked && !item.is_deleted)
+    {
+        let project = counts.entry(item.project_id.clone()).or_default();
+        project.open += 1;
+        if is_overdue(item, today) {
+            project.overdue += 1;
+        }
+    }
+    for section in sections
+        .iter()
+        .filter(|section| !section.is_archived && !section.is_deleted)
+    {
+        counts
+            .entry(section.project_id.clone())
+            .or_default()
+            .sections += 1;
+    }
+    counts
+}
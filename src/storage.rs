@@ -0,0 +1,1678 @@
+//! Local storage for synced Todoist data, backed by SQLite.
+//!
+//! Each resource type gets a table holding its JSON representation, with the
+//! fields we filter on exposed as generated columns. The schema is versioned
+//! with SQLite's `user_version` pragma; to change it, append a migration to
+//! [`MIGRATIONS`] rather than editing an existing one.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Transaction};
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    dependency::Dependency,
+    idempotency,
+    stream::Batch,
+    sync::{
+        Collaborator, CollaboratorState, Command, CompletedItem, Filter, Item, Label,
+        LiveNotification, Note, Project, Reminder, Response, Section, Workspace,
+    },
+    template::Template,
+    track::{Timer, WorkSession, WorkTotal},
+    trash::{self, TrashedItem},
+    undo, Result,
+};
+
+/// The sync token that asks the server for a full sync.
+pub const FULL_SYNC_TOKEN: &str = "*";
+
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE sync_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+
+    CREATE TABLE items (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        project_id TEXT GENERATED ALWAYS AS (json_extract(data, '$.project_id')) VIRTUAL,
+        checked INTEGER GENERATED ALWAYS AS (json_extract(data, '$.checked')) VIRTUAL
+    );
+    CREATE INDEX items_project_id ON items (project_id);
+
+    CREATE TABLE projects (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+
+    CREATE TABLE labels (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+
+    CREATE TABLE command_queue (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        data TEXT NOT NULL
+    );
+
+    CREATE TABLE last_list (
+        position INTEGER PRIMARY KEY,
+        id TEXT NOT NULL,
+        content TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE collaborators (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE sections (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        project_id TEXT GENERATED ALWAYS AS (json_extract(data, '$.project_id')) VIRTUAL
+    );
+",
+    "
+    CREATE TABLE notes (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        item_id TEXT GENERATED ALWAYS AS (json_extract(data, '$.item_id')) VIRTUAL
+    );
+    CREATE INDEX notes_item_id ON notes (item_id);
+",
+    "
+    CREATE TABLE reminders (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        item_id TEXT GENERATED ALWAYS AS (json_extract(data, '$.item_id')) VIRTUAL
+    );
+",
+    "
+    CREATE TABLE undo_journal (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        data TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE sent_notifications (
+        key TEXT PRIMARY KEY,
+        sent_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+",
+    "
+    CREATE TABLE conflict_bases (
+        uuid TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+",
+    // filters only come with a full sync, so the next sync has to be one
+    "
+    CREATE TABLE filters (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+    DELETE FROM sync_state WHERE key = 'sync_token';
+",
+    "
+    CREATE TABLE trash (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        deleted_at TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE focus_sessions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        item_id TEXT NOT NULL,
+        content TEXT NOT NULL,
+        started_at TEXT NOT NULL,
+        seconds INTEGER NOT NULL
+    );
+",
+    // time tracked with `track` goes in with focus sessions
+    "
+    ALTER TABLE focus_sessions RENAME TO work_sessions;
+    ALTER TABLE work_sessions ADD COLUMN project_id TEXT NOT NULL DEFAULT '';
+",
+    "
+    CREATE TABLE pushed_commits (
+        sha TEXT PRIMARY KEY,
+        pushed_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+",
+    // when an item was first seen completed, for pruning the ones that don't
+    // say when they were
+    "
+    ALTER TABLE items ADD COLUMN checked_at TEXT;
+",
+    "
+    CREATE TABLE dependencies (
+        task_id TEXT NOT NULL,
+        blocker_id TEXT NOT NULL,
+        PRIMARY KEY (task_id, blocker_id)
+    );
+",
+    "
+    CREATE TABLE templates (
+        name TEXT PRIMARY KEY COLLATE NOCASE,
+        data TEXT NOT NULL,
+        saved_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+",
+    // the commands that Todoist hasn't answered for yet, by their digests
+    "
+    CREATE TABLE sent_commands (
+        digest TEXT PRIMARY KEY,
+        uuid TEXT NOT NULL,
+        sent_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    );
+",
+    "
+    CREATE TABLE workspaces (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL
+    );
+",
+    "
+    CREATE TABLE collaborator_states (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        project_id TEXT GENERATED ALWAYS AS (json_extract(data, '$.project_id')) VIRTUAL
+    );
+",
+    "
+    CREATE TABLE live_notifications (
+        id TEXT PRIMARY KEY,
+        data TEXT NOT NULL,
+        created_at TEXT GENERATED ALWAYS AS (json_extract(data, '$.created_at')) VIRTUAL
+    );
+",
+    "
+    CREATE TABLE last_completed (
+        position INTEGER PRIMARY KEY,
+        id TEXT NOT NULL,
+        content TEXT NOT NULL
+    );
+",
+];
+
+/// The tables of the resources that syncs bring in, named as their resource
+/// types are.
+const RESOURCE_TABLES: [&str; 11] = [
+    Item::TABLE,
+    Project::TABLE,
+    Label::TABLE,
+    Section::TABLE,
+    Collaborator::TABLE,
+    Note::TABLE,
+    Reminder::TABLE,
+    Filter::TABLE,
+    Workspace::TABLE,
+    CollaboratorState::TABLE,
+    LiveNotification::TABLE,
+];
+
+/// The resources seen so far in a full sync that's read in batches, which only
+/// last as long as the connection.
+const CREATE_SYNCED: &str =
+    "CREATE TEMP TABLE IF NOT EXISTS synced (kind TEXT, id TEXT, PRIMARY KEY (kind, id))";
+
+/// A synced resource that is stored in its own table, keyed by ID.
+trait Resource: Serialize + DeserializeOwned {
+    const TABLE: &'static str;
+
+    fn id(&self) -> Cow<'_, str>;
+    fn is_deleted(&self) -> bool;
+}
+
+impl Resource for Item {
+    const TABLE: &'static str = "items";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Project {
+    const TABLE: &'static str = "projects";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Label {
+    const TABLE: &'static str = "labels";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Section {
+    const TABLE: &'static str = "sections";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Note {
+    const TABLE: &'static str = "notes";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Reminder {
+    const TABLE: &'static str = "reminders";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Filter {
+    const TABLE: &'static str = "filters";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Workspace {
+    const TABLE: &'static str = "workspaces";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for LiveNotification {
+    const TABLE: &'static str = "live_notifications";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted
+    }
+}
+
+impl Resource for Collaborator {
+    const TABLE: &'static str = "collaborators";
+
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.id)
+    }
+
+    // collaborators are removed by omission from a full sync, rather than by
+    // being marked as deleted
+    fn is_deleted(&self) -> bool {
+        false
+    }
+}
+
+impl Resource for CollaboratorState {
+    const TABLE: &'static str = "collaborator_states";
+
+    // there's one for each project a collaborator is in, without an ID of its
+    // own
+    fn id(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("{}:{}", self.project_id, self.user_id))
+    }
+
+    fn is_deleted(&self) -> bool {
+        self.is_deleted || self.state == "deleted"
+    }
+}
+
+pub struct Store {
+    connection: Connection,
+
+    /// The file that [`Store::lock`] locks, or `None` for stores in memory.
+    lock_path: Option<PathBuf>,
+
+    /// How many days completed items are kept for. See
+    /// [`Store::keep_completed_for`].
+    keep_completed_days: Option<u32>,
+}
+
+/// What [`Store::prune`] removed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Pruned {
+    /// Completed items.
+    pub items: usize,
+
+    /// Comments on items that aren't cached anymore.
+    pub notes: usize,
+
+    /// Reminders for items that aren't cached anymore.
+    pub reminders: usize,
+}
+
+/// Holds a store's lock until it's dropped. See [`Store::lock`].
+#[must_use]
+pub struct StoreLock {
+    _file: Option<fs::File>,
+}
+
+impl Store {
+    /// Opens the database at `path`, creating it and bringing its schema up to
+    /// date as needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be opened or migrated.
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let connection = Connection::open(path)?;
+        // the daemon may be syncing into the store while another command uses
+        // it; with a write-ahead log, reading doesn't have to wait for writing
+        connection.busy_timeout(Duration::from_secs(5))?;
+        connection
+            .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        let mut store = Store::migrate(connection)?;
+        store.lock_path = Some(path.with_extension("lock"));
+        Ok(store)
+    }
+
+    /// Takes the store's lock, waiting for whoever holds it to let go. It's
+    /// advisory, and only taken around changes that read and write the store
+    /// with requests to the server in between, like sending the queue, so
+    /// that two processes don't both send it, or write back different
+    /// versions of it. Stores in memory have no lock to take.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the lock file can't be opened or locked.
+    pub fn lock(&self) -> Result<StoreLock> {
+        let Some(path) = &self.lock_path else {
+            return Ok(StoreLock { _file: None });
+        };
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        file.lock()?;
+        Ok(StoreLock { _file: Some(file) })
+    }
+
+    /// Opens a fresh database that only lives as long as the returned store.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be created.
+    pub fn open_in_memory() -> Result<Self> {
+        Store::migrate(Connection::open_in_memory()?)
+    }
+
+    fn migrate(mut connection: Connection) -> Result<Self> {
+        let version: u32 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        let tx = connection.transaction()?;
+        for migration in MIGRATIONS.iter().skip(usize::try_from(version)?) {
+            tx.execute_batch(migration)?;
+        }
+        tx.pragma_update(None, "user_version", u32::try_from(MIGRATIONS.len())?)?;
+        tx.commit()?;
+
+        Ok(Store {
+            connection,
+            lock_path: None,
+            keep_completed_days: None,
+        })
+    }
+
+    /// Drops completed items once they've been completed for `days`, each
+    /// time a sync response is merged in, or keeps them for as long as
+    /// they're synced if `None`. They stay in Todoist either way.
+    #[must_use]
+    pub fn keep_completed_for(mut self, days: Option<u32>) -> Self {
+        self.keep_completed_days = days;
+        self
+    }
+
+    /// The token to send with the next sync request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn sync_token(&self) -> Result<String> {
+        let token = self
+            .connection
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = 'sync_token'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(token.unwrap_or(FULL_SYNC_TOKEN.to_string()))
+    }
+
+    /// When a sync response was last merged into the store, or `None` if one
+    /// never has been.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn last_synced_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let timestamp: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = 'last_synced_at'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(timestamp
+            .and_then(|timestamp| timestamp.parse().ok())
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0)))
+    }
+
+    /// Merges a sync response into the store. A full sync replaces each
+    /// returned resource type wholesale; an incremental one updates, adds, or
+    /// removes individual resources.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn apply(&mut self, response: &Response) -> Result<()> {
+        let tx = self.connection.transaction()?;
+
+        if let Some(items) = &response.items {
+            merge(&tx, items, response.full_sync)?;
+        }
+        if let Some(projects) = &response.projects {
+            merge(&tx, projects, response.full_sync)?;
+        }
+        if let Some(labels) = &response.labels {
+            merge(&tx, labels, response.full_sync)?;
+        }
+        if let Some(sections) = &response.sections {
+            merge(&tx, sections, response.full_sync)?;
+        }
+        if let Some(collaborators) = &response.collaborators {
+            merge(&tx, collaborators, response.full_sync)?;
+        }
+        if let Some(states) = &response.collaborator_states {
+            merge(&tx, states, response.full_sync)?;
+        }
+        if let Some(notes) = &response.notes {
+            merge(&tx, notes, response.full_sync)?;
+        }
+        if let Some(reminders) = &response.reminders {
+            merge(&tx, reminders, response.full_sync)?;
+        }
+        if let Some(filters) = &response.filters {
+            merge(&tx, filters, response.full_sync)?;
+        }
+        if let Some(workspaces) = &response.workspaces {
+            merge(&tx, workspaces, response.full_sync)?;
+        }
+        if let Some(notifications) = &response.live_notifications {
+            merge(&tx, notifications, response.full_sync)?;
+        }
+        finish_sync(&tx, &response.sync_token, self.keep_completed_days)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Merges a batch of resources from a sync response that's [read in
+    /// batches](crate::stream) into the store, without touching the sync
+    /// token. Resources that are gone from a full sync can only be told apart
+    /// once all of its batches are in, so with `full`, which resources were
+    /// in the batch is noted for [`Store::remove_unsynced`] instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn apply_batch(&mut self, batch: &Batch, full: bool) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        match batch {
+            Batch::Items(items) => merge(&tx, items, false)?,
+            Batch::Projects(projects) => merge(&tx, projects, false)?,
+            Batch::Labels(labels) => merge(&tx, labels, false)?,
+            Batch::Sections(sections) => merge(&tx, sections, false)?,
+            Batch::Collaborators(collaborators) => merge(&tx, collaborators, false)?,
+            Batch::CollaboratorStates(states) => merge(&tx, states, false)?,
+            Batch::Notes(notes) => merge(&tx, notes, false)?,
+            Batch::Reminders(reminders) => merge(&tx, reminders, false)?,
+            Batch::Filters(filters) => merge(&tx, filters, false)?,
+            Batch::Workspaces(workspaces) => merge(&tx, workspaces, false)?,
+            Batch::LiveNotifications(notifications) => merge(&tx, notifications, false)?,
+        }
+        if full {
+            tx.execute(CREATE_SYNCED, [])?;
+            let ids = match batch {
+                Batch::Items(items) => ids(items),
+                Batch::Projects(projects) => ids(projects),
+                Batch::Labels(labels) => ids(labels),
+                Batch::Sections(sections) => ids(sections),
+                Batch::Collaborators(collaborators) => ids(collaborators),
+                Batch::CollaboratorStates(states) => ids(states),
+                Batch::Notes(notes) => ids(notes),
+                Batch::Reminders(reminders) => ids(reminders),
+                Batch::Filters(filters) => ids(filters),
+                Batch::Workspaces(workspaces) => ids(workspaces),
+                Batch::LiveNotifications(notifications) => ids(notifications),
+            };
+            let mut insert =
+                tx.prepare("INSERT OR IGNORE INTO synced (kind, id) VALUES (?1, ?2)")?;
+            for id in ids {
+                insert.execute(params![batch.kind(), id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Finishes a full sync that was read in batches, removing the resources
+    /// of each of `kinds` that weren't in any of its batches. Either way,
+    /// forgets which resources were, ready for the next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn remove_unsynced(&mut self, kinds: &[&str]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute(CREATE_SYNCED, [])?;
+        for kind in kinds {
+            // only the resource tables, since the name goes into the query
+            let Some(table) = RESOURCE_TABLES.iter().find(|table| *table == kind) else {
+                continue;
+            };
+            tx.execute(
+                &format!(
+                    "DELETE FROM {table} WHERE id NOT IN (SELECT id FROM synced WHERE kind = ?1)"
+                ),
+                params![table],
+            )?;
+        }
+        tx.execute("DELETE FROM synced", [])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Finishes a sync that was read in batches, with the sync token to use
+    /// for the next one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn finish_sync(&mut self, sync_token: &str) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        finish_sync(&tx, sync_token, self.keep_completed_days)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// All cached items, including completed ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn items(&self) -> Result<Vec<Item>> {
+        self.query("SELECT data FROM items ORDER BY rowid", [])
+    }
+
+    /// Adds or replaces individual items, without touching the sync token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn put_items(&mut self, items: &[Item]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        merge(&tx, items, false)?;
+        stamp_completed(&tx)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Looks up a cached item by its ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn item(&self, id: &str) -> Result<Option<Item>> {
+        let mut items = self.query("SELECT data FROM items WHERE id = ?1", params![id])?;
+        Ok(items.pop())
+    }
+
+    /// The uncompleted items in a project.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn open_items_in_project(&self, project_id: &str) -> Result<Vec<Item>> {
+        self.query(
+            "SELECT data FROM items WHERE project_id = ?1 AND NOT checked ORDER BY rowid",
+            params![project_id],
+        )
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn projects(&self) -> Result<Vec<Project>> {
+        self.query("SELECT data FROM projects ORDER BY rowid", [])
+    }
+
+    /// Adds or replaces individual projects, without touching the sync token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn put_projects(&mut self, projects: &[Project]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        merge(&tx, projects, false)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn labels(&self) -> Result<Vec<Label>> {
+        self.query("SELECT data FROM labels ORDER BY rowid", [])
+    }
+
+    /// The user's saved filters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn filters(&self) -> Result<Vec<Filter>> {
+        self.query("SELECT data FROM filters ORDER BY rowid", [])
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn sections(&self) -> Result<Vec<Section>> {
+        self.query("SELECT data FROM sections ORDER BY rowid", [])
+    }
+
+    /// The active sections in a project, in the order the user arranged them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn sections_in_project(&self, project_id: &str) -> Result<Vec<Section>> {
+        let mut sections: Vec<Section> = self.query(
+            "SELECT data FROM sections WHERE project_id = ?1 ORDER BY rowid",
+            params![project_id],
+        )?;
+        sections.retain(|section| !section.is_archived);
+        sections.sort_by_key(|section| section.section_order);
+        Ok(sections)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn collaborators(&self) -> Result<Vec<Collaborator>> {
+        self.query("SELECT data FROM collaborators ORDER BY rowid", [])
+    }
+
+    /// What the user has been told about, newest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn live_notifications(&self) -> Result<Vec<LiveNotification>> {
+        self.query(
+            "SELECT data FROM live_notifications ORDER BY created_at DESC, rowid DESC",
+            [],
+        )
+    }
+
+    /// Adds or replaces individual notifications, without touching the sync
+    /// token.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn put_live_notifications(&mut self, notifications: &[LiveNotification]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        merge(&tx, notifications, false)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Who's in a shared project, or has been invited to it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn collaborator_states(&self, project_id: &str) -> Result<Vec<CollaboratorState>> {
+        self.query(
+            "SELECT data FROM collaborator_states WHERE project_id = ?1 ORDER BY rowid",
+            params![project_id],
+        )
+    }
+
+    /// The business account workspaces the user is in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn workspaces(&self) -> Result<Vec<Workspace>> {
+        self.query("SELECT data FROM workspaces ORDER BY rowid", [])
+    }
+
+    /// The comments on an item, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn notes_for_item(&self, item_id: &str) -> Result<Vec<Note>> {
+        self.query(
+            "SELECT data FROM notes WHERE item_id = ?1
+                ORDER BY json_extract(data, '$.posted_at'), rowid",
+            params![item_id],
+        )
+    }
+
+    /// All the reminders, on any item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn reminders(&self) -> Result<Vec<Reminder>> {
+        self.query("SELECT data FROM reminders ORDER BY rowid", [])
+    }
+
+    /// The reminders on an item.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn reminders_for_item(&self, item_id: &str) -> Result<Vec<Reminder>> {
+        self.query(
+            "SELECT data FROM reminders WHERE item_id = ?1 ORDER BY rowid",
+            params![item_id],
+        )
+    }
+
+    /// The number of comments on each item that has any, keyed by item ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn note_counts(&self) -> Result<HashMap<String, usize>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT item_id, COUNT(*) FROM notes GROUP BY item_id")?;
+        let counts = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    usize::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                ))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(counts)
+    }
+
+    /// Commands waiting to be sent, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn queued_commands(&self) -> Result<Vec<Command>> {
+        self.query("SELECT data FROM command_queue ORDER BY seq", [])
+    }
+
+    /// Replaces the queue of commands waiting to be sent. The saved items of
+    /// commands that are no longer queued are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn set_queued_commands(&mut self, commands: &[Command]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute("DELETE FROM command_queue", [])?;
+        for command in commands {
+            tx.execute(
+                "INSERT INTO command_queue (data) VALUES (?1)",
+                params![serde_json::to_string(command)?],
+            )?;
+        }
+        tx.execute(
+            "DELETE FROM conflict_bases
+                WHERE uuid NOT IN (SELECT json_extract(data, '$.uuid') FROM command_queue)",
+            [],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Saves an item as it was when a command that changes it was queued, to
+    /// check the command for [conflicts](crate::conflict) before it's sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn set_conflict_base(&mut self, uuid: &Uuid, item: &Item) -> Result<()> {
+        self.connection.execute(
+            "INSERT OR REPLACE INTO conflict_bases (uuid, data) VALUES (?1, ?2)",
+            params![uuid.to_string(), serde_json::to_string(item)?],
+        )?;
+        Ok(())
+    }
+
+    /// Forgets the item saved for a queued command, so that it's sent without
+    /// checking it for conflicts again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn forget_conflict_base(&mut self, uuid: &Uuid) -> Result<()> {
+        self.connection.execute(
+            "DELETE FROM conflict_bases WHERE uuid = ?1",
+            params![uuid.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// The items saved for queued commands, by the commands' UUIDs.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn conflict_bases(&self) -> Result<HashMap<Uuid, Item>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT uuid, data FROM conflict_bases")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+
+        let mut bases = HashMap::new();
+        for row in rows {
+            let (uuid, data) = row?;
+            // a malformed UUID can't match a command, so it's skipped
+            if let Ok(uuid) = Uuid::parse_str(&uuid) {
+                bases.insert(uuid, serde_json::from_str(&data)?);
+            }
+        }
+        Ok(bases)
+    }
+
+    /// Adds an entry to the undo journal, dropping the oldest ones so that it
+    /// holds at most [`undo::JOURNAL_LENGTH`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn push_undo_entry(&mut self, entry: &undo::Entry) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "INSERT INTO undo_journal (data) VALUES (?1)",
+            params![serde_json::to_string(entry)?],
+        )?;
+        tx.execute(
+            "DELETE FROM undo_journal WHERE seq NOT IN
+                (SELECT seq FROM undo_journal ORDER BY seq DESC LIMIT ?1)",
+            params![i64::try_from(undo::JOURNAL_LENGTH)?],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The latest entry in the undo journal, which is left there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn last_undo_entry(&self) -> Result<Option<undo::Entry>> {
+        let data: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT data FROM undo_journal ORDER BY seq DESC LIMIT 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(data) = data else {
+            return Ok(None);
+        };
+        Ok(Some(serde_json::from_str(&data)?))
+    }
+
+    /// Removes and returns the latest entry in the undo journal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read or written.
+    pub fn pop_undo_entry(&mut self) -> Result<Option<undo::Entry>> {
+        let tx = self.connection.transaction()?;
+        let entry: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT seq, data FROM undo_journal ORDER BY seq DESC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let Some((seq, data)) = entry else {
+            return Ok(None);
+        };
+        tx.execute("DELETE FROM undo_journal WHERE seq = ?1", params![seq])?;
+        tx.commit()?;
+        Ok(Some(serde_json::from_str(&data)?))
+    }
+
+    /// Puts a copy of an item in the trash, dropping the oldest ones so that
+    /// it holds at most [`trash::TRASH_LENGTH`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn put_in_trash(&mut self, item: &Item) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "INSERT OR REPLACE INTO trash (id, data, deleted_at) VALUES (?1, ?2, ?3)",
+            params![
+                item.id,
+                serde_json::to_string(item)?,
+                Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+            ],
+        )?;
+        tx.execute(
+            "DELETE FROM trash WHERE id NOT IN
+                (SELECT id FROM trash ORDER BY deleted_at DESC LIMIT ?1)",
+            params![i64::try_from(trash::TRASH_LENGTH)?],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The items in the trash, most recently deleted first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn trash(&self) -> Result<Vec<TrashedItem>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT data, deleted_at FROM trash ORDER BY deleted_at DESC, id")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut trashed = Vec::new();
+        for row in rows {
+            let (data, deleted_at) = row?;
+            trashed.push(TrashedItem {
+                item: serde_json::from_str(&data)?,
+                deleted_at,
+            });
+        }
+        Ok(trashed)
+    }
+
+    /// Removes an item from the trash, by the ID it had before it was deleted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn remove_from_trash(&mut self, id: &str) -> Result<()> {
+        self.connection
+            .execute("DELETE FROM trash WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+
+    /// Records a work session on an item, from a focus session or the timer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn record_work(&mut self, session: &WorkSession) -> Result<()> {
+        self.connection.execute(
+            "INSERT INTO work_sessions (item_id, content, project_id, started_at, seconds)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                session.item_id,
+                session.content,
+                session.project_id,
+                session.started_at,
+                i64::try_from(session.seconds)?
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// The work sessions that started at or after `since`, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn work_sessions(&self, since: DateTime<Utc>) -> Result<Vec<WorkSession>> {
+        let mut statement = self.connection.prepare(
+            "SELECT item_id, content, project_id, started_at, seconds FROM work_sessions
+                WHERE started_at >= ?1 ORDER BY started_at, id",
+        )?;
+        let since = since.to_rfc3339_opts(SecondsFormat::Secs, true);
+        let rows = statement.query_map(params![since], |row| {
+            Ok((
+                WorkSession {
+                    item_id: row.get(0)?,
+                    content: row.get(1)?,
+                    project_id: row.get(2)?,
+                    started_at: row.get(3)?,
+                    seconds: 0,
+                },
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+        let mut sessions = Vec::new();
+        for row in rows {
+            let (session, seconds) = row?;
+            sessions.push(WorkSession {
+                seconds: u64::try_from(seconds)?,
+                ..session
+            });
+        }
+        Ok(sessions)
+    }
+
+    /// The time spent working on each item, the most first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn work_totals(&self) -> Result<Vec<WorkTotal>> {
+        // SQLite takes `content` from the row with the largest `id`
+        let mut statement = self.connection.prepare(
+            "SELECT item_id, content, MAX(id), SUM(seconds), COUNT(*) FROM work_sessions
+                GROUP BY item_id ORDER BY SUM(seconds) DESC, item_id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+            ))
+        })?;
+        let mut totals = Vec::new();
+        for row in rows {
+            let (item_id, content, seconds, sessions) = row?;
+            totals.push(WorkTotal {
+                item_id,
+                content,
+                seconds: u64::try_from(seconds)?,
+                sessions: usize::try_from(sessions)?,
+            });
+        }
+        Ok(totals)
+    }
+
+    /// The timer, if it's running.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn timer(&self) -> Result<Option<Timer>> {
+        let timer: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = 'timer'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(timer
+            .map(|timer| serde_json::from_str(&timer))
+            .transpose()?)
+    }
+
+    /// Starts the timer, or stops it if `timer` is `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn set_timer(&mut self, timer: Option<&Timer>) -> Result<()> {
+        match timer {
+            Some(timer) => self.connection.execute(
+                "INSERT OR REPLACE INTO sync_state (key, value) VALUES ('timer', ?1)",
+                params![serde_json::to_string(timer)?],
+            )?,
+            None => self
+                .connection
+                .execute("DELETE FROM sync_state WHERE key = 'timer'", [])?,
+        };
+        Ok(())
+    }
+
+    /// Remembers the items shown by `list`, so that later commands can refer
+    /// to them by their (1-based) index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn set_last_list(&mut self, items: &[&Item]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute("DELETE FROM last_list", [])?;
+        for (position, item) in (1i64..).zip(items) {
+            tx.execute(
+                "INSERT INTO last_list (position, id, content) VALUES (?1, ?2, ?3)",
+                params![position, item.id, item.content],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Looks up an item shown by the last `list`, returning its ID and content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn last_list_entry(&self, index: usize) -> Result<Option<(String, String)>> {
+        let entry = self
+            .connection
+            .query_row(
+                "SELECT id, content FROM last_list WHERE position = ?1",
+                params![i64::try_from(index)?],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    /// Remembers the items shown by `completed`, so that `reopen` can refer to
+    /// them by their (1-based) index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn set_last_completed(&mut self, items: &[CompletedItem]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        tx.execute("DELETE FROM last_completed", [])?;
+        for (position, item) in (1i64..).zip(items) {
+            tx.execute(
+                "INSERT INTO last_completed (position, id, content) VALUES (?1, ?2, ?3)",
+                params![position, item.task_id, item.content],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Looks up an item shown by the last `completed`, returning its ID and
+    /// content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn last_completed_entry(&self, index: usize) -> Result<Option<(String, String)>> {
+        let entry = self
+            .connection
+            .query_row(
+                "SELECT id, content FROM last_completed WHERE position = ?1",
+                params![i64::try_from(index)?],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    /// Remembers the item that was just added, so that later commands can
+    /// refer to it as `last`. If the add is still queued, `id` is its temp ID,
+    /// which is swapped for the real one once the server assigns it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn set_last_added(&mut self, id: &str, content: &str) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        for (key, value) in [("last_added_id", id), ("last_added_content", content)] {
+            tx.execute(
+                "INSERT OR REPLACE INTO sync_state (key, value) VALUES (?1, ?2)",
+                params![key, value],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The ID and content of the most recently added item, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn last_added(&self) -> Result<Option<(String, String)>> {
+        let entry = self
+            .connection
+            .query_row(
+                "SELECT id.value, content.value FROM sync_state AS id, sync_state AS content
+                    WHERE id.key = 'last_added_id' AND content.key = 'last_added_content'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(entry)
+    }
+
+    /// Records that a notification was sent, returning `false` if it already
+    /// had been. Records older than a month are dropped along the way, since
+    /// the due dates they're for have long passed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn mark_notified(&mut self, key: &str) -> Result<bool> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "DELETE FROM sent_notifications WHERE sent_at < datetime('now', '-1 month')",
+            [],
+        )?;
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO sent_notifications (key) VALUES (?1)",
+            params![key],
+        )?;
+        tx.commit()?;
+        Ok(inserted > 0)
+    }
+
+    /// Records that the `pre-push` hook has handled the tasks a commit refers
+    /// to, returning `false` if it already had, like when the commit is pushed
+    /// to a second remote.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn mark_pushed(&mut self, sha: &str) -> Result<bool> {
+        let inserted = self.connection.execute(
+            "INSERT OR IGNORE INTO pushed_commits (sha) VALUES (?1)",
+            params![sha],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Has one task wait for another, returning `false` if it already did.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn add_dependency(&mut self, task_id: &str, blocker_id: &str) -> Result<bool> {
+        let inserted = self.connection.execute(
+            "INSERT OR IGNORE INTO dependencies (task_id, blocker_id) VALUES (?1, ?2)",
+            params![task_id, blocker_id],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Stops one task from waiting for another, returning `false` if it
+    /// wasn't.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn remove_dependency(&mut self, task_id: &str, blocker_id: &str) -> Result<bool> {
+        let deleted = self.connection.execute(
+            "DELETE FROM dependencies WHERE task_id = ?1 AND blocker_id = ?2",
+            params![task_id, blocker_id],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Which tasks wait for which others, in the order they were set up.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn dependencies(&self) -> Result<Vec<Dependency>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT task_id, blocker_id FROM dependencies ORDER BY rowid")?;
+        let dependencies = statement
+            .query_map([], |row| {
+                Ok(Dependency {
+                    task_id: row.get(0)?,
+                    blocker_id: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(dependencies)
+    }
+
+    /// Saves a template under `name`, replacing any with the same name,
+    /// ignoring case, and returning whether there was one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn save_template(&mut self, name: &str, template: &Template) -> Result<bool> {
+        let tx = self.connection.transaction()?;
+        let replaced = tx.execute("DELETE FROM templates WHERE name = ?1", params![name])?;
+        tx.execute(
+            "INSERT INTO templates (name, data) VALUES (?1, ?2)",
+            params![name, serde_json::to_string(template)?],
+        )?;
+        tx.commit()?;
+        Ok(replaced > 0)
+    }
+
+    /// The template saved under `name`, ignoring case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn template(&self, name: &str) -> Result<Option<Template>> {
+        Ok(self
+            .query("SELECT data FROM templates WHERE name = ?1", params![name])?
+            .pop())
+    }
+
+    /// The saved templates' names, along with the templates, in order of name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn templates(&self) -> Result<Vec<(String, Template)>> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT name, data FROM templates ORDER BY name")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut templates = Vec::new();
+        for row in rows {
+            let (name, data) = row?;
+            templates.push((name, serde_json::from_str(&data)?));
+        }
+        Ok(templates)
+    }
+
+    /// Deletes the template saved under `name`, ignoring case, returning
+    /// `false` if there wasn't one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn delete_template(&mut self, name: &str) -> Result<bool> {
+        let deleted = self
+            .connection
+            .execute("DELETE FROM templates WHERE name = ?1", params![name])?;
+        Ok(deleted > 0)
+    }
+
+    /// The `uuid` to send the command with `digest` with: the one it was sent
+    /// with before, if Todoist hasn't answered for it and it was sent within
+    /// the last [`idempotency::WINDOW`] seconds, and otherwise a new one, which
+    /// is kept until [`acknowledge_commands`](Store::acknowledge_commands).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read or written.
+    pub fn command_uuid(&mut self, digest: &str) -> Result<Uuid> {
+        let tx = self.connection.transaction()?;
+        tx.execute(
+            "DELETE FROM sent_commands WHERE sent_at < datetime('now', ?1)",
+            params![format!("-{} seconds", idempotency::WINDOW)],
+        )?;
+        let sent: Option<String> = tx
+            .query_row(
+                "SELECT uuid FROM sent_commands WHERE digest = ?1",
+                params![digest],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(uuid) = sent.and_then(|uuid| uuid.parse().ok()) {
+            return Ok(uuid);
+        }
+
+        let sequence: Option<String> = tx
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = 'command_sequence'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let sequence = sequence.and_then(|value| value.parse().ok()).unwrap_or(0) + 1;
+        let uuid = idempotency::uuid(digest, sequence);
+        tx.execute(
+            "INSERT OR REPLACE INTO sync_state (key, value) VALUES ('command_sequence', ?1)",
+            params![sequence.to_string()],
+        )?;
+        tx.execute(
+            "INSERT OR REPLACE INTO sent_commands (digest, uuid) VALUES (?1, ?2)",
+            params![digest, uuid.to_string()],
+        )?;
+        tx.commit()?;
+        Ok(uuid)
+    }
+
+    /// Forgets the `uuid`s of commands that Todoist has answered for, so that
+    /// commands like them get new ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn acknowledge_commands(&mut self, uuids: &[Uuid]) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        for uuid in uuids {
+            tx.execute(
+                "DELETE FROM sent_commands WHERE uuid = ?1",
+                params![uuid.to_string()],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Drops the items that were completed before `completed_before`, if it's
+    /// given, along with the comments and reminders for items that aren't
+    /// cached anymore and the saved copies of items for commands that aren't
+    /// queued anymore. Items that don't say when they were completed count as
+    /// completed when they were first synced that way.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn prune(&mut self, completed_before: Option<DateTime<Utc>>) -> Result<Pruned> {
+        let tx = self.connection.transaction()?;
+        let pruned = prune(&tx, completed_before)?;
+        tx.commit()?;
+        Ok(pruned)
+    }
+
+    /// Compacts the database, giving the space that removed data took up back
+    /// to the file system.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is in use or cannot be written.
+    pub fn vacuum(&mut self) -> Result<()> {
+        self.connection
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE); VACUUM;")?;
+        Ok(())
+    }
+
+    /// How many bytes the database takes up, not counting its write-ahead log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be read.
+    pub fn size(&self) -> Result<u64> {
+        let pages: i64 = self
+            .connection
+            .pragma_query_value(None, "page_count", |row| row.get(0))?;
+        let page_size: i64 = self
+            .connection
+            .pragma_query_value(None, "page_size", |row| row.get(0))?;
+        Ok(u64::try_from(pages * page_size)?)
+    }
+
+    /// Replaces any stored temp IDs with the real IDs from a sync response's
+    /// `temp_id_mapping`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be written.
+    pub fn resolve_temp_ids(&mut self, temp_id_mapping: &HashMap<Uuid, String>) -> Result<()> {
+        for (temp_id, id) in temp_id_mapping {
+            self.connection.execute(
+                "UPDATE sync_state SET value = ?2 WHERE key = 'last_added_id' AND value = ?1",
+                params![temp_id.to_string(), id],
+            )?;
+            for column in ["task_id", "blocker_id"] {
+                self.connection.execute(
+                    &format!("UPDATE OR IGNORE dependencies SET {column} = ?2 WHERE {column} = ?1"),
+                    params![temp_id.to_string(), id],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn query<T: DeserializeOwned>(
+        &self,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<T>> {
+        let mut statement = self.connection.prepare(sql)?;
+        let mut rows = statement.query(params)?;
+
+        let mut resources = Vec::new();
+        while let Some(row) = rows.next()? {
+            // parsed straight from SQLite's copy, without making one first
+            let data = row.get_ref(0)?.as_str().map_err(rusqlite::Error::from)?;
+            resources.push(serde_json::from_str(data)?);
+        }
+        Ok(resources)
+    }
+}
+
+/// What's left of a sync once its resources are merged: stamping newly
+/// completed items, pruning old ones, and keeping the sync token.
+fn finish_sync(tx: &Transaction, sync_token: &str, keep_completed_days: Option<u32>) -> Result<()> {
+    stamp_completed(tx)?;
+    if let Some(days) = keep_completed_days {
+        prune(tx, Some(Utc::now() - TimeDelta::days(days.into())))?;
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO sync_state (key, value) VALUES ('sync_token', ?1)",
+        params![sync_token],
+    )?;
+    tx.execute(
+        "INSERT OR REPLACE INTO sync_state (key, value) VALUES ('last_synced_at', ?1)",
+        params![Utc::now().timestamp().to_string()],
+    )?;
+    Ok(())
+}
+
+fn ids<T: Resource>(resources: &[T]) -> Vec<Cow<'_, str>> {
+    resources.iter().map(Resource::id).collect()
+}
+
+/// Notes when items were first seen completed, and forgets it for ones that
+/// aren't anymore.
+fn stamp_completed(tx: &Transaction) -> Result<()> {
+    tx.execute_batch(
+        "UPDATE items SET checked_at = datetime('now') WHERE checked AND checked_at IS NULL;
+        UPDATE items SET checked_at = NULL WHERE NOT checked AND checked_at IS NOT NULL;",
+    )?;
+    Ok(())
+}
+
+/// See [`Store::prune`].
+fn prune(tx: &Transaction, completed_before: Option<DateTime<Utc>>) -> Result<Pruned> {
+    let mut pruned = Pruned::default();
+    if let Some(before) = completed_before {
+        pruned.items = tx.execute(
+            "DELETE FROM items WHERE checked
+                AND datetime(coalesce(json_extract(data, '$.completed_at'), checked_at))
+                    < datetime(?1)",
+            params![before.to_rfc3339_opts(SecondsFormat::Secs, true)],
+        )?;
+    }
+    pruned.notes = tx.execute(
+        "DELETE FROM notes WHERE item_id NOT IN (SELECT id FROM items)",
+        [],
+    )?;
+    pruned.reminders = tx.execute(
+        "DELETE FROM reminders WHERE item_id NOT IN (SELECT id FROM items)",
+        [],
+    )?;
+    tx.execute(
+        "DELETE FROM conflict_bases
+            WHERE uuid NOT IN (SELECT json_extract(data, '$.uuid') FROM command_queue)",
+        [],
+    )?;
+    Ok(pruned)
+}
+
+fn merge<T: Resource>(tx: &Transaction, updates: &[T], full_sync: bool) -> Result<()> {
+    let table = T::TABLE;
+    if full_sync {
+        tx.execute(&format!("DELETE FROM {table}"), [])?;
+    }
+
+    // prepared once for all of them, which big syncs spend most of their
+    // time on otherwise
+    let mut delete = tx.prepare_cached(&format!("DELETE FROM {table} WHERE id = ?1"))?;
+    let mut upsert = tx.prepare_cached(&format!(
+        "INSERT INTO {table} (id, data) VALUES (?1, ?2)
+            ON CONFLICT (id) DO UPDATE SET data = excluded.data"
+    ))?;
+    for update in updates {
+        if update.is_deleted() {
+            delete.execute(params![update.id()])?;
+        } else {
+            upsert.execute(params![update.id(), serde_json::to_string(update)?])?;
+        }
+    }
+    Ok(())
+}
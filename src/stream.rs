@@ -0,0 +1,328 @@
+//! Reading a sync response as it comes in, for accounts so big that holding the
+//! whole response in memory, and everything parsed from it, would be too much.
+//! The resources in it are handed over in batches, to be written to the
+//! [store](crate::storage::Store) and dropped before the next batch is parsed;
+//! the rest of the response, like its sync token and command statuses, is
+//! parsed as usual.
+//!
+//! The response's body is read on the async runtime and parsed on a blocking
+//! thread, with bounded channels between them, so that a slow store holds up
+//! the download rather than letting parsed batches pile up.
+
+use std::io::{self, BufRead, BufReader, Read};
+
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess,
+};
+use serde_json::{Map, Value};
+use tokio::sync::mpsc;
+
+use crate::{
+    sync::{
+        Collaborator, CollaboratorState, Filter, Item, Label, LiveNotification, Note, Project,
+        Reminder, Response, Section, Workspace,
+    },
+    Result,
+};
+
+/// How many resources of a kind are parsed before they're handed over.
+pub const BATCH_SIZE: usize = 500;
+
+/// How many chunks of the body can be downloaded ahead of the parser.
+const CHUNKS_AHEAD: usize = 16;
+
+/// Some of the resources of one kind from a sync response.
+#[derive(Debug)]
+pub enum Batch {
+    Items(Vec<Item>),
+    Projects(Vec<Project>),
+    Labels(Vec<Label>),
+    Sections(Vec<Section>),
+    Collaborators(Vec<Collaborator>),
+    CollaboratorStates(Vec<CollaboratorState>),
+    Notes(Vec<Note>),
+    Reminders(Vec<Reminder>),
+    Filters(Vec<Filter>),
+    Workspaces(Vec<Workspace>),
+    LiveNotifications(Vec<LiveNotification>),
+}
+
+impl Batch {
+    /// The resource type the batch is of, as it's named in sync requests.
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Batch::Items(_) => "items",
+            Batch::Projects(_) => "projects",
+            Batch::Labels(_) => "labels",
+            Batch::Sections(_) => "sections",
+            Batch::Collaborators(_) => "collaborators",
+            Batch::CollaboratorStates(_) => "collaborator_states",
+            Batch::Notes(_) => "notes",
+            Batch::Reminders(_) => "reminders",
+            Batch::Filters(_) => "filters",
+            Batch::Workspaces(_) => "workspaces",
+            Batch::LiveNotifications(_) => "live_notifications",
+        }
+    }
+
+    /// How many resources are in the batch.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        match self {
+            Batch::Items(items) => items.len(),
+            Batch::Projects(projects) => projects.len(),
+            Batch::Labels(labels) => labels.len(),
+            Batch::Sections(sections) => sections.len(),
+            Batch::Collaborators(collaborators) => collaborators.len(),
+            Batch::CollaboratorStates(states) => states.len(),
+            Batch::Notes(notes) => notes.len(),
+            Batch::Reminders(reminders) => reminders.len(),
+            Batch::Filters(filters) => filters.len(),
+            Batch::Workspaces(workspaces) => workspaces.len(),
+            Batch::LiveNotifications(notifications) => notifications.len(),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Parses a sync response from `reader`, handing its resources to `on_batch`
+/// `size` at a time instead of keeping them in the response. Each list of
+/// resources that's in the response is handed over in at least one batch,
+/// even if it's empty, so that a full sync can tell it was there. Parsing
+/// stops if `on_batch` returns `false`.
+///
+/// # Errors
+///
+/// Returns [`Error::Serialization`](crate::Error::Serialization) if the
+/// response can't be parsed, or parsing was stopped.
+pub fn parse(
+    reader: impl BufRead,
+    size: usize,
+    mut on_batch: impl FnMut(Batch) -> bool,
+) -> Result<Response> {
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let response = ResponseSeed {
+        size: size.max(1),
+        on_batch: &mut on_batch,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    Ok(response)
+}
+
+/// Reads the sync response in `response`'s body as it's downloaded, handing
+/// its resources to `on_batch` as described for [`parse`].
+///
+/// # Errors
+///
+/// Returns [`Error::Network`](crate::Error::Network) if the body can't be
+/// downloaded, [`Error::Serialization`](crate::Error::Serialization) if it
+/// can't be parsed, and whatever `on_batch` returns, which stops the download.
+pub async fn read(
+    mut response: reqwest::Response,
+    size: usize,
+    mut on_batch: impl FnMut(Batch) -> Result<()>,
+) -> Result<Response> {
+    let (chunk_sender, chunk_receiver) = mpsc::channel(CHUNKS_AHEAD);
+    let download = tokio::spawn(async move {
+        while let Some(chunk) = response.chunk().await? {
+            if chunk_sender.send(chunk).await.is_err() {
+                break;
+            }
+        }
+        Ok::<_, reqwest::Error>(())
+    });
+
+    let (batch_sender, mut batch_receiver) = mpsc::channel(1);
+    let parser = tokio::task::spawn_blocking(move || {
+        let body = BufReader::new(Chunks {
+            receiver: chunk_receiver,
+            chunk: None,
+            at: 0,
+        });
+        parse(body, size, |batch| {
+            batch_sender.blocking_send(batch).is_ok()
+        })
+    });
+
+    while let Some(batch) = batch_receiver.recv().await {
+        if let Err(err) = on_batch(batch) {
+            // without anywhere to send batches or chunks, the parser stops too
+            download.abort();
+            return Err(err);
+        }
+    }
+    let parsed = parser.await.map_err(io::Error::other)?;
+    download.await.map_err(io::Error::other)??;
+    parsed
+}
+
+/// The chunks of a body as they're downloaded, read one after another.
+struct Chunks<B> {
+    receiver: mpsc::Receiver<B>,
+    chunk: Option<B>,
+    at: usize,
+}
+
+impl<B: AsRef<[u8]>> Read for Chunks<B> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if let Some(chunk) = &self.chunk {
+                let rest = &chunk.as_ref()[self.at..];
+                if !rest.is_empty() {
+                    let length = rest.len().min(buf.len());
+                    buf[..length].copy_from_slice(&rest[..length]);
+                    self.at += length;
+                    return Ok(length);
+                }
+            }
+            // the download stopping early shows up as the body ending early
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = Some(chunk);
+                    self.at = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Deserializes a response, handing over its resources instead of keeping
+/// them.
+struct ResponseSeed<'a, F> {
+    size: usize,
+    on_batch: &'a mut F,
+}
+
+impl<'de, F: FnMut(Batch) -> bool> DeserializeSeed<'de> for ResponseSeed<'_, F> {
+    type Value = Response;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<Response, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, F: FnMut(Batch) -> bool> de::Visitor<'de> for ResponseSeed<'_, F> {
+    type Value = Response;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a sync response")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<Response, A::Error> {
+        let (size, on_batch) = (self.size, self.on_batch);
+        let mut rest = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "items" => map.next_value_seed(Batches::new(size, on_batch, Batch::Items))?,
+                "projects" => map.next_value_seed(Batches::new(size, on_batch, Batch::Projects))?,
+                "labels" => map.next_value_seed(Batches::new(size, on_batch, Batch::Labels))?,
+                "sections" => map.next_value_seed(Batches::new(size, on_batch, Batch::Sections))?,
+                "collaborators" => {
+                    map.next_value_seed(Batches::new(size, on_batch, Batch::Collaborators))?;
+                }
+                "collaborator_states" => {
+                    map.next_value_seed(Batches::new(size, on_batch, Batch::CollaboratorStates))?;
+                }
+                "notes" => map.next_value_seed(Batches::new(size, on_batch, Batch::Notes))?,
+                "reminders" => {
+                    map.next_value_seed(Batches::new(size, on_batch, Batch::Reminders))?;
+                }
+                "filters" => map.next_value_seed(Batches::new(size, on_batch, Batch::Filters))?,
+                "workspaces" => {
+                    map.next_value_seed(Batches::new(size, on_batch, Batch::Workspaces))?;
+                }
+                "live_notifications" => {
+                    map.next_value_seed(Batches::new(size, on_batch, Batch::LiveNotifications))?;
+                }
+                // the rest of what the response keeps
+                "full_sync" | "sync_status" | "sync_token" | "temp_id_mapping" | "user" => {
+                    rest.insert(key, map.next_value()?);
+                }
+                // like `day_orders`, which would only be thrown away later
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        serde_json::from_value(Value::Object(rest)).map_err(de::Error::custom)
+    }
+}
+
+/// Deserializes a list of resources, handing them over in batches.
+struct Batches<'a, T, F> {
+    size: usize,
+    on_batch: &'a mut F,
+    make: fn(Vec<T>) -> Batch,
+}
+
+impl<'a, T, F> Batches<'a, T, F> {
+    fn new(size: usize, on_batch: &'a mut F, make: fn(Vec<T>) -> Batch) -> Self {
+        Batches {
+            size,
+            on_batch,
+            make,
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(Batch) -> bool> DeserializeSeed<'de> for Batches<'_, T, F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, T: DeserializeOwned, F: FnMut(Batch) -> bool> de::Visitor<'de> for Batches<'_, T, F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a list of resources")
+    }
+
+    /// A `null` list, which is the same as it being left out.
+    fn visit_unit<E: de::Error>(self) -> std::result::Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        let mut batch = Vec::with_capacity(self.size);
+        let mut handed_over = false;
+        while let Some(resource) = seq.next_element()? {
+            batch.push(resource);
+            if batch.len() == self.size {
+                let full = std::mem::replace(&mut batch, Vec::with_capacity(self.size));
+                hand_over(self.on_batch, (self.make)(full))?;
+                handed_over = true;
+            }
+        }
+        if !batch.is_empty() || !handed_over {
+            hand_over(self.on_batch, (self.make)(batch))?;
+        }
+        Ok(())
+    }
+}
+
+fn hand_over<E: de::Error>(
+    on_batch: &mut impl FnMut(Batch) -> bool,
+    batch: Batch,
+) -> std::result::Result<(), E> {
+    if on_batch(batch) {
+        Ok(())
+    } else {
+        Err(E::custom("stopped reading the response"))
+    }
+}
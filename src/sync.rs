@@ -1,76 +1,1006 @@
-use std::collections::HashMap;
+//! The typed models for sync requests, responses, and commands. They only have
+//! the fields that are used: the rest of what Todoist sends, like the `v2_`
+//! IDs that are only there for older clients, is skipped over while a
+//! response is parsed instead of being kept. `cargo bench` times how long
+//! parsing a big account's full sync takes.
 
-use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::Result;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Response {
     pub full_sync: bool,
 
-    // TODO: make value type more specific?
-    pub sync_status: Option<HashMap<String, String>>,
+    pub sync_status: Option<HashMap<Uuid, CommandStatus>>,
 
     pub sync_token: String,
     pub temp_id_mapping: HashMap<Uuid, String>,
     pub user: Option<User>,
+    pub projects: Option<Vec<Project>>,
+    pub items: Option<Vec<Item>>,
+    pub labels: Option<Vec<Label>>,
+    pub sections: Option<Vec<Section>>,
+    pub collaborators: Option<Vec<Collaborator>>,
+    pub collaborator_states: Option<Vec<CollaboratorState>>,
+    pub notes: Option<Vec<Note>>,
+    pub reminders: Option<Vec<Reminder>>,
+    pub filters: Option<Vec<Filter>>,
+    pub workspaces: Option<Vec<Workspace>>,
+    pub live_notifications: Option<Vec<LiveNotification>>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Response {
+    /// The server's verdict on a command that was sent, if it gave one.
+    #[must_use]
+    pub fn status(&self, uuid: &Uuid) -> Option<&CommandStatus> {
+        self.sync_status.as_ref()?.get(uuid)
+    }
+}
+
+/// The server's verdict on a single command, keyed by the command's `uuid` in
+/// the response's `sync_status`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CommandStatus {
+    Ok(String),
+    Error { error_code: i64, error: String },
+}
+
+impl CommandStatus {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        matches!(self, CommandStatus::Ok(status) if status == "ok")
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct User {
+    /// Missing from user data saved by older versions.
+    #[serde(default)]
+    pub id: String,
+
     pub full_name: String,
     pub inbox_project_id: String,
+
+    #[serde(default)]
+    pub email: String,
+
+    /// The language the user reads Todoist in, like `de`.
+    #[serde(default)]
+    pub lang: Option<String>,
+
+    /// Where the user is, for showing times in their timezone. Missing from
+    /// user data saved by older versions.
+    #[serde(default)]
+    pub tz_info: Option<TzInfo>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct AddItemRequest {
-    pub sync_token: String,
-    pub resource_types: Vec<String>,
-    pub commands: Vec<AddItemCommand>,
+/// The timezone that a user's account is set to.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TzInfo {
+    /// The timezone's name, like `Europe/Berlin`.
+    pub timezone: String,
+
+    /// The timezone's current offset from UTC, like `+02:00`.
+    pub gmt_string: String,
 }
 
-// they're the same, fine for now
 #[derive(Debug, Serialize, Deserialize)]
-pub struct GetUserRequest {
+pub struct Request {
     pub sync_token: String,
     pub resource_types: Vec<String>,
-    pub commands: Vec<AddItemCommand>,
+    pub commands: Vec<Command>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AddItemCommand {
-    #[serde(rename = "type")]
-    pub request_type: String,
+pub struct Command {
+    #[serde(flatten)]
+    pub args: CommandArgs,
 
-    pub temp_id: Uuid,
     pub uuid: Uuid,
-    pub args: AddItemRequestArgs,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temp_id: Option<Uuid>,
+}
+
+impl Command {
+    /// Replaces temp IDs that the command refers to (e.g. as an item's project)
+    /// with the real IDs that a sync response mapped them to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command can't be converted to and from JSON.
+    pub fn resolve_temp_ids(&mut self, temp_id_mapping: &HashMap<Uuid, String>) -> Result<()> {
+        fn resolve(value: &mut serde_json::Value, mapping: &HashMap<String, &String>) {
+            match value {
+                serde_json::Value::String(text) => {
+                    if let Some(id) = mapping.get(text.as_str()) {
+                        text.clone_from(id);
+                    }
+                }
+                serde_json::Value::Array(values) => {
+                    for value in values {
+                        resolve(value, mapping);
+                    }
+                }
+                serde_json::Value::Object(fields) => {
+                    for value in fields.values_mut() {
+                        resolve(value, mapping);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if temp_id_mapping.is_empty() {
+            return Ok(());
+        }
+        let mapping = temp_id_mapping
+            .iter()
+            .map(|(temp_id, id)| (temp_id.to_string(), id))
+            .collect();
+        let mut args = serde_json::to_value(&self.args)?;
+        resolve(&mut args, &mapping);
+        self.args = serde_json::from_value(args)?;
+        Ok(())
+    }
+
+    /// Creates a command with a fresh `uuid`. Commands that create a new
+    /// resource also get a `temp_id`, so that the real ID can be looked up in
+    /// the response.
+    #[must_use]
+    pub fn new(args: CommandArgs) -> Self {
+        let temp_id = match args {
+            CommandArgs::ItemAdd(_)
+            | CommandArgs::ProjectAdd(_)
+            | CommandArgs::SectionAdd(_)
+            | CommandArgs::LabelAdd(_)
+            | CommandArgs::NoteAdd(_)
+            | CommandArgs::ReminderAdd(_) => Some(Uuid::new_v4()),
+            CommandArgs::ItemClose(_)
+            | CommandArgs::ItemComplete(_)
+            | CommandArgs::ItemUpdateDateComplete(_)
+            | CommandArgs::ItemUpdate(_)
+            | CommandArgs::ItemDelete(_)
+            | CommandArgs::ItemUncomplete(_)
+            | CommandArgs::ItemMove(_)
+            | CommandArgs::ItemReorder(_)
+            | CommandArgs::ProjectUpdate(_)
+            | CommandArgs::ProjectArchive(_)
+            | CommandArgs::ProjectUnarchive(_)
+            | CommandArgs::ProjectDelete(_)
+            | CommandArgs::ProjectReorder(_)
+            | CommandArgs::ShareProject(_)
+            | CommandArgs::LabelUpdate(_)
+            | CommandArgs::LabelDelete(_)
+            | CommandArgs::ReminderDelete(_)
+            | CommandArgs::LiveNotificationsMarkRead(_) => None,
+        };
+
+        Command {
+            args,
+            uuid: Uuid::new_v4(),
+            temp_id,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct AddItemRequestArgs {
+#[serde(tag = "type", content = "args", rename_all = "snake_case")]
+pub enum CommandArgs {
+    ItemAdd(ItemAddArgs),
+    ItemClose(ItemCloseArgs),
+
+    /// Completes an item for good, even if it's recurring.
+    ItemComplete(ItemIdArgs),
+
+    /// Completes the current occurrence of a recurring item, moving it to the
+    /// next one.
+    ItemUpdateDateComplete(ItemIdArgs),
+
+    ItemUpdate(ItemUpdateArgs),
+    ItemDelete(ItemIdArgs),
+
+    /// Reopens a completed item.
+    ItemUncomplete(ItemIdArgs),
+
+    ItemMove(ItemMoveArgs),
+    ItemReorder(ItemReorderArgs),
+
+    ProjectAdd(ProjectAddArgs),
+    ProjectUpdate(ProjectUpdateArgs),
+    ProjectArchive(ProjectIdArgs),
+    ProjectUnarchive(ProjectIdArgs),
+    ProjectDelete(ProjectIdArgs),
+    ProjectReorder(ProjectReorderArgs),
+
+    /// Invites someone to a project by their email address.
+    ShareProject(ShareProjectArgs),
+
+    SectionAdd(SectionAddArgs),
+    LabelAdd(LabelAddArgs),
+    LabelUpdate(LabelUpdateArgs),
+    LabelDelete(LabelIdArgs),
+    NoteAdd(NoteAddArgs),
+    ReminderAdd(ReminderAddArgs),
+    ReminderDelete(ReminderIdArgs),
+    LiveNotificationsMarkRead(LiveNotificationIdsArgs),
+}
+
+impl CommandArgs {
+    /// A short, human-readable summary of what the command does.
+    #[must_use]
+    pub fn description(&self) -> String {
+        match self {
+            CommandArgs::ItemAdd(args) => format!("add '{}'", args.content),
+            CommandArgs::ItemClose(args) => format!("complete task {}", args.id),
+            CommandArgs::ItemComplete(args) => {
+                format!("complete all occurrences of task {}", args.id)
+            }
+            CommandArgs::ItemUpdateDateComplete(args) => {
+                format!("complete an occurrence of task {}", args.id)
+            }
+            CommandArgs::ItemUpdate(args) => format!("update task {}", args.id),
+            CommandArgs::ItemDelete(args) => format!("delete task {}", args.id),
+            CommandArgs::ItemUncomplete(args) => format!("reopen task {}", args.id),
+            CommandArgs::ItemMove(args) => format!("move task {}", args.id),
+            CommandArgs::ItemReorder(_) => "reorder tasks".to_string(),
+            CommandArgs::ProjectAdd(args) => format!("add project '{}'", args.name),
+            CommandArgs::ProjectUpdate(args) => format!("update project {}", args.id),
+            CommandArgs::ProjectArchive(args) => format!("archive project {}", args.id),
+            CommandArgs::ProjectUnarchive(args) => format!("unarchive project {}", args.id),
+            CommandArgs::ProjectDelete(args) => format!("delete project {}", args.id),
+            CommandArgs::ProjectReorder(_) => "reorder projects".to_string(),
+            CommandArgs::ShareProject(args) => {
+                format!("share project {} with {}", args.project_id, args.email)
+            }
+            CommandArgs::SectionAdd(args) => format!("add section '{}'", args.name),
+            CommandArgs::LabelAdd(args) => format!("add label '{}'", args.name),
+            CommandArgs::LabelUpdate(args) => format!("update label {}", args.id),
+            CommandArgs::LabelDelete(args) => format!("delete label {}", args.id),
+            CommandArgs::NoteAdd(args) => format!("comment on task {}", args.item_id),
+            CommandArgs::ReminderAdd(args) => format!("add a reminder to task {}", args.item_id),
+            CommandArgs::ReminderDelete(args) => format!("delete reminder {}", args.id),
+            CommandArgs::LiveNotificationsMarkRead(args) => {
+                format!("mark {} notifications as read", args.ids.len())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ItemAddArgs {
     pub project_id: String,
     pub content: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DueArgs>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<Deadline>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<TaskDuration>,
+
+    /// In the API's numbering, where `4` is the most urgent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub responsible_uid: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+
+    /// Makes the new item a subtask of this one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemCloseArgs {
+    pub id: String,
+}
+
+/// For commands that only need to know which item to act on.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ItemIdArgs {
+    pub id: String,
+}
+
+/// Exactly one of the destinations should be set: a project (outside of any
+/// section), a section, or a parent item.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ItemMoveArgs {
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub section_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+/// New positions for items that share a parent.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ItemReorderArgs {
+    pub items: Vec<ChildOrder>,
+}
+
+/// New positions for projects that share a parent.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectReorderArgs {
+    pub projects: Vec<ChildOrder>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChildOrder {
+    pub id: String,
+    pub child_order: i64,
+}
+
+/// Only the fields that are set are changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ItemUpdateArgs {
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DueArgs>,
+
+    /// The item's new duration, or `Some(None)` to remove it.
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration: Option<Option<TaskDuration>>,
+
+    /// In the API's numbering, where `4` is the most urgent.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u8>,
+
+    /// Replaces all of the item's labels.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub labels: Option<Vec<String>>,
+
+    /// The collaborator to assign the item to, or `Some(None)` to unassign it.
+    #[serde(
+        default,
+        deserialize_with = "double_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub responsible_uid: Option<Option<String>>,
+}
+
+/// Tells a field that's missing (`None`) apart from one that's `null`
+/// (`Some(None)`), which serde would otherwise treat the same.
+#[allow(clippy::option_option)]
+fn double_option<'de, D, T>(deserializer: D) -> std::result::Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectAddArgs {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+}
+
+/// Only the fields that are set are changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProjectUpdateArgs {
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
 }
 
+/// For commands that only need to know which project to act on.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct ProjectDataRequest {
+pub struct ProjectIdArgs {
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShareProjectArgs {
     pub project_id: String,
+    pub email: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SectionAddArgs {
+    pub name: String,
+    pub project_id: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LabelAddArgs {
+    pub name: String,
 }
 
+/// Only the fields that are set are changed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LabelUpdateArgs {
+    pub id: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// For commands that only need to know which label to act on.
 #[derive(Debug, Serialize, Deserialize)]
-pub struct ProjectDataResponse {
-    pub project: Project,
-    pub items: Vec<Item>,
+pub struct LabelIdArgs {
+    pub id: String,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
+pub struct LiveNotificationIdsArgs {
+    pub ids: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NoteAddArgs {
+    pub item_id: String,
+    pub content: String,
+}
+
+/// Relative reminders need a `minute_offset`; absolute ones need a `due`; and
+/// location ones need a place's `name`, coordinates, and `loc_trigger`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderAddArgs {
+    pub item_id: String,
+
+    #[serde(rename = "type")]
+    pub kind: ReminderKind,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due: Option<DueArgs>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minute_offset: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc_lat: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc_long: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub loc_trigger: Option<LocationTrigger>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radius: Option<u32>,
+}
+
+/// For commands that only need to know which reminder to act on.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReminderIdArgs {
+    pub id: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DueArgs {
+    /// A natural-language date, like "tomorrow at 5pm" or "every monday".
+    pub string: String,
+
+    /// The language `string` is in, if it isn't English.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Project {
     pub id: String,
     pub name: String,
+
+    /// One of Todoist's named colors, like `berry_red`.
+    #[serde(default)]
+    pub color: String,
+
+    pub parent_id: Option<String>,
+
+    /// The project's position among its siblings.
+    #[serde(default)]
+    pub child_order: i64,
+
+    #[serde(default)]
+    pub is_favorite: bool,
+
+    #[serde(default)]
+    pub is_archived: bool,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+
+    /// The [workspace](Workspace) the project belongs to, for business
+    /// accounts. Personal projects aren't in one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Item {
     pub id: String,
     pub content: String,
+
+    /// Longer notes about the item, below its content.
+    #[serde(default)]
+    pub description: String,
+
+    #[serde(default)]
+    pub project_id: String,
+
+    pub section_id: Option<String>,
+
+    /// The item this one is a subtask of.
+    pub parent_id: Option<String>,
+
+    /// The item's position among its siblings.
+    #[serde(default)]
+    pub child_order: i64,
+
+    pub due: Option<Due>,
+
+    /// The date the item has to be done by, which, unlike its due date, it
+    /// isn't scheduled for.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deadline: Option<Deadline>,
+
+    /// How long the item is expected to take.
+    #[serde(default)]
+    pub duration: Option<TaskDuration>,
+
+    /// The names of the item's labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
+
+    /// Note that the API's priorities are inverted relative to the apps: `4` is
+    /// "p1" (the most urgent) and `1` is "p4".
+    #[serde(default = "Item::default_priority")]
+    pub priority: u8,
+
+    /// The collaborator the item is assigned to, in shared projects.
+    #[serde(default)]
+    pub responsible_uid: Option<String>,
+
+    #[serde(default)]
+    pub checked: bool,
+
+    /// When the item was completed, as an RFC 3339 timestamp in UTC. Only
+    /// archived items come with this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<String>,
+
+    /// When the item was added, as an RFC 3339 timestamp in UTC.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub added_at: Option<String>,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+impl Item {
+    fn default_priority() -> u8 {
+        1
+    }
+}
+
+impl Default for Item {
+    fn default() -> Self {
+        Item {
+            id: String::default(),
+            content: String::default(),
+            description: String::default(),
+            project_id: String::default(),
+            section_id: None,
+            parent_id: None,
+            child_order: 0,
+            due: None,
+            deadline: None,
+            duration: None,
+            labels: Vec::new(),
+            priority: Item::default_priority(),
+            responsible_uid: None,
+            checked: false,
+            completed_at: None,
+            added_at: None,
+            is_deleted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Due {
+    pub date: String,
+    pub string: Option<String>,
+
+    #[serde(default)]
+    pub is_recurring: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Deadline {
+    /// Like `2024-05-01`.
+    pub date: String,
+}
+
+/// How long a task is expected to take, for blocking out time for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskDuration {
+    pub amount: u32,
+    pub unit: DurationUnit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DurationUnit {
+    Minute,
+    Day,
+}
+
+impl fmt::Display for TaskDuration {
+    /// Formats the duration compactly, like `45m`, `1h 30m`, or `2d`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let amount = self.amount;
+        match self.unit {
+            DurationUnit::Day => write!(f, "{amount}d"),
+            DurationUnit::Minute if amount < 60 => write!(f, "{amount}m"),
+            DurationUnit::Minute if amount.is_multiple_of(60) => write!(f, "{}h", amount / 60),
+            DurationUnit::Minute => write!(f, "{}h {}m", amount / 60, amount % 60),
+        }
+    }
+}
+
+/// A named group of tasks within a project.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Section {
+    pub id: String,
+    pub name: String,
+    pub project_id: String,
+
+    /// The section's position within its project.
+    #[serde(default)]
+    pub section_order: i64,
+
+    #[serde(default)]
+    pub is_archived: bool,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Label {
+    pub id: String,
+    pub name: String,
+
+    /// One of Todoist's named colors, like `berry_red`.
+    #[serde(default)]
+    pub color: String,
+
+    /// The label's position in the user's list of labels.
+    #[serde(default)]
+    pub item_order: i64,
+
+    #[serde(default)]
+    pub is_favorite: bool,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// A filter the user saved in their account, like "Priority Work" for
+/// `p1 & #Work`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Filter {
+    pub id: String,
+    pub name: String,
+
+    /// The filter itself, in Todoist's filter syntax.
+    pub query: String,
+
+    /// One of Todoist's named colors, like `berry_red`.
+    #[serde(default)]
+    pub color: String,
+
+    /// The filter's position in the user's list of filters.
+    #[serde(default)]
+    pub item_order: i64,
+
+    #[serde(default)]
+    pub is_favorite: bool,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// Someone who shares at least one project with the user.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Collaborator {
+    pub id: String,
+    pub email: String,
+    pub full_name: String,
+}
+
+/// Whether a [collaborator](Collaborator) is in a shared project, or has only
+/// been invited to it so far.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CollaboratorState {
+    pub project_id: String,
+    pub user_id: String,
+
+    /// `active`, `invited`, or `deleted` for someone who's left the project.
+    pub state: String,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// Something the user is told about that someone else did, like inviting them
+/// to a project or assigning them a task. Which of the optional fields are
+/// there depends on the `notification_type`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LiveNotification {
+    pub id: String,
+
+    /// Like `share_invitation_sent`, `item_assigned`, or `note_added`.
+    pub notification_type: String,
+
+    #[serde(default)]
+    pub created_at: String,
+
+    #[serde(default)]
+    pub is_unread: bool,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+
+    /// Who it's from, by ID.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_uid: Option<String>,
+
+    /// Who it's from, for invitations from people who aren't collaborators
+    /// yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_user: Option<Collaborator>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+
+    /// The name of the project an invitation is to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_content: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note_id: Option<String>,
+}
+
+/// A team's shared space in a business account, with projects of its own
+/// alongside the user's personal ones.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// A comment on an item.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Note {
+    pub id: String,
+    pub item_id: String,
+    pub content: String,
+
+    /// When the comment was made, as an RFC 3339 timestamp in UTC.
+    #[serde(default)]
+    pub posted_at: String,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReminderKind {
+    /// Goes off some number of minutes before the item is due.
+    Relative,
+
+    /// Goes off at a fixed time.
+    Absolute,
+
+    /// Goes off when arriving at or leaving a place.
+    Location,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Reminder {
+    pub id: String,
+    pub item_id: String,
+
+    #[serde(rename = "type")]
+    pub kind: ReminderKind,
+
+    /// When an absolute reminder goes off.
+    pub due: Option<Due>,
+
+    /// How long before the item is due a relative reminder goes off.
+    pub minute_offset: Option<u32>,
+
+    /// The name of the place where a location reminder goes off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The place's latitude and longitude, as decimal degrees.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loc_lat: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loc_long: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loc_trigger: Option<LocationTrigger>,
+
+    /// How close to the place, in meters, counts as being there.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius: Option<u32>,
+
+    #[serde(default)]
+    pub is_deleted: bool,
+}
+
+/// Whether a location reminder goes off when arriving at its place or when
+/// leaving it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocationTrigger {
+    OnEnter,
+    OnLeave,
+}
+
+/// A page of completed items, from the `completed/get_all` endpoint.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CompletedPage {
+    pub items: Vec<CompletedItem>,
+}
+
+/// The record of an item being completed. Recurring items get one of these
+/// for every occurrence.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CompletedItem {
+    /// The ID of the completion itself.
+    pub id: String,
+
+    pub task_id: String,
+    pub content: String,
+    pub project_id: String,
+
+    /// When the item was completed, as an RFC 3339 timestamp in UTC.
+    pub completed_at: String,
+
+    /// The collaborator who completed it.
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+/// Which completed items to ask for.
+#[derive(Debug, Default, Serialize)]
+pub struct CompletedQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_id: Option<String>,
+
+    /// Only items completed after this UTC time, like `2024-05-01T00:00:00`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+
+    pub limit: u32,
+    pub offset: u32,
+}
+
+/// A page of a project's completed items, from the `archive/items` endpoint.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ArchivedPage {
+    pub items: Vec<Item>,
+
+    #[serde(default)]
+    pub has_more: bool,
+
+    /// What to ask for the next page with, if there is one.
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// Which archived items to ask for.
+#[derive(Debug, Default, Serialize)]
+pub struct ArchivedQuery {
+    pub project_id: String,
+    pub limit: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
+/// A page of the activity log, from the `activity/get` endpoint.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ActivityPage {
+    pub events: Vec<Event>,
+}
+
+/// Something that happened to an item, project, or comment.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Event {
+    pub id: String,
+
+    /// What kind of thing the event happened to, like `item` or `project`.
+    pub object_type: String,
+    pub object_id: String,
+
+    /// What happened, like `added`, `completed`, or `updated`.
+    pub event_type: String,
+
+    /// When it happened, as an RFC 3339 timestamp in UTC.
+    pub event_date: String,
+
+    pub parent_project_id: Option<String>,
+
+    /// The collaborator who made it happen.
+    #[serde(default)]
+    pub initiator_id: Option<String>,
+
+    #[serde(default)]
+    pub extra_data: EventData,
+}
+
+/// Details that some events come with.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EventData {
+    /// An item's content, as of the event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
+    /// A project's name, as of the event.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// Which events to ask for.
+#[derive(Debug, Default, Serialize)]
+pub struct ActivityQuery {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub object_type: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_project_id: Option<String>,
+
+    pub limit: u32,
+    pub offset: u32,
 }
@@ -0,0 +1,230 @@
+//! Reading the tasks that Taskwarrior's `task export` writes, for moving them
+//! to Todoist with `import --from taskwarrior`.
+//!
+//! Each task's description becomes its content, and its tags its labels.
+//! Projects in Taskwarrior can be nested, like `Home.Garden`: the first part
+//! names the project, and the rest names a section in it. The `H`, `M`, and `L`
+//! priorities become `p1`, `p2`, and `p3`, and annotations become comments.
+//! Recurring tasks are imported once, from the task that the others are made
+//! from, repeating as it says; due dates and times are in local time.
+//! Deleted tasks are left out.
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Timelike, Utc};
+use serde::Deserialize;
+
+use crate::{import, Error, Result};
+
+/// The tasks to import, along with what couldn't be brought over.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Migration {
+    pub tasks: Vec<import::Task>,
+
+    /// What was left out or changed, for the user to look over.
+    pub notes: Vec<String>,
+}
+
+/// A task as `task export` writes it.
+#[derive(Debug, Deserialize)]
+struct Task {
+    #[serde(default)]
+    uuid: String,
+    description: String,
+    status: String,
+    project: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    priority: Option<String>,
+    due: Option<String>,
+    recur: Option<String>,
+
+    /// For a recurring task, the task it was made from.
+    parent: Option<String>,
+
+    #[serde(default)]
+    annotations: Vec<Annotation>,
+
+    /// The UUIDs of the tasks this one waits for, separated by commas in older
+    /// versions of Taskwarrior.
+    depends: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Annotation {
+    description: String,
+}
+
+/// Parses an export, with due dates in local time.
+///
+/// # Errors
+///
+/// The same as [`parse_in`].
+pub fn parse(text: &str) -> Result<Migration> {
+    parse_in(text, &Local)
+}
+
+/// Parses an export, with due dates in the time zone `zone`.
+///
+/// # Errors
+///
+/// Returns [`Error::Import`] if the text isn't an export, or a task has no
+/// description or a due date that isn't one.
+pub fn parse_in<Tz: TimeZone>(text: &str, zone: &Tz) -> Result<Migration>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let exported: Vec<Task> = serde_json::from_str(text)
+        .map_err(|err| Error::Import(format!("this isn't a Taskwarrior export: {err}")))?;
+    let templates: Vec<&str> = exported
+        .iter()
+        .filter(|task| task.status == "recurring")
+        .map(|task| task.uuid.as_str())
+        .collect();
+
+    let mut migration = Migration::default();
+    let mut deleted = 0;
+    let mut has_dependencies = false;
+    for task in &exported {
+        if task.status == "deleted" {
+            deleted += 1;
+            continue;
+        }
+        // the task they're made from stands for all of them
+        if task
+            .parent
+            .as_deref()
+            .is_some_and(|parent| templates.contains(&parent))
+        {
+            continue;
+        }
+        let content = task.description.trim();
+        if content.is_empty() {
+            return Err(Error::Import(format!(
+                "task {} has no description",
+                task.uuid
+            )));
+        }
+        has_dependencies |= task.depends.as_ref().is_some_and(|depends| {
+            depends.as_str().is_none_or(|depends| !depends.is_empty())
+                && depends.as_array().is_none_or(|depends| !depends.is_empty())
+        });
+
+        let mut due = task
+            .due
+            .as_deref()
+            .map(|due| due_string(due, zone))
+            .transpose()?;
+        if let Some(recur) = task.recur.as_deref().filter(|_| task.status == "recurring") {
+            match (recurrence(recur), &due) {
+                (Some(every), Some(start)) => due = Some(format!("{every} starting {start}")),
+                (Some(every), None) => due = Some(every),
+                (None, _) => migration.notes.push(format!(
+                    "'{content}' repeats '{recur}', which can't be brought over, so it's only \
+                     due once."
+                )),
+            }
+        }
+
+        let (project, section) = match task.project.as_deref() {
+            Some(project) => match project.split_once('.') {
+                Some((project, section)) => (Some(project), Some(section)),
+                None => (Some(project), None),
+            },
+            None => (None, None),
+        };
+        migration.tasks.push(import::Task {
+            content: content.to_string(),
+            project: project.map(ToString::to_string),
+            section: section.map(ToString::to_string),
+            due,
+            priority: match task.priority.as_deref() {
+                Some("H") => Some(1),
+                Some("M") => Some(2),
+                Some("L") => Some(3),
+                _ => None,
+            },
+            labels: task.tags.clone(),
+            completed: task.status == "completed",
+            comments: task
+                .annotations
+                .iter()
+                .map(|annotation| annotation.description.clone())
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    if deleted > 0 {
+        migration
+            .notes
+            .push(format!("{deleted} deleted tasks are left out."));
+    }
+    if has_dependencies {
+        migration
+            .notes
+            .push("Which tasks depend on which others isn't brought over.".to_string());
+    }
+    Ok(migration)
+}
+
+/// A due date in `zone`, from a Taskwarrior date like `20240501T120000Z`.
+/// Dates at midnight, which is what Taskwarrior gives dates without times,
+/// are just dates.
+fn due_string<Tz: TimeZone>(due: &str, zone: &Tz) -> Result<String>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let time = NaiveDateTime::parse_from_str(due, "%Y%m%dT%H%M%SZ")
+        .map_err(|_| Error::Import(format!("'{due}' isn't a Taskwarrior date")))?;
+    let time: DateTime<Tz> = Utc.from_utc_datetime(&time).with_timezone(zone);
+    Ok(if time.hour() == 0 && time.minute() == 0 {
+        time.format("%Y-%m-%d").to_string()
+    } else {
+        time.format("%Y-%m-%d %H:%M").to_string()
+    })
+}
+
+/// How often a Taskwarrior recurrence, like `weekly` or `2w`, repeats, as a
+/// due date like "every 2 weeks", or `None` if it's one Todoist can't say.
+#[must_use]
+pub fn recurrence(recur: &str) -> Option<String> {
+    let recur = recur.trim().to_lowercase();
+    let every = match recur.as_str() {
+        "daily" | "day" => "every day",
+        "weekdays" => "every weekday",
+        "weekly" | "week" => "every week",
+        "biweekly" | "fortnight" => "every 2 weeks",
+        "monthly" | "month" => "every month",
+        "bimonthly" => "every 2 months",
+        "quarterly" => "every 3 months",
+        "semiannual" => "every 6 months",
+        "yearly" | "annual" | "year" => "every year",
+        "biannual" | "biyearly" => "every 2 years",
+        _ => {
+            let split = recur
+                .find(|char: char| !char.is_ascii_digit())
+                .unwrap_or(recur.len());
+            let (count, unit) = recur.split_at(split);
+            let count: u32 = if count.is_empty() {
+                1
+            } else {
+                count.parse().ok()?
+            };
+            let unit = match unit {
+                "d" | "day" | "days" => "day",
+                "w" | "wk" | "wks" | "week" | "weeks" => "week",
+                "mo" | "mos" | "month" | "months" => "month",
+                "q" | "qtr" | "qtrs" | "quarter" | "quarters" => {
+                    return Some(format!("every {} months", count * 3));
+                }
+                "y" | "yr" | "yrs" | "year" | "years" => "year",
+                _ => return None,
+            };
+            return Some(if count == 1 {
+                format!("every {unit}")
+            } else {
+                format!("every {count} {unit}s")
+            });
+        }
+    };
+    Some(every.to_string())
+}
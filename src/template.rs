@@ -0,0 +1,126 @@
+//! Projects saved as templates, to be made again whenever they're needed, like
+//! a packing list for each trip. A template keeps a project's sections and
+//! open tasks, with their descriptions, priorities, labels, and subtasks, but
+//! not their due dates, which wouldn't be right the next time. Templates are
+//! only kept in the local store.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    import, order,
+    sync::{Item, Section},
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    /// The name of the project the template was saved from.
+    pub project: String,
+
+    /// The names of the sections, in order, including empty ones.
+    pub sections: Vec<String>,
+
+    /// The tasks, with tasks outside of sections first, then each section's,
+    /// and subtasks right after their parents.
+    pub tasks: Vec<Task>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Task {
+    pub content: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+
+    /// The name of the task's section, if it's in one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+
+    /// From 1 (most urgent) to 4, the way users write them.
+    pub priority: u8,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+
+    /// The index of the task's parent among the template's tasks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<usize>,
+}
+
+impl Template {
+    /// A template of the project named `project`, from its sections and the
+    /// open tasks in it.
+    #[must_use]
+    pub fn snapshot(project: &str, sections: &[Section], items: &[Item]) -> Self {
+        let mut sections: Vec<&Section> = sections
+            .iter()
+            .filter(|section| !section.is_archived && !section.is_deleted)
+            .collect();
+        sections.sort_by_key(|section| section.section_order);
+        let open: Vec<&Item> = items
+            .iter()
+            .filter(|item| !item.checked && !item.is_deleted)
+            .collect();
+        let section_of = |item: &Item| {
+            let id = item.section_id.as_deref()?;
+            sections.iter().find(|section| section.id == id)
+        };
+
+        let mut groups = vec![(None, Vec::new())];
+        groups.extend(sections.iter().map(|section| (Some(section), Vec::new())));
+        for item in open {
+            let section = section_of(item);
+            if let Some((_, items)) = groups.iter_mut().find(|(group, _)| {
+                group.map(|group| &group.id) == section.map(|section| &section.id)
+            }) {
+                items.push(item);
+            }
+        }
+
+        let mut ids: Vec<&str> = Vec::new();
+        let mut tasks = Vec::new();
+        for (section, items) in groups {
+            for item in order::nest_subtasks(&items) {
+                let parent = item
+                    .parent_id
+                    .as_deref()
+                    .and_then(|parent_id| ids.iter().position(|id| *id == parent_id));
+                ids.push(&item.id);
+                tasks.push(Task {
+                    content: item.content.clone(),
+                    description: item.description.clone(),
+                    section: section.map(|section| section.name.clone()),
+                    priority: 5 - item.priority.clamp(1, 4),
+                    labels: item.labels.clone(),
+                    parent,
+                });
+            }
+        }
+        Template {
+            project: project.to_string(),
+            sections: sections
+                .into_iter()
+                .map(|section| section.name.clone())
+                .collect(),
+            tasks,
+        }
+    }
+
+    /// The tasks to add to make the template again in the project named
+    /// `project`.
+    #[must_use]
+    pub fn tasks_for(&self, project: &str) -> Vec<import::Task> {
+        self.tasks
+            .iter()
+            .map(|task| import::Task {
+                content: task.content.clone(),
+                description: Some(task.description.clone()).filter(|text| !text.is_empty()),
+                project: Some(project.to_string()),
+                section: task.section.clone(),
+                priority: Some(task.priority),
+                labels: task.labels.clone(),
+                parent: task.parent,
+                ..Default::default()
+            })
+            .collect()
+    }
+}
@@ -1,15 +1,160 @@
 mod utils;
 
+#[cfg(test)]
+mod agenda;
+
+#[cfg(test)]
+mod arrange;
+
+#[cfg(test)]
+mod batch;
+
+#[cfg(test)]
+mod changes;
+
+#[cfg(test)]
+mod checklist;
+
+#[cfg(test)]
+mod client;
+
+#[cfg(test)]
+mod color;
+
+#[cfg(test)]
+mod config;
+
+#[cfg(test)]
+mod conflict;
+
+#[cfg(test)]
+mod daemon;
+
+#[cfg(test)]
+mod dedupe;
+
+#[cfg(test)]
+mod dependency;
+
+#[cfg(test)]
+mod due;
+
+#[cfg(test)]
+mod export;
+
+#[cfg(test)]
+mod files;
+
+#[cfg(test)]
+mod filter;
+
+#[cfg(test)]
+mod focus;
+
+#[cfg(test)]
+mod forecast;
+
+#[cfg(test)]
+mod fuzzy;
+
+#[cfg(test)]
+mod githook;
+
+#[cfg(test)]
+mod hooks;
+
+#[cfg(test)]
+mod ical;
+
+#[cfg(test)]
+mod idempotency;
+
+#[cfg(test)]
+mod import;
+
+#[cfg(test)]
+mod locale;
+
+#[cfg(test)]
+mod notify;
+
+#[cfg(test)]
+mod order;
+
+#[cfg(test)]
+mod porcelain;
+
+#[cfg(test)]
+mod quick_add;
+
+#[cfg(test)]
+mod rate_limit;
+
+#[cfg(test)]
+mod reminder;
+
+#[cfg(test)]
+mod rest;
+
+#[cfg(test)]
+mod review;
+
+#[cfg(test)]
+mod schedule;
+
+#[cfg(test)]
+mod search;
+
+#[cfg(test)]
+mod session;
+
+#[cfg(test)]
+mod stats;
+
+#[cfg(test)]
+mod storage;
+
+#[cfg(test)]
+mod stream;
+
+#[cfg(test)]
+mod taskwarrior;
+
+#[cfg(test)]
+mod template;
+
+#[cfg(test)]
+mod track;
+
+#[cfg(test)]
+mod trash;
+
+#[cfg(test)]
+mod undo;
+
+#[cfg(test)]
+mod webhook;
+
+#[cfg(test)]
+mod widget;
+
 #[cfg(test)]
 pub mod e2e {
     use crate::{
+        daemon,
+        storage::Store,
         sync::{
-            AddItemRequest, GetUserRequest, Item, Project, ProjectDataRequest, ProjectDataResponse,
-            Response, User,
+            ActivityPage, ArchivedPage, Collaborator, CollaboratorState, Command as SyncCommand,
+            CommandArgs, CommandStatus, CompletedItem, CompletedPage, Due, DurationUnit, Event,
+            EventData, Filter, Item, ItemAddArgs, ItemIdArgs, Label, LiveNotification,
+            LocationTrigger, Note, Project, Reminder, ReminderKind, Request, Response, Section,
+            TaskDuration, User, Workspace,
         },
-        tests::utils::{ApiMockBuilder, FsMockBuilder},
+        tests::utils::{ApiMockBuilder, FakeSyncServer, FsMockBuilder},
+        undo, webhook,
     };
     use assert_cmd::Command;
+    use predicates::prelude::*;
     use std::collections::HashMap;
 
     #[tokio::test]
@@ -31,8 +176,11 @@ pub mod e2e {
             .await
             .mock_response(
                 "sync",
-                |request: AddItemRequest| {
-                    request.commands[0].args.project_id == "MOCK_INBOX_PROJECT_ID"
+                |request: Request| {
+                    matches!(
+                        &request.commands[0].args,
+                        CommandArgs::ItemAdd(args) if args.project_id == "MOCK_INBOX_PROJECT_ID"
+                    )
                 },
                 Response {
                     full_sync: true,
@@ -40,6 +188,7 @@ pub mod e2e {
                     sync_token: String::from("MOCK_SYNC_TOKEN"),
                     temp_id_mapping: HashMap::new(),
                     user: None,
+                    ..Default::default()
                 },
             )
             .await;
@@ -71,10 +220,10 @@ pub mod e2e {
             .await
             .mock_response(
                 "sync",
-                |request: GetUserRequest| {
+                |request: Request| {
                     request
                         .resource_types
-                        .get(0)
+                        .first()
                         .is_some_and(|resource| resource == "user")
                 },
                 Response {
@@ -85,17 +234,21 @@ pub mod e2e {
                     user: Some(User {
                         full_name: "Drew".to_string(),
                         inbox_project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
                     }),
+                    ..Default::default()
                 },
             )
             .await
             .mock_response(
                 "sync",
-                |request: AddItemRequest| {
-                    request
-                        .commands
-                        .get(0)
-                        .is_some_and(|command| command.args.project_id == "MOCK_INBOX_PROJECT_ID")
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.project_id == "MOCK_INBOX_PROJECT_ID"
+                        )
+                    })
                 },
                 Response {
                     full_sync: true,
@@ -103,6 +256,7 @@ pub mod e2e {
                     sync_token: String::from("MOCK_SYNC_TOKEN"),
                     temp_id_mapping: HashMap::new(),
                     user: None,
+                    ..Default::default()
                 },
             )
             .await;
@@ -117,7 +271,7 @@ pub mod e2e {
 
         // check output
         cmd.assert()
-            .stdout(predicates::str::contains("Fetching user data"))
+            .stdout(predicates::str::contains("Fetching user data").not())
             .stdout(predicates::str::contains("Storing user data"))
             .stdout(predicates::str::contains("Todo 'new todo!' added"));
 
@@ -145,27 +299,42 @@ pub mod e2e {
         let mock_server = ApiMockBuilder::new()
             .await
             .mock_response(
-                "projects/get_data",
-                |request: ProjectDataRequest| request.project_id == "MOCK_INBOX_PROJECT_ID",
-                ProjectDataResponse {
-                    project: Project {
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    projects: Some(vec![Project {
                         id: "MOCK_INBOX_PROJECT_ID".to_string(),
                         name: "Inbox".to_string(),
-                    },
-                    items: vec![
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![
                         Item {
                             id: "MOCK_ITEM_ID_1".to_string(),
                             content: "Todo One!".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
                         },
                         Item {
                             id: "MOCK_ITEM_ID_2".to_string(),
                             content: "Todo Two!".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
                         },
                         Item {
                             id: "MOCK_ITEM_ID_3".to_string(),
                             content: "Todo Three!".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
                         },
-                    ],
+                        Item {
+                            id: "MOCK_ITEM_ID_4".to_string(),
+                            content: "Not in the inbox".to_string(),
+                            project_id: "MOCK_OTHER_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
                 },
             )
             .await;
@@ -180,10 +349,6763 @@ pub mod e2e {
 
         // check output
         cmd.assert()
-            .stdout(predicates::str::contains("- Todo One!"))
-            .stdout(predicates::str::contains("- Todo Two!"))
-            .stdout(predicates::str::contains("- Todo Three!"));
+            .stdout(predicates::str::contains("1  Todo One!"))
+            .stdout(predicates::str::contains("2  Todo Two!"))
+            .stdout(predicates::str::contains("3  Todo Three!"))
+            .stdout(predicates::str::contains("Not in the inbox").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_project_items() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    projects: Some(vec![
+                        Project {
+                            id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            name: "Inbox".to_string(),
+                            ..Default::default()
+                        },
+                        Project {
+                            id: "MOCK_WORK_PROJECT_ID".to_string(),
+                            name: "Work".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    items: Some(vec![
+                        Item {
+                            id: "MOCK_ITEM_ID_1".to_string(),
+                            content: "Inbox todo".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "MOCK_ITEM_ID_2".to_string(),
+                            content: "Write report".to_string(),
+                            project_id: "MOCK_WORK_PROJECT_ID".to_string(),
+                            priority: 4,
+                            due: Some(Due {
+                                date: "2023-07-01".to_string(),
+                                string: Some("Jul 1".to_string()),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("list").arg("--project").arg("work");
+
+        // check output
+        cmd.assert()
+            .stdout(predicates::str::contains("Work:"))
+            .stdout(predicates::str::is_match(r"1 +Write report +Jul 1 +p1")?)
+            .stdout(predicates::str::contains("Inbox todo").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn complete_item_by_list_index() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a previous `list`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                let items = [
+                    Item {
+                        id: "MOCK_ITEM_ID_1".to_string(),
+                        content: "Todo One!".to_string(),
+                        ..Default::default()
+                    },
+                    Item {
+                        id: "MOCK_ITEM_ID_2".to_string(),
+                        content: "Todo Two!".to_string(),
+                        ..Default::default()
+                    },
+                ];
+                store.set_last_list(&items.iter().collect::<Vec<_>>())
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemClose(args) if args.id == "MOCK_ITEM_ID_2"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("complete").arg("2");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Completed 'Todo Two!'"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn complete_item_by_id() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemClose(args) if args.id == "6Jf8VQXxpwv56VQ7"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("complete").arg("6Jf8VQXxpwv56VQ7");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Completed task 6Jf8VQXxpwv56VQ7"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_uses_incremental_sync() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a previously synced cache
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_OLD_SYNC_TOKEN".to_string(),
+                    items: Some(vec![
+                        Item {
+                            id: "1".to_string(),
+                            content: "Cached todo".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "2".to_string(),
+                            content: "Deleted todo".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server, which only knows about changes since the old token
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.sync_token == "MOCK_OLD_SYNC_TOKEN",
+                Response {
+                    full_sync: false,
+                    sync_token: String::from("MOCK_NEW_SYNC_TOKEN"),
+                    items: Some(vec![
+                        Item {
+                            id: "2".to_string(),
+                            content: "Deleted todo".to_string(),
+                            is_deleted: true,
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "3".to_string(),
+                            content: "New todo".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("list");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("1  Cached todo"))
+            .stdout(predicates::str::contains("2  New todo"))
+            .stdout(predicates::str::contains("Deleted todo").not());
+
+        // check that the new sync token was stored
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert_eq!(store.sync_token()?, "MOCK_NEW_SYNC_TOKEN");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_queues_command_when_offline() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, pointed at a port that nothing is listening on
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--add").arg("plane todo");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Could not reach Todoist"));
+
+        // check that the command was queued
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        let queued_commands = store.queued_commands()?;
+        assert!(matches!(
+            &queued_commands[..],
+            [SyncCommand { args: CommandArgs::ItemAdd(args), .. }] if args.content == "plane todo"
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sync_flushes_queued_commands() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a queued command
+        let queued_uuid = "5e3ec7ea-8de2-4d7c-a1c5-5e0c2aa0b8a4".parse()?;
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.set_queued_commands(&[SyncCommand {
+                    args: CommandArgs::ItemAdd(ItemAddArgs {
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        content: "plane todo".to_string(),
+                        ..Default::default()
+                    }),
+                    uuid: queued_uuid,
+                    temp_id: None,
+                }])
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.len() == 1
+                        && request.resource_types.contains(&"items".to_string())
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    sync_status: Some(HashMap::from([(
+                        "5e3ec7ea-8de2-4d7c-a1c5-5e0c2aa0b8a4".parse()?,
+                        CommandStatus::Ok("ok".to_string()),
+                    )])),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "plane todo".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("list");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Queued command succeeded: add 'plane todo'",
+            ))
+            .stdout(predicates::str::contains("1  plane todo"));
+
+        // check that the queue was emptied
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert!(store.queued_commands()?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn auth_stores_valid_token() -> Result<(), Box<dyn std::error::Error>> {
+        // start without any stored files
+        let mock_fs = FsMockBuilder::new()?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types == ["user"],
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    user: Some(User {
+                        id: "MOCK_USER_ID".to_string(),
+                        full_name: "Drew".to_string(),
+                        inbox_project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        email: "drew@example.com".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("auth").arg("MOCK_API_KEY");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Authenticated as drew@example.com",
+        ));
+
+        // check that the token and user data were stored
+        let auth_path = mock_data_dir.join("client_auth.toml");
+        let auth_file = std::fs::read_to_string(&auth_path)?;
+        assert!(auth_file.contains("api_key = \"MOCK_API_KEY\""));
+        assert!(mock_data_dir.join("data").join("user.json").exists());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&auth_path)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn auth_rejects_invalid_token() -> Result<(), Box<dyn std::error::Error>> {
+        // start without any stored files
+        let mock_fs = FsMockBuilder::new()?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new().await.mock_status("sync", 401).await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("auth").arg("BAD_API_KEY");
+
+        // check output
+        cmd.assert()
+            .code(4)
+            .stderr(predicates::str::contains("did not accept that API token"));
+
+        // check that nothing was stored
+        assert!(!mock_data_dir.join("client_auth.toml").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_with_quick_add_syntax() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced projects
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_ERRANDS_PROJECT_ID".to_string(),
+                        name: "Errands".to_string(),
+                        ..Default::default()
+                    }]),
+                    labels: Some(vec![Label {
+                        id: "MOCK_LABEL_ID".to_string(),
+                        name: "Store".to_string(),
+                        ..Default::default()
+                    }]),
+                    collaborators: Some(vec![Collaborator {
+                        id: "MOCK_SAM_ID".to_string(),
+                        email: "sam@example.com".to_string(),
+                        full_name: "Sam Smith".to_string(),
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.project_id == "MOCK_ERRANDS_PROJECT_ID"
+                                && args.content == "Buy milk"
+                                && args.due.as_ref().is_some_and(|due| due.string == "tomorrow 5pm")
+                                && args.priority == Some(4)
+                                && args.labels == ["Store"]
+                                && args.responsible_uid.as_deref() == Some("MOCK_SAM_ID")
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add")
+            .arg("Buy milk tomorrow 5pm #errands @store p1 +sam");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Todo 'Buy milk' added to Errands",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_projects_as_tree() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let project = |id: &str, name: &str, parent_id: Option<&str>, child_order| Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            parent_id: parent_id.map(ToString::to_string),
+            child_order,
+            ..Default::default()
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"projects".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    projects: Some(vec![
+                        project("MOCK_HOME_ID", "Home", None, 2),
+                        project("MOCK_MEETINGS_ID", "Meetings", Some("MOCK_WORK_ID"), 1),
+                        Project {
+                            is_favorite: true,
+                            ..project("MOCK_WORK_ID", "Work", None, 1)
+                        },
+                        project("MOCK_INBOX_PROJECT_ID", "Inbox", None, 0),
+                        project("MOCK_REPORTS_ID", "Reports", Some("MOCK_WORK_ID"), 0),
+                        Project {
+                            is_archived: true,
+                            ..project("MOCK_OLD_ID", "Old Stuff", None, 3)
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("projects").arg("list");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Projects:\n  Inbox\n  Work ★\n    Reports\n    Meetings\n  Home\n",
+        ));
+        cmd.assert()
+            .stdout(predicates::str::contains("Old Stuff").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_project_under_parent() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced projects
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        command.temp_id.is_some()
+                            && matches!(
+                                &command.args,
+                                CommandArgs::ProjectAdd(args) if args.name == "Meetings"
+                                    && args.parent_id.as_deref() == Some("MOCK_WORK_PROJECT_ID")
+                            )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["projects", "add", "Meetings", "--parent", "work"]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Project 'Meetings' added."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_project_after_confirmation() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced projects
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ProjectDelete(args) if args.id == "MOCK_WORK_PROJECT_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["projects", "delete", "Work"]).write_stdin("y\n");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Permanently delete project 'Work' and all of its tasks? [y/N]",
+            ))
+            .stdout(predicates::str::contains("Project 'Work' deleted."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_project_cancelled() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced projects
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up a mock server that doesn't expect any requests
+        let mock_server = ApiMockBuilder::new().await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["projects", "delete", "Work"]).write_stdin("\n");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Cancelled."))
+            .stdout(predicates::str::contains("deleted").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn retries_rate_limited_requests() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up a mock server that rate limits the first two requests
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_rate_limited("sync", 2)
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Todo One!".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--list");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("1  Todo One!"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_configured_retries() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up a mock server that rate limits the first two requests
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_rate_limited("sync", 2)
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Todo One!".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--list").arg("--retries").arg("1");
+
+        // check output
+        cmd.assert()
+            .failure()
+            .stderr(predicates::str::contains("429"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn explains_missing_api_token() -> Result<(), Box<dyn std::error::Error>> {
+        // start with an empty data directory
+        let mock_fs = FsMockBuilder::new()?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--list");
+
+        // check output
+        cmd.assert().failure().stderr(predicates::str::contains(
+            "Error: No API token found. Run `todoist setup`, or `todoist auth <token>`, to add one.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_with_due_flag() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.content == "pay rent on time"
+                                && args.due.as_ref().is_some_and(|due| due.string == "every month on the 1st")
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add").arg("pay rent on time");
+        cmd.arg("--due").arg("every month on the 1st");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo 'pay rent on time' added"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_with_priority_flag() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server; the flag wins over the quick-add priority
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.content == "file taxes"
+                                && args.priority == Some(3)
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add").arg("file taxes p4").arg("-p").arg("p2");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo 'file taxes' added"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_out_of_range_priority() {
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--add")
+            .arg("file taxes")
+            .arg("--priority")
+            .arg("5");
+
+        cmd.assert()
+            .failure()
+            .stderr(predicates::str::contains("expected a priority from 1 to 4"));
+    }
+
+    #[tokio::test]
+    async fn add_to_fuzzy_matched_project() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`, with nothing synced yet
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server, which has to be asked for the projects
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"projects".to_string()),
+                Response {
+                    full_sync: true,
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    projects: Some(vec![
+                        Project {
+                            id: "MOCK_HOME_PROJECT_ID".to_string(),
+                            name: "Home".to_string(),
+                            ..Default::default()
+                        },
+                        Project {
+                            id: "MOCK_RENOVATION_PROJECT_ID".to_string(),
+                            name: "Home Renovation".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.content == "buy paint"
+                                && args.project_id == "MOCK_RENOVATION_PROJECT_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add")
+            .arg("buy paint")
+            .arg("--project")
+            .arg("home reno");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Todo 'buy paint' added to Home Renovation.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_labels_in_order() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"labels".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    labels: Some(vec![
+                        Label {
+                            id: "MOCK_LABEL_ID_1".to_string(),
+                            name: "waiting".to_string(),
+                            item_order: 2,
+                            ..Default::default()
+                        },
+                        Label {
+                            id: "MOCK_LABEL_ID_2".to_string(),
+                            name: "errand".to_string(),
+                            item_order: 1,
+                            is_favorite: true,
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["labels", "list"]);
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Labels:\n  @errand ★\n  @waiting\n",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rename_label_by_id() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced labels
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    labels: Some(vec![Label {
+                        id: "MOCK_ERRAND_LABEL_ID".to_string(),
+                        name: "Errand".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::LabelUpdate(args) if args.id == "MOCK_ERRAND_LABEL_ID"
+                                && args.name.as_deref() == Some("Errands")
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["labels", "rename", "errand", "Errands"]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Label 'Errand' renamed."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_with_label_flags() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced labels
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    labels: Some(vec![Label {
+                        id: "MOCK_ERRAND_LABEL_ID".to_string(),
+                        name: "Errand".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.content == "return books"
+                                && args.labels == ["Errand", "library"]
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add").arg("return books");
+        cmd.args(["--label", "errand", "--label", "library"]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo 'return books' added"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_groups_items_by_section() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let item = |id: &str, content: &str, section_id: Option<&str>| Item {
+            id: id.to_string(),
+            content: content.to_string(),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            section_id: section_id.map(ToString::to_string),
+            ..Default::default()
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"sections".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    sections: Some(vec![
+                        Section {
+                            id: "MOCK_LATER_SECTION_ID".to_string(),
+                            name: "Later".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            section_order: 2,
+                            ..Default::default()
+                        },
+                        Section {
+                            id: "MOCK_SOON_SECTION_ID".to_string(),
+                            name: "Soon".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            section_order: 1,
+                            ..Default::default()
+                        },
+                    ]),
+                    items: Some(vec![
+                        item("MOCK_ITEM_ID_1", "Someday", Some("MOCK_LATER_SECTION_ID")),
+                        item("MOCK_ITEM_ID_2", "Next up", Some("MOCK_SOON_SECTION_ID")),
+                        item("MOCK_ITEM_ID_3", "Loose end", None),
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--list");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "  1  Loose end       p4\n\n  Soon:\n  2  Next up         p4\n\n  Later:\n  3  Someday         p4\n",
+        ));
+
+        // check that indices follow the grouped order
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert_eq!(
+            store.last_list_entry(2)?,
+            Some(("MOCK_ITEM_ID_2".to_string(), "Next up".to_string()))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_to_section() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced sections
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    sections: Some(vec![Section {
+                        id: "MOCK_SOON_SECTION_ID".to_string(),
+                        name: "Soon".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.content == "call the bank"
+                                && args.section_id.as_deref() == Some("MOCK_SOON_SECTION_ID")
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add")
+            .arg("call the bank")
+            .arg("--section")
+            .arg("soon");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Todo 'call the bank' added to inbox / Soon.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn today_shows_overdue_items_first() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server, with items due relative to today
+        let today = chrono::Local::now().date_naive();
+        let item = |id: &str, content: &str, days_from_today: i64| Item {
+            id: id.to_string(),
+            content: content.to_string(),
+            project_id: "MOCK_OTHER_PROJECT_ID".to_string(),
+            due: Some(Due {
+                date: (today + chrono::Duration::days(days_from_today))
+                    .format("%Y-%m-%d")
+                    .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    items: Some(vec![
+                        item("MOCK_ITEM_ID_1", "Due today", 0),
+                        item("MOCK_ITEM_ID_2", "Due yesterday", -1),
+                        item("MOCK_ITEM_ID_3", "Due in two days", 2),
+                        item("MOCK_ITEM_ID_4", "Due in two weeks", 14),
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run `today`
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(&server_url);
+        cmd.arg("today");
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::is_match(
+                "Overdue:\n  1  Due yesterday .*\n\n  Today:\n  2  Due today ",
+            )?)
+            .stdout(predicates::str::contains("Due in two days").not());
+
+        // run `upcoming`
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(&server_url);
+        cmd.args(["upcoming", "--days", "3"]);
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("3  Due in two days"))
+            .stdout(predicates::str::contains("Due in two weeks").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_with_filter() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let item = |id: &str, content: &str, project_id: &str, priority| Item {
+            id: id.to_string(),
+            content: content.to_string(),
+            project_id: project_id.to_string(),
+            priority,
+            ..Default::default()
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![
+                        item("MOCK_ITEM_ID_1", "Urgent work", "MOCK_WORK_PROJECT_ID", 4),
+                        item("MOCK_ITEM_ID_2", "Other work", "MOCK_WORK_PROJECT_ID", 1),
+                        item("MOCK_ITEM_ID_3", "Urgent chore", "MOCK_INBOX_PROJECT_ID", 4),
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["list", "--filter", "#Work & p1"]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("#Work & p1:"))
+            .stdout(predicates::str::contains("1  Urgent work"))
+            .stdout(predicates::str::contains("Other work").not())
+            .stdout(predicates::str::contains("Urgent chore").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn complete_last_added_item() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a recently added todo
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| store.set_last_added("MOCK_ITEM_ID", "new todo"))?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemClose(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("complete").arg("last");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Completed 'new todo'"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn batch_sends_one_request() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server, which only answers if all three commands arrive together
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    matches!(
+                        &request.commands[..],
+                        [
+                            SyncCommand { args: CommandArgs::ItemAdd(first), .. },
+                            SyncCommand { args: CommandArgs::ItemAdd(second), .. },
+                            SyncCommand { args: CommandArgs::ItemClose(close), .. },
+                        ] if first.content == "a" && second.content == "b"
+                            && close.id == "MOCK_ITEM_ID"
+                    )
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["batch", "add", "a", "add", "b", "complete", "MOCK_ITEM_ID"]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo 'a' added to inbox."))
+            .stdout(predicates::str::contains("Todo 'b' added to inbox."))
+            .stdout(predicates::str::contains("Completed task MOCK_ITEM_ID."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn edit_item_shows_changes() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached item
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "buy milk".to_string(),
+                        labels: vec!["errands".to_string()],
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemUpdate(args) if args.id == "MOCK_ITEM_ID"
+                                && args.content.as_deref() == Some("buy oat milk")
+                                && args.priority == Some(4)
+                                && args.due.is_none()
+                                && args.labels.is_none()
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args([
+            "edit",
+            "MOCK_ITEM_ID",
+            "--content",
+            "buy oat milk",
+            "-p",
+            "1",
+        ]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Updated 'buy milk':"))
+            .stdout(predicates::str::contains(
+                "  Content: buy milk → buy oat milk",
+            ))
+            .stdout(predicates::str::contains("  Priority: p4 → p1"))
+            .stdout(predicates::str::contains("Labels").not());
+
+        Ok(())
+    }
+
+    #[test]
+    fn edit_requires_a_change() {
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.args(["edit", "3"]);
+
+        cmd.assert().failure().stderr(predicates::str::contains(
+            "required arguments were not provided",
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_item_with_force() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a listed item
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.set_last_list(&[&Item {
+                    id: "MOCK_ITEM_ID".to_string(),
+                    content: "old todo".to_string(),
+                    ..Default::default()
+                }])
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemDelete(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing, without anything on stdin to answer a prompt
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["delete", "1", "--force"]);
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("[y/N]").not())
+            .stdout(predicates::str::contains("Deleted 'old todo'."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn delete_item_cancelled() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a listed item
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.set_last_list(&[&Item {
+                    id: "MOCK_ITEM_ID".to_string(),
+                    content: "old todo".to_string(),
+                    ..Default::default()
+                }])
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, pointed at a server that isn't there since nothing
+        // should be sent
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.args(["delete", "1"]).write_stdin("n\n");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Permanently delete 'old todo'? [y/N]",
+            ))
+            .stdout(predicates::str::contains("Cancelled."));
+
+        // check that nothing was queued
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert!(store.queued_commands()?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_as_json() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"items".to_string()),
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    items: Some(vec![
+                        Item {
+                            id: "MOCK_ITEM_ID_1".to_string(),
+                            content: "Todo One!".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "MOCK_ITEM_ID_2".to_string(),
+                            content: "Todo Two!".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            priority: 4,
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url.clone());
+        cmd.args(["list", "--output", "json"]);
+
+        // check that stdout is nothing but the items
+        let stdout = cmd.assert().success().get_output().stdout.clone();
+        let items: Vec<Item> = serde_json::from_slice(&stdout)?;
+        let contents: Vec<&str> = items.iter().map(|item| item.content.as_str()).collect();
+        assert_eq!(contents, ["Todo One!", "Todo Two!"]);
+
+        // and plain output is one tab-separated line per item
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.args(["list", "--output", "plain"]);
+        cmd.assert()
+            .success()
+            .stdout("1\tMOCK_ITEM_ID_1\tTodo One!\t\tp4\n2\tMOCK_ITEM_ID_2\tTodo Two!\t\tp1\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn completions_include_cached_names() -> Result<(), Box<dyn std::error::Error>> {
+        // create a store with a project and a label, but no `client_auth.toml`
+        let mock_fs = FsMockBuilder::new()?.mock_store(|store| {
+            store.apply(&Response {
+                full_sync: true,
+                sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                projects: Some(vec![Project {
+                    id: "MOCK_WORK_PROJECT_ID".to_string(),
+                    name: "Work".to_string(),
+                    ..Default::default()
+                }]),
+                labels: Some(vec![Label {
+                    id: "MOCK_LABEL_ID".to_string(),
+                    name: "errands".to_string(),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            })
+        })?;
+        let mock_data_dir = mock_fs.path();
+
+        // PowerShell scripts from `clap_complete` don't complete values
+        for shell in ["bash", "zsh", "fish"] {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.args(["completions", shell]);
+
+            cmd.assert()
+                .success()
+                .stdout(predicates::str::contains("todoist"))
+                .stdout(predicates::str::contains("Work"))
+                .stdout(predicates::str::contains("errands"));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_to_configured_default_project() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and synced projects
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+        let config_path = mock_data_dir.join("config").join("config.toml");
+
+        // set the default project
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--config").arg(&config_path);
+        cmd.args(["config", "set", "default_project", "Work"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Set default_project to Work."));
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.project_id == "MOCK_WORK_PROJECT_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--config").arg(&config_path);
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add").arg("write report");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Todo 'write report' added to Work.",
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn config_rejects_invalid_values() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?;
+        let config_path = mock_fs.path().join("config.toml");
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--config").arg(&config_path);
+        cmd.args(["config", "set", "output", "xml"]);
+        cmd.assert().failure().stderr(predicates::str::contains(
+            "output must be one of table, json, plain, not 'xml'",
+        ));
+        assert!(!config_path.exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_token_file() -> Result<(), Box<dyn std::error::Error>> {
+        // create a config that prefers the keyring, which has no token, and a
+        // token file that does
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("config.toml", "token_storage = \"keyring\"")?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.commands.len() == 1,
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--config").arg(mock_data_dir.join("config.toml"));
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add").arg("new todo");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo 'new todo' added to inbox."));
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_to_keyring_needs_token_file() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--config").arg(mock_data_dir.join("config.toml"));
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["auth", "--migrate-to-keyring"]);
+
+        cmd.assert().failure().stderr(predicates::str::contains(
+            "There's no API token file to migrate.",
+        ));
+        assert!(!mock_data_dir.join("config.toml").exists());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn complete_recurring_item_advances_it() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached recurring task
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Water plants".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        due: Some(Due {
+                            date: "2024-01-01".to_string(),
+                            string: Some("every monday".to_string()),
+                            is_recurring: true,
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemUpdateDateComplete(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("complete").arg("MOCK_ITEM_ID");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Completed this occurrence of 'Water plants'.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn complete_recurring_item_all_occurrences() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached recurring task
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Water plants".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        due: Some(Due {
+                            date: "2024-01-01".to_string(),
+                            string: Some("every monday".to_string()),
+                            is_recurring: true,
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemComplete(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("complete")
+            .arg("MOCK_ITEM_ID")
+            .arg("--all-occurrences");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Completed every occurrence of 'Water plants'.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_marks_recurring_items() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached recurring task
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Water plants".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        due: Some(Due {
+                            date: "2024-01-01".to_string(),
+                            string: Some("every monday".to_string()),
+                            is_recurring: true,
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |_: Request| true,
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("list");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("every monday ↻"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_nests_subtasks_under_parents() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached parent task
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![
+                        Item {
+                            id: "CHILD_2".to_string(),
+                            content: "Second step".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            parent_id: Some("PARENT".to_string()),
+                            child_order: 2,
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "PARENT".to_string(),
+                            content: "Big project".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "CHILD_1".to_string(),
+                            content: "First step".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            parent_id: Some("PARENT".to_string()),
+                            child_order: 1,
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |_: Request| true,
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("list");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("1  Big project"))
+            .stdout(predicates::str::contains("2    First step"))
+            .stdout(predicates::str::contains("3    Second step"));
+
+        // check that indices refer to the nested order
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert_eq!(
+            store.last_list_entry(2)?,
+            Some(("CHILD_1".to_string(), "First step".to_string()))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_subtask_under_parent() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached parent task
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "PARENT".to_string(),
+                        content: "Big project".to_string(),
+                        project_id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        section_id: Some("MOCK_SECTION_ID".to_string()),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args)
+                                if args.content == "First step"
+                                    && args.parent_id.as_deref() == Some("PARENT")
+                                    && args.project_id == "MOCK_WORK_PROJECT_ID"
+                                    && args.section_id.as_deref() == Some("MOCK_SECTION_ID")
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("--add")
+            .arg("First step")
+            .arg("--parent")
+            .arg("PARENT");
+
+        // check output
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Todo 'First step' added to 'Big project' as a subtask.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn comment_add_sends_note_add() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached task with a comment
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Plan trip".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    notes: Some(vec![Note {
+                        id: "MOCK_NOTE_ID".to_string(),
+                        item_id: "MOCK_ITEM_ID".to_string(),
+                        content: "Check flights".to_string(),
+                        posted_at: "2024-01-01T09:00:00Z".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::NoteAdd(args)
+                                if args.item_id == "MOCK_ITEM_ID" && args.content == "Book hotel"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("comment")
+            .arg("add")
+            .arg("MOCK_ITEM_ID")
+            .arg("Book hotel");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Comment added to 'Plan trip'."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn comment_list_shows_cached_notes() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached task with a comment
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Plan trip".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    notes: Some(vec![Note {
+                        id: "MOCK_NOTE_ID".to_string(),
+                        item_id: "MOCK_ITEM_ID".to_string(),
+                        content: "Check flights".to_string(),
+                        posted_at: "2024-01-01T09:00:00Z".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, offline
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("comment").arg("list").arg("MOCK_ITEM_ID");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Comments on 'Plan trip':"))
+            .stdout(predicates::str::contains("Check flights"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_shows_comment_counts() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached task with a comment
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Plan trip".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    notes: Some(vec![Note {
+                        id: "MOCK_NOTE_ID".to_string(),
+                        item_id: "MOCK_ITEM_ID".to_string(),
+                        content: "Check flights".to_string(),
+                        posted_at: "2024-01-01T09:00:00Z".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, offline
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("list");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Plan trip [1 comment]"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reminder_add_sends_relative_reminder() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached task with a reminder
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Dentist".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        due: Some(Due {
+                            date: "2024-01-01T10:00:00".to_string(),
+                            string: Some("jan 1 10am".to_string()),
+                            is_recurring: false,
+                        }),
+                        ..Default::default()
+                    }]),
+                    reminders: Some(vec![Reminder {
+                        id: "MOCK_REMINDER_ID".to_string(),
+                        item_id: "MOCK_ITEM_ID".to_string(),
+                        kind: ReminderKind::Relative,
+                        due: None,
+                        minute_offset: Some(60),
+                        name: None,
+                        loc_lat: None,
+                        loc_long: None,
+                        loc_trigger: None,
+                        radius: None,
+                        is_deleted: false,
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ReminderAdd(args)
+                                if args.item_id == "MOCK_ITEM_ID"
+                                    && args.kind == ReminderKind::Relative
+                                    && args.minute_offset == Some(30)
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server_url);
+        cmd.arg("reminder")
+            .arg("add")
+            .arg("MOCK_ITEM_ID")
+            .arg("30 minutes before");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Reminder added to 'Dentist'."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reminder_list_shows_cached_reminders() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached task with a reminder
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Dentist".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        due: Some(Due {
+                            date: "2024-01-01T10:00:00".to_string(),
+                            string: Some("jan 1 10am".to_string()),
+                            is_recurring: false,
+                        }),
+                        ..Default::default()
+                    }]),
+                    reminders: Some(vec![Reminder {
+                        id: "MOCK_REMINDER_ID".to_string(),
+                        item_id: "MOCK_ITEM_ID".to_string(),
+                        kind: ReminderKind::Relative,
+                        due: None,
+                        minute_offset: Some(60),
+                        name: None,
+                        loc_lat: None,
+                        loc_long: None,
+                        loc_trigger: None,
+                        radius: None,
+                        is_deleted: false,
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, offline
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("reminder").arg("list").arg("MOCK_ITEM_ID");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Reminders for 'Dentist':"))
+            .stdout(predicates::str::contains("MOCK_REMINDER_ID  1 hour before"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn undo_keeps_the_entry_if_the_reversal_fails() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_store(|store| {
+                store.push_undo_entry(&undo::Entry {
+                    action: "completing 'Buy milk'".to_string(),
+                    inverse: CommandArgs::ItemUncomplete(ItemIdArgs {
+                        id: "MOCK_ITEM_ID".to_string(),
+                    }),
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+        let mock_server = ApiMockBuilder::new().await.mock_status("sync", 500).await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args(["--retries", "0", "undo"]);
+        cmd.assert().failure();
+
+        // so it can be tried again
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert!(store
+            .last_undo_entry()?
+            .is_some_and(|entry| entry.action == "completing 'Buy milk'"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn undo_reopens_completed_item() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a cached task
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Buy milk".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // set up mock server, which accepts the completion and then its reversal
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemClose(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemUncomplete(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let server_url = mock_server.uri();
+
+        // run the thing: complete the task, then undo that
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(&server_url);
+        cmd.arg("complete").arg("MOCK_ITEM_ID");
+        cmd.assert().success();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(&server_url);
+        cmd.arg("undo");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Undid completing 'Buy milk'."));
+
+        // there's nothing left to undo
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(&server_url);
+        cmd.arg("undo");
+        cmd.assert()
+            .failure()
+            .stderr(predicates::str::contains("There's nothing to undo."));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_narrows_by_label() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and some cached tasks
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![
+                        Item {
+                            id: "1".to_string(),
+                            content: "Call the dentist".to_string(),
+                            labels: vec!["phone".to_string()],
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "2".to_string(),
+                            content: "Pay the dentist".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, offline
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("search").arg("dentst").arg("--label").arg("@Phone");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("1  Call the dentist"))
+            .stdout(predicates::str::contains("Pay the dentist").not());
+
+        // check that the results can be referred to by index
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert_eq!(
+            store.last_list_entry(1)?,
+            Some(("1".to_string(), "Call the dentist".to_string()))
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_list_and_complete_against_fake_server() -> Result<(), Box<dyn std::error::Error>> {
+        // only the API key exists locally; the user is fetched from the server
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert().success()
+        };
+
+        run(&["--add", "Buy milk"])
+            .stdout(predicates::str::contains("Todo 'Buy milk' added to inbox."));
+        run(&["--add", "Call mom"]);
+        run(&["list"])
+            .stdout(predicates::str::contains("1  Buy milk"))
+            .stdout(predicates::str::contains("2  Call mom"));
+
+        run(&["complete", "1"]).stdout(predicates::str::contains("Completed 'Buy milk'."));
+
+        // the next list only gets the changes since the last one
+        run(&["list"])
+            .stdout(predicates::str::contains("1  Call mom"))
+            .stdout(predicates::str::contains("Buy milk").not());
+
+        let items = server.items();
+        assert!(items
+            .iter()
+            .any(|item| item.content == "Buy milk" && item.checked));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn queued_add_reaches_fake_server() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml` and `data/user.json`
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        // add while offline
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--add").arg("plane todo");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Could not reach Todoist"));
+
+        // then list once back online
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.arg("list");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Queued command succeeded: add 'plane todo'.",
+            ))
+            .stdout(predicates::str::contains("1  plane todo"));
+
+        assert_eq!(server.items().len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_completed_tasks_with_who_completed_them() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    projects: Some(vec![Project {
+                        id: "MOCK_HOME_PROJECT_ID".to_string(),
+                        name: "Home".to_string(),
+                        ..Default::default()
+                    }]),
+                    collaborators: Some(vec![Collaborator {
+                        id: "MOCK_SAM_ID".to_string(),
+                        email: "sam@example.com".to_string(),
+                        full_name: "Sam Smith".to_string(),
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |_: Request| true,
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_get(
+                "completed/get_all",
+                &[("project_id", "MOCK_HOME_PROJECT_ID"), ("offset", "0")],
+                CompletedPage {
+                    items: vec![
+                        CompletedItem {
+                            id: "C2".to_string(),
+                            task_id: "T2".to_string(),
+                            content: "Water plants".to_string(),
+                            project_id: "MOCK_HOME_PROJECT_ID".to_string(),
+                            completed_at: "2024-05-02T09:30:00Z".to_string(),
+                            user_id: Some("MOCK_SAM_ID".to_string()),
+                        },
+                        CompletedItem {
+                            id: "C1".to_string(),
+                            task_id: "T1".to_string(),
+                            content: "Take out trash".to_string(),
+                            project_id: "MOCK_HOME_PROJECT_ID".to_string(),
+                            completed_at: "2024-05-01T18:00:00Z".to_string(),
+                            user_id: None,
+                        },
+                    ],
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_fs.path());
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("completed").arg("--project").arg("home");
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Water plants (Home, by Sam Smith)",
+            ))
+            .stdout(predicates::str::contains("Take out trash (Home)"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn show_activity_in_a_shared_project() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    collaborators: Some(vec![Collaborator {
+                        id: "MOCK_SAM_ID".to_string(),
+                        email: "sam@example.com".to_string(),
+                        full_name: "Sam Smith".to_string(),
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+
+        let event = |event_type: &str, content: &str| Event {
+            id: format!("{event_type} {content}"),
+            object_type: "item".to_string(),
+            object_id: "T1".to_string(),
+            event_type: event_type.to_string(),
+            event_date: "2024-05-02T09:30:00Z".to_string(),
+            parent_project_id: Some("MOCK_HOME_PROJECT_ID".to_string()),
+            initiator_id: Some("MOCK_SAM_ID".to_string()),
+            extra_data: EventData {
+                content: Some(content.to_string()),
+                ..Default::default()
+            },
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |_: Request| true,
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_HOME_PROJECT_ID".to_string(),
+                        name: "Home".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_get(
+                "activity/get",
+                &[("parent_project_id", "MOCK_HOME_PROJECT_ID")],
+                ActivityPage {
+                    events: vec![
+                        event("completed", "Water plants"),
+                        event("added", "Water plants"),
+                    ],
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_fs.path());
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("activity").arg("--project").arg("home");
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Completed 'Water plants' (Home, by Sam Smith)",
+            ))
+            .stdout(predicates::str::contains("Added 'Water plants'"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn move_task_to_another_project_after_a_task() -> Result<(), Box<dyn std::error::Error>> {
+        let item = |id: &str, project_id: &str, child_order: i64| Item {
+            id: id.to_string(),
+            content: format!("todo {id}"),
+            project_id: project_id.to_string(),
+            child_order,
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    projects: Some(vec![Project {
+                        id: "MOCK_HOME_PROJECT_ID".to_string(),
+                        name: "Home".to_string(),
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![
+                        item("T1", "MOCK_INBOX_PROJECT_ID", 1),
+                        item("H2", "MOCK_HOME_PROJECT_ID", 2),
+                        item("H1", "MOCK_HOME_PROJECT_ID", 1),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| match request.commands.as_slice() {
+                    [SyncCommand {
+                        args: CommandArgs::ItemMove(moved),
+                        ..
+                    }, SyncCommand {
+                        args: CommandArgs::ItemReorder(reordered),
+                        ..
+                    }] => {
+                        let order: Vec<(&str, i64)> = reordered
+                            .items
+                            .iter()
+                            .map(|child| (child.id.as_str(), child.child_order))
+                            .collect();
+                        moved.id == "T1"
+                            && moved.project_id.as_deref() == Some("MOCK_HOME_PROJECT_ID")
+                            && order == [("H1", 1), ("T1", 2), ("H2", 3)]
+                    }
+                    _ => false,
+                },
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_fs.path());
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args([
+            "move",
+            "T1",
+            "--to-project",
+            "home",
+            "--position",
+            "after",
+            "H1",
+        ]);
+
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Moved 'todo T1' to Home, after 'todo H1'.",
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_todos_from_stdin_in_chunks() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?;
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        // more todos than fit in one request, with a blank line to skip
+        let mut input = (1..=100)
+            .map(|index| format!("todo {index}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        input.push('\n');
+        input.push_str("\ncall mom p1\n");
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_fs.path());
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.arg("add").arg("--stdin").write_stdin(input);
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Added 101 todos:"))
+            .stdout(predicates::str::contains("ITEM_1  todo 1\n"))
+            .stdout(predicates::str::contains("ITEM_101  call mom"));
+
+        let items = server.items();
+        assert_eq!(items.len(), 101);
+        assert!(items
+            .iter()
+            .any(|item| item.content == "call mom" && item.priority == 4));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn export_a_project_to_a_markdown_file() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    projects: Some(vec![
+                        Project {
+                            id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            name: "Inbox".to_string(),
+                            ..Default::default()
+                        },
+                        Project {
+                            id: "MOCK_HOME_PROJECT_ID".to_string(),
+                            name: "Home".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    items: Some(vec![
+                        Item {
+                            id: "T1".to_string(),
+                            content: "Water plants".to_string(),
+                            project_id: "MOCK_HOME_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "T2".to_string(),
+                            content: "Call mom".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let out = mock_fs.path().join("home.md");
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_fs.path());
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.args(["export", "--format", "md", "--project", "home", "--out"])
+            .arg(&out);
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Exported 1 tasks to"));
+        assert_eq!(
+            std::fs::read_to_string(out)?,
+            "# Home\n\n- [ ] Water plants\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn import_creates_projects_and_subtasks() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents(
+                "garden.md",
+                "- [ ] Water plants\n\n# Garden\n- [ ] Plant tulips @errand\n  - [x] Buy bulbs\n",
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+        let file = mock_data_dir.join("garden.md");
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.arg("import").arg(&file);
+            cmd.args(args);
+            cmd.assert().success()
+        };
+
+        run(&["--dry-run"])
+            .stdout(predicates::str::contains("Would create project 'Garden'."))
+            .stdout(predicates::str::contains("Would add 3 tasks:"))
+            .stdout(predicates::str::contains("    - [x] Buy bulbs"));
+        assert!(server.items().is_empty());
+
+        run(&[]).stdout(predicates::str::contains(
+            "Imported 3 tasks, creating 1 projects and sections.",
+        ));
+        let items = server.items();
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].project_id, "MOCK_INBOX_PROJECT_ID");
+        assert!(items[1].project_id.starts_with("CONTAINER_"));
+        assert_eq!(items[1].labels, ["errand"]);
+        assert_eq!(items[2].parent_id.as_deref(), Some(items[1].id.as_str()));
+        assert!(items[2].checked);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn assign_tasks_and_list_my_own() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew Zemke",
+                    "email": "drew@example.com",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    collaborators: Some(vec![
+                        Collaborator {
+                            id: "MOCK_DREW_ID".to_string(),
+                            email: "drew@example.com".to_string(),
+                            full_name: "Drew Zemke".to_string(),
+                        },
+                        Collaborator {
+                            id: "MOCK_SAM_ID".to_string(),
+                            email: "sam@example.com".to_string(),
+                            full_name: "Sam Smith".to_string(),
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert().success()
+        };
+
+        run(&["--add", "Water plants", "--assign", "sam@example.com"]);
+        run(&["--add", "Buy milk", "--assign", "drew"]);
+        run(&["list"]).stdout(predicates::str::contains("Water plants +Sam"));
+
+        // older user data has no ID, so the user is found by email
+        run(&["list", "--assigned-to-me"])
+            .stdout(predicates::str::contains("1  Buy milk +Drew"))
+            .stdout(predicates::str::contains("Water plants").not());
+
+        run(&["edit", "1", "--assign", ""])
+            .stdout(predicates::str::contains("Assignee: Drew Zemke → (none)"));
+        let items = server.items();
+        assert_eq!(items[0].responsible_uid.as_deref(), Some("MOCK_SAM_ID"));
+        assert_eq!(items[1].responsible_uid, None);
+        Ok(())
+    }
+
+    #[test]
+    fn complete_without_a_task_needs_a_terminal() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        // the picker only opens on a terminal, which tests don't have
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("complete");
+        cmd.assert()
+            .failure()
+            .stderr(predicates::str::contains("No task given."));
+        Ok(())
+    }
+
+    #[test]
+    fn notify_announces_each_overdue_task_once() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Pay rent".to_string(),
+                        due: Some(Due {
+                            date: "2020-01-01".to_string(),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let run = || {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+            cmd.args(["--retries", "0"]);
+            cmd.arg("notify");
+            cmd.assert().success()
+        };
+        run().stdout(predicates::str::contains("Task overdue: Pay rent"));
+        run().stdout(predicates::str::contains("Pay rent").not());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn daemon_keeps_the_store_synced() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.arg("--add").arg("Buy milk");
+        cmd.assert().success();
+
+        let mut daemon = std::process::Command::new(assert_cmd::cargo::cargo_bin("todoist"))
+            .arg("--local-dir")
+            .arg(mock_data_dir)
+            .arg("--sync-url")
+            .arg(server.uri())
+            .args(["daemon", "--interval", "3600"])
+            .stdout(std::process::Stdio::null())
+            .spawn()?;
+
+        // wait for the daemon to start listening, then for it to sync
+        let socket = mock_data_dir.join("data").join(daemon::SOCKET_NAME);
+        let mut status = None;
+        for _ in 0..100 {
+            status = daemon::request(&socket, &daemon::Request::Sync).await?;
+            if status.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        let synced = status.is_some_and(|status| status.last_sync.is_some());
+
+        // with the daemon's sync fresh, listing doesn't try to reach the server
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("list");
+        let listed = cmd.assert();
+        daemon.kill()?;
+        daemon.wait()?;
+
+        assert!(synced);
+        listed
+            .success()
+            .stdout(predicates::str::contains("1  Buy milk"))
+            .stdout(predicates::str::contains("Could not reach").not());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn daemon_stops_when_the_token_is_rejected() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"REVOKED_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let mock_server = ApiMockBuilder::new().await.mock_status("sync", 401).await;
+
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args(["daemon", "--interval", "3600"]);
+        cmd.timeout(std::time::Duration::from_secs(10));
+        cmd.assert()
+            .failure()
+            .stderr(predicates::str::contains("run `todoist auth <token>`"));
+        assert!(!mock_data_dir
+            .join("data")
+            .join(daemon::SOCKET_NAME)
+            .exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn verbose_prints_request_timing() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.args(["list", "--verbose"]);
+        cmd.assert()
+            .success()
+            .stderr(predicates::str::contains(format!(
+                "POST {}/sync: 200 OK",
+                server.uri()
+            )))
+            .stderr(predicates::str::contains(" ms"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dry_run_prints_commands_without_sending_them() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        // JSON output keeps other messages out of stdout
+        cmd.args([
+            "--dry-run",
+            "--output",
+            "json",
+            "--add",
+            "Buy milk p1 @errand",
+        ]);
+        let output = cmd.assert().success().get_output().stdout.clone();
+
+        let commands: Vec<serde_json::Value> = serde_json::from_slice(&output)?;
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0]["type"], "item_add");
+        assert_eq!(commands[0]["args"]["content"], "Buy milk");
+        assert_eq!(commands[0]["args"]["priority"], 4);
+        assert!(server.items().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn log_file_records_sync_summaries() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents("config.toml", "log_file = true")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.arg("--config").arg(mock_data_dir.join("config.toml"));
+        cmd.arg("list");
+        cmd.assert()
+            .success()
+            .stderr(predicates::str::contains("POST").not());
+
+        let mut logs = String::new();
+        for entry in std::fs::read_dir(mock_data_dir.join("logs"))? {
+            logs.push_str(&std::fs::read_to_string(entry?.path())?);
+        }
+        assert!(logs.contains("sync response"));
+        assert!(!logs.contains("MOCK_API_KEY"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn requests_go_through_the_proxy() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let proxy = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        // the sync URL can't be reached directly, only through the proxy
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--proxy").arg(proxy.uri());
+        cmd.args(["--add", "Buy milk"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo 'Buy milk' added to inbox."));
+        assert_eq!(proxy.items().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn open_links_to_tasks_and_projects() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    projects: Some(vec![Project {
+                        id: "P_WORK".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![Item {
+                        id: "T_REPORT".to_string(),
+                        content: "Write the quarterly report".to_string(),
+                        project_id: "P_WORK".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let open = |args: &[&str]| -> Result<String, Box<dyn std::error::Error>> {
+            let mut cmd = Command::cargo_bin("todoist")?;
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+            cmd.args(["--retries", "0"]);
+            cmd.arg("open").args(args).arg("--print");
+            let output = cmd.assert().success().get_output().stdout.clone();
+            let output = String::from_utf8(output)?;
+            Ok(output.lines().next().unwrap_or_default().to_string())
+        };
+
+        assert_eq!(
+            open(&["quarterly"])?,
+            "https://app.todoist.com/app/task/T_REPORT"
+        );
+        assert_eq!(
+            open(&["#work"])?,
+            "https://app.todoist.com/app/project/P_WORK"
+        );
+        assert_eq!(open(&["Work", "--app"])?, "todoist://project?id=P_WORK");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn descriptions_are_added_edited_and_listed() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert().success()
+        };
+
+        let description = "Ask about the fence.\nAnd the gutters, which have been leaking since \
+                           the storm last spring.";
+        run(&["--add", "Call the landlord", "-d", description]);
+        assert_eq!(server.items()[0].description, description);
+
+        run(&["list"])
+            .stdout(predicates::str::contains("      Ask about the fence.…"))
+            .stdout(predicates::str::contains("gutters").not());
+        run(&["list", "--long"]).stdout(predicates::str::contains(
+            "      And the gutters, which have been leaking",
+        ));
+
+        run(&["edit", "1", "--description", "Just the fence."]).stdout(predicates::str::contains(
+            "Description: Ask about the fence.… → Just the fence.",
+        ));
+        assert_eq!(server.items()[0].description, "Just the fence.");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn add_and_edit_durations() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert()
+        };
+
+        run(&["--add", "Write report", "--duration", "1h30m"]).success();
+        run(&["list"])
+            .success()
+            .stdout(predicates::str::contains("(1h 30m)"));
+        run(&["--add", "Nap", "--duration", "soon"])
+            .failure()
+            .stderr(predicates::str::contains("expected a duration"));
+
+        run(&["edit", "1", "--duration", "45m"])
+            .success()
+            .stdout(predicates::str::contains("1h 30m → 45m"));
+        let duration = |server: &FakeSyncServer| {
+            server
+                .items()
+                .into_iter()
+                .find(|item| item.content == "Write report")
+                .and_then(|item| item.duration)
+        };
+        assert_eq!(
+            duration(&server),
+            Some(TaskDuration {
+                amount: 45,
+                unit: DurationUnit::Minute,
+            })
+        );
+
+        run(&["edit", "1", "--duration", ""]).success();
+        assert_eq!(duration(&server), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn today_schedule_lays_out_a_timeline() -> Result<(), Box<dyn std::error::Error>> {
+        let today = chrono::Local::now().date_naive();
+        let item = |id: &str, content: &str, time: Option<&str>, minutes: Option<u32>| Item {
+            id: id.to_string(),
+            content: content.to_string(),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            due: Some(Due {
+                date: match time {
+                    Some(time) => format!("{}T{time}:00", today.format("%Y-%m-%d")),
+                    None => today.format("%Y-%m-%d").to_string(),
+                },
+                ..Default::default()
+            }),
+            duration: minutes.map(|amount| TaskDuration {
+                amount,
+                unit: DurationUnit::Minute,
+            }),
+            ..Default::default()
+        };
+        let items = vec![
+            item("MOCK_ITEM_ID_1", "Lunch", Some("12:00"), Some(60)),
+            item("MOCK_ITEM_ID_2", "Standup", Some("09:00"), Some(45)),
+            item("MOCK_ITEM_ID_3", "Review", Some("09:30"), Some(30)),
+            item("MOCK_ITEM_ID_4", "Errands", None, Some(20)),
+        ];
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(items),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.args(["today", "--schedule"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "   1  09:00–09:45  Standup\n   2  09:30–10:00  Review  (overlaps)\n      10:00–12:00  (free, 2h)\n   3  12:00–13:00  Lunch\n\n  Any time today:\n   4  Errands (20m)",
+        ));
+
+        // the timeline's numbers can be used to refer to its tasks
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert_eq!(
+            store.last_list_entry(2)?,
+            Some(("MOCK_ITEM_ID_3".to_string(), "Review".to_string()))
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn snooze_one_task_or_all_overdue() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert()
+        };
+        let due = |content: &str| {
+            server
+                .items()
+                .into_iter()
+                .find(|item| item.content == content)
+                .and_then(|item| item.due)
+                .map(|due| due.date)
+        };
+
+        run(&["--add", "Pay rent"]).success();
+        let in_three_days = (chrono::Local::now().date_naive() + chrono::Duration::days(3))
+            .format("%Y-%m-%d")
+            .to_string();
+        run(&["snooze", "last", "+3d"])
+            .success()
+            .stdout(predicates::str::contains(format!(
+                "Snoozed 'Pay rent' until {in_three_days}."
+            )));
+        assert_eq!(due("Pay rent"), Some(in_three_days));
+
+        run(&["snooze", "last", "+3 fortnights"])
+            .failure()
+            .stderr(predicates::str::contains(
+                "Can't snooze until '+3 fortnights'",
+            ));
+
+        run(&["--add", "Water plants", "--due", "2020-01-01"]).success();
+        run(&["--add", "File taxes", "--due", "2020-04-15"]).success();
+        run(&["list"]).success();
+        run(&["snooze", "--all-overdue", "tomorrow"])
+            .success()
+            .stdout(predicates::str::contains(
+                "Snoozed 2 overdue tasks until tomorrow.",
+            ));
+        assert_eq!(due("Water plants").as_deref(), Some("tomorrow"));
+        assert_eq!(due("File taxes").as_deref(), Some("tomorrow"));
+
+        run(&["snooze", "last", "tomorrow", "--all-overdue"])
+            .failure()
+            .stderr(predicates::str::contains(
+                "Pass only WHEN with --all-overdue",
+            ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn misspelled_due_dates_are_caught_before_sending(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert()
+        };
+
+        run(&["--add", "Water plants", "--due", "evry day"])
+            .failure()
+            .stderr(predicates::str::contains(
+                "Invalid due date: did you mean 'every' instead of 'evry'",
+            ));
+        assert!(server.items().is_empty());
+
+        run(&["--add", "Water plants", "--due", "every day"]).success();
+        run(&["edit", "last", "--due", "every 25:00"])
+            .failure()
+            .stderr(predicates::str::contains("'25:00' isn't a time"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn offline_edits_that_conflict_are_not_sent_blindly(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mine = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let theirs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |data_dir: &std::path::Path, sync_url: &str, args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(data_dir);
+            cmd.arg("--sync-url").arg(sync_url);
+            cmd.args(["--retries", "0"]);
+            cmd.args(args);
+            cmd.assert()
+        };
+
+        run(mine.path(), &server.uri(), &["--add", "Buy milk"]).success();
+        run(mine.path(), &server.uri(), &["list"]).success();
+        run(
+            mine.path(),
+            "http://127.0.0.1:1",
+            &["edit", "1", "--content", "Buy oat milk"],
+        )
+        .success();
+        run(
+            theirs.path(),
+            &server.uri(),
+            &["edit", "ITEM_1", "--content", "Buy almond milk"],
+        )
+        .success();
+
+        // there's no terminal to ask on, so nothing is sent
+        run(mine.path(), &server.uri(), &["list"])
+            .failure()
+            .stderr(predicates::str::contains(
+                "1 queued changes conflict with changes made elsewhere",
+            ));
+        run(
+            mine.path(),
+            &server.uri(),
+            &["list", "--on-conflict", "keep-remote"],
+        )
+        .success()
+        .stdout(predicates::str::contains(
+            "Dropped queued command: update task ITEM_1",
+        ))
+        .stdout(predicates::str::contains("1  Buy almond milk"));
+        assert_eq!(server.items()[0].content, "Buy almond milk");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn triage_goes_through_the_inbox() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        for todo in ["Buy milk", "Call mom", "Old idea", "Someday"] {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.arg("--add").arg(todo);
+            cmd.assert().success();
+        }
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.arg("triage");
+        // complete the first task; label, date, and skip the second; delete
+        // the third; and quit at the fourth
+        cmd.write_stdin("c\nl\n@phone family\nd\nevry day\nd\nevery day\ns\nx\ny\nq\n");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("(1/4) Buy milk"))
+            .stdout(predicates::str::contains("Completed."))
+            .stdout(predicates::str::contains("Labeled @phone @family."))
+            .stdout(predicates::str::contains("did you mean 'every'"))
+            .stdout(predicates::str::contains("Due every day."))
+            .stdout(predicates::str::contains("Deleted."))
+            .stdout(predicates::str::contains("(4/4) Someday"))
+            .stdout(predicates::str::contains("Triaged 2 of 4 inbox tasks."));
+
+        let items = server.items();
+        let item = |content: &str| items.iter().find(|item| item.content == content);
+        assert!(item("Buy milk").is_some_and(|item| item.checked));
+        assert!(
+            item("Call mom").is_some_and(|item| item.labels == ["phone", "family"]
+                && item.due.as_ref().is_some_and(|due| due.date == "every day"))
+        );
+        assert!(item("Old idea").is_some_and(|item| item.is_deleted));
+        assert!(item("Someday").is_some_and(|item| !item.checked && !item.is_deleted));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_projects_with_counts() -> Result<(), Box<dyn std::error::Error>> {
+        let project = |id: &str, name: &str, parent_id: Option<&str>, child_order| Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            parent_id: parent_id.map(ToString::to_string),
+            child_order,
+            ..Default::default()
+        };
+        let item = |id: &str, project_id: &str, date: Option<&str>| Item {
+            id: id.to_string(),
+            project_id: project_id.to_string(),
+            content: format!("Todo {id}"),
+            due: date.map(|date| Due {
+                date: date.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![
+                        project("MOCK_INBOX_PROJECT_ID", "Inbox", None, 0),
+                        project("MOCK_WORK_ID", "Work", None, 1),
+                        project("MOCK_REPORTS_ID", "Reports", Some("MOCK_WORK_ID"), 0),
+                    ]),
+                    items: Some(vec![
+                        item("1", "MOCK_INBOX_PROJECT_ID", None),
+                        item("2", "MOCK_REPORTS_ID", Some("2000-01-01")),
+                        item("3", "MOCK_REPORTS_ID", Some("2000-01-02")),
+                        item("4", "MOCK_REPORTS_ID", None),
+                        Item {
+                            checked: true,
+                            ..item("5", "MOCK_WORK_ID", Some("2000-01-01"))
+                        },
+                    ]),
+                    sections: Some(vec![Section {
+                        id: "MOCK_SECTION_ID".to_string(),
+                        name: "Planning".to_string(),
+                        project_id: "MOCK_WORK_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("projects").arg("list").arg("--counts");
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Projects:     Open  Overdue  Sections\n  Inbox          1        0         0\n  Work           0        0         1\n    Reports      3        2         0\n",
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--output").arg("plain");
+        cmd.arg("projects")
+            .arg("list")
+            .arg("--counts")
+            .arg("--sort")
+            .arg("sections");
+        cmd.assert().success().stdout(
+            "MOCK_WORK_ID\tWork\t0\t0\t1\nMOCK_INBOX_PROJECT_ID\tInbox\t1\t0\t0\nMOCK_REPORTS_ID\tReports\t3\t2\t0\n",
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn archived_projects_and_items_are_fetched_on_demand(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_get(
+                "projects/get_archived",
+                &[],
+                vec![Project {
+                    id: "MOCK_OLD_ID".to_string(),
+                    name: "Old Stuff".to_string(),
+                    is_archived: true,
+                    ..Default::default()
+                }],
+            )
+            .await
+            .mock_get(
+                "archive/items",
+                &[("project_id", "MOCK_OLD_ID")],
+                ArchivedPage {
+                    items: vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Finish the old thing".to_string(),
+                        project_id: "MOCK_OLD_ID".to_string(),
+                        checked: true,
+                        completed_at: Some("2024-05-01T12:00:00Z".to_string()),
+                        ..Default::default()
+                    }],
+                    has_more: false,
+                    next_cursor: None,
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("archive").arg("list");
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Archived projects:\n  Old Stuff\n",
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("archive").arg("show").arg("old");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Completed in Old Stuff:"))
+            .stdout(predicates::str::contains("Finish the old thing"));
+
+        // both are in the cache now, for when Todoist can't be reached
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--output").arg("plain");
+        cmd.arg("archive").arg("show").arg("old");
+        cmd.assert()
+            .success()
+            .stdout("MOCK_ITEM_ID\t2024-05-01T12:00:00Z\tFinish the old thing\n");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn favorites_can_be_listed_by_name() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        let item = |id: &str, project_id: &str, priority| Item {
+            id: id.to_string(),
+            content: format!("Todo {id}"),
+            project_id: project_id.to_string(),
+            priority,
+            ..Default::default()
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types.contains(&"filters".to_string()),
+                Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![
+                        Project {
+                            id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            name: "Inbox".to_string(),
+                            ..Default::default()
+                        },
+                        Project {
+                            id: "MOCK_WORK_ID".to_string(),
+                            name: "Work".to_string(),
+                            is_favorite: true,
+                            ..Default::default()
+                        },
+                    ]),
+                    labels: Some(vec![Label {
+                        id: "MOCK_LABEL_ID".to_string(),
+                        name: "email".to_string(),
+                        is_favorite: true,
+                        ..Default::default()
+                    }]),
+                    filters: Some(vec![
+                        Filter {
+                            id: "MOCK_FILTER_ID".to_string(),
+                            name: "Priority Work".to_string(),
+                            query: "p1 & #Work".to_string(),
+                            is_favorite: true,
+                            ..Default::default()
+                        },
+                        Filter {
+                            id: "MOCK_OTHER_FILTER_ID".to_string(),
+                            name: "Someday".to_string(),
+                            query: "no date".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    items: Some(vec![
+                        item("urgent", "MOCK_WORK_ID", 4),
+                        item("whenever", "MOCK_WORK_ID", 1),
+                        item("elsewhere", "MOCK_INBOX_PROJECT_ID", 4),
+                    ]),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("fav");
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Favorites:\n  #Work\n  @email\n  Priority Work  (p1 & #Work)\n",
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("list").arg("priority");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo urgent"))
+            .stdout(predicates::str::contains("Todo whenever").not())
+            .stdout(predicates::str::contains("Todo elsewhere").not());
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("list").arg("someday");
+        cmd.assert().failure().stderr(predicates::str::contains(
+            "Could not find a favorite named 'someday'.",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn saved_filters_are_listed_and_run_locally() -> Result<(), Box<dyn std::error::Error>> {
+        let item = |id: &str, labels: &[&str]| Item {
+            id: id.to_string(),
+            content: format!("Todo {id}"),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            labels: labels.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    filters: Some(vec![
+                        Filter {
+                            id: "MOCK_ERRANDS_ID".to_string(),
+                            name: "Errands".to_string(),
+                            query: "@errand | @shopping".to_string(),
+                            item_order: 2,
+                            ..Default::default()
+                        },
+                        Filter {
+                            id: "MOCK_CALLS_ID".to_string(),
+                            name: "Calls".to_string(),
+                            query: "@phone".to_string(),
+                            item_order: 1,
+                            is_favorite: true,
+                            ..Default::default()
+                        },
+                    ]),
+                    items: Some(vec![
+                        item("milk", &["shopping"]),
+                        item("bank", &["errand"]),
+                        item("mom", &["phone"]),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("filters").arg("list");
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Filters:\n  Calls ★  (@phone)\n  Errands  (@errand | @shopping)\n",
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("list").arg("--filter-name").arg("errands");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Todo milk"))
+            .stdout(predicates::str::contains("Todo bank"))
+            .stdout(predicates::str::contains("Todo mom").not());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn dates_follow_the_locale() -> Result<(), Box<dyn std::error::Error>> {
+        let item = |id: &str, due: Due| Item {
+            id: id.to_string(),
+            content: format!("Todo {id}"),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            due: Some(due),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID",
+                    "lang": "de",
+                    "tz_info": { "timezone": "Europe/Berlin", "gmt_string": "+02:00" }
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![
+                        item(
+                            "dated",
+                            Due {
+                                date: "2030-05-01".to_string(),
+                                ..Default::default()
+                            },
+                        ),
+                        item(
+                            "timed",
+                            Due {
+                                date: "2030-05-02T10:00:00Z".to_string(),
+                                ..Default::default()
+                            },
+                        ),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // dates in the given format, and times in the account's timezone
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--date-format").arg("%d.%m.%Y");
+        cmd.arg("list");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("01.05.2030"))
+            .stdout(predicates::str::contains("02.05.2030 12:00"));
+
+        // due dates go out in the account's language, without an English
+        // spell check
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemUpdate(args) if args.due.as_ref().is_some_and(
+                                |due| due.string == "jeden montag" && due.lang.as_deref() == Some("de")
+                            )
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args(["edit", "dated", "--due", "jeden montag"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Updated 'Todo dated':"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn quick_adds_are_parsed_by_todoist() -> Result<(), Box<dyn std::error::Error>> {
+        let text = "Lunch with Sam tomorrow 1pm #Personal @food";
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_PERSONAL_ID".to_string(),
+                        name: "Personal".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "quick/add",
+                move |body: serde_json::Value| body["text"] == text,
+                Item {
+                    id: "MOCK_ITEM_ID".to_string(),
+                    content: "Lunch with Sam".to_string(),
+                    project_id: "MOCK_PERSONAL_ID".to_string(),
+                    labels: vec!["food".to_string()],
+                    due: Some(Due {
+                        date: "2024-05-02T13:00:00".to_string(),
+                        string: Some("tomorrow 1pm".to_string()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("quick").arg(text);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Todo 'Lunch with Sam' added to Personal, due tomorrow 1pm.",
+        ));
+
+        // the new task is the one that `last` refers to
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("--dry-run");
+        cmd.arg("complete").arg("last");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("MOCK_ITEM_ID"));
+
+        // it can't be queued like `add`
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("quick").arg(text);
+        cmd.assert()
+            .failure()
+            .stderr(predicates::str::contains("Use `add`"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sync_reports_what_changed() -> Result<(), Box<dyn std::error::Error>> {
+        let inbox = || Project {
+            id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            name: "Inbox".to_string(),
+            ..Default::default()
+        };
+        let todo = |id: &str, content: &str| Item {
+            id: id.to_string(),
+            content: content.to_string(),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_OLD_SYNC_TOKEN".to_string(),
+                    items: Some(vec![todo("1", "Done elsewhere")]),
+                    projects: Some(vec![inbox()]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.sync_token == "*",
+                Response {
+                    full_sync: true,
+                    sync_token: "MOCK_FULL_SYNC_TOKEN".to_string(),
+                    items: Some(Vec::new()),
+                    projects: Some(vec![inbox()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.sync_token == "MOCK_OLD_SYNC_TOKEN",
+                Response {
+                    sync_token: "MOCK_NEW_SYNC_TOKEN".to_string(),
+                    items: Some(vec![
+                        Item {
+                            checked: true,
+                            ..todo("1", "Done elsewhere")
+                        },
+                        todo("2", "Added elsewhere"),
+                    ]),
+                    projects: Some(vec![Project {
+                        is_favorite: true,
+                        ..inbox()
+                    }]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.sync_token == "MOCK_NEW_SYNC_TOKEN",
+                Response {
+                    sync_token: "MOCK_NEW_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("sync");
+        cmd.assert().success().stdout(predicates::str::starts_with(
+            "Synced: 1 item added, 1 completed remotely; 1 project updated.\n",
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("sync");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::starts_with("Already up to date.\n"));
+
+        // the added item is gone from the full sync, and the project is back
+        // to how it was
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("sync").arg("--full");
+        cmd.assert().success().stdout(predicates::str::starts_with(
+            "Synced: 1 item deleted; 1 project updated.\n",
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fresh_caches_are_read_without_syncing() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents("config.toml", "cache_ttl = 3600\n")?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "1".to_string(),
+                        content: "Cached todo".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // the store was synced just now, so there's no need to try Todoist
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--config").arg(mock_data_dir.join("config.toml"));
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("list");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Cached todo"))
+            .stdout(predicates::str::contains("Could not reach Todoist").not());
+
+        // without a TTL, `--offline` has the same effect
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("list").arg("--offline");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Cached todo"))
+            .stdout(predicates::str::contains("Could not reach Todoist").not());
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("list");
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Could not reach Todoist"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deleted_items_can_be_restored_from_the_trash() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Buy milk".to_string(),
+                        project_id: "MOCK_ERRANDS_ID".to_string(),
+                        labels: vec!["shopping".to_string()],
+                        ..Default::default()
+                    }]),
+                    projects: Some(vec![Project {
+                        id: "MOCK_ERRANDS_ID".to_string(),
+                        name: "Errands".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemDelete(args) if args.id == "MOCK_ITEM_ID"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemAdd(args) if args.content == "Buy milk"
+                                && args.project_id == "MOCK_ERRANDS_ID"
+                                && args.labels == ["shopping"]
+                        )
+                    })
+                },
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args(["delete", "MOCK_ITEM_ID", "--force"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Deleted 'Buy milk'."));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--output").arg("plain");
+        cmd.args(["trash", "list"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::is_match("^MOCK_ITEM_ID\t.+\tBuy milk\n")?);
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args(["trash", "restore", "MOCK_ITEM_ID"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Restored 'Buy milk' to Errands."));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["trash", "list"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Deleted tasks:\n  (no tasks)\n"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn slow_requests_time_out() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_delayed(
+                "sync",
+                std::time::Duration::from_secs(10),
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // a timed-out request is treated like one that couldn't get through
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args(["--timeout", "1", "--retries", "0"]);
+        cmd.arg("--add").arg("Buy milk");
+        cmd.timeout(std::time::Duration::from_secs(5));
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Could not reach Todoist. Todo 'Buy milk' will be added on the next sync.",
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn ctrl_c_cancels_requests_in_flight() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_delayed(
+                "sync",
+                std::time::Duration::from_secs(30),
+                Response::default(),
+            )
+            .await;
+
+        let child = std::process::Command::new(assert_cmd::cargo::cargo_bin("todoist"))
+            .arg("--local-dir")
+            .arg(mock_data_dir)
+            .arg("--sync-url")
+            .arg(mock_server.uri())
+            .args(["--timeout", "0", "list"])
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::piped())
+            .spawn()?;
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        std::process::Command::new("kill")
+            .args(["-INT", &child.id().to_string()])
+            .status()?;
+        let output = child.wait_with_output()?;
+
+        assert_eq!(output.status.code(), Some(130));
+        assert!(String::from_utf8(output.stderr)?.contains("Cancelled."));
+
+        // the store is still fine to read
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["--offline", "list"]);
+        cmd.assert().success();
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn setup_saves_the_token_and_downloads_everything(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // start without any stored files
+        let mock_fs = FsMockBuilder::new()?;
+        let mock_data_dir = mock_fs.path();
+        let config_path = mock_data_dir.join("config.toml");
+
+        // set up mock server
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types == ["user"],
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    user: Some(User {
+                        id: "MOCK_USER_ID".to_string(),
+                        full_name: "Drew".to_string(),
+                        inbox_project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        email: "drew@example.com".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.resource_types != ["user"],
+                Response {
+                    full_sync: true,
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        project_id: "MOCK_ERRANDS_PROJECT_ID".to_string(),
+                        content: "Buy milk".to_string(),
+                        ..Default::default()
+                    }]),
+                    projects: Some(vec![Project {
+                        id: "MOCK_ERRANDS_PROJECT_ID".to_string(),
+                        name: "Errands".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // run the thing, with an empty answer first
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.arg("--config").arg(&config_path);
+        cmd.arg("setup").write_stdin("\nMOCK_API_KEY\nerrands\n");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Authenticated as drew@example.com",
+            ))
+            .stdout(predicates::str::contains(
+                "Downloaded 1 tasks in 1 projects.",
+            ));
+
+        // check that the token, the tasks, and the settings were stored
+        let auth_file = std::fs::read_to_string(mock_data_dir.join("client_auth.toml"))?;
+        assert!(auth_file.contains("api_key = \"MOCK_API_KEY\""));
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        assert_eq!(store.items()?.len(), 1);
+        let config = std::fs::read_to_string(&config_path)?;
+        assert!(config.contains("default_project = \"Errands\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn review_lists_added_and_overdue_tasks() -> Result<(), Box<dyn std::error::Error>> {
+        // create mock `client_auth.toml`, `data/user.json`, and a task added
+        // just now, and one long overdue
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![
+                        Item {
+                            id: "MOCK_NEW_ITEM_ID".to_string(),
+                            content: "Buy milk".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            added_at: Some(chrono::Utc::now().to_rfc3339()),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "MOCK_LATE_ITEM_ID".to_string(),
+                            content: "Pay rent".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            due: Some(Due {
+                                date: "2000-01-01".to_string(),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        },
+                    ]),
+                    projects: Some(vec![Project {
+                        id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        name: "Inbox".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // run the thing, offline
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg("http://127.0.0.1:1");
+        cmd.args(["--retries", "0"]);
+        cmd.arg("review").arg("--markdown");
+
+        // check output
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "## Added (1)\n\n- [ ] Buy milk (Inbox)\n",
+            ))
+            .stdout(predicates::str::contains(
+                "## Overdue (1)\n\n- [ ] Pay rent (due 2000-01-01)\n",
+            ))
+            .stderr(predicates::str::contains("completed tasks are left out"));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn focus_sessions_stopped_early_are_still_counted(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Plan trip".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // start a session, then stop it with ctrl-c
+        let child = std::process::Command::new(assert_cmd::cargo::cargo_bin("todoist"))
+            .arg("--local-dir")
+            .arg(mock_data_dir)
+            .args(["--offline", "focus", "MOCK_ITEM_ID"])
+            .stdout(std::process::Stdio::piped())
+            .spawn()?;
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        std::process::Command::new("kill")
+            .args(["-INT", &child.id().to_string()])
+            .status()?;
+        let output = child.wait_with_output()?;
+
+        assert!(output.status.success());
+        assert!(String::from_utf8(output.stdout)?.contains(
+            "Stopped early, after focusing on 'Plan trip' for 0m. That's 0m in total, over 1 session."
+        ));
+
+        // the session shows up in the totals
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["--offline", "focus"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("0m  Plan trip  (1 session)"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn tracked_time_is_reported_by_project() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents("config.toml", "track_comments = true")?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Write report".to_string(),
+                        project_id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::NoteAdd(args)
+                                if args.item_id == "MOCK_ITEM_ID" && args.content == "Worked on for 0m."
+                        )
+                    })
+                },
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let todoist = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--config").arg(mock_data_dir.join("config.toml"));
+            cmd.arg("--sync-url").arg(mock_server.uri());
+            cmd.args(["--offline"]).args(args);
+            cmd
+        };
+
+        todoist(&["track", "start", "MOCK_ITEM_ID"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Started tracking time on 'Write report'.",
+            ));
+        todoist(&["track", "status"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Tracking time on 'Write report'"));
+        todoist(&["track", "stop"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Tracked 0m on 'Write report'."))
+            .stdout(predicates::str::contains("Could not reach").not());
+        todoist(&["track", "stop"])
+            .assert()
+            .failure()
+            .stderr(predicates::str::contains("The timer isn't running."));
+        todoist(&["track", "report", "--week"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Time tracked this week:\n       0m  Work\n         0m  Write report\n",
+            ));
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn pushed_commits_complete_the_tasks_they_close() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemClose(args) if args.id == "6Jf8VQXx"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+        // a repository with a commit that closes a task
+        let repo = assert_fs::TempDir::new()?;
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=Drew", "-c", "user.email=drew@example.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .output()
+        };
+        git(&["init", "-q"])?;
+        git(&[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-m",
+            "Fix login\n\nCloses todoist:6Jf8VQXx",
+        ])?;
+        let sha = String::from_utf8(git(&["rev-parse", "HEAD"])?.stdout)?;
+        let todoist = || {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.current_dir(repo.path());
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(mock_server.uri());
+            cmd.arg("--offline");
+            cmd
+        };
+
+        // install the hooks
+        todoist()
+            .args(["hook", "install"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Installed the commit-msg and pre-push hooks",
+            ));
+        let hook = std::fs::read_to_string(repo.path().join(".git/hooks/pre-push"))?;
+        assert!(hook.contains("hook pre-push \"$@\" || true"));
+
+        // push the commit, twice
+        let push = format!(
+            "refs/heads/main {} refs/heads/main {}\n",
+            sha.trim(),
+            "0".repeat(40)
+        );
+        todoist()
+            .args(["hook", "pre-push", "origin"])
+            .write_stdin(push.clone())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Completed").not())
+            .stderr(predicates::str::contains("Completed task 6Jf8VQXx ("));
+        todoist()
+            .args(["hook", "pre-push", "origin"])
+            .write_stdin(push)
+            .assert()
+            .success()
+            .stderr(predicates::str::contains("Completed").not());
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn webhooks_update_the_cache_and_run_hooks() -> Result<(), Box<dyn std::error::Error>> {
+        use std::io::BufRead;
+
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents(
+                "config.toml",
+                "[webhook_hooks]\n\"item:added\" = \"cat > added.json\"",
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("todoist"))
+            .arg("--local-dir")
+            .arg(mock_data_dir)
+            .arg("--config")
+            .arg(mock_data_dir.join("config.toml"))
+            .args(["serve-webhooks", "--port", "0"])
+            .env("TODOIST_CLIENT_SECRET", "MOCK_SECRET")
+            .current_dir(mock_data_dir)
+            .stdout(std::process::Stdio::piped())
+            .spawn()?;
+        let mut line = String::new();
+        let mut stdout = std::io::BufReader::new(child.stdout.take().ok_or("no stdout")?);
+        stdout.read_line(&mut line)?;
+        let address = line
+            .strip_prefix("Listening for webhooks on ")
+            .and_then(|rest| rest.split_once(". "))
+            .ok_or("no address")?
+            .0;
+        let url = format!("http://{address}/");
+
+        let body = serde_json::json!({
+            "event_name": "item:added",
+            "user_id": "MOCK_USER_ID",
+            "event_data": {
+                "id": "MOCK_ITEM_ID",
+                "content": "Buy milk",
+                "project_id": "MOCK_INBOX_PROJECT_ID",
+                "section_id": null,
+                "parent_id": null,
+                "due": null,
+            },
+        })
+        .to_string();
+        let client = reqwest::Client::new();
+        let unsigned = client.post(&url).body(body.clone()).send().await?;
+        let signed = client
+            .post(&url)
+            .header(
+                webhook::SIGNATURE_HEADER,
+                webhook::signature("MOCK_SECRET", body.as_bytes()),
+            )
+            .body(body.clone())
+            .send()
+            .await?;
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        std::process::Command::new("kill")
+            .args(["-INT", &child.id().to_string()])
+            .status()?;
+        let mut events = String::new();
+        std::io::Read::read_to_string(&mut stdout, &mut events)?;
+        assert!(child.wait()?.success());
+
+        assert!(events.contains("item:added: Buy milk"));
+        assert_eq!(unsigned.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(signed.status(), reqwest::StatusCode::OK);
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        let item = store.item("MOCK_ITEM_ID")?.ok_or("item wasn't cached")?;
+        assert_eq!(item.content, "Buy milk");
+        assert_eq!(
+            std::fs::read_to_string(mock_data_dir.join("added.json"))?,
+            body
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn hooks_run_with_events_on_stdin() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = assert_fs::TempDir::new()?;
+        let log = dir.path().join("events.jsonl");
+        // each event goes on a line of its own
+        let hook = format!("\"cat >> '{0}'; echo >> '{0}'\"", log.display());
+        let config =
+            format!("on_task_added = {hook}\non_task_completed = {hook}\non_sync = {hook}\n");
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents("config.toml", &config)?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--config").arg(mock_data_dir.join("config.toml"));
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(args);
+            cmd.assert().success()
+        };
+        run(&["--add", "Buy milk"]);
+        run(&["sync"]);
+        run(&["complete", "ITEM_1"]);
+
+        let events: Vec<serde_json::Value> = std::fs::read_to_string(&log)?
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<_, _>>()?;
+        let names: Vec<&str> = events
+            .iter()
+            .filter_map(|event| event["event"].as_str())
+            .collect();
+        assert_eq!(names, ["task_added", "sync", "task_completed"]);
+        assert_eq!(events[0]["id"], "ITEM_1");
+        assert_eq!(events[0]["task"]["content"], "Buy milk");
+        assert_eq!(events[1]["changes"]["items_added"], 1);
+        assert_eq!(events[2]["task"]["content"], "Buy milk");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn ical_writes_due_tasks_as_events() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    projects: Some(vec![Project {
+                        id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        name: "Inbox".to_string(),
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![
+                        Item {
+                            id: "T1".to_string(),
+                            content: "Water plants".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            due: Some(Due {
+                                date: "2023-07-12".to_string(),
+                                string: Some("every 3 days".to_string()),
+                                is_recurring: true,
+                            }),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "T2".to_string(),
+                            content: "Call mom".to_string(),
+                            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let out = mock_fs.path().join("tasks.ics");
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_fs.path());
+        cmd.args(["--offline", "ical", "--out"]).arg(&out);
+
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Wrote 1 tasks to"));
+        let calendar = std::fs::read_to_string(out)?;
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.contains("SUMMARY:Water plants\r\n"));
+        assert!(calendar.contains("DTSTART;VALUE=DATE:20230712\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=DAILY;INTERVAL=3\r\n"));
+        assert!(!calendar.contains("Call mom"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn import_reads_org_outlines() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents(
+                "plans.txt",
+                "* Garden\n\
+                 ** TODO [#A] Plant tulips :errand:\n\
+                 SCHEDULED: <2024-05-01 Wed +1w> DEADLINE: <2024-05-03 Fri>\n\
+                 *** DONE Buy bulbs\n",
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.arg("import").arg(mock_data_dir.join("plans.txt"));
+        cmd.args(["--format", "org"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Imported 2 tasks, creating 1 projects and sections.",
+        ));
+
+        let items = server.items();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].priority, 4);
+        assert_eq!(items[0].labels, ["errand"]);
+        assert_eq!(
+            items[0]
+                .deadline
+                .as_ref()
+                .map(|deadline| deadline.date.as_str()),
+            Some("2024-05-03")
+        );
+        assert_eq!(items[1].parent_id.as_deref(), Some(items[0].id.as_str()));
+        assert!(items[1].checked);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn import_from_taskwarrior_asks_first_and_skips_known_tasks(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents(
+                "export.json",
+                r#"[
+                    {"uuid": "a1", "description": "Plant tulips", "status": "pending",
+                     "tags": ["errand"], "priority": "M",
+                     "annotations": [{"description": "The red ones"}]},
+                    {"uuid": "b2", "description": "Old idea", "status": "deleted"}
+                ]"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |answer: &str| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.arg("import").arg(mock_data_dir.join("export.json"));
+            cmd.args(["--from", "taskwarrior"]);
+            cmd.write_stdin(answer);
+            cmd.assert().success()
+        };
+
+        run("n\n")
+            .stdout(predicates::str::contains("Would add 1 tasks:"))
+            .stdout(predicates::str::contains("1 deleted tasks are left out."))
+            .stdout(predicates::str::contains(
+                "Import 1 tasks? [y/N] Cancelled.",
+            ));
+        assert!(server.items().is_empty());
+
+        run("y\n").stdout(predicates::str::contains("Imported 1 tasks."));
+        let items = server.items();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].priority, 3);
+        assert_eq!(items[0].labels, ["errand"]);
+
+        run("").stdout(predicates::str::contains(
+            "'Plant tulips' is already in Todoist, so it's left out.",
+        ));
+        assert_eq!(server.items().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn maintenance_prune_drops_old_completed_tasks() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    items: Some(vec![
+                        Item {
+                            id: "OLD".to_string(),
+                            content: "Done long ago".to_string(),
+                            checked: true,
+                            completed_at: Some("2020-01-01T09:00:00Z".to_string()),
+                            ..Default::default()
+                        },
+                        Item {
+                            id: "OPEN".to_string(),
+                            content: "Still to do".to_string(),
+                            ..Default::default()
+                        },
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd = Command::cargo_bin("todoist")?;
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["--offline", "maintenance", "prune"]);
+        cmd.args(["--keep-completed", "30"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Removed 1 completed tasks, 0 comments, and 0 reminders.",
+        ));
+
+        let store = Store::open(&mock_data_dir.join("data").join("todoist.db"))?;
+        let ids: Vec<String> = store.items()?.into_iter().map(|item| item.id).collect();
+        assert_eq!(ids, ["OPEN"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn porcelain_output_is_tab_separated() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.arg("--porcelain").args(args);
+            cmd.assert().success()
+        };
+
+        run(&["add", "Buy milk", "--priority", "2"]).stdout("added\tITEM_1\tBuy milk\n");
+        run(&["list"]).stdout("ITEM_1\tBuy milk\tMOCK_INBOX_PROJECT_ID\t\t\t\tp2\t\tfalse\n");
+        run(&["complete", "ITEM_1"]).stdout("completed\tITEM_1\tBuy milk\n");
+        Ok(())
+    }
+
+    #[test]
+    fn list_can_fail_if_tasks_are_listed() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "LATE".to_string(),
+                        content: "Pay rent".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        due: Some(Due {
+                            date: "2020-01-01".to_string(),
+                            string: None,
+                            is_recurring: false,
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let run = |filter: &str, check: &str| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--offline");
+            cmd.args(["list", "--filter", filter, check]);
+            cmd.assert()
+        };
+
+        run("overdue", "--fail-if-nonempty")
+            .code(3)
+            .stdout(predicates::str::contains("Pay rent"))
+            .stderr("1 tasks were listed.\n");
+        run("today", "--fail-if-nonempty").success();
+        run("today", "--fail-if-empty")
+            .code(3)
+            .stderr("No tasks were listed.\n");
+        run("overdue", "--fail-if-empty").success();
+        Ok(())
+    }
+
+    #[test]
+    fn status_summarizes_the_cache_for_status_bars() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "LATE".to_string(),
+                        content: "Pay rent".to_string(),
+                        due: Some(Due {
+                            date: "2020-01-01".to_string(),
+                            string: None,
+                            is_recurring: false,
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        // no sync URL: it shouldn't need the server
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["status", "--format", "tmux"]);
+        cmd.assert()
+            .success()
+            .stdout("#[fg=red]1 overdue#[default]\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn location_reminders_can_be_added_and_listed() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "MOCK_ITEM_ID".to_string(),
+                        content: "Buy stamps".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    reminders: Some(vec![Reminder {
+                        id: "MOCK_REMINDER_ID".to_string(),
+                        item_id: "MOCK_ITEM_ID".to_string(),
+                        kind: ReminderKind::Location,
+                        due: None,
+                        minute_offset: None,
+                        name: Some("Post office".to_string()),
+                        loc_lat: Some("40.7128".to_string()),
+                        loc_long: Some("-74.0060".to_string()),
+                        loc_trigger: Some(LocationTrigger::OnEnter),
+                        radius: Some(100),
+                        is_deleted: false,
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["--offline", "list", "--with-reminders"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "⏰ when arriving at Post office (within 100 m)",
+        ));
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ReminderAdd(args)
+                                if args.kind == ReminderKind::Location
+                                    && args.name.as_deref() == Some("Home")
+                                    && args.loc_lat.as_deref() == Some("51.5")
+                                    && args.loc_long.as_deref() == Some("-0.12")
+                                    && args.loc_trigger == Some(LocationTrigger::OnLeave)
+                                    && args.radius == Some(50)
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(mock_server.uri());
+        cmd.args([
+            "reminder",
+            "add",
+            "MOCK_ITEM_ID",
+            "--location",
+            "51.5,-0.12,50",
+        ]);
+        cmd.args(["--name", "Home", "--leaving"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Reminder added to 'Buy stamps'."));
+        Ok(())
+    }
+
+    #[test]
+    fn tasks_that_wait_for_others_are_hidden() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                let item = |id: &str, content: &str| Item {
+                    id: id.to_string(),
+                    content: content.to_string(),
+                    project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                    ..Default::default()
+                };
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    items: Some(vec![item("BUY", "Buy paint"), item("PAINT", "Paint walls")]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--offline").args(args);
+            cmd.assert()
+        };
+
+        run(&["depend", "PAINT", "--on", "BUY"])
+            .success()
+            .stdout(predicates::str::contains(
+                "'Paint walls' now waits for 'Buy paint'",
+            ));
+        run(&["list"])
+            .success()
+            .stdout(predicates::str::contains("Buy paint"))
+            .stdout(predicates::str::contains("Paint walls").not());
+        run(&["depend", "BUY", "--on", "PAINT"])
+            .failure()
+            .stderr(predicates::str::contains(
+                "'Paint walls' already waits for 'Buy paint'",
+            ));
+        run(&["depend", "PAINT"])
+            .success()
+            .stdout(predicates::str::contains(
+                "'Paint walls' waits for:\n  'Buy paint'",
+            ));
+
+        run(&["depend", "PAINT", "--on", "BUY", "--remove"]).success();
+        run(&["list"])
+            .success()
+            .stdout(predicates::str::contains("Paint walls"));
+        Ok(())
+    }
+
+    #[test]
+    fn forecast_counts_tasks_on_each_day() -> Result<(), Box<dyn std::error::Error>> {
+        let today = chrono::Local::now().date_naive();
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "WATER".to_string(),
+                        content: "Water plants".to_string(),
+                        due: Some(Due {
+                            date: today.to_string(),
+                            string: Some("every 2 days".to_string()),
+                            is_recurring: true,
+                        }),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["--offline", "--output", "plain", "forecast", "--days", "4"]);
+        let day = |offset| today + chrono::Days::new(offset);
+        cmd.assert().success().stdout(format!(
+            "{}\t1\n{}\t0\n{}\t1\n{}\t0\n",
+            day(0),
+            day(1),
+            day(2),
+            day(3)
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.args(["--offline", "forecast", "--days", "14"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Tasks due over the next 14 days:\n          M T W T F S S\n",
+            ))
+            .stdout(predicates::str::contains(format!(
+                "Busiest: {}, with 1 tasks.",
+                today.format("%a %b %-d")
+            )));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn projects_can_be_saved_as_templates_and_applied(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                let item = |id: &str, content: &str, parent_id: Option<&str>| Item {
+                    id: id.to_string(),
+                    content: content.to_string(),
+                    project_id: "MOCK_PACKING_PROJECT_ID".to_string(),
+                    section_id: Some("MOCK_CLOTHES_SECTION_ID".to_string()),
+                    parent_id: parent_id.map(str::to_string),
+                    ..Default::default()
+                };
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_PACKING_PROJECT_ID".to_string(),
+                        name: "Packing".to_string(),
+                        ..Default::default()
+                    }]),
+                    sections: Some(vec![Section {
+                        id: "MOCK_CLOTHES_SECTION_ID".to_string(),
+                        name: "Clothes".to_string(),
+                        project_id: "MOCK_PACKING_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![
+                        item("SHIRTS", "Shirts", None),
+                        item("LINEN", "Linen ones", Some("SHIRTS")),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--offline")
+            .args(["template", "save", "packing", "trip"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Saved the 'trip' template, with 1 sections and 2 tasks from Packing.",
+        ));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.args(["template", "apply", "trip", "--project", "Lisbon"]);
+        cmd.assert()
+            .success()
+            .stdout(predicates::str::contains("Added 2 tasks to Lisbon."));
+
+        let items = server.items();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].content, "Shirts");
+        assert_ne!(items[0].project_id, "MOCK_PACKING_PROJECT_ID");
+        assert_eq!(items[1].project_id, items[0].project_id);
+        assert_eq!(items[1].section_id, items[0].section_id);
+        assert_eq!(items[1].parent_id.as_deref(), Some("ITEM_1"));
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--offline").args(["template", "list"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "trip: 1 sections and 2 tasks, from Packing",
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn checklists_are_added_with_dates_from_the_start(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_file_contents(
+                "moving.yaml",
+                "tasks:\n  - content: Book movers\n    due: D-14\n    subtasks:\n      - Get quotes\n  - content: Hand over the keys\n    due: D+1 noon\n",
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+
+        let mut cmd =
+            Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+        cmd.arg("--local-dir").arg(mock_data_dir);
+        cmd.arg("--sync-url").arg(server.uri());
+        cmd.args(["checklist", "run"])
+            .arg(mock_data_dir.join("moving.yaml"))
+            .args(["--start", "2024-06-01"]);
+        cmd.assert().success().stdout(predicates::str::contains(
+            "Added 3 tasks from the checklist, starting 2024-06-01.",
+        ));
+
+        let items = server.items();
+        let dues: Vec<_> = items
+            .iter()
+            .map(|item| item.due.as_ref().map(|due| due.date.as_str()))
+            .collect();
+        assert_eq!(dues, [Some("2024-05-18"), None, Some("2024-06-02 noon")]);
+        assert_eq!(items[1].parent_id.as_deref(), Some("ITEM_1"));
+        assert_eq!(items[2].project_id, "MOCK_INBOX_PROJECT_ID");
+        Ok(())
+    }
+
+    #[test]
+    fn todos_that_look_like_open_tasks_can_be_skipped() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        name: "Inbox".to_string(),
+                        ..Default::default()
+                    }]),
+                    items: Some(vec![Item {
+                        id: "MILK".to_string(),
+                        content: "Buy milk".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let mock_data_dir = mock_fs.path();
+
+        let run = |args: &[&str], stdin: &str| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--offline").args(args).write_stdin(stdin);
+            cmd.assert().success()
+        };
+
+        run(&["add", "buy  Milk!", "--dedupe=skip"], "")
+            .stdout(predicates::str::contains(
+                "Skipped 'buy Milk!', since 'Buy milk' is already in inbox.",
+            ))
+            .stdout(predicates::str::contains("will be added").not());
+        run(&["add", "Buy milk", "--dedupe"], "")
+            .stdout(predicates::str::contains(
+                "Warning: 'Buy milk' is already in inbox, and looks the same.",
+            ))
+            .stdout(predicates::str::contains(
+                "Todo 'Buy milk' will be added on the next sync.",
+            ));
+        run(&["add", "Buy bread"], "").stdout(predicates::str::contains("Warning").not());
+
+        // queued todos, and earlier ones in the same list, count too
+        run(
+            &["add", "--stdin", "--dedupe=skip"],
+            "Buy bread\nBuy eggs\nbuy eggs\n",
+        )
+        .stdout(predicates::str::contains(
+            "Skipped 'Buy bread', since 'Buy bread' is already in inbox.",
+        ))
+        .stdout(predicates::str::contains(
+            "Skipped 'buy eggs', since 'Buy eggs' is already in inbox.",
+        ))
+        .stdout(predicates::str::contains(
+            "1 todos will be added on the next sync.",
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn adds_that_time_out_are_not_made_twice() -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "full_name": "Drew",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?;
+        let mock_data_dir = mock_fs.path();
+        let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+        let run = |args: &[&str]| {
+            let mut cmd =
+                Command::cargo_bin("todoist").expect("could not run program using 'assert_cmd'");
+            cmd.arg("--local-dir").arg(mock_data_dir);
+            cmd.arg("--sync-url").arg(server.uri());
+            cmd.args(["--retries", "0", "--timeout", "1"]).args(args);
+            cmd.assert().success()
+        };
+
+        // the server adds the task, but the answer never arrives
+        server.delay_responses(std::time::Duration::from_secs(3));
+        run(&["add", "Buy milk"]).stdout(predicates::str::contains(
+            "Could not reach Todoist. Todo 'Buy milk' will be added on the next sync.",
+        ));
+        assert_eq!(server.items().len(), 1);
+
+        // so the queued command going out again doesn't add it twice, but
+        // adding the same todo on purpose does
+        server.delay_responses(std::time::Duration::ZERO);
+        run(&["add", "Buy milk"]).stdout(predicates::str::contains("Todo 'Buy milk' added"));
+        assert_eq!(server.items().len(), 2);
+
+        // and so does adding it once more, after both have been answered for
+        run(&["add", "Buy milk"]).stdout(predicates::str::contains("Todo 'Buy milk' added"));
+        assert_eq!(server.items().len(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn projects_and_tasks_can_be_scoped_to_a_workspace() -> Result<(), Box<dyn std::error::Error>> {
+        let project = |id: &str, name: &str, workspace: Option<&str>| Project {
+            id: id.to_string(),
+            name: name.to_string(),
+            workspace_id: workspace.map(ToString::to_string),
+            ..Default::default()
+        };
+        let item = |id: &str, content: &str, project_id: &str| Item {
+            id: id.to_string(),
+            content: content.to_string(),
+            project_id: project_id.to_string(),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{"full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID"}"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    projects: Some(vec![
+                        project("MOCK_INBOX_PROJECT_ID", "Inbox", None),
+                        project("P_ROADMAP", "Roadmap", Some("W_ACME")),
+                        project("P_HIRING", "Hiring", Some("W_ACME")),
+                    ]),
+                    items: Some(vec![
+                        item("I1", "Buy milk", "MOCK_INBOX_PROJECT_ID"),
+                        item("I2", "Plan the launch", "P_ROADMAP"),
+                        item("I3", "Interview Sam", "P_HIRING"),
+                    ]),
+                    workspaces: Some(vec![Workspace {
+                        id: "W_ACME".to_string(),
+                        name: "Acme".to_string(),
+                        ..Default::default()
+                    }]),
+                    ..Default::default()
+                })
+            })?;
+        let run = |args: &[&str]| {
+            Command::cargo_bin("todoist")
+                .expect("could not run program using 'assert_cmd'")
+                .arg("--local-dir")
+                .arg(mock_fs.path())
+                .arg("--offline")
+                .args(args)
+                .assert()
+                .success()
+        };
+
+        run(&["projects", "list"])
+            .stdout(predicates::str::contains("Projects:\n  Inbox\n"))
+            .stdout(predicates::str::contains(
+                "Projects in Acme:\n  Roadmap\n  Hiring\n",
+            ));
+        run(&[
+            "--output",
+            "plain",
+            "projects",
+            "list",
+            "--workspace",
+            "acme",
+        ])
+        .stdout("P_ROADMAP\tRoadmap\tAcme\nP_HIRING\tHiring\tAcme\n");
+
+        run(&["list", "--workspace", "acme"])
+            .stdout(predicates::str::contains("Acme:"))
+            .stdout(predicates::str::contains("Plan the launch"))
+            .stdout(predicates::str::contains("Interview Sam"))
+            .stdout(predicates::str::contains("Buy milk").not());
+        run(&["list", "--workspace", "acme", "--filter", "search: launch"])
+            .stdout(predicates::str::contains("search: launch in Acme:"))
+            .stdout(predicates::str::contains("Interview Sam").not());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn projects_list_their_collaborators_and_can_be_shared(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let state = |user_id: &str, state: &str| CollaboratorState {
+            project_id: "MOCK_WORK_PROJECT_ID".to_string(),
+            user_id: user_id.to_string(),
+            state: state.to_string(),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{
+                    "id": "U_DREW",
+                    "full_name": "Drew",
+                    "email": "drew@example.com",
+                    "inbox_project_id": "MOCK_INBOX_PROJECT_ID"
+                }"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    projects: Some(vec![Project {
+                        id: "MOCK_WORK_PROJECT_ID".to_string(),
+                        name: "Work".to_string(),
+                        ..Default::default()
+                    }]),
+                    collaborators: Some(vec![Collaborator {
+                        id: "U_ANA".to_string(),
+                        email: "ana@example.com".to_string(),
+                        full_name: "Ana Lopez".to_string(),
+                    }]),
+                    collaborator_states: Some(vec![
+                        state("U_DREW", "active"),
+                        state("U_ANA", "invited"),
+                        state("U_GONE", "deleted"),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+
+        let run = |args: &[&str]| {
+            Command::cargo_bin("todoist")
+                .expect("could not run program using 'assert_cmd'")
+                .arg("--local-dir")
+                .arg(mock_fs.path())
+                .arg("--offline")
+                .args(args)
+                .assert()
+                .success()
+        };
+        run(&["collaborators", "work"]).stdout(predicates::str::contains(
+            "Collaborators in Work:\n  Drew <drew@example.com>\n  Ana Lopez <ana@example.com> (invited)\n",
+        ));
+        run(&["--output", "plain", "collaborators", "work"]).stdout(
+            "U_DREW\tDrew\tdrew@example.com\tactive\nU_ANA\tAna Lopez\tana@example.com\tinvited\n",
+        );
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ShareProject(args)
+                                if args.project_id == "MOCK_WORK_PROJECT_ID"
+                                    && args.email == "sam@example.com"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        Command::cargo_bin("todoist")
+            .expect("could not run program using 'assert_cmd'")
+            .arg("--local-dir")
+            .arg(mock_fs.path())
+            .arg("--sync-url")
+            .arg(mock_server.uri())
+            .args(["share", "work", "sam@example.com"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "Invited sam@example.com to 'Work'.",
+            ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn notifications_can_be_listed_and_marked_read() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let notification = |id: &str, kind: &str, created_at: &str, is_unread| LiveNotification {
+            id: id.to_string(),
+            notification_type: kind.to_string(),
+            created_at: created_at.to_string(),
+            is_unread,
+            from_uid: Some("U_ANA".to_string()),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{"full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID"}"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    items: Some(vec![Item {
+                        id: "I1".to_string(),
+                        content: "Book flights".to_string(),
+                        project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+                        ..Default::default()
+                    }]),
+                    collaborators: Some(vec![Collaborator {
+                        id: "U_ANA".to_string(),
+                        email: "ana@example.com".to_string(),
+                        full_name: "Ana".to_string(),
+                    }]),
+                    live_notifications: Some(vec![
+                        LiveNotification {
+                            project_name: Some("Trip".to_string()),
+                            ..notification(
+                                "N1",
+                                "share_invitation_sent",
+                                "2024-05-01T09:00:00Z",
+                                false,
+                            )
+                        },
+                        LiveNotification {
+                            item_id: Some("I1".to_string()),
+                            ..notification("N2", "item_assigned", "2024-05-02T09:00:00Z", true)
+                        },
+                        // not something for the user to do
+                        notification("N3", "item_completed", "2024-05-03T09:00:00Z", true),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+
+        let run = |args: &[&str]| {
+            Command::cargo_bin("todoist")
+                .expect("could not run program using 'assert_cmd'")
+                .arg("--local-dir")
+                .arg(mock_fs.path())
+                .arg("--offline")
+                .args(args)
+                .assert()
+                .success()
+        };
+        run(&["--output", "plain", "notifications"]).stdout(
+            "N2\t2024-05-02T09:00:00Z\titem_assigned\tAna assigned you 'Book flights'\n\
+             N1\t2024-05-01T09:00:00Z\tshare_invitation_sent\tAna invited you to 'Trip'\n",
+        );
+        run(&["notifications", "--unread"])
+            .stdout(predicates::str::contains(
+                "Ana assigned you 'Book flights' (unread)",
+            ))
+            .stdout(predicates::str::contains("Trip").not());
+
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::LiveNotificationsMarkRead(args) if args.ids == ["N3", "N2"]
+                        )
+                    })
+                },
+                Response {
+                    sync_token: String::from("MOCK_SYNC_TOKEN"),
+                    ..Default::default()
+                },
+            )
+            .await;
+        Command::cargo_bin("todoist")
+            .expect("could not run program using 'assert_cmd'")
+            .arg("--local-dir")
+            .arg(mock_fs.path())
+            .arg("--sync-url")
+            .arg(mock_server.uri())
+            .args(["notifications", "mark-read"])
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("Marked 2 notifications as read."));
+
+        run(&["notifications", "--unread"]).stdout(predicates::str::contains("(no notifications)"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn reopen_a_task_by_its_index_in_the_completed_list(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{ "full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID" }"#,
+            )?;
+        let completed = |id: &str, content: &str, completed_at: &str| CompletedItem {
+            id: format!("C_{id}"),
+            task_id: id.to_string(),
+            content: content.to_string(),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            completed_at: completed_at.to_string(),
+            user_id: None,
+        };
+        let mock_server = ApiMockBuilder::new()
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| request.commands.is_empty(),
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_response(
+                "sync",
+                |request: Request| {
+                    request.commands.first().is_some_and(|command| {
+                        matches!(
+                            &command.args,
+                            CommandArgs::ItemUncomplete(args) if args.id == "T1"
+                        )
+                    })
+                },
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await
+            .mock_get(
+                "completed/get_all",
+                &[("offset", "0")],
+                CompletedPage {
+                    items: vec![
+                        completed("T2", "Water plants", "2024-05-02T09:30:00Z"),
+                        completed("T1", "Take out trash", "2024-05-01T18:00:00Z"),
+                    ],
+                },
+            )
+            .await;
+        let run = |args: &[&str]| {
+            Command::cargo_bin("todoist")
+                .expect("could not run program using 'assert_cmd'")
+                .arg("--local-dir")
+                .arg(mock_fs.path())
+                .arg("--sync-url")
+                .arg(mock_server.uri())
+                .args(args)
+                .assert()
+                .success()
+        };
+
+        run(&["completed"]).stdout(
+            predicates::str::contains("   2  ").and(predicates::str::contains("Take out trash")),
+        );
+        run(&["reopen", "2"]).stdout(predicates::str::contains("Reopened 'Take out trash'."));
+        Ok(())
+    }
+
+    #[test]
+    fn list_sorts_and_groups_tasks_from_the_cache() -> Result<(), Box<dyn std::error::Error>> {
+        let item = |id: &str, priority: u8, labels: &[&str]| Item {
+            id: id.to_string(),
+            content: format!("Task {id}"),
+            project_id: "MOCK_INBOX_PROJECT_ID".to_string(),
+            priority,
+            labels: labels.iter().map(ToString::to_string).collect(),
+            ..Default::default()
+        };
+        let mock_fs = FsMockBuilder::new()?
+            .mock_file_contents("client_auth.toml", "api_key = \"MOCK_API_KEY\"")?
+            .mock_file_contents(
+                "data/user.json",
+                r#"{"full_name": "Drew", "inbox_project_id": "MOCK_INBOX_PROJECT_ID"}"#,
+            )?
+            .mock_store(|store| {
+                store.apply(&Response {
+                    full_sync: true,
+                    items: Some(vec![
+                        item("A", 1, &["home"]),
+                        item("B", 4, &["work"]),
+                        item("C", 2, &["home"]),
+                    ]),
+                    ..Default::default()
+                })
+            })?;
+        let run = |args: &[&str]| {
+            Command::cargo_bin("todoist")
+                .expect("could not run program using 'assert_cmd'")
+                .arg("--local-dir")
+                .arg(mock_fs.path())
+                .arg("--offline")
+                .args(args)
+                .assert()
+                .success()
+        };
 
+        run(&["--output", "plain", "list", "--sort", "priority"])
+            .stdout("1\tB\tTask B\t\tp1\n2\tC\tTask C\t\tp3\n3\tA\tTask A\t\tp4\n");
+        run(&["list", "--sort", "priority", "--group-by", "label"]).stdout(
+            predicates::str::is_match(r"(?s)@work:.*Task B.*@home:.*Task C.*Task A")?,
+        );
         Ok(())
     }
 }
@@ -0,0 +1,56 @@
+use chrono::NaiveDate;
+
+use crate::{
+    agenda::{agenda, day_heading},
+    sync::Item,
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, date: &str, priority: u8) -> Item {
+    ItemBuilder::new(id).due(date).priority(priority).build()
+}
+
+fn ids(items: &[&Item]) -> Vec<String> {
+    items.iter().map(|item| item.id.clone()).collect()
+}
+
+#[test]
+fn splits_items_into_overdue_and_days() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let items = vec![
+        item("later", "2023-07-20", 1),
+        item("today_low", "2023-07-10", 1),
+        item("yesterday", "2023-07-09", 1),
+        item("today_high", "2023-07-10", 4),
+        item("tomorrow_timed", "2023-07-11T09:00:00", 1),
+        Item {
+            checked: true,
+            ..item("done", "2023-07-01", 1)
+        },
+        Item {
+            due: None,
+            ..item("undated", "", 1)
+        },
+        item("last_week", "2023-07-03", 1),
+    ];
+
+    let agenda = agenda(&items, today, 3);
+
+    assert_eq!(ids(&agenda.overdue), ["last_week", "yesterday"]);
+    assert_eq!(agenda.days.len(), 3);
+    assert_eq!(ids(&agenda.days[0].1), ["today_high", "today_low"]);
+    assert_eq!(ids(&agenda.days[1].1), ["tomorrow_timed"]);
+    assert!(agenda.days[2].1.is_empty());
+    Ok(())
+}
+
+#[test]
+fn headings_are_relative_to_today() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let day = |day| NaiveDate::from_ymd_opt(2023, 7, day).ok_or("bad date");
+
+    assert_eq!(day_heading(day(10)?, today), "Today");
+    assert_eq!(day_heading(day(11)?, today), "Tomorrow");
+    assert_eq!(day_heading(day(12)?, today), "Wed Jul 12");
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use chrono::NaiveDate;
+
+use crate::{
+    arrange::{group, sort, GroupBy, Sort},
+    sync::{Item, Project},
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, due: Option<&str>, priority: u8) -> Item {
+    ItemBuilder::new(id)
+        .project("P1")
+        .due(due)
+        .priority(priority)
+        .build()
+}
+
+fn ids(items: &[&Item]) -> Vec<String> {
+    items.iter().map(|item| item.id.clone()).collect()
+}
+
+#[test]
+fn sorts_by_due_date_and_then_time() {
+    let items = [
+        item("undated", None, 1),
+        item("tomorrow", Some("2023-07-11"), 1),
+        item("today_all_day", Some("2023-07-10"), 1),
+        item("today_at_five", Some("2023-07-10T17:00:00"), 1),
+        item("today_at_nine", Some("2023-07-10T09:00:00"), 1),
+    ];
+    let mut listed: Vec<&Item> = items.iter().collect();
+    sort(&mut listed, Sort::Due, &[]);
+    assert_eq!(
+        ids(&listed),
+        [
+            "today_at_nine",
+            "today_at_five",
+            "today_all_day",
+            "tomorrow",
+            "undated"
+        ]
+    );
+
+    sort(&mut listed, Sort::Priority, &[]);
+    assert_eq!(ids(&listed)[0], "today_at_nine");
+    let urgent = item("urgent_undated", None, 4);
+    listed.push(&urgent);
+    sort(&mut listed, Sort::Priority, &[]);
+    assert_eq!(ids(&listed)[0], "urgent_undated");
+}
+
+#[test]
+fn sorts_by_project_name_keeping_each_projects_order() {
+    let project = |id: &str, name: &str| Project {
+        id: id.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    };
+    let in_project = |id: &str, project_id: &str, child_order| Item {
+        project_id: project_id.to_string(),
+        child_order,
+        ..item(id, None, 1)
+    };
+    let items = [
+        in_project("work_2", "P_WORK", 2),
+        in_project("home", "P_HOME", 1),
+        in_project("work_1", "P_WORK", 1),
+    ];
+    let mut listed: Vec<&Item> = items.iter().collect();
+    sort(
+        &mut listed,
+        Sort::Project,
+        &[project("P_WORK", "Work"), project("P_HOME", "home")],
+    );
+    assert_eq!(ids(&listed), ["home", "work_1", "work_2"]);
+}
+
+#[test]
+fn groups_by_due_date_with_overdue_first_and_undated_last() -> Result<(), Box<dyn std::error::Error>>
+{
+    let today = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let items = [
+        item("undated", None, 1),
+        item("tomorrow", Some("2023-07-11"), 1),
+        item("last_week", Some("2023-07-03"), 1),
+        item("today", Some("2023-07-10T09:00:00"), 1),
+        item("yesterday", Some("2023-07-09"), 1),
+    ];
+    let listed: Vec<&Item> = items.iter().collect();
+    let groups: Vec<(String, Vec<String>)> = group(&listed, GroupBy::DueDate, &[], today)
+        .into_iter()
+        .map(|(heading, items)| (heading, ids(&items)))
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("Overdue".to_string(), ids(&[&items[2], &items[4]])),
+            ("Today".to_string(), ids(&[&items[3]])),
+            ("Tomorrow".to_string(), ids(&[&items[1]])),
+            ("No due date".to_string(), ids(&[&items[0]])),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn groups_by_each_label_a_task_has() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let labelled = |id: &str, labels: &[&str]| Item {
+        labels: labels.iter().map(ToString::to_string).collect(),
+        ..item(id, None, 1)
+    };
+    let items = [
+        labelled("plain", &[]),
+        labelled("call", &["phone", "work"]),
+        labelled("email", &["work"]),
+    ];
+    let listed: Vec<&Item> = items.iter().collect();
+    let groups: Vec<(String, Vec<String>)> = group(&listed, GroupBy::Label, &[], today)
+        .into_iter()
+        .map(|(heading, items)| (heading, ids(&items)))
+        .collect();
+    assert_eq!(
+        groups,
+        [
+            ("@phone".to_string(), vec!["call".to_string()]),
+            (
+                "@work".to_string(),
+                vec!["call".to_string(), "email".to_string()]
+            ),
+            ("No labels".to_string(), vec!["plain".to_string()]),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use crate::batch::{parse_args, parse_file, Step};
+
+fn args(words: &[&str]) -> Vec<String> {
+    words.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn parses_args() -> Result<(), Box<dyn std::error::Error>> {
+    let steps = parse_args(&args(&[
+        "add", "buy milk", "ADD", "call mom", "complete", "3",
+    ]))?;
+    assert_eq!(
+        steps,
+        [
+            Step::Add("buy milk".to_string()),
+            Step::Add("call mom".to_string()),
+            Step::Complete("3".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn rejects_bad_args() {
+    assert!(parse_args(&args(&["add", "buy milk", "complete"])).is_err());
+    assert!(parse_args(&args(&["add", " "])).is_err());
+    assert!(parse_args(&args(&["remove", "3"])).is_err());
+}
+
+#[test]
+fn parses_file() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "
+        # errands
+        add buy milk tomorrow #Errands
+
+        complete last
+    ";
+    assert_eq!(
+        parse_file(text)?,
+        [
+            Step::Add("buy milk tomorrow #Errands".to_string()),
+            Step::Complete("last".to_string()),
+        ]
+    );
+    assert!(parse_file("complete").is_err());
+    Ok(())
+}
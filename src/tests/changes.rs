@@ -0,0 +1,97 @@
+use crate::{
+    changes::{compare, Changes},
+    sync::{Item, Project, Response},
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, content: &str) -> Item {
+    ItemBuilder::new(id).content(content).build()
+}
+
+fn project(id: &str, name: &str) -> Project {
+    Project {
+        id: id.to_string(),
+        name: name.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn counts_what_an_incremental_sync_changed() {
+    let items = vec![
+        item("1", "Unchanged"),
+        item("2", "Completed"),
+        item("3", "Renamed"),
+        item("4", "Deleted"),
+    ];
+    let projects = vec![project("A", "Work"), project("B", "Home")];
+    let response = Response {
+        items: Some(vec![
+            item("1", "Unchanged"),
+            Item {
+                checked: true,
+                ..item("2", "Completed")
+            },
+            item("3", "Renamed again"),
+            Item {
+                is_deleted: true,
+                ..item("4", "Deleted")
+            },
+            item("5", "New"),
+            Item {
+                is_deleted: true,
+                ..item("6", "Added and deleted elsewhere")
+            },
+        ]),
+        projects: Some(vec![project("B", "House"), project("C", "Errands")]),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        compare(&items, &projects, &response),
+        Changes {
+            items_added: 1,
+            items_completed: 1,
+            items_updated: 1,
+            items_deleted: 1,
+            projects_added: 1,
+            projects_updated: 1,
+            projects_deleted: 0,
+        }
+    );
+}
+
+#[test]
+fn a_full_sync_deletes_what_it_leaves_out() {
+    let items = vec![
+        item("1", "Still there"),
+        item("2", "Gone"),
+        Item {
+            checked: true,
+            ..item("3", "Completed earlier")
+        },
+    ];
+    let projects = vec![
+        project("A", "Work"),
+        Project {
+            is_archived: true,
+            ..project("B", "Archived")
+        },
+    ];
+    let response = Response {
+        full_sync: true,
+        items: Some(vec![item("1", "Still there")]),
+        projects: Some(vec![project("A", "Work")]),
+        ..Default::default()
+    };
+
+    let changes = compare(&items, &projects, &response);
+    assert_eq!(changes.items_deleted, 1);
+    assert_eq!(changes.projects_deleted, 0);
+}
+
+#[test]
+fn nothing_changes_without_resources() {
+    let items = vec![item("1", "Todo")];
+    assert!(compare(&items, &[], &Response::default()).is_empty());
+}
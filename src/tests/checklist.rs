@@ -0,0 +1,113 @@
+use chrono::NaiveDate;
+
+use crate::{
+    checklist::{offset, parse_yaml, schedule},
+    import::Task,
+};
+
+#[test]
+fn parses_offsets() {
+    assert_eq!(offset("D"), Some((0, "")));
+    assert_eq!(offset("D+2"), Some((2, "")));
+    assert_eq!(offset("d-14"), Some((-14, "")));
+    assert_eq!(offset("D+1w"), Some((7, "")));
+    assert_eq!(offset("D+3d 9am"), Some((3, "9am")));
+    assert_eq!(offset("every monday"), None);
+    assert_eq!(offset("daily"), None);
+    assert_eq!(offset("D+"), None);
+}
+
+#[test]
+fn parses_yaml_checklists() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "\
+# moving house
+project: Moving
+tasks:
+  - content: Book movers
+    due: D-14  # two weeks ahead
+    priority: p1
+  - content: 'Pack: the kitchen'
+    section: Packing
+    labels: [home, '@boxes']
+    subtasks:
+      - Wrap the glasses
+      - content: Label the boxes
+        due: D-1
+  - Hand over the keys
+";
+    let tasks = parse_yaml(text)?;
+    let moving = Some("Moving".to_string());
+    assert_eq!(
+        tasks,
+        vec![
+            Task {
+                content: "Book movers".to_string(),
+                project: moving.clone(),
+                due: Some("D-14".to_string()),
+                priority: Some(1),
+                ..Default::default()
+            },
+            Task {
+                content: "Pack: the kitchen".to_string(),
+                project: moving.clone(),
+                section: Some("Packing".to_string()),
+                labels: vec!["home".to_string(), "boxes".to_string()],
+                ..Default::default()
+            },
+            Task {
+                content: "Wrap the glasses".to_string(),
+                project: moving.clone(),
+                parent: Some(1),
+                ..Default::default()
+            },
+            Task {
+                content: "Label the boxes".to_string(),
+                project: moving.clone(),
+                due: Some("D-1".to_string()),
+                parent: Some(1),
+                ..Default::default()
+            },
+            Task {
+                content: "Hand over the keys".to_string(),
+                project: moving,
+                ..Default::default()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn yaml_checklists_can_be_just_the_tasks() -> Result<(), Box<dyn std::error::Error>> {
+    let tasks = parse_yaml("- Call the bank\n- content: Cancel the lease\n")?;
+    let contents: Vec<&str> = tasks.iter().map(|task| task.content.as_str()).collect();
+    assert_eq!(contents, ["Call the bank", "Cancel the lease"]);
+
+    assert!(parse_yaml("tasks:\n  - due: D+1\n").is_err());
+    assert!(parse_yaml("tasks:\n  - content: Pack\n    color: red\n").is_err());
+    assert!(parse_yaml("owner: Sam\n").is_err());
+    Ok(())
+}
+
+#[test]
+fn offsets_are_scheduled_from_the_start() -> Result<(), Box<dyn std::error::Error>> {
+    let task = |due: &str| Task {
+        content: "Pack".to_string(),
+        due: Some(due.to_string()),
+        ..Default::default()
+    };
+    let mut tasks = [task("D-14"), task("D+1w 6pm"), task("every monday")];
+    let start = NaiveDate::from_ymd_opt(2024, 6, 1).ok_or("bad date")?;
+    schedule(&mut tasks, start)?;
+
+    let dues: Vec<_> = tasks.iter().map(|task| task.due.as_deref()).collect();
+    assert_eq!(
+        dues,
+        [
+            Some("2024-05-18"),
+            Some("2024-06-08 6pm"),
+            Some("every monday")
+        ]
+    );
+    Ok(())
+}
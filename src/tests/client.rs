@@ -0,0 +1,89 @@
+use std::{net::TcpListener, path::Path, time::Duration};
+
+use crate::{
+    client::{http_client, pem_certificates, HttpOptions, SyncClient},
+    sync::{Request, Response},
+    tests::utils::{ApiMockBuilder, FsMockBuilder},
+    Error,
+};
+
+#[test]
+fn splits_pem_bundles_into_certificates() {
+    let bundle = "\
+# Corporate root
+-----BEGIN CERTIFICATE-----
+AAAA
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+BBBB
+-----END CERTIFICATE-----
+-----BEGIN CERTIFICATE-----
+unfinished
+";
+    assert_eq!(
+        pem_certificates(bundle),
+        [
+            "-----BEGIN CERTIFICATE-----\nAAAA\n-----END CERTIFICATE-----",
+            "-----BEGIN CERTIFICATE-----\nBBBB\n-----END CERTIFICATE-----",
+        ]
+    );
+}
+
+#[test]
+fn rejects_invalid_connection_options() -> Result<(), Box<dyn std::error::Error>> {
+    let bad_proxy = HttpOptions {
+        proxy: Some("not a url"),
+        ..Default::default()
+    };
+    assert!(matches!(http_client(&bad_proxy), Err(Error::Config(_))));
+
+    let mock_fs = FsMockBuilder::new()?.mock_file_contents("bundle.pem", "no certificates")?;
+    let bundle = mock_fs.path().join("bundle.pem");
+    let empty_bundle = HttpOptions {
+        ca_bundle: Some(&bundle),
+        ..Default::default()
+    };
+    assert!(matches!(http_client(&empty_bundle), Err(Error::Config(_))));
+
+    let missing_bundle = HttpOptions {
+        ca_bundle: Some(Path::new("/nonexistent/bundle.pem")),
+        ..Default::default()
+    };
+    assert!(matches!(http_client(&missing_bundle), Err(Error::Io(_))));
+    Ok(())
+}
+
+#[tokio::test]
+async fn retries_requests_that_cannot_connect() -> Result<(), Box<dyn std::error::Error>> {
+    // a port that nothing's listening on, until the server starts on it
+    let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+    let client = SyncClient::new(&format!("http://{address}"), "MOCK_API_KEY", 2);
+    let request = Request {
+        sync_token: "*".to_string(),
+        resource_types: vec![],
+        commands: vec![],
+    };
+
+    // the server comes up while the client waits to try again
+    let server = async {
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let listener = TcpListener::bind(address)?;
+        let server = ApiMockBuilder::listening_on(listener)
+            .await
+            .mock_response(
+                "sync",
+                |_: Request| true,
+                Response {
+                    sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+        Ok::<_, std::io::Error>(server)
+    };
+    let (server, response) = tokio::join!(server, client.sync(&request));
+
+    server?;
+    assert_eq!(response?.sync_token, "MOCK_SYNC_TOKEN");
+    Ok(())
+}
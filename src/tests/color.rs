@@ -0,0 +1,34 @@
+use crate::color::{self, Theme};
+
+#[test]
+fn paints_in_true_color() {
+    assert_eq!(
+        color::paint_with(Theme::Todoist, "p1", (0xd1, 0x45, 0x3b)),
+        "\x1b[38;2;209;69;59mp1\x1b[0m"
+    );
+}
+
+#[test]
+fn maps_todoist_colors_to_basic_ones() {
+    let code = |name: &str| {
+        let rgb = color::named(name).expect("a Todoist color");
+        color::paint_with(Theme::Basic, "", rgb)
+    };
+    assert_eq!(code("red"), "\x1b[31m\x1b[0m");
+    assert_eq!(code("orange"), "\x1b[33m\x1b[0m");
+    assert_eq!(code("green"), "\x1b[32m\x1b[0m");
+    assert_eq!(code("teal"), "\x1b[36m\x1b[0m");
+    assert_eq!(code("sky_blue"), "\x1b[34m\x1b[0m");
+    assert_eq!(code("grape"), "\x1b[35m\x1b[0m");
+    assert_eq!(code("berry_red"), "\x1b[35m\x1b[0m");
+    assert_eq!(code("grey"), "\x1b[90m\x1b[0m");
+
+    let priorities = [4, 3, 2].map(|priority| {
+        let rgb = color::priority(priority).expect("a priority color");
+        color::paint_with(Theme::Basic, "", rgb)
+    });
+    assert_eq!(
+        priorities,
+        ["\x1b[31m\x1b[0m", "\x1b[33m\x1b[0m", "\x1b[34m\x1b[0m"]
+    );
+}
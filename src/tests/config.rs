@@ -0,0 +1,105 @@
+use std::path::PathBuf;
+
+use crate::{
+    color,
+    config::{Config, Output},
+};
+
+#[test]
+fn sets_and_gets_values() -> Result<(), Box<dyn std::error::Error>> {
+    let mut config = Config::default();
+    config.set("output", Some("JSON"))?;
+    config.set("color", Some("never"))?;
+    config.set("theme", Some("basic"))?;
+    config.set("sync_on_start", Some("no"))?;
+    config.set("default_project", Some(" Work "))?;
+    config.set("token_storage", Some("keyring"))?;
+    config.set("backend", Some("Sync"))?;
+    config.set("http2", Some("on"))?;
+    config.set("on_conflict", Some("keep-remote"))?;
+    config.set("date_format", Some("%d.%m.%Y"))?;
+    config.set("locale", Some("de"))?;
+    config.set("cache_ttl", Some("300"))?;
+    config.set("keep_completed_days", Some("30"))?;
+    config.set("timeout", Some("10"))?;
+    config.set("retries", Some("5"))?;
+    config.set("track_comments", Some("yes"))?;
+    config.set("dedupe", Some("skip"))?;
+    config.set("webhook_hooks.item:added", Some("notify-send added"))?;
+    config.set("on_sync", Some("notify-send synced"))?;
+
+    assert_eq!(config.output, Some(Output::Json));
+    assert_eq!(config.color, Some(color::Choice::Never));
+    assert_eq!(config.theme, Some(color::Theme::Basic));
+    assert_eq!(config.get("output")?.as_deref(), Some("json"));
+    assert_eq!(config.get("sync_on_start")?.as_deref(), Some("false"));
+    assert_eq!(config.get("default_project")?.as_deref(), Some("Work"));
+    assert_eq!(config.get("token_storage")?.as_deref(), Some("keyring"));
+    assert_eq!(config.get("backend")?.as_deref(), Some("sync"));
+    assert_eq!(config.get("http2")?.as_deref(), Some("true"));
+    assert_eq!(config.get("on_conflict")?.as_deref(), Some("keep-remote"));
+    assert_eq!(config.get("date_format")?.as_deref(), Some("%d.%m.%Y"));
+    assert_eq!(config.get("locale")?.as_deref(), Some("de"));
+    assert_eq!(config.cache_ttl, Some(300));
+    assert_eq!(config.get("keep_completed_days")?.as_deref(), Some("30"));
+    assert_eq!(config.get("timeout")?.as_deref(), Some("10"));
+    assert_eq!(config.retries, Some(5));
+    assert_eq!(config.track_comments, Some(true));
+    assert_eq!(config.get("dedupe")?.as_deref(), Some("skip"));
+    assert_eq!(config.get("data_dir")?, None);
+    assert_eq!(
+        config.get("webhook_hooks.item:added")?.as_deref(),
+        Some("notify-send added")
+    );
+    assert_eq!(config.get("webhook_hooks.item:deleted")?, None);
+    assert_eq!(
+        config.get("on_sync")?.as_deref(),
+        Some("notify-send synced")
+    );
+    assert!(config
+        .keys()
+        .contains(&"webhook_hooks.item:added".to_string()));
+
+    config.set("output", None)?;
+    assert_eq!(config.output, None);
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_values() {
+    let mut config = Config::default();
+    assert!(config.set("colour", Some("never")).is_err());
+    assert!(config.get("colour").is_err());
+    assert!(config.set("color", Some("sometimes")).is_err());
+    assert!(config.set("sync_on_start", Some("maybe")).is_err());
+    assert!(config.set("data_dir", Some("relative/dir")).is_err());
+    assert!(config.set("ca_bundle", Some("certs/bundle.pem")).is_err());
+    assert!(config.set("default_project", Some("  ")).is_err());
+    assert!(config.set("date_format", Some("%Q")).is_err());
+    assert!(config.set("locale", Some("klingon")).is_err());
+    assert!(config.set("cache_ttl", Some("5m")).is_err());
+    assert!(config.set("keep_completed_days", Some("a month")).is_err());
+    assert!(config.set("timeout", Some("-1")).is_err());
+    assert!(config.set("retries", Some("a few")).is_err());
+    assert!(config.set("webhook_hooks.", Some("true")).is_err());
+}
+
+#[test]
+fn round_trips_through_file() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = assert_fs::TempDir::new()?;
+    let path = dir.path().join("nested").join("config.toml");
+    assert_eq!(Config::load(&path)?, Config::default());
+
+    let config = Config {
+        default_project: Some("Work".to_string()),
+        data_dir: Some(PathBuf::from("/tmp/todoist")),
+        webhook_hooks: [("item:added".to_string(), "cat".to_string())].into(),
+        ..Default::default()
+    };
+    config.save(&path)?;
+    assert_eq!(Config::load(&path)?, config);
+
+    std::fs::write(&path, "colour = \"never\"\n")?;
+    assert!(Config::load(&path).is_err());
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use crate::{
+    conflict::{detect, Difference},
+    sync::{Command, CommandArgs, Item, ItemCloseArgs, ItemIdArgs, ItemUpdateArgs},
+    tests::utils::ItemBuilder,
+};
+
+fn item(content: &str, priority: u8) -> Item {
+    ItemBuilder::new("I1")
+        .content(content)
+        .priority(priority)
+        .build()
+}
+
+fn update(args: ItemUpdateArgs) -> Command {
+    Command::new(CommandArgs::ItemUpdate(ItemUpdateArgs {
+        id: "I1".to_string(),
+        ..args
+    }))
+}
+
+#[test]
+fn same_field_changed_on_both_sides_conflicts() {
+    let command = update(ItemUpdateArgs {
+        content: Some("Buy oat milk".to_string()),
+        priority: Some(4),
+        ..Default::default()
+    });
+    let conflict = detect(
+        command,
+        item("Buy milk", 1),
+        Some(&item("Buy almond milk", 1)),
+    );
+
+    let differences = conflict.map(|conflict| conflict.differences);
+    assert_eq!(
+        differences,
+        Some(vec![Difference {
+            field: "Content",
+            local: "Buy oat milk".to_string(),
+            remote: "Buy almond milk".to_string(),
+        }])
+    );
+}
+
+#[test]
+fn different_fields_changed_dont_conflict() {
+    let command = update(ItemUpdateArgs {
+        content: Some("Buy oat milk".to_string()),
+        ..Default::default()
+    });
+    assert!(detect(command, item("Buy milk", 1), Some(&item("Buy milk", 4))).is_none());
+
+    // nor does making the same change on both sides
+    let command = update(ItemUpdateArgs {
+        priority: Some(4),
+        ..Default::default()
+    });
+    assert!(detect(command, item("Buy milk", 1), Some(&item("Buy milk", 4))).is_none());
+}
+
+#[test]
+fn changing_a_deleted_item_conflicts() {
+    let command = Command::new(CommandArgs::ItemClose(ItemCloseArgs {
+        id: "I1".to_string(),
+    }));
+    let conflict = detect(command, item("Buy milk", 1), None);
+    assert!(conflict.is_some_and(|conflict| conflict.remote.is_none()));
+
+    let deleted = Item {
+        is_deleted: true,
+        ..item("Buy milk", 1)
+    };
+    let command = update(ItemUpdateArgs {
+        content: Some("Buy oat milk".to_string()),
+        ..Default::default()
+    });
+    assert!(detect(command, item("Buy milk", 1), Some(&deleted)).is_some());
+}
+
+#[test]
+fn deleting_a_deleted_item_doesnt_conflict() {
+    let command = Command::new(CommandArgs::ItemDelete(ItemIdArgs {
+        id: "I1".to_string(),
+    }));
+    assert!(detect(command, item("Buy milk", 1), None).is_none());
+}
@@ -0,0 +1,28 @@
+use crate::daemon::{Request, Status};
+
+#[test]
+fn status_is_fresh_within_two_intervals() {
+    let status = Status {
+        last_sync: Some(1_000),
+        interval: 60,
+    };
+    assert!(status.is_fresh(1_000));
+    assert!(status.is_fresh(1_120));
+    assert!(!status.is_fresh(1_121));
+
+    let never_synced = Status {
+        last_sync: None,
+        interval: 60,
+    };
+    assert!(!never_synced.is_fresh(1_000));
+}
+
+#[test]
+fn requests_are_single_json_words() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(serde_json::to_string(&Request::Sync)?, "\"sync\"");
+    assert_eq!(
+        serde_json::from_str::<Request>("\"status\"")?,
+        Request::Status
+    );
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use crate::{dedupe::duplicate, sync::Item, tests::utils::ItemBuilder};
+
+fn item(id: &str, content: &str) -> Item {
+    ItemBuilder::new(id).content(content).build()
+}
+
+#[test]
+fn duplicates_ignore_case_spacing_and_punctuation() {
+    let items = [item("MILK", "Buy milk"), item("CALL", "Call the dentist")];
+    let found = |content| duplicate(content, &items).map(|item| item.id.as_str());
+    assert_eq!(found("buy  Milk!"), Some("MILK"));
+    assert_eq!(found("Call the dentst"), Some("CALL"));
+    assert_eq!(found("Buy silk"), None);
+    assert_eq!(found("Call the dentist's office"), None);
+    assert_eq!(found("!!!"), None);
+}
+
+#[test]
+fn only_open_tasks_are_duplicates() {
+    let items = [
+        Item {
+            checked: true,
+            ..item("DONE", "Water the plants")
+        },
+        Item {
+            is_deleted: true,
+            ..item("GONE", "Water the plants")
+        },
+    ];
+    assert!(duplicate("Water the plants", &items).is_none());
+}
@@ -0,0 +1,47 @@
+use crate::{
+    dependency::{blocked, would_cycle, Dependency},
+    sync::Item,
+};
+
+fn dependency(task_id: &str, blocker_id: &str) -> Dependency {
+    Dependency {
+        task_id: task_id.to_string(),
+        blocker_id: blocker_id.to_string(),
+    }
+}
+
+#[test]
+fn loops_are_caught() {
+    let dependencies = [dependency("HANG", "PAINT"), dependency("PAINT", "BUY")];
+    assert!(would_cycle(&dependencies, "BUY", "HANG"));
+    assert!(would_cycle(&dependencies, "PAINT", "HANG"));
+    assert!(would_cycle(&dependencies, "BUY", "BUY"));
+    assert!(!would_cycle(&dependencies, "HANG", "BUY"));
+    assert!(!would_cycle(&dependencies, "BUY", "CALL"));
+}
+
+#[test]
+fn tasks_are_blocked_until_what_they_wait_for_is_done() {
+    let item = |id: &str, checked| Item {
+        id: id.to_string(),
+        checked,
+        ..Default::default()
+    };
+    let dependencies = [
+        dependency("HANG", "PAINT"),
+        dependency("PAINT", "BUY"),
+        dependency("CALL", "GONE"),
+    ];
+    let items = [
+        item("BUY", false),
+        item("PAINT", false),
+        item("HANG", false),
+    ];
+    let mut ids: Vec<String> = blocked(&dependencies, &items).into_iter().collect();
+    ids.sort();
+    assert_eq!(ids, ["HANG", "PAINT"]);
+
+    let items = [item("BUY", true), item("PAINT", false), item("HANG", false)];
+    let ids: Vec<String> = blocked(&dependencies, &items).into_iter().collect();
+    assert_eq!(ids, ["HANG"]);
+}
@@ -0,0 +1,89 @@
+use crate::{
+    due::{validate, validate_in},
+    Error,
+};
+
+fn reason(text: &str) -> Option<String> {
+    match validate(text) {
+        Err(Error::Due(reason)) => Some(reason),
+        _ => None,
+    }
+}
+
+#[test]
+fn accepts_due_strings_todoist_understands() {
+    for text in [
+        "today",
+        "tomorrow at 5pm",
+        "every day",
+        "every other week",
+        "every 2 mondays",
+        "ev weekday at 9:30am",
+        "next friday",
+        "in 3 days",
+        "jan 27",
+        "every 3rd of the month starting 2024-02-01",
+        "2023-07-10 14:30",
+        "every workday until march 1",
+        "7/10",
+    ] {
+        assert_eq!(reason(text), None, "{text}");
+    }
+}
+
+#[test]
+fn leaves_unknown_words_to_todoist() {
+    assert_eq!(reason("after lunch"), None);
+    assert_eq!(reason("the 5th, sometime"), None);
+}
+
+#[test]
+fn suggests_fixes_for_misspelled_date_words() {
+    assert_eq!(
+        reason("evry day").as_deref(),
+        Some("did you mean 'every' instead of 'evry', in 'evry day'?")
+    );
+    assert_eq!(
+        reason("tomorow 5pm").as_deref(),
+        Some("did you mean 'tomorrow' instead of 'tomorow', in 'tomorow 5pm'?")
+    );
+    assert_eq!(
+        reason("every wednesdya").as_deref(),
+        Some("did you mean 'wednesday' instead of 'wednesdya', in 'every wednesdya'?")
+    );
+}
+
+#[test]
+fn rejects_times_and_dates_that_dont_exist() {
+    assert_eq!(
+        reason("today 25:00").as_deref(),
+        Some("'25:00' isn't a time")
+    );
+    assert_eq!(
+        reason("tomorrow 13pm").as_deref(),
+        Some("'13pm' isn't a time")
+    );
+    assert_eq!(reason("at 9:75").as_deref(), Some("'9:75' isn't a time"));
+    assert_eq!(
+        reason("2023-02-30").as_deref(),
+        Some("'2023-02-30' isn't a date")
+    );
+}
+
+#[test]
+fn rejects_empty_and_unfinished_recurrences() {
+    assert_eq!(reason("  ").as_deref(), Some("it's empty"));
+    assert_eq!(
+        reason("every").as_deref(),
+        Some("'every' needs to say how often, like 'every day' or 'every monday'")
+    );
+}
+
+#[test]
+fn only_checks_times_and_dates_in_other_languages() {
+    assert!(validate_in("jeden Montag um 9", "de").is_ok());
+    assert!(validate_in("evry day", "fr").is_ok());
+    assert!(validate_in("morgen 25:00", "de").is_err());
+    assert!(validate_in(" ", "de").is_err());
+    assert!(validate_in("evry day", "en").is_err());
+}
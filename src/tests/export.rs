@@ -0,0 +1,157 @@
+use crate::{
+    export::{Export, Format},
+    sync::{Deadline, Due, Item, Project, Section},
+};
+
+fn fixtures() -> (Vec<Project>, Vec<Section>, Vec<Item>) {
+    let projects = vec![
+        Project {
+            id: "P2".to_string(),
+            name: "Work".to_string(),
+            child_order: 2,
+            ..Default::default()
+        },
+        Project {
+            id: "P1".to_string(),
+            name: "Home".to_string(),
+            child_order: 1,
+            ..Default::default()
+        },
+    ];
+    let sections = vec![Section {
+        id: "S1".to_string(),
+        name: "Garden".to_string(),
+        project_id: "P1".to_string(),
+        ..Default::default()
+    }];
+    let item = |id: &str, content: &str, child_order: i64| Item {
+        id: id.to_string(),
+        content: content.to_string(),
+        project_id: "P1".to_string(),
+        child_order,
+        ..Default::default()
+    };
+    let items = vec![
+        Item {
+            parent_id: Some("T1".to_string()),
+            checked: true,
+            ..item("T2", "Buy bulbs", 1)
+        },
+        Item {
+            labels: vec!["errand".to_string()],
+            due: Some(Due {
+                date: "2024-05-01".to_string(),
+                string: Some("every wed".to_string()),
+                is_recurring: true,
+            }),
+            ..item("T1", "Plant tulips", 1)
+        },
+        Item {
+            section_id: Some("S1".to_string()),
+            ..item("T3", "Mow the lawn", 1)
+        },
+        Item {
+            description: "with \"care\", please".to_string(),
+            priority: 4,
+            ..item("T4", "Fix, the gate", 2)
+        },
+    ];
+    (projects, sections, items)
+}
+
+#[test]
+fn exports_nested_markdown() -> Result<(), Box<dyn std::error::Error>> {
+    let (projects, sections, items) = fixtures();
+    let export = Export {
+        projects: &projects,
+        sections: &sections,
+        items: &items,
+    };
+    assert_eq!(
+        export.render(Format::Markdown)?,
+        "# Home\n\
+         \n\
+         - [ ] Plant tulips (due every wed) @errand\n  \
+           - [x] Buy bulbs\n\
+         - [ ] Fix, the gate\n\
+         \n\
+         ## Garden\n\
+         \n\
+         - [ ] Mow the lawn\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn exports_org_outlines() -> Result<(), Box<dyn std::error::Error>> {
+    let (projects, sections, mut items) = fixtures();
+    items[3].deadline = Some(Deadline {
+        date: "2024-05-03".to_string(),
+    });
+    let export = Export {
+        projects: &projects,
+        sections: &sections,
+        items: &items,
+    };
+    assert_eq!(
+        export.render(Format::Org)?,
+        "* Home\n\
+         ** TODO Plant tulips :errand:\n\
+         SCHEDULED: <2024-05-01 Wed>\n\
+         :PROPERTIES:\n\
+         :DUE: every wed\n\
+         :END:\n\
+         *** DONE Buy bulbs\n\
+         ** TODO [#A] Fix, the gate\n\
+         DEADLINE: <2024-05-03 Fri>\n\
+         with \"care\", please\n\
+         ** Garden\n\
+         *** TODO Mow the lawn\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn exports_csv_with_quoting() -> Result<(), Box<dyn std::error::Error>> {
+    let (projects, sections, items) = fixtures();
+    let export = Export {
+        projects: &projects,
+        sections: &sections,
+        items: &items,
+    };
+    let csv = export.render(Format::Csv)?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[0],
+        "id,content,description,project,section,parent_id,due,priority,labels,completed"
+    );
+    assert_eq!(lines[1], "T2,Buy bulbs,,Home,,T1,,p4,,true");
+    assert_eq!(lines[3], "T3,Mow the lawn,,Home,Garden,,,p4,,false");
+    assert_eq!(
+        lines[4],
+        r#"T4,"Fix, the gate","with ""care"", please",Home,,,,p1,,false"#
+    );
+    Ok(())
+}
+
+#[test]
+fn leaves_out_other_projects() -> Result<(), Box<dyn std::error::Error>> {
+    let (projects, sections, items) = fixtures();
+    let work: Vec<Project> = projects
+        .into_iter()
+        .filter(|project| project.name == "Work")
+        .collect();
+    let export = Export {
+        projects: &work,
+        sections: &sections,
+        items: &items,
+    };
+    assert!(export.is_empty());
+
+    let json: serde_json::Value = serde_json::from_str(&export.render(Format::Json)?)?;
+    assert_eq!(json["projects"].as_array().map(Vec::len), Some(1));
+    assert_eq!(json["sections"].as_array().map(Vec::len), Some(0));
+    assert_eq!(json["items"].as_array().map(Vec::len), Some(0));
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use crate::files;
+
+#[test]
+fn writes_replace_files_without_leaving_anything_behind() -> Result<(), Box<dyn std::error::Error>>
+{
+    let dir = assert_fs::TempDir::new()?;
+    let path = dir.path().join("user.json");
+    files::write(&path, b"first")?;
+    files::write(&path, b"second")?;
+
+    assert_eq!(std::fs::read_to_string(&path)?, "second");
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn private_files_are_only_readable_by_their_owner() -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = assert_fs::TempDir::new()?;
+    let path = dir.path().join("client_auth.toml");
+    std::fs::write(&path, "api_key = \"OLD\"")?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644))?;
+    files::write_private(&path, b"api_key = \"NEW\"")?;
+
+    let mode = std::fs::metadata(&path)?.permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    Ok(())
+}
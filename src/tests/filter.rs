@@ -0,0 +1,164 @@
+use chrono::NaiveDate;
+
+use crate::{
+    filter::{parse, Context, DateSpec, Filter, Term},
+    sync::{Due, Item, Project, Section},
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str) -> Item {
+    ItemBuilder::new(id).project("INBOX").build()
+}
+
+fn due(date: &str) -> Due {
+    Due {
+        date: date.to_string(),
+        ..Default::default()
+    }
+}
+
+fn project(id: &str, name: &str, parent_id: Option<&str>) -> Project {
+    Project {
+        id: id.to_string(),
+        name: name.to_string(),
+        parent_id: parent_id.map(ToString::to_string),
+        ..Default::default()
+    }
+}
+
+/// Runs a single-query filter over `items`, returning the IDs that match.
+fn run(filter: &str, items: &[Item]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let projects = [
+        project("INBOX", "Inbox", None),
+        project("WORK", "Work", None),
+        project("MEETINGS", "Work Meetings", Some("WORK")),
+        project("STANDUP", "Standup", Some("MEETINGS")),
+    ];
+    let sections = [Section {
+        id: "SOON".to_string(),
+        name: "Soon".to_string(),
+        project_id: "WORK".to_string(),
+        ..Default::default()
+    }];
+    let context = Context {
+        today: NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?,
+        projects: &projects,
+        sections: &sections,
+    };
+
+    let queries = parse(filter)?;
+    assert_eq!(queries.len(), 1);
+    Ok(items
+        .iter()
+        .filter(|item| queries[0].1.matches(item, &context))
+        .map(|item| item.id.clone())
+        .collect())
+}
+
+#[test]
+fn parses_with_precedence() -> Result<(), Box<dyn std::error::Error>> {
+    let term = |term| Box::new(Filter::Term(term));
+    let queries = parse("!p1 | today & @email")?;
+
+    assert_eq!(
+        queries[0].1,
+        Filter::Or(
+            Box::new(Filter::Not(term(Term::Priority(1)))),
+            Box::new(Filter::And(
+                term(Term::DueOn(DateSpec::Relative(0))),
+                term(Term::Label("email".to_string()))
+            ))
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn splits_queries_on_commas() -> Result<(), Box<dyn std::error::Error>> {
+    let queries = parse("today, (overdue | p1), #Home Renovation")?;
+    let texts: Vec<&str> = queries.iter().map(|(text, _)| text.as_str()).collect();
+    assert_eq!(texts, ["today", "(overdue | p1)", "#Home Renovation"]);
+    assert_eq!(
+        queries[2].1,
+        Filter::Term(Term::Project {
+            name: "Home Renovation".to_string(),
+            with_subprojects: false
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_filters() {
+    assert!(parse("today &").is_err());
+    assert!(parse("(today | p1").is_err());
+    assert!(parse("today )").is_err());
+    assert!(parse("someday soon").is_err());
+}
+
+#[test]
+fn evaluates_date_terms() -> Result<(), Box<dyn std::error::Error>> {
+    let items = [
+        Item {
+            due: Some(due("2023-07-09")),
+            ..item("yesterday")
+        },
+        Item {
+            due: Some(due("2023-07-10T17:00:00")),
+            ..item("today")
+        },
+        Item {
+            due: Some(due("2023-07-12")),
+            ..item("soon")
+        },
+        Item {
+            due: Some(due("2023-07-30")),
+            ..item("later")
+        },
+        item("undated"),
+    ];
+
+    assert_eq!(run("today", &items)?, ["today"]);
+    assert_eq!(run("overdue | today", &items)?, ["yesterday", "today"]);
+    assert_eq!(run("no date", &items)?, ["undated"]);
+    assert_eq!(run("next 7 days", &items)?, ["today", "soon"]);
+    assert_eq!(run("3 days", &items)?, ["today", "soon"]);
+    assert_eq!(run("due before: tomorrow", &items)?, ["yesterday", "today"]);
+    assert_eq!(run("due after: 2023-07-12", &items)?, ["later"]);
+    assert_eq!(run("2023-07-12", &items)?, ["soon"]);
+    Ok(())
+}
+
+#[test]
+fn evaluates_attribute_terms() -> Result<(), Box<dyn std::error::Error>> {
+    let items = [
+        Item {
+            priority: 4,
+            labels: vec!["Email".to_string()],
+            ..item("urgent_email")
+        },
+        Item {
+            project_id: "WORK".to_string(),
+            section_id: Some("SOON".to_string()),
+            labels: vec!["email-later".to_string()],
+            ..item("work")
+        },
+        Item {
+            project_id: "STANDUP".to_string(),
+            content: "Prepare notes".to_string(),
+            ..item("standup")
+        },
+    ];
+
+    assert_eq!(run("p1", &items)?, ["urgent_email"]);
+    assert_eq!(run("@email", &items)?, ["urgent_email"]);
+    assert_eq!(run("@email*", &items)?, ["urgent_email", "work"]);
+    assert_eq!(run("no labels", &items)?, ["standup"]);
+    assert_eq!(run("#work", &items)?, ["work"]);
+    assert_eq!(run("##Work", &items)?, ["work", "standup"]);
+    assert_eq!(run("#work*", &items)?, ["work"]);
+    assert_eq!(run("/Soon", &items)?, ["work"]);
+    assert_eq!(run("search: NOTES", &items)?, ["standup"]);
+    assert_eq!(run("!#Inbox & !p1", &items)?, ["work", "standup"]);
+    Ok(())
+}
@@ -0,0 +1,8 @@
+use crate::focus::format_duration;
+
+#[test]
+fn durations_are_shown_to_the_minute() {
+    assert_eq!(format_duration(59), "0m");
+    assert_eq!(format_duration(25 * 60), "25m");
+    assert_eq!(format_duration(65 * 60 + 30), "1h 05m");
+}
@@ -0,0 +1,96 @@
+use chrono::NaiveDate;
+
+use crate::{
+    forecast::{forecast, shade},
+    sync::{Due, Item},
+};
+
+fn task(date: &str, string: &str, is_recurring: bool) -> Item {
+    Item {
+        id: string.to_string(),
+        content: string.to_string(),
+        due: Some(Due {
+            date: date.to_string(),
+            string: Some(string.to_string()),
+            is_recurring,
+        }),
+        ..Default::default()
+    }
+}
+
+fn counts(items: &[Item], days: u64) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    // a Monday
+    let today = NaiveDate::from_ymd_opt(2024, 5, 6).ok_or("bad date")?;
+    Ok(forecast(items, today, days)
+        .into_iter()
+        .map(|day| day.count)
+        .collect())
+}
+
+#[test]
+fn recurring_tasks_count_every_time_they_come_back() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        counts(&[task("2024-05-06", "every 2 days", true)], 7)?,
+        [1, 0, 1, 0, 1, 0, 1]
+    );
+    assert_eq!(
+        counts(
+            &[task(
+                "2024-05-08T09:00:00",
+                "every wed and fri at 9am",
+                true
+            )],
+            14
+        )?,
+        [0, 0, 1, 0, 1, 0, 0, 0, 0, 1, 0, 1, 0, 0]
+    );
+    assert_eq!(
+        counts(&[task("2024-05-07", "every other tuesday", true)], 15)?,
+        [0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+    );
+    assert_eq!(
+        counts(&[task("2024-05-06", "every month", true)], 62)?
+            .iter()
+            .sum::<usize>(),
+        3
+    );
+    assert_eq!(
+        counts(&[task("2024-05-07", "every! 3 days", true)], 7)?,
+        [0, 1, 0, 0, 1, 0, 0]
+    );
+    Ok(())
+}
+
+#[test]
+fn other_tasks_count_once() -> Result<(), Box<dyn std::error::Error>> {
+    let items = [
+        task("2024-05-07", "tomorrow", false),
+        task("2024-05-09", "every 15th", true),
+        task("2024-05-01", "may 1", false),
+        task("2024-05-20", "may 20", false),
+        Item {
+            checked: true,
+            ..task("2024-05-06", "today", false)
+        },
+    ];
+    assert_eq!(counts(&items, 7)?, [1, 1, 0, 1, 0, 0, 0]);
+    Ok(())
+}
+
+#[test]
+fn overdue_recurring_tasks_count_once_today() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        counts(&[task("2024-05-01", "every 3 days", true)], 7)?,
+        [1, 1, 0, 0, 1, 0, 0]
+    );
+    Ok(())
+}
+
+#[test]
+fn shades_scale_with_the_busiest_day() {
+    assert_eq!(shade(0, 5), '·');
+    assert_eq!(shade(1, 5), '░');
+    assert_eq!(shade(3, 5), '▓');
+    assert_eq!(shade(5, 5), '█');
+    assert_eq!(shade(1, 100), '░');
+}
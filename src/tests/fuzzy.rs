@@ -0,0 +1,72 @@
+use crate::fuzzy::{best_match, match_ranges, rank, score};
+
+#[test]
+fn ranks_kinds_of_matches() {
+    let exact = score("home renovation", "Home Renovation");
+    let prefix = score("home", "Home Renovation");
+    let word_prefix = score("reno", "Home Renovation");
+    let substring = score("novat", "Home Renovation");
+    let subsequence = score("hmrn", "Home Renovation");
+
+    assert!(exact > prefix);
+    assert!(prefix > word_prefix);
+    assert!(word_prefix > substring);
+    assert!(substring > subsequence);
+    assert!(subsequence.is_some());
+    assert_eq!(score("xyz", "Home Renovation"), None);
+}
+
+#[test]
+fn picks_best_match() {
+    let names = ["Work", "Home Renovation", "Homework"];
+
+    assert_eq!(
+        best_match("home reno", names, |name| name),
+        Some("Home Renovation")
+    );
+    assert_eq!(best_match("HOMEWORK", names, |name| name), Some("Homework"));
+    assert_eq!(best_match("work", names, |name| name), Some("Work"));
+    assert_eq!(best_match("garden", names, |name| name), None);
+}
+
+#[test]
+fn ties_go_to_shorter_names() {
+    let names = ["Errands Later", "Errands"];
+    assert_eq!(best_match("err", names, |name| name), Some("Errands"));
+}
+
+#[test]
+fn finds_match_ranges() {
+    let spans = |query, text| {
+        match_ranges(query, text).map(|ranges| {
+            ranges
+                .into_iter()
+                .map(|range| (range.start, range.end))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // contiguous matches are preferred, even across whitespace
+    assert_eq!(spans("dentist", "Call the Dentist"), Some(vec![(9, 16)]));
+    assert_eq!(
+        spans("home reno", "My Home Renovation"),
+        Some(vec![(3, 12)])
+    );
+    // scattered matches get a range for each run of characters
+    assert_eq!(
+        spans("dntst", "Call the dentist"),
+        Some(vec![(9, 10), (11, 13), (14, 16)])
+    );
+    assert_eq!(spans("xyz", "Call the dentist"), None);
+}
+
+#[test]
+fn ranks_candidates_as_the_query_is_typed() {
+    let names = ["Buy milk", "Call mom", "Buy bulbs", "Water plants"];
+
+    assert_eq!(rank("", &names), [0, 1, 2, 3]);
+    assert_eq!(rank("buy", &names), [0, 2]);
+    assert_eq!(rank("bulbs", &names), [2]);
+    assert_eq!(rank("al", &names), [1, 3]);
+    assert!(rank("xyz", &names).is_empty());
+}
@@ -0,0 +1,91 @@
+use crate::githook::{parse_log, push_range, references, script, Action, Commit, Reference};
+
+fn reference(task_id: &str, action: Action) -> Reference {
+    Reference {
+        task_id: task_id.to_string(),
+        action,
+    }
+}
+
+#[test]
+fn finds_references_and_what_to_do_with_them() {
+    let message = "Fix the login page (todoist:123)\n\
+                   \n\
+                   Closes https://app.todoist.com/app/task/fix-login-6Jf8VQXx.\n\
+                   See https://todoist.com/showTask?id=789&sync_id=1, and Fixes todoist:123\n\
+                   # todoist:999 is in a comment";
+
+    assert_eq!(
+        references(message),
+        [
+            reference("123", Action::Close),
+            reference("6Jf8VQXx", Action::Close),
+            reference("789", Action::Comment),
+        ]
+    );
+}
+
+#[test]
+fn ignores_things_that_are_not_references() {
+    assert!(references("todoist: the CLI, and todoist:a-b, and app.todoist.com").is_empty());
+}
+
+#[test]
+fn parses_git_log_output() {
+    let log =
+        "abc1234567\u{1f}Add login\n\nCloses todoist:1\n\u{1e}\ndef9876543\u{1f}Tidy up\n\u{1e}\n";
+
+    let commits = parse_log(log);
+
+    assert_eq!(
+        commits,
+        [
+            Commit {
+                sha: "abc1234567".to_string(),
+                message: "Add login\n\nCloses todoist:1".to_string(),
+            },
+            Commit {
+                sha: "def9876543".to_string(),
+                message: "Tidy up".to_string(),
+            },
+        ]
+    );
+    assert_eq!(commits[0].subject(), "Add login");
+    assert_eq!(commits[0].short_sha(), "abc1234");
+}
+
+#[test]
+fn pushes_cover_the_commits_the_remote_does_not_have() {
+    let zero = "0000000000000000000000000000000000000000";
+
+    assert_eq!(
+        push_range("refs/heads/main abc refs/heads/main def", "origin"),
+        Some(vec!["def..abc".to_string()])
+    );
+    assert_eq!(
+        push_range(
+            &format!("refs/heads/new abc refs/heads/new {zero}"),
+            "origin"
+        ),
+        Some(vec![
+            "abc".to_string(),
+            "--not".to_string(),
+            "--remotes=origin".to_string()
+        ])
+    );
+    assert_eq!(
+        push_range(&format!("(delete) {zero} refs/heads/old def"), "origin"),
+        None
+    );
+    assert_eq!(push_range("", "origin"), None);
+}
+
+#[test]
+fn scripts_quote_the_program() {
+    assert_eq!(
+        script("commit-msg", "/opt/it's here/todoist"),
+        "#!/bin/sh\n\
+         # Installed by `todoist hook install`.\n\
+         '/opt/it'\\''s here/todoist' hook commit-msg \"$1\" || true\n"
+    );
+}
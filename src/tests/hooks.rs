@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::{
+    changes::Changes,
+    hooks::{task_events, Event, Hooks},
+    storage::Store,
+    sync::{
+        Command, CommandArgs, CommandStatus, Item, ItemAddArgs, ItemCloseArgs, ItemIdArgs, Response,
+    },
+};
+
+#[test]
+fn commands_that_went_through_set_off_events() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    store.put_items(&[Item {
+        id: "milk".to_string(),
+        content: "Buy milk".to_string(),
+        ..Default::default()
+    }])?;
+    let add = Command::new(CommandArgs::ItemAdd(ItemAddArgs {
+        content: "Call mom".to_string(),
+        ..Default::default()
+    }));
+    let close = Command::new(CommandArgs::ItemClose(ItemCloseArgs {
+        id: "milk".to_string(),
+    }));
+    let rejected = Command::new(CommandArgs::ItemComplete(ItemIdArgs {
+        id: "gone".to_string(),
+    }));
+    let response = Response {
+        sync_status: Some(HashMap::from([
+            (add.uuid, CommandStatus::Ok("ok".to_string())),
+            (close.uuid, CommandStatus::Ok("ok".to_string())),
+            (
+                rejected.uuid,
+                CommandStatus::Error {
+                    error_code: 22,
+                    error: "Item not found".to_string(),
+                },
+            ),
+        ])),
+        temp_id_mapping: HashMap::from([(add.temp_id.ok_or("no temp ID")?, "call".to_string())]),
+        ..Default::default()
+    };
+    let commands = [add, close, rejected];
+
+    let events = task_events(&store, &commands, &response)?;
+
+    let events: Vec<serde_json::Value> = events
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0]["event"], "task_added");
+    assert_eq!(events[0]["id"], "call");
+    assert_eq!(events[0]["task"]["content"], "Call mom");
+    assert_eq!(events[1]["event"], "task_completed");
+    assert_eq!(events[1]["task"]["content"], "Buy milk");
+    Ok(())
+}
+
+#[test]
+fn only_events_with_hooks_run() -> Result<(), Box<dyn std::error::Error>> {
+    let hooks = Hooks {
+        on_sync: Some("cat".to_string()),
+        ..Default::default()
+    };
+    let events = [
+        Event::TaskCompleted {
+            id: "milk".to_string(),
+            task: None,
+        },
+        Event::Sync {
+            changes: Changes::default(),
+        },
+    ];
+
+    let runs = hooks.runs(&events)?;
+
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].setting, "on_sync");
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use chrono::{TimeZone, Utc};
+
+use crate::{
+    ical::{rrule, Calendar},
+    sync::{Due, DurationUnit, Item, Project, TaskDuration},
+};
+
+#[test]
+fn recurring_due_dates_become_rrules() {
+    assert_eq!(rrule("every day").as_deref(), Some("FREQ=DAILY"));
+    assert_eq!(rrule("Daily").as_deref(), Some("FREQ=DAILY"));
+    assert_eq!(rrule("every day at 9am").as_deref(), Some("FREQ=DAILY"));
+    assert_eq!(
+        rrule("every 3 weeks").as_deref(),
+        Some("FREQ=WEEKLY;INTERVAL=3")
+    );
+    assert_eq!(
+        rrule("every other month").as_deref(),
+        Some("FREQ=MONTHLY;INTERVAL=2")
+    );
+    assert_eq!(
+        rrule("every weekday").as_deref(),
+        Some("FREQ=WEEKLY;BYDAY=MO,TU,WE,TH,FR")
+    );
+    assert_eq!(
+        rrule("every mon, wed and fri").as_deref(),
+        Some("FREQ=WEEKLY;BYDAY=MO,WE,FR")
+    );
+    assert_eq!(rrule("every! 3 days"), None);
+    assert_eq!(rrule("every 15th"), None);
+    assert_eq!(rrule("tomorrow"), None);
+}
+
+#[test]
+fn due_tasks_become_events() -> Result<(), Box<dyn std::error::Error>> {
+    let projects = vec![Project {
+        id: "home".to_string(),
+        name: "Home".to_string(),
+        ..Default::default()
+    }];
+    let due = |date: &str, string: &str, is_recurring| {
+        Some(Due {
+            date: date.to_string(),
+            string: Some(string.to_string()),
+            is_recurring,
+        })
+    };
+    let items = vec![
+        Item {
+            id: "trash".to_string(),
+            content: "Take out trash, recycling".to_string(),
+            project_id: "home".to_string(),
+            due: due("2023-07-12", "every wed", true),
+            ..Default::default()
+        },
+        Item {
+            id: "dentist".to_string(),
+            content: "Dentist".to_string(),
+            description: "Bring forms\nand card".to_string(),
+            project_id: "home".to_string(),
+            due: due("2023-07-14T15:30:00Z", "jul 14 3:30pm", false),
+            duration: Some(TaskDuration {
+                amount: 45,
+                unit: DurationUnit::Minute,
+            }),
+            ..Default::default()
+        },
+        Item {
+            id: "someday".to_string(),
+            content: "No due date".to_string(),
+            project_id: "home".to_string(),
+            ..Default::default()
+        },
+        Item {
+            id: "elsewhere".to_string(),
+            content: "Other project".to_string(),
+            project_id: "work".to_string(),
+            due: due("2023-07-12", "jul 12", false),
+            ..Default::default()
+        },
+    ];
+    let calendar = Calendar {
+        projects: &projects,
+        items: &items,
+    };
+    let now = Utc
+        .with_ymd_and_hms(2023, 7, 10, 8, 0, 0)
+        .single()
+        .ok_or("bad time")?;
+
+    assert_eq!(calendar.len(), 2);
+    assert_eq!(
+        calendar.render(now),
+        "BEGIN:VCALENDAR\r\n\
+         VERSION:2.0\r\n\
+         PRODID:-//todoist-cli//EN\r\n\
+         CALSCALE:GREGORIAN\r\n\
+         X-WR-CALNAME:Todoist\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:trash@todoist.com\r\n\
+         DTSTAMP:20230710T080000Z\r\n\
+         SUMMARY:Take out trash\\, recycling\r\n\
+         DTSTART;VALUE=DATE:20230712\r\n\
+         DTEND;VALUE=DATE:20230713\r\n\
+         RRULE:FREQ=WEEKLY;BYDAY=WE\r\n\
+         CATEGORIES:Home\r\n\
+         URL:https://app.todoist.com/app/task/trash\r\n\
+         END:VEVENT\r\n\
+         BEGIN:VEVENT\r\n\
+         UID:dentist@todoist.com\r\n\
+         DTSTAMP:20230710T080000Z\r\n\
+         SUMMARY:Dentist\r\n\
+         DTSTART:20230714T153000Z\r\n\
+         DURATION:PT45M\r\n\
+         DESCRIPTION:Bring forms\\nand card\r\n\
+         CATEGORIES:Home\r\n\
+         URL:https://app.todoist.com/app/task/dentist\r\n\
+         END:VEVENT\r\n\
+         END:VCALENDAR\r\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn long_lines_are_folded() {
+    let projects = vec![Project {
+        id: "home".to_string(),
+        name: "Home".to_string(),
+        ..Default::default()
+    }];
+    let items = vec![Item {
+        id: "long".to_string(),
+        content: "é".repeat(50),
+        project_id: "home".to_string(),
+        due: Some(Due {
+            date: "2023-07-12".to_string(),
+            ..Default::default()
+        }),
+        ..Default::default()
+    }];
+    let calendar = Calendar {
+        projects: &projects,
+        items: &items,
+    };
+
+    let text = calendar.render(Utc::now());
+    let summary: Vec<&str> = text
+        .split("\r\n")
+        .skip_while(|line| !line.starts_with("SUMMARY:"))
+        .take(2)
+        .collect();
+    assert!(summary.iter().all(|line| line.len() <= 75));
+    assert_eq!(summary[1], format!(" {}", "é".repeat(17)));
+}
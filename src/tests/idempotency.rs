@@ -0,0 +1,36 @@
+use crate::{
+    idempotency::{digest, uuid},
+    sync::{CommandArgs, ItemAddArgs, ItemCloseArgs, ItemIdArgs},
+};
+
+fn add(content: &str) -> CommandArgs {
+    CommandArgs::ItemAdd(ItemAddArgs {
+        content: content.to_string(),
+        project_id: "INBOX".to_string(),
+        ..Default::default()
+    })
+}
+
+#[test]
+fn commands_that_do_the_same_thing_have_the_same_digest() -> Result<(), Box<dyn std::error::Error>>
+{
+    assert_eq!(digest(&add("Buy milk"))?, digest(&add("Buy milk"))?);
+    assert_ne!(digest(&add("Buy milk"))?, digest(&add("Buy eggs"))?);
+    let complete = CommandArgs::ItemClose(ItemCloseArgs {
+        id: "Buy milk".to_string(),
+    });
+    let delete = CommandArgs::ItemDelete(ItemIdArgs {
+        id: "Buy milk".to_string(),
+    });
+    assert_ne!(digest(&complete)?, digest(&delete)?);
+    Ok(())
+}
+
+#[test]
+fn uuids_follow_the_digest_and_the_sequence() -> Result<(), Box<dyn std::error::Error>> {
+    let digest = digest(&add("Buy milk"))?;
+    assert_eq!(uuid(&digest, 1), uuid(&digest, 1));
+    assert_ne!(uuid(&digest, 1), uuid(&digest, 2));
+    assert_eq!(uuid(&digest, 1).get_version_num(), 4);
+    Ok(())
+}
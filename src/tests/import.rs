@@ -0,0 +1,225 @@
+use crate::import::{parse_csv, parse_markdown, parse_org, Task};
+
+#[test]
+fn parses_nested_markdown_checklists() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "\
+# Home
+Some notes that aren't tasks.
+
+- [ ] Plant tulips (due every wed) @errand @garden
+  - [x] Buy bulbs
+  - [ ] Dig holes
+- [ ] Call mom
+
+## Chores
+* [ ] Take out trash
+";
+    let tasks = parse_markdown(text)?;
+    let home = Some("Home".to_string());
+    assert_eq!(
+        tasks,
+        vec![
+            Task {
+                content: "Plant tulips".to_string(),
+                project: home.clone(),
+                due: Some("every wed".to_string()),
+                labels: vec!["errand".to_string(), "garden".to_string()],
+                ..Default::default()
+            },
+            Task {
+                content: "Buy bulbs".to_string(),
+                project: home.clone(),
+                completed: true,
+                parent: Some(0),
+                ..Default::default()
+            },
+            Task {
+                content: "Dig holes".to_string(),
+                project: home.clone(),
+                parent: Some(0),
+                ..Default::default()
+            },
+            Task {
+                content: "Call mom".to_string(),
+                project: home.clone(),
+                ..Default::default()
+            },
+            Task {
+                content: "Take out trash".to_string(),
+                project: home,
+                section: Some("Chores".to_string()),
+                ..Default::default()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn rejects_markdown_tasks_without_text() {
+    assert!(parse_markdown("- [ ] Fine\n- [ ]\n").is_err());
+}
+
+#[test]
+fn parses_csv_with_any_column_order() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "\
+Priority,Content,id,parent_id,labels,Completed,description
+p1,\"Plant tulips, red ones\",1,,@errand garden,,\"Along the \"\"fence\"\"\"
+,Buy bulbs,2,1,,true,
+4,Call mom,,,,,
+";
+    let tasks = parse_csv(text)?;
+    assert_eq!(
+        tasks,
+        vec![
+            Task {
+                content: "Plant tulips, red ones".to_string(),
+                description: Some("Along the \"fence\"".to_string()),
+                priority: Some(1),
+                labels: vec!["errand".to_string(), "garden".to_string()],
+                ..Default::default()
+            },
+            Task {
+                content: "Buy bulbs".to_string(),
+                completed: true,
+                parent: Some(0),
+                ..Default::default()
+            },
+            Task {
+                content: "Call mom".to_string(),
+                priority: Some(4),
+                ..Default::default()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn puts_csv_parents_before_their_subtasks() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "id,content,parent_id\nB,Buy bulbs,A\nA,Plant tulips,\nC,Water,B\n";
+    let tasks = parse_csv(text)?;
+    let contents: Vec<_> = tasks.iter().map(|task| task.content.as_str()).collect();
+    assert_eq!(contents, ["Plant tulips", "Buy bulbs", "Water"]);
+    assert_eq!(tasks[1].parent, Some(0));
+    assert_eq!(tasks[2].parent, Some(1));
+    Ok(())
+}
+
+#[test]
+fn reads_the_csv_that_export_writes() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "\
+id,content,description,project,section,parent_id,due,priority,labels,completed
+T1,Plant tulips,,Home,Garden,,every wed,p4,errand,false
+";
+    let tasks = parse_csv(text)?;
+    assert_eq!(tasks[0].project.as_deref(), Some("Home"));
+    assert_eq!(tasks[0].section.as_deref(), Some("Garden"));
+    assert_eq!(tasks[0].due.as_deref(), Some("every wed"));
+    assert!(!tasks[0].completed);
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_csv() {
+    assert!(parse_csv("name\nPlant tulips\n").is_err());
+    assert!(parse_csv("content,priority\nPlant tulips,p7\n").is_err());
+    assert!(parse_csv("content\n\"Plant tulips\n").is_err());
+    assert!(parse_csv("id,content,parent_id\nA,One,B\nB,Two,A\n").is_err());
+}
+
+#[test]
+fn parses_nested_org_outlines() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "\
+#+TITLE: Plans
+* Home
+Some notes that aren't tasks.
+** TODO [#A] Plant tulips :errand:garden:
+SCHEDULED: <2024-05-01 Wed 09:00 +1w> DEADLINE: <2024-05-03 Fri>
+:LOGBOOK:
+- State \"DONE\" from \"TODO\" [2024-04-24 Wed]
+:END:
+Before it rains.
+,* not a headline
+*** DONE Buy bulbs
+*** NEXT Dig holes
+:PROPERTIES:
+:DUE: every other day
+:END:
+** Chores
+*** CANCELLED Take out trash
+* Work
+** TODO [#D] Nothing
+";
+    assert!(parse_org(text).is_err());
+
+    let text = text.replace("[#D] ", "");
+    let tasks = parse_org(&text)?;
+    let home = Some("Home".to_string());
+    assert_eq!(
+        tasks,
+        vec![
+            Task {
+                content: "Plant tulips".to_string(),
+                description: Some("Before it rains.\n* not a headline".to_string()),
+                project: home.clone(),
+                due: Some("every week starting 2024-05-01 09:00".to_string()),
+                deadline: Some("2024-05-03".to_string()),
+                priority: Some(1),
+                labels: vec!["errand".to_string(), "garden".to_string()],
+                ..Default::default()
+            },
+            Task {
+                content: "Buy bulbs".to_string(),
+                project: home.clone(),
+                completed: true,
+                parent: Some(0),
+                ..Default::default()
+            },
+            Task {
+                content: "Dig holes".to_string(),
+                project: home.clone(),
+                due: Some("every other day".to_string()),
+                parent: Some(0),
+                ..Default::default()
+            },
+            Task {
+                content: "Take out trash".to_string(),
+                project: home,
+                section: Some("Chores".to_string()),
+                completed: true,
+                ..Default::default()
+            },
+            Task {
+                content: "Nothing".to_string(),
+                project: Some("Work".to_string()),
+                ..Default::default()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn reads_the_org_that_export_writes() -> Result<(), Box<dyn std::error::Error>> {
+    let text = "\
+* Home
+** TODO [#B] Plant tulips :errand:
+SCHEDULED: <2024-05-01 Wed>
+:PROPERTIES:
+:DUE: every wed
+:END:
+*** DONE Buy bulbs
+** Garden
+*** TODO Mow the lawn
+SCHEDULED: <2024-05-02 Thu 17:30>
+";
+    let tasks = parse_org(text)?;
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[0].due.as_deref(), Some("every wed"));
+    assert_eq!(tasks[0].priority, Some(2));
+    assert_eq!(tasks[1].parent, Some(0));
+    assert_eq!(tasks[2].section.as_deref(), Some("Garden"));
+    assert_eq!(tasks[2].due.as_deref(), Some("2024-05-02 17:30"));
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use chrono::FixedOffset;
+
+use crate::locale::{format_due_date, is_valid_date_format, parse_offset};
+
+#[test]
+fn parses_offsets() {
+    assert_eq!(parse_offset("+02:00"), FixedOffset::east_opt(2 * 3600));
+    assert_eq!(
+        parse_offset("+05:30"),
+        FixedOffset::east_opt(5 * 3600 + 30 * 60)
+    );
+    assert_eq!(parse_offset("-03:00"), FixedOffset::west_opt(3 * 3600));
+    assert_eq!(parse_offset("02:00"), None);
+    assert_eq!(parse_offset("+two"), None);
+    assert_eq!(parse_offset(""), None);
+}
+
+#[test]
+fn checks_date_formats() {
+    assert!(is_valid_date_format("%d.%m.%Y"));
+    assert!(is_valid_date_format("%b %-d"));
+    assert!(!is_valid_date_format("%Q"));
+    assert!(!is_valid_date_format(""));
+}
+
+#[test]
+fn formats_due_dates_without_a_locale() {
+    assert_eq!(format_due_date("2024-05-01"), "2024-05-01");
+    assert_eq!(format_due_date("2024-05-01T09:30:00"), "2024-05-01 09:30");
+    assert_eq!(format_due_date("next week"), "next week");
+}
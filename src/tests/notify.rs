@@ -0,0 +1,50 @@
+use chrono::NaiveDate;
+
+use crate::{
+    notify::{alerts, batch_summary, Kind},
+    sync::Item,
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, due: &str) -> Item {
+    ItemBuilder::new(id).due(due).build()
+}
+
+#[test]
+fn alerts_when_tasks_become_due_or_overdue() -> Result<(), Box<dyn std::error::Error>> {
+    let now = NaiveDate::from_ymd_opt(2024, 5, 1)
+        .and_then(|date| date.and_hms_opt(12, 0, 0))
+        .ok_or("invalid date")?;
+    let items = vec![
+        item("later", "2024-05-01T15:00:00"),
+        item("noon", "2024-05-01T11:30:00"),
+        item("today", "2024-05-01"),
+        item("yesterday", "2024-04-30T09:00:00"),
+        item("tomorrow", "2024-05-02"),
+        Item {
+            checked: true,
+            ..item("done", "2024-04-29")
+        },
+        Item {
+            due: None,
+            ..item("someday", "")
+        },
+    ];
+
+    let alerts = alerts(&items, now);
+    let found: Vec<(&str, Kind)> = alerts
+        .iter()
+        .map(|alert| (alert.item.id.as_str(), alert.kind))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("yesterday", Kind::Overdue),
+            ("today", Kind::Due),
+            ("noon", Kind::Due),
+        ]
+    );
+    assert_eq!(alerts[0].key(), "yesterday@2024-04-30T09:00:00@overdue");
+    assert_eq!(batch_summary(&alerts), "2 tasks due, 1 overdue");
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use crate::order::{reorder, Position};
+
+fn words(text: &str) -> Vec<String> {
+    text.split_whitespace().map(ToString::to_string).collect()
+}
+
+#[test]
+fn parses_positions() {
+    assert_eq!(Position::parse(&words("top")).ok(), Some(Position::Top));
+    assert_eq!(
+        Position::parse(&words("Bottom")).ok(),
+        Some(Position::Bottom)
+    );
+    assert_eq!(
+        Position::parse(&words("after 3")).ok(),
+        Some(Position::After("3".to_string()))
+    );
+    assert!(Position::parse(&words("after")).is_err());
+    assert!(Position::parse(&words("middle")).is_err());
+    assert!(Position::parse(&words("top 3")).is_err());
+}
+
+#[test]
+fn moves_among_siblings() -> Result<(), Box<dyn std::error::Error>> {
+    let siblings = ["A", "B", "C", "D"];
+    assert_eq!(
+        reorder(&siblings, "C", &Position::Top)?,
+        ["C", "A", "B", "D"]
+    );
+    assert_eq!(
+        reorder(&siblings, "B", &Position::Bottom)?,
+        ["A", "C", "D", "B"]
+    );
+    assert_eq!(
+        reorder(&siblings, "A", &Position::After("C".to_string()))?,
+        ["B", "C", "A", "D"]
+    );
+    assert_eq!(
+        reorder(&siblings, "D", &Position::After("A".to_string()))?,
+        ["A", "D", "B", "C"]
+    );
+    Ok(())
+}
+
+#[test]
+fn joins_new_siblings() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        reorder(&["A", "B"], "X", &Position::After("A".to_string()))?,
+        ["A", "X", "B"]
+    );
+    assert_eq!(reorder(&[], "X", &Position::Top)?, ["X"]);
+    Ok(())
+}
+
+#[test]
+fn rejects_going_after_a_stranger_or_itself() {
+    let siblings = ["A", "B"];
+    assert!(reorder(&siblings, "A", &Position::After("Z".to_string())).is_err());
+    assert!(reorder(&siblings, "A", &Position::After("A".to_string())).is_err());
+}
@@ -0,0 +1,56 @@
+use crate::{
+    porcelain::{self, Status},
+    sync::{Due, Item},
+};
+
+#[test]
+fn tasks_are_tab_separated_in_a_fixed_order() {
+    let item = Item {
+        id: "task".to_string(),
+        content: "Water the\tplants\nevery week".to_string(),
+        project_id: "home".to_string(),
+        section_id: Some("garden".to_string()),
+        due: Some(Due {
+            date: "2024-05-01T09:00:00".to_string(),
+            string: Some("every wed at 9am".to_string()),
+            is_recurring: true,
+        }),
+        priority: 4,
+        labels: vec!["outside".to_string(), "chores".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(
+        porcelain::task(&item),
+        "task\tWater the plants every week\thome\tgarden\t\t2024-05-01T09:00:00\tp1\toutside,chores\tfalse"
+    );
+
+    let item = Item {
+        id: "subtask".to_string(),
+        content: "Buy seeds".to_string(),
+        project_id: "home".to_string(),
+        parent_id: Some("task".to_string()),
+        priority: 1,
+        checked: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        porcelain::task(&item),
+        "subtask\tBuy seeds\thome\t\ttask\t\tp4\t\ttrue"
+    );
+}
+
+#[test]
+fn results_start_with_what_happened() {
+    assert_eq!(
+        porcelain::result(Status::Added, "task", "Buy milk"),
+        "added\ttask\tBuy milk"
+    );
+    assert_eq!(
+        porcelain::result(Status::Queued, "temp", "Call\tmom"),
+        "queued\ttemp\tCall mom"
+    );
+    assert_eq!(
+        porcelain::result(Status::Completed, "task", ""),
+        "completed\ttask\t"
+    );
+}
@@ -0,0 +1,85 @@
+use crate::quick_add::{parse, parse_without_dates, QuickAdd};
+
+#[test]
+fn plain_text_is_all_content() {
+    assert_eq!(
+        parse("Buy 5 apples for the party"),
+        QuickAdd {
+            content: "Buy 5 apples for the party".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn parses_all_attributes() {
+    assert_eq!(
+        parse("Buy milk tomorrow 5pm #Errands @store @dairy p1 +sam"),
+        QuickAdd {
+            content: "Buy milk".to_string(),
+            project: Some("Errands".to_string()),
+            labels: vec!["store".to_string(), "dairy".to_string()],
+            priority: Some(1),
+            assignee: Some("sam".to_string()),
+            due: Some("tomorrow 5pm".to_string()),
+        }
+    );
+}
+
+#[test]
+fn parses_date_phrases() {
+    let due = |text| parse(text).due;
+
+    assert_eq!(due("Call mom on monday"), Some("on monday".to_string()));
+    assert_eq!(due("Read chapter in 3 days"), Some("in 3 days".to_string()));
+    assert_eq!(
+        due("Pay rent every month on the 1st"),
+        Some("every month on the 1st".to_string())
+    );
+    assert_eq!(
+        due("Dentist jan 5 at 9:30am"),
+        Some("jan 5 at 9:30am".to_string())
+    );
+    assert_eq!(
+        due("Submit report by 2023-07-01"),
+        Some("by 2023-07-01".to_string())
+    );
+    assert_eq!(
+        due("Water plants every other day!"),
+        Some("every other day".to_string())
+    );
+    assert_eq!(due("Standup next week"), Some("next week".to_string()));
+}
+
+#[test]
+fn ignores_words_that_only_look_like_dates() {
+    let due = |text| parse(text).due;
+
+    assert_eq!(due("Meet at the park"), None);
+    assert_eq!(due("Put 5 things in 2 boxes"), None);
+    assert_eq!(due("Buy sun cream"), None);
+    assert_eq!(due("I may go"), None);
+    assert_eq!(due("Work on report"), None);
+}
+
+#[test]
+fn date_phrase_is_removed_from_content() {
+    let quick_add = parse("Call mom tomorrow at 5pm about dinner");
+    assert_eq!(quick_add.content, "Call mom about dinner");
+    assert_eq!(quick_add.due, Some("tomorrow at 5pm".to_string()));
+}
+
+#[test]
+fn only_p1_to_p4_are_priorities() {
+    assert_eq!(parse("Fix p5 cable").priority, None);
+    assert_eq!(parse("Fix p5 cable").content, "Fix p5 cable");
+    assert_eq!(parse("Fix cable P2").priority, Some(2));
+}
+
+#[test]
+fn dates_can_be_left_in_content() {
+    let quick_add = parse_without_dates("Call mom on monday #Family");
+    assert_eq!(quick_add.content, "Call mom on monday");
+    assert_eq!(quick_add.project, Some("Family".to_string()));
+    assert_eq!(quick_add.due, None);
+}
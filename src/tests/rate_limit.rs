@@ -0,0 +1,73 @@
+use std::time::{Duration, Instant};
+
+use crate::rate_limit::{Limiter, BURST, REQUESTS_PER_WINDOW, WINDOW};
+
+#[test]
+fn lets_a_burst_through_then_spaces_requests_out() {
+    let limiter = Limiter::new(5, Duration::from_secs(4), 3);
+    let now = Instant::now();
+    let waits: Vec<Duration> = (0..6).map(|_| limiter.take(now)).collect();
+    assert_eq!(
+        waits,
+        [0, 0, 0, 2, 4, 6].map(Duration::from_secs),
+        "after the burst, one token comes back every two seconds"
+    );
+
+    let later = now + Duration::from_secs(20);
+    assert_eq!(
+        limiter.take(later),
+        Duration::ZERO,
+        "the bucket fills up again"
+    );
+}
+
+#[test]
+fn a_limit_of_zero_still_lets_one_request_through_per_window() {
+    let limiter = Limiter::new(0, Duration::from_secs(4), 3);
+    let now = Instant::now();
+    let waits: Vec<Duration> = (0..3).map(|_| limiter.take(now)).collect();
+    assert_eq!(waits, [0, 4, 8].map(Duration::from_secs));
+}
+
+#[test]
+fn never_goes_over_the_limit_in_a_window() {
+    let limiter = Limiter::default();
+    let start = Instant::now();
+    let mut sent_at = Vec::new();
+    let mut now = start;
+    while now < start + WINDOW * 2 {
+        now += limiter.take(now);
+        sent_at.push(now);
+    }
+    assert!(sent_at.len() > REQUESTS_PER_WINDOW as usize);
+    assert_eq!(
+        sent_at.iter().filter(|at| **at == start).count(),
+        BURST as usize
+    );
+    for (index, first) in sent_at.iter().enumerate() {
+        let in_window = sent_at[index..]
+            .iter()
+            .take_while(|at| **at < *first + WINDOW)
+            .count();
+        assert!(in_window <= REQUESTS_PER_WINDOW as usize);
+    }
+}
+
+#[test]
+fn holds_off_for_as_long_as_asked() {
+    let limiter = Limiter::new(5, Duration::from_secs(4), 3);
+    let now = Instant::now();
+    limiter.hold_off(now, Duration::from_secs(10));
+    assert_eq!(limiter.take(now), Duration::from_secs(10));
+    assert_eq!(limiter.take(now), Duration::from_secs(12));
+}
+
+#[tokio::test]
+async fn is_shared_between_clones() {
+    let limiter = Limiter::new(2, Duration::from_millis(200), 1);
+    let clone = limiter.clone();
+    let start = Instant::now();
+    limiter.acquire().await;
+    clone.acquire().await;
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
@@ -0,0 +1,54 @@
+use crate::reminder::{describe_offset, parse, parse_place, Place, When};
+
+#[test]
+fn parses_relative_reminders() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(parse("30 minutes before")?, When::Before(30));
+    assert_eq!(parse("1 hour before")?, When::Before(60));
+    assert_eq!(parse("2h before")?, When::Before(120));
+    assert_eq!(parse("1 Day Before")?, When::Before(60 * 24));
+    Ok(())
+}
+
+#[test]
+fn other_text_is_an_absolute_time() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        parse(" tomorrow 9am ")?,
+        When::At("tomorrow 9am".to_string())
+    );
+    Ok(())
+}
+
+#[test]
+fn rejects_malformed_reminders() {
+    assert!(parse("").is_err());
+    assert!(parse("soon before").is_err());
+    assert!(parse("30 fortnights before").is_err());
+}
+
+#[test]
+fn describes_offsets() {
+    assert_eq!(describe_offset(0), "when due");
+    assert_eq!(describe_offset(1), "1 minute before");
+    assert_eq!(describe_offset(90), "90 minutes before");
+    assert_eq!(describe_offset(120), "2 hours before");
+    assert_eq!(describe_offset(60 * 24), "1 day before");
+}
+
+#[test]
+fn parses_places() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(
+        parse_place("40.7128, -74.0060, 100")?,
+        Place {
+            lat: "40.7128".to_string(),
+            long: "-74.0060".to_string(),
+            radius: Some(100),
+        }
+    );
+    assert_eq!(parse_place("-33.86,151.21")?.radius, None);
+    assert!(parse_place("40.7128").is_err());
+    assert!(parse_place("91,0,100").is_err());
+    assert!(parse_place("0,-181").is_err());
+    assert!(parse_place("0,0,0").is_err());
+    assert!(parse_place("home,0,100").is_err());
+    Ok(())
+}
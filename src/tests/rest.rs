@@ -0,0 +1,73 @@
+use serde_json::json;
+use wiremock::{matchers, Mock, MockServer, ResponseTemplate};
+
+use crate::{
+    client::SyncClient, rest::RestClient, session::fetch_item, storage::Store, sync::Item, Error,
+};
+
+async fn mock_task_server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(matchers::method("GET"))
+        .and(matchers::path("/tasks/T1"))
+        .and(matchers::header("Authorization", "Bearer MOCK_API_KEY"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "id": "T1",
+            "content": "water plants",
+            "description": "the ones on the balcony",
+            "project_id": "P1",
+            "section_id": null,
+            "parent_id": null,
+            "order": 3,
+            "priority": 4,
+            "labels": ["home"],
+            "due": {"date": "2024-05-01", "string": "every wed", "is_recurring": true},
+            "is_completed": false
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(matchers::path("/tasks/T2"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+    Mock::given(matchers::path("/tasks/T3"))
+        .respond_with(ResponseTemplate::new(401))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn fetches_a_task_as_an_item() -> Result<(), Box<dyn std::error::Error>> {
+    let server = mock_task_server().await;
+    let rest = RestClient::new(&server.uri(), "MOCK_API_KEY");
+
+    let item = Item::from(rest.task("T1").await?.ok_or("task not found")?);
+    assert_eq!(item.content, "water plants");
+    assert_eq!(item.description, "the ones on the balcony");
+    assert_eq!(item.child_order, 3);
+    assert_eq!(item.priority, 4);
+    assert_eq!(item.labels, vec!["home".to_string()]);
+    assert!(item.due.is_some_and(|due| due.is_recurring));
+
+    assert!(rest.task("T2").await?.is_none());
+    assert!(matches!(rest.task("T3").await, Err(Error::Auth)));
+    Ok(())
+}
+
+#[tokio::test]
+async fn looks_up_uncached_items_without_syncing() -> Result<(), Box<dyn std::error::Error>> {
+    let server = mock_task_server().await;
+    let mut store = Store::open_in_memory()?;
+
+    // nothing is listening on the sync port, so a sync would fail
+    let client = SyncClient::new("http://127.0.0.1:1", "MOCK_API_KEY", 0)
+        .with_rest(RestClient::new(&server.uri(), "MOCK_API_KEY"));
+
+    let item = fetch_item(&mut store, &client, "T1").await?;
+    assert_eq!(
+        item.map(|item| item.content).as_deref(),
+        Some("water plants")
+    );
+    assert!(store.item("T1")?.is_some());
+    Ok(())
+}
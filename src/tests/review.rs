@@ -0,0 +1,122 @@
+use chrono::NaiveDate;
+
+use crate::{
+    review::{review, week_start},
+    sync::{CompletedItem, Deadline, Item, Project},
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, due: Option<&str>) -> Item {
+    ItemBuilder::new(id).project("inbox").due(due).build()
+}
+
+fn completed(id: &str, project_id: &str, completed_at: &str) -> CompletedItem {
+    CompletedItem {
+        task_id: id.to_string(),
+        content: format!("Todo {id}"),
+        project_id: project_id.to_string(),
+        completed_at: completed_at.to_string(),
+        ..Default::default()
+    }
+}
+
+fn project(id: &str, name: &str, child_order: i64) -> Project {
+    Project {
+        id: id.to_string(),
+        name: name.to_string(),
+        child_order,
+        ..Default::default()
+    }
+}
+
+fn ids(items: &[&Item]) -> Vec<String> {
+    items.iter().map(|item| item.id.clone()).collect()
+}
+
+#[test]
+fn weeks_start_on_monday() -> Result<(), Box<dyn std::error::Error>> {
+    let monday = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let sunday = NaiveDate::from_ymd_opt(2023, 7, 16).ok_or("bad date")?;
+
+    assert_eq!(week_start(monday), monday);
+    assert_eq!(week_start(sunday), monday);
+    Ok(())
+}
+
+#[test]
+fn reviews_the_week_so_far() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2023, 7, 12).ok_or("bad date")?;
+    let projects = vec![project("work", "Work", 2), project("inbox", "Inbox", 1)];
+    let done = vec![
+        completed("report", "work", "2023-07-11T12:00:00Z"),
+        completed("milk", "inbox", "2023-07-12T12:00:00Z"),
+        completed("eggs", "inbox", "2023-07-10T12:00:00Z"),
+        completed("last_week", "inbox", "2023-07-07T12:00:00Z"),
+    ];
+    let items = vec![
+        Item {
+            added_at: Some("2023-07-11T12:00:00Z".to_string()),
+            ..item("new", None)
+        },
+        Item {
+            added_at: Some("2023-07-01T12:00:00Z".to_string()),
+            ..item("old", Some("2023-07-14"))
+        },
+        item("late", Some("2023-07-05")),
+        Item {
+            deadline: Some(Deadline {
+                date: "2023-07-18".to_string(),
+            }),
+            ..item("deadline", Some("2023-07-30"))
+        },
+        item("far_off", Some("2023-07-19")),
+        Item {
+            checked: true,
+            ..item("done_late", Some("2023-07-05"))
+        },
+    ];
+
+    let review = review(&done, &items, &projects, today);
+
+    let completed: Vec<(&str, Vec<&str>)> = review
+        .completed
+        .iter()
+        .map(|(project_id, items)| {
+            let ids = items.iter().map(|item| item.task_id.as_str()).collect();
+            (project_id.as_str(), ids)
+        })
+        .collect();
+    assert_eq!(
+        completed,
+        [("inbox", vec!["eggs", "milk"]), ("work", vec!["report"])]
+    );
+    assert_eq!(ids(&review.added), ["new"]);
+    assert_eq!(ids(&review.overdue), ["late"]);
+    let upcoming: Vec<&Item> = review.upcoming.iter().map(|(_, item)| *item).collect();
+    assert_eq!(ids(&upcoming), ["old", "deadline"]);
+    Ok(())
+}
+
+#[test]
+fn reviews_render_as_markdown() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2023, 7, 12).ok_or("bad date")?;
+    let projects = vec![project("inbox", "Inbox", 1)];
+    let done = vec![completed("milk", "inbox", "2023-07-11T12:00:00Z")];
+    let items = vec![item("late", Some("2023-07-05"))];
+
+    let review = review(&done, &items, &projects, today);
+    let markdown = review.markdown(str::to_uppercase);
+
+    assert_eq!(
+        markdown,
+        "# Week of 2023-07-10\n\n\
+         ## Completed (1)\n\n\
+         ### INBOX\n\n\
+         - [x] Todo milk\n\n\
+         ## Added (0)\n\n\
+         ## Overdue (1)\n\n\
+         - [ ] Todo late (due 2023-07-05)\n\n\
+         ## Coming up (0)\n"
+    );
+    Ok(())
+}
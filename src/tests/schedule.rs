@@ -0,0 +1,156 @@
+use chrono::{NaiveDate, NaiveTime};
+
+use crate::{
+    schedule::{parse_duration, schedule, snoozed_due},
+    sync::{DurationUnit, Item, TaskDuration},
+    tests::utils::ItemBuilder,
+};
+
+fn minutes(amount: u32) -> TaskDuration {
+    TaskDuration {
+        amount,
+        unit: DurationUnit::Minute,
+    }
+}
+
+fn item(id: &str, date: &str, duration: Option<TaskDuration>) -> Item {
+    ItemBuilder::new(id).due(date).duration(duration).build()
+}
+
+#[test]
+fn parses_durations() {
+    assert_eq!(parse_duration("45m"), Some(minutes(45)));
+    assert_eq!(parse_duration("45"), Some(minutes(45)));
+    assert_eq!(parse_duration("1h30m"), Some(minutes(90)));
+    assert_eq!(parse_duration("1.5 hours"), Some(minutes(90)));
+    assert_eq!(parse_duration("2H"), Some(minutes(120)));
+    assert_eq!(
+        parse_duration("2d"),
+        Some(TaskDuration {
+            amount: 2,
+            unit: DurationUnit::Day,
+        })
+    );
+    assert_eq!(parse_duration("1d 2h"), Some(minutes(26 * 60)));
+}
+
+#[test]
+fn rejects_invalid_durations() {
+    assert_eq!(parse_duration(""), None);
+    assert_eq!(parse_duration("0m"), None);
+    assert_eq!(parse_duration("soon"), None);
+    assert_eq!(parse_duration("5 weeks"), None);
+    assert_eq!(parse_duration("h"), None);
+}
+
+#[test]
+fn formats_durations() {
+    let format = |text: &str| parse_duration(text).map(|duration| duration.to_string());
+    assert_eq!(format("45m").as_deref(), Some("45m"));
+    assert_eq!(format("60m").as_deref(), Some("1h"));
+    assert_eq!(format("90m").as_deref(), Some("1h 30m"));
+    assert_eq!(format("3d").as_deref(), Some("3d"));
+}
+
+#[test]
+fn lays_out_a_day_in_order() -> Result<(), Box<dyn std::error::Error>> {
+    let date = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let items = vec![
+        item("lunch", "2023-07-10T12:00:00", Some(minutes(60))),
+        item("standup", "2023-07-10T09:00:00", Some(minutes(45))),
+        item("review", "2023-07-10T09:30:00", Some(minutes(30))),
+        item("anytime", "2023-07-10", Some(minutes(20))),
+        item("tomorrow", "2023-07-11T09:00:00", Some(minutes(30))),
+    ];
+
+    let schedule = schedule(&items, date);
+    let blocks: Vec<(&str, String, String, bool)> = schedule
+        .blocks
+        .iter()
+        .map(|block| {
+            (
+                block.item.id.as_str(),
+                block.start.format("%H:%M").to_string(),
+                block.end.format("%H:%M").to_string(),
+                block.overlaps,
+            )
+        })
+        .collect();
+    assert_eq!(
+        blocks,
+        vec![
+            ("standup", "09:00".to_string(), "09:45".to_string(), false),
+            ("review", "09:30".to_string(), "10:00".to_string(), true),
+            ("lunch", "12:00".to_string(), "13:00".to_string(), false),
+        ]
+    );
+    let unscheduled: Vec<&str> = schedule
+        .unscheduled
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect();
+    assert_eq!(unscheduled, vec!["anytime"]);
+    Ok(())
+}
+
+#[test]
+fn leaves_whole_day_tasks_unscheduled() -> Result<(), Box<dyn std::error::Error>> {
+    let date = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let items = vec![
+        item(
+            "offsite",
+            "2023-07-10T09:00:00",
+            Some(TaskDuration {
+                amount: 1,
+                unit: DurationUnit::Day,
+            }),
+        ),
+        item("call", "2023-07-10T15:00:00", None),
+    ];
+
+    let schedule = schedule(&items, date);
+    assert_eq!(schedule.blocks.len(), 1);
+    assert_eq!(schedule.blocks[0].item.id, "call");
+    assert_eq!(schedule.blocks[0].start, schedule.blocks[0].end);
+    assert_eq!(schedule.unscheduled.len(), 1);
+    assert_eq!(schedule.unscheduled[0].id, "offsite");
+    Ok(())
+}
+
+#[test]
+fn snoozes_by_offsets() -> Result<(), Box<dyn std::error::Error>> {
+    let now = NaiveDate::from_ymd_opt(2023, 7, 10)
+        .and_then(|date| date.and_hms_opt(22, 15, 0))
+        .ok_or("bad date")?;
+    let nine = NaiveTime::from_hms_opt(9, 0, 0);
+
+    assert_eq!(
+        snoozed_due("+2h", None, now).as_deref(),
+        Some("2023-07-11 00:15")
+    );
+    assert_eq!(
+        snoozed_due("+30m", nine, now).as_deref(),
+        Some("2023-07-10 22:45")
+    );
+    assert_eq!(snoozed_due("+3d", None, now).as_deref(), Some("2023-07-13"));
+    assert_eq!(
+        snoozed_due("+1d", nine, now).as_deref(),
+        Some("2023-07-11 09:00")
+    );
+    assert_eq!(snoozed_due("+2w", None, now).as_deref(), Some("2023-07-24"));
+    assert_eq!(snoozed_due("+soon", None, now), None);
+    Ok(())
+}
+
+#[test]
+fn passes_other_snoozes_along() -> Result<(), Box<dyn std::error::Error>> {
+    let now = NaiveDate::from_ymd_opt(2023, 7, 10)
+        .and_then(|date| date.and_hms_opt(9, 0, 0))
+        .ok_or("bad date")?;
+    assert_eq!(
+        snoozed_due(" next monday ", None, now).as_deref(),
+        Some("next monday")
+    );
+    assert_eq!(snoozed_due("", None, now), None);
+    Ok(())
+}
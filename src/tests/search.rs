@@ -0,0 +1,43 @@
+use crate::{search::search, sync::Item, tests::utils::ItemBuilder};
+
+fn item(id: &str, content: &str, description: &str) -> Item {
+    ItemBuilder::new(id)
+        .content(content)
+        .description(description)
+        .build()
+}
+
+#[test]
+fn ranks_content_matches_above_description_matches() {
+    let items = [
+        item("1", "Book appointment", "Ask the dentist about Tuesday"),
+        item("2", "Call the dentist", ""),
+        item("3", "Buy groceries", ""),
+        Item {
+            checked: true,
+            ..item("4", "Pay the dentist", "")
+        },
+    ];
+
+    let hits = search("dentist", &items);
+    let ids: Vec<&str> = hits.iter().map(|hit| hit.item.id.as_str()).collect();
+    assert_eq!(ids, ["2", "1"]);
+    let spans = |ranges: &[std::ops::Range<usize>]| {
+        ranges
+            .iter()
+            .map(|range| (range.start, range.end))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(spans(&hits[0].content_matches), [(9, 16)]);
+    assert_eq!(spans(&hits[1].description_matches), [(8, 15)]);
+}
+
+#[test]
+fn descriptions_need_more_than_a_scattered_match() {
+    let items = [item(
+        "1",
+        "Groceries",
+        "don't forget the twine, it's for the stakes",
+    )];
+    assert!(search("dentist", &items).is_empty());
+}
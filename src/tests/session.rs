@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+
+use crate::{
+    client::SyncClient,
+    conflict::Strategy,
+    session::{
+        archived_items, completed_items, is_fresh, item_activity, send_command, sync,
+        sync_in_parts, COMPLETED_PAGE_SIZE, MAX_COMMANDS_PER_REQUEST,
+    },
+    storage::Store,
+    sync::{
+        ActivityPage, ArchivedPage, Command, CommandArgs, CommandStatus, CompletedItem,
+        CompletedPage, Event, Item, ItemAddArgs, ItemIdArgs, ItemUpdateArgs, Request, Response,
+    },
+    tests::utils::{ApiMockBuilder, FakeSyncServer},
+    Error,
+};
+
+fn add_command(content: &str) -> Command {
+    Command::new(CommandArgs::ItemAdd(ItemAddArgs {
+        project_id: "P1".to_string(),
+        content: content.to_string(),
+        ..Default::default()
+    }))
+}
+
+#[tokio::test]
+async fn queued_commands_go_out_with_the_next_request() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+
+    // nothing is listening on this port, so the command is queued
+    let offline = SyncClient::new("http://127.0.0.1:1", "MOCK_API_KEY", 0);
+    let outcome = send_command(&mut store, &offline, add_command("plane todo")).await?;
+    assert!(outcome.response.is_none());
+    assert_eq!(store.queued_commands()?.len(), 1);
+
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_response(
+            "sync",
+            |request: Request| request.commands.len() == 1,
+            Response {
+                sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                ..Default::default()
+            },
+        )
+        .await;
+    let online = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+    let outcome = sync(&mut store, &online).await?;
+
+    assert!(outcome.response.is_some());
+    assert!(matches!(
+        &outcome.flushed[..],
+        [Command { args: CommandArgs::ItemAdd(args), .. }] if args.content == "plane todo"
+    ));
+    assert!(store.queued_commands()?.is_empty());
+    assert_eq!(store.sync_token()?, "MOCK_SYNC_TOKEN");
+    Ok(())
+}
+
+#[tokio::test]
+async fn commands_like_queued_ones_get_their_own_uuids() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+
+    // nothing is listening on this port, so the command is queued
+    let offline = SyncClient::new("http://127.0.0.1:1", "MOCK_API_KEY", 0);
+    send_command(&mut store, &offline, add_command("Buy milk")).await?;
+
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_response(
+            "sync",
+            |request: Request| {
+                matches!(&request.commands[..], [first, second] if first.uuid != second.uuid)
+            },
+            Response {
+                sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                ..Default::default()
+            },
+        )
+        .await;
+    let online = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+    let outcome = send_command(&mut store, &online, add_command("Buy milk")).await?;
+
+    assert!(outcome.response.is_some());
+    assert_eq!(outcome.flushed.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn long_queues_go_out_a_request_at_a_time() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let queued: Vec<Command> = (0..MAX_COMMANDS_PER_REQUEST + 50)
+        .map(|index| add_command(&format!("plane todo {index}")))
+        .collect();
+    store.set_queued_commands(&queued)?;
+
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_response(
+            "sync",
+            |request: Request| request.commands.len() <= MAX_COMMANDS_PER_REQUEST,
+            Response {
+                sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                ..Default::default()
+            },
+        )
+        .await;
+    let client = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+    let outcome = send_command(&mut store, &client, add_command("Buy milk")).await?;
+
+    assert!(outcome.response.is_some());
+    assert_eq!(outcome.flushed.len(), MAX_COMMANDS_PER_REQUEST + 50);
+    assert!(store.queued_commands()?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn dry_runs_hand_commands_back() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+
+    // the command never goes out, so it doesn't matter that nothing's listening
+    let client = SyncClient::new("http://127.0.0.1:1", "MOCK_API_KEY", 0).dry_run(true);
+    let result = send_command(&mut store, &client, add_command("pretend todo")).await;
+
+    assert!(matches!(
+        result,
+        Err(Error::DryRun(commands)) if matches!(
+            &commands[..],
+            [Command { args: CommandArgs::ItemAdd(args), .. }] if args.content == "pretend todo"
+        )
+    ));
+    assert!(store.queued_commands()?.is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejected_commands_are_errors() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let command = add_command("bad todo");
+
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_response(
+            "sync",
+            |_: Request| true,
+            Response {
+                sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                sync_status: Some(HashMap::from([(
+                    command.uuid,
+                    CommandStatus::Error {
+                        error_code: 20,
+                        error: "Project not found".to_string(),
+                    },
+                )])),
+                ..Default::default()
+            },
+        )
+        .await;
+    let client = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+
+    let result = send_command(&mut store, &client, command).await;
+    assert!(matches!(
+        result,
+        Err(Error::CommandRejected { code: 20, ref message, .. }) if message == "Project not found"
+    ));
+    Ok(())
+}
+
+#[tokio::test]
+async fn completed_items_go_through_every_page() -> Result<(), Box<dyn std::error::Error>> {
+    let completed = |count: u32| CompletedPage {
+        items: (0..count)
+            .map(|index| CompletedItem {
+                id: index.to_string(),
+                content: format!("todo {index}"),
+                ..Default::default()
+            })
+            .collect(),
+    };
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_get(
+            "completed/get_all",
+            &[("offset", "0"), ("since", "2024-05-01T00:00:00")],
+            completed(COMPLETED_PAGE_SIZE),
+        )
+        .await
+        .mock_get(
+            "completed/get_all",
+            &[("offset", "200"), ("since", "2024-05-01T00:00:00")],
+            completed(3),
+        )
+        .await;
+    let client = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+
+    let items = completed_items(&client, None, Some("2024-05-01T00:00:00")).await?;
+    assert_eq!(items.len(), COMPLETED_PAGE_SIZE as usize + 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn archived_items_follow_the_cursor_into_the_store() -> Result<(), Box<dyn std::error::Error>>
+{
+    let item = |id: &str| Item {
+        id: id.to_string(),
+        project_id: "P1".to_string(),
+        checked: true,
+        completed_at: Some("2024-05-01T12:00:00Z".to_string()),
+        ..Default::default()
+    };
+    // the later page goes first, since the first page's query matches it too
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_get(
+            "archive/items",
+            &[("project_id", "P1"), ("cursor", "MOCK_CURSOR")],
+            ArchivedPage {
+                items: vec![item("3")],
+                has_more: false,
+                next_cursor: None,
+            },
+        )
+        .await
+        .mock_get(
+            "archive/items",
+            &[("project_id", "P1")],
+            ArchivedPage {
+                items: vec![item("1"), item("2")],
+                has_more: true,
+                next_cursor: Some("MOCK_CURSOR".to_string()),
+            },
+        )
+        .await;
+    let client = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+    let mut store = Store::open_in_memory()?;
+
+    let items = archived_items(&mut store, &client, "P1").await?;
+    assert_eq!(items.len(), 3);
+    assert!(store
+        .item("3")?
+        .is_some_and(|item| item.checked && item.completed_at.is_some()));
+    Ok(())
+}
+
+#[tokio::test]
+async fn item_activity_skips_other_events() -> Result<(), Box<dyn std::error::Error>> {
+    let event = |id: &str, event_type: &str| Event {
+        id: id.to_string(),
+        object_type: "item".to_string(),
+        event_type: event_type.to_string(),
+        ..Default::default()
+    };
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_get(
+            "activity/get",
+            &[("object_type", "item"), ("parent_project_id", "P1")],
+            ActivityPage {
+                events: vec![
+                    event("E4", "updated"),
+                    event("E3", "deleted"),
+                    event("E2", "completed"),
+                    event("E1", "added"),
+                ],
+            },
+        )
+        .await;
+    let client = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+
+    let events = item_activity(&client, Some("P1"), 2).await?;
+    let ids: Vec<&str> = events.iter().map(|event| event.id.as_str()).collect();
+    assert_eq!(ids, ["E4", "E2"]);
+    Ok(())
+}
+
+/// A store with one item, and a queued edit to it made while offline, along
+/// with a server where the item has been edited differently since.
+async fn conflicting_edit() -> Result<(Store, ApiMockBuilder), Box<dyn std::error::Error>> {
+    let item = |content: &str| Item {
+        id: "I1".to_string(),
+        content: content.to_string(),
+        ..Default::default()
+    };
+    let mut store = Store::open_in_memory()?;
+    store.put_items(&[item("Buy milk")])?;
+
+    let offline = SyncClient::new("http://127.0.0.1:1", "MOCK_API_KEY", 0);
+    let edit = Command::new(CommandArgs::ItemUpdate(ItemUpdateArgs {
+        id: "I1".to_string(),
+        content: Some("Buy oat milk".to_string()),
+        ..Default::default()
+    }));
+    send_command(&mut store, &offline, edit).await?;
+
+    let mock_server = ApiMockBuilder::new()
+        .await
+        .mock_response(
+            "sync",
+            |_: Request| true,
+            Response {
+                sync_token: "MOCK_SYNC_TOKEN".to_string(),
+                items: Some(vec![item("Buy almond milk")]),
+                ..Default::default()
+            },
+        )
+        .await;
+    Ok((store, mock_server))
+}
+
+#[tokio::test]
+async fn conflicting_commands_stay_queued_when_asking() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, mock_server) = conflicting_edit().await?;
+    let client = SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0);
+
+    let result = send_command(&mut store, &client, add_command("new todo")).await;
+    assert!(matches!(
+        result,
+        Err(Error::Conflicts(conflicts)) if conflicts.len() == 1
+            && conflicts[0].differences[0].remote == "Buy almond milk"
+    ));
+    // the new command waits behind the conflicting one
+    assert_eq!(store.queued_commands()?.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn conflicting_commands_can_be_dropped() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, mock_server) = conflicting_edit().await?;
+    let client =
+        SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0).on_conflict(Strategy::KeepRemote);
+
+    let outcome = sync(&mut store, &client).await?;
+    assert_eq!(outcome.dropped.len(), 1);
+    assert!(outcome.flushed.is_empty());
+    assert!(store.queued_commands()?.is_empty());
+    assert_eq!(
+        store.item("I1")?.map(|item| item.content).as_deref(),
+        Some("Buy almond milk")
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn conflicting_commands_can_be_sent_anyway() -> Result<(), Box<dyn std::error::Error>> {
+    let (mut store, mock_server) = conflicting_edit().await?;
+    let client =
+        SyncClient::new(&mock_server.uri(), "MOCK_API_KEY", 0).on_conflict(Strategy::KeepLocal);
+
+    let outcome = sync(&mut store, &client).await?;
+    assert!(outcome.dropped.is_empty());
+    assert_eq!(outcome.flushed.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn stores_are_fresh_for_a_while_after_syncing() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let ttl = std::time::Duration::from_mins(1);
+    assert!(!is_fresh(&store, ttl)?);
+
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "MOCK_SYNC_TOKEN".to_string(),
+        ..Default::default()
+    })?;
+    assert!(store.last_synced_at()?.is_some());
+    assert!(is_fresh(&store, ttl)?);
+    assert!(!is_fresh(&store, std::time::Duration::ZERO)?);
+    Ok(())
+}
+
+#[tokio::test]
+async fn big_accounts_can_be_synced_in_parts() -> Result<(), Box<dyn std::error::Error>> {
+    let server = FakeSyncServer::start("MOCK_INBOX_PROJECT_ID").await;
+    let mut elsewhere = Store::open_in_memory()?;
+    let writer = SyncClient::new(&server.uri(), "MOCK_API_KEY", 0);
+    for content in ["keep", "delete"] {
+        send_command(&mut elsewhere, &writer, add_command(content)).await?;
+    }
+
+    let mut store = Store::open_in_memory()?;
+    let client = SyncClient::new(&server.uri(), "MOCK_API_KEY", 0).in_parts(true);
+    let outcome = sync(&mut store, &client).await?;
+    assert!(outcome
+        .response
+        .is_some_and(|response| response.items.is_none()));
+    assert_eq!(store.items()?.len(), 2);
+
+    let delete = Command::new(CommandArgs::ItemDelete(ItemIdArgs {
+        id: "ITEM_2".to_string(),
+    }));
+    send_command(&mut elsewhere, &writer, delete).await?;
+    send_command(&mut elsewhere, &writer, add_command("new")).await?;
+    sync(&mut store, &client).await?;
+    let contents = |store: &Store| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(store
+            .items()?
+            .into_iter()
+            .map(|item| item.content)
+            .collect())
+    };
+    assert_eq!(contents(&store)?, ["keep", "new"]);
+
+    // deleted items are left out of full syncs rather than marked deleted
+    let mut stale = Item {
+        id: "ITEM_9".to_string(),
+        content: "stale".to_string(),
+        ..Default::default()
+    };
+    store.put_items(std::slice::from_mut(&mut stale))?;
+    sync_in_parts(&mut store, &client, true).await?;
+    assert_eq!(contents(&store)?, ["keep", "new"]);
+    assert_ne!(store.sync_token()?, "*");
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+
+use crate::{
+    stats::{project_counts, ProjectCounts},
+    sync::{Item, Section},
+    tests::utils::ItemBuilder,
+};
+
+fn item(project_id: &str, date: Option<&str>) -> Item {
+    ItemBuilder::new("").project(project_id).due(date).build()
+}
+
+fn section(project_id: &str) -> Section {
+    Section {
+        project_id: project_id.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn counts_open_and_overdue_tasks_and_sections() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2023, 7, 10).ok_or("bad date")?;
+    let items = vec![
+        item("work", Some("2023-07-09")),
+        item("work", Some("2023-07-10T09:00:00")),
+        item("work", None),
+        Item {
+            checked: true,
+            ..item("work", Some("2023-07-01"))
+        },
+        Item {
+            is_deleted: true,
+            ..item("home", None)
+        },
+        item("home", Some("2023-06-30")),
+    ];
+    let sections = vec![
+        section("work"),
+        section("errands"),
+        Section {
+            is_archived: true,
+            ..section("work")
+        },
+    ];
+
+    let counts = project_counts(&items, &sections, today);
+
+    let expected = |open, overdue, sections| ProjectCounts {
+        open,
+        overdue,
+        sections,
+    };
+    assert_eq!(counts.len(), 3);
+    assert_eq!(counts["work"], expected(3, 1, 1));
+    assert_eq!(counts["home"], expected(1, 1, 0));
+    assert_eq!(counts["errands"], expected(0, 0, 1));
+    Ok(())
+}
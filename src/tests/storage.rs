@@ -0,0 +1,446 @@
+use std::collections::HashMap;
+
+use chrono::{TimeDelta, Utc};
+
+use crate::{
+    dependency::Dependency,
+    storage::Store,
+    stream::Batch,
+    sync::{CollaboratorState, Item, Label, Note, Project, Response, Section, Workspace},
+    template::{Task, Template},
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, content: &str) -> Item {
+    ItemBuilder::new(id).content(content).project("P1").build()
+}
+
+#[test]
+fn full_sync_replaces_stored_resources() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN_1".to_string(),
+        items: Some(vec![item("1", "Stale todo")]),
+        ..Default::default()
+    })?;
+
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN_2".to_string(),
+        items: Some(vec![item("2", "Fresh todo")]),
+        projects: Some(vec![Project {
+            id: "P1".to_string(),
+            name: "Inbox".to_string(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    })?;
+
+    assert_eq!(store.sync_token()?, "TOKEN_2");
+    let items = store.items()?;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].content, "Fresh todo");
+    assert_eq!(store.projects()?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn incremental_sync_merges_deltas() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN_1".to_string(),
+        items: Some(vec![
+            item("1", "Keep me"),
+            item("2", "Change me"),
+            item("3", "Delete me"),
+        ]),
+        labels: Some(vec![Label {
+            id: "L1".to_string(),
+            name: "errands".to_string(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    })?;
+
+    store.apply(&Response {
+        full_sync: false,
+        sync_token: "TOKEN_2".to_string(),
+        items: Some(vec![
+            item("2", "Changed"),
+            Item {
+                is_deleted: true,
+                ..item("3", "Delete me")
+            },
+            item("4", "New todo"),
+        ]),
+        ..Default::default()
+    })?;
+
+    let contents: Vec<String> = store
+        .items()?
+        .into_iter()
+        .map(|item| item.content)
+        .collect();
+    assert_eq!(contents, vec!["Keep me", "Changed", "New todo"]);
+    assert_eq!(store.labels()?.len(), 1);
+    assert_eq!(store.sync_token()?, "TOKEN_2");
+    Ok(())
+}
+
+#[test]
+fn open_items_excludes_completed_and_other_projects() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN".to_string(),
+        items: Some(vec![
+            item("1", "Open"),
+            Item {
+                checked: true,
+                ..item("2", "Done")
+            },
+            Item {
+                project_id: "P2".to_string(),
+                ..item("3", "Elsewhere")
+            },
+        ]),
+        ..Default::default()
+    })?;
+
+    let items = store.open_items_in_project("P1")?;
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].content, "Open");
+    Ok(())
+}
+
+#[test]
+fn empty_store_requests_full_sync() -> Result<(), Box<dyn std::error::Error>> {
+    assert_eq!(Store::open_in_memory()?.sync_token()?, "*");
+    Ok(())
+}
+
+#[test]
+fn sections_are_ordered_and_filtered_by_project() -> Result<(), Box<dyn std::error::Error>> {
+    let section = |id: &str, project_id: &str, section_order| Section {
+        id: id.to_string(),
+        name: format!("Section {id}"),
+        project_id: project_id.to_string(),
+        section_order,
+        ..Default::default()
+    };
+
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN".to_string(),
+        sections: Some(vec![
+            section("S1", "P1", 2),
+            section("S2", "P1", 1),
+            section("S3", "P2", 0),
+            Section {
+                is_archived: true,
+                ..section("S4", "P1", 0)
+            },
+        ]),
+        ..Default::default()
+    })?;
+
+    let ids: Vec<String> = store
+        .sections_in_project("P1")?
+        .into_iter()
+        .map(|section| section.id)
+        .collect();
+    assert_eq!(ids, ["S2", "S1"]);
+    Ok(())
+}
+
+#[test]
+fn last_added_temp_id_is_resolved() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_id = uuid::Uuid::new_v4();
+    let mut store = Store::open_in_memory()?;
+    assert_eq!(store.last_added()?, None);
+
+    store.set_last_added(&temp_id.to_string(), "Queued todo")?;
+    store.resolve_temp_ids(&HashMap::from([(
+        uuid::Uuid::new_v4(),
+        "OTHER".to_string(),
+    )]))?;
+    assert_eq!(
+        store.last_added()?,
+        Some((temp_id.to_string(), "Queued todo".to_string()))
+    );
+
+    store.resolve_temp_ids(&HashMap::from([(temp_id, "REAL_ID".to_string())]))?;
+    assert_eq!(
+        store.last_added()?,
+        Some(("REAL_ID".to_string(), "Queued todo".to_string()))
+    );
+    Ok(())
+}
+
+#[test]
+fn dependencies_are_kept_and_follow_temp_ids() -> Result<(), Box<dyn std::error::Error>> {
+    let temp_id = uuid::Uuid::new_v4();
+    let mut store = Store::open_in_memory()?;
+    assert!(store.add_dependency("PAINT", &temp_id.to_string())?);
+    assert!(!store.add_dependency("PAINT", &temp_id.to_string())?);
+    assert!(store.add_dependency("HANG", "PAINT")?);
+
+    store.resolve_temp_ids(&HashMap::from([(temp_id, "BUY".to_string())]))?;
+    let dependency = |task_id: &str, blocker_id: &str| Dependency {
+        task_id: task_id.to_string(),
+        blocker_id: blocker_id.to_string(),
+    };
+    assert_eq!(
+        store.dependencies()?,
+        [dependency("PAINT", "BUY"), dependency("HANG", "PAINT")]
+    );
+
+    assert!(store.remove_dependency("PAINT", "BUY")?);
+    assert!(!store.remove_dependency("PAINT", "BUY")?);
+    assert_eq!(store.dependencies()?, [dependency("HANG", "PAINT")]);
+    Ok(())
+}
+
+#[test]
+fn templates_are_saved_by_name() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let template = |content: &str| Template {
+        project: "Packing".to_string(),
+        sections: vec!["Clothes".to_string()],
+        tasks: vec![Task {
+            content: content.to_string(),
+            priority: 4,
+            ..Default::default()
+        }],
+    };
+    assert!(!store.save_template("trip", &template("Shirts"))?);
+    assert!(store.save_template("Trip", &template("Socks"))?);
+    assert!(!store.save_template("camping", &template("Tent"))?);
+
+    assert_eq!(store.template("TRIP")?, Some(template("Socks")));
+    let names: Vec<String> = store
+        .templates()?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names, ["camping", "Trip"]);
+
+    assert!(store.delete_template("trip")?);
+    assert!(!store.delete_template("trip")?);
+    assert_eq!(store.template("trip")?, None);
+    Ok(())
+}
+
+#[test]
+fn commands_keep_their_uuids_until_answered() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let first = store.command_uuid("ADD_MILK")?;
+    assert_eq!(store.command_uuid("ADD_MILK")?, first);
+    let other = store.command_uuid("ADD_EGGS")?;
+    assert_ne!(other, first);
+
+    store.acknowledge_commands(&[first])?;
+    let second = store.command_uuid("ADD_MILK")?;
+    assert_ne!(second, first);
+    assert_eq!(store.command_uuid("ADD_EGGS")?, other);
+    Ok(())
+}
+
+#[test]
+fn notes_are_grouped_by_item() -> Result<(), Box<dyn std::error::Error>> {
+    let note = |id: &str, item_id: &str, posted_at: &str| Note {
+        id: id.to_string(),
+        item_id: item_id.to_string(),
+        content: format!("Note {id}"),
+        posted_at: posted_at.to_string(),
+        ..Default::default()
+    };
+
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN".to_string(),
+        notes: Some(vec![
+            note("N1", "I1", "2024-01-02T09:00:00Z"),
+            note("N2", "I1", "2024-01-01T09:00:00Z"),
+            note("N3", "I2", "2024-01-01T09:00:00Z"),
+        ]),
+        ..Default::default()
+    })?;
+    store.apply(&Response {
+        full_sync: false,
+        sync_token: "TOKEN_2".to_string(),
+        notes: Some(vec![Note {
+            is_deleted: true,
+            ..note("N3", "I2", "2024-01-01T09:00:00Z")
+        }]),
+        ..Default::default()
+    })?;
+
+    let ids: Vec<String> = store
+        .notes_for_item("I1")?
+        .into_iter()
+        .map(|note| note.id)
+        .collect();
+    assert_eq!(ids, ["N2", "N1"]);
+    assert_eq!(store.note_counts()?, HashMap::from([("I1".to_string(), 2)]));
+    Ok(())
+}
+
+#[test]
+fn notifications_are_only_marked_once() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+
+    assert!(store.mark_notified("1@2024-05-01@due")?);
+    assert!(!store.mark_notified("1@2024-05-01@due")?);
+    assert!(store.mark_notified("1@2024-05-01@overdue")?);
+    Ok(())
+}
+
+#[test]
+fn the_lock_is_held_until_it_is_dropped() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = assert_fs::TempDir::new()?;
+    let store = Store::open(&dir.path().join("todoist.db"))?;
+    let lock_file = || std::fs::File::open(dir.path().join("todoist.lock"));
+
+    let lock = store.lock()?;
+    assert!(lock_file()?.try_lock().is_err());
+    drop(lock);
+    assert!(lock_file()?.try_lock().is_ok());
+
+    // stores in memory have nothing to lock
+    let _lock = Store::open_in_memory()?.lock()?;
+    Ok(())
+}
+
+#[test]
+fn completed_items_are_pruned_with_their_notes() -> Result<(), Box<dyn std::error::Error>> {
+    let completed = |id: &str, completed_at: &str| Item {
+        checked: true,
+        completed_at: Some(completed_at.to_string()),
+        ..item(id, "Done")
+    };
+    let note = |id: &str, item_id: &str| Note {
+        id: id.to_string(),
+        item_id: item_id.to_string(),
+        ..Default::default()
+    };
+
+    let mut store = Store::open_in_memory()?.keep_completed_for(Some(30));
+    let now = Utc::now();
+    let long_ago = (now - TimeDelta::days(60)).to_rfc3339();
+    let lately = (now - TimeDelta::days(2)).to_rfc3339();
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN".to_string(),
+        items: Some(vec![
+            item("1", "Open"),
+            completed("2", &long_ago),
+            completed("3", &lately),
+        ]),
+        notes: Some(vec![note("N1", "1"), note("N2", "2")]),
+        ..Default::default()
+    })?;
+    let ids: Vec<String> = store.items()?.into_iter().map(|item| item.id).collect();
+    assert_eq!(ids, ["1", "3"]);
+    assert!(store.notes_for_item("2")?.is_empty());
+
+    // items that don't say when they were completed count from when they were
+    // synced completed
+    store.put_items(&[Item {
+        checked: true,
+        ..item("4", "Done just now")
+    }])?;
+    let pruned = store.prune(Some(now - TimeDelta::days(1)))?;
+    assert_eq!(pruned.items, 1);
+    let ids: Vec<String> = store.items()?.into_iter().map(|item| item.id).collect();
+    assert_eq!(ids, ["1", "4"]);
+
+    store.vacuum()?;
+    assert!(store.size()? > 0);
+    Ok(())
+}
+
+#[test]
+fn full_syncs_read_in_batches_remove_what_they_leave_out() -> Result<(), Box<dyn std::error::Error>>
+{
+    let mut store = Store::open_in_memory()?;
+    store.put_items(&[item("gone", "Gone"), item("kept", "Kept")])?;
+
+    store.apply_batch(&Batch::Items(vec![item("kept", "Kept")]), true)?;
+    store.apply_batch(&Batch::Items(vec![item("new", "New")]), true)?;
+    assert_eq!(store.items()?.len(), 3, "nothing is removed until the end");
+
+    store.remove_unsynced(&["items"])?;
+    let ids: Vec<String> = store.items()?.into_iter().map(|item| item.id).collect();
+    assert_eq!(ids, ["kept", "new"]);
+
+    store.finish_sync("TOKEN")?;
+    assert_eq!(store.sync_token()?, "TOKEN");
+    Ok(())
+}
+
+#[test]
+fn workspaces_are_synced_like_other_resources() -> Result<(), Box<dyn std::error::Error>> {
+    let workspace = |id: &str, is_deleted| Workspace {
+        id: id.to_string(),
+        name: format!("Workspace {id}"),
+        is_deleted,
+    };
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        workspaces: Some(vec![workspace("W1", false), workspace("W2", false)]),
+        ..Default::default()
+    })?;
+    store.apply(&Response {
+        workspaces: Some(vec![workspace("W1", true)]),
+        ..Default::default()
+    })?;
+    let ids: Vec<String> = store
+        .workspaces()?
+        .into_iter()
+        .map(|workspace| workspace.id)
+        .collect();
+    assert_eq!(ids, ["W2"]);
+    Ok(())
+}
+
+#[test]
+fn collaborator_states_are_kept_for_each_project_someone_is_in(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let state = |project_id: &str, user_id: &str, state: &str| CollaboratorState {
+        project_id: project_id.to_string(),
+        user_id: user_id.to_string(),
+        state: state.to_string(),
+        is_deleted: false,
+    };
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        collaborator_states: Some(vec![
+            state("P1", "U1", "active"),
+            state("P1", "U2", "invited"),
+            state("P2", "U1", "active"),
+        ]),
+        ..Default::default()
+    })?;
+    // someone leaving one project stays in the other
+    store.apply(&Response {
+        collaborator_states: Some(vec![state("P1", "U1", "deleted")]),
+        ..Default::default()
+    })?;
+    let users = |project_id| -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        Ok(store
+            .collaborator_states(project_id)?
+            .into_iter()
+            .map(|state| state.user_id)
+            .collect())
+    };
+    assert_eq!(users("P1")?, ["U2"]);
+    assert_eq!(users("P2")?, ["U1"]);
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use crate::{
+    stream::{parse, Batch},
+    Error,
+};
+
+const RESPONSE: &str = r#"{
+    "full_sync": true,
+    "sync_token": "TOKEN",
+    "temp_id_mapping": {},
+    "items": [
+        {"id": "1", "content": "One", "project_id": "P"},
+        {"id": "2", "content": "Two", "project_id": "P"},
+        {"id": "3", "content": "Three", "project_id": "P"}
+    ],
+    "labels": [],
+    "projects": null,
+    "day_orders": {"1": 0}
+}"#;
+
+#[test]
+fn hands_over_resources_in_batches() -> Result<(), Box<dyn std::error::Error>> {
+    let mut batches = Vec::new();
+    let response = parse(RESPONSE.as_bytes(), 2, |batch| {
+        batches.push(batch);
+        true
+    })?;
+
+    assert!(response.full_sync);
+    assert_eq!(response.sync_token, "TOKEN");
+    assert!(response.items.is_none());
+
+    let ids: Vec<Vec<&str>> = batches
+        .iter()
+        .map(|batch| match batch {
+            Batch::Items(items) => items.iter().map(|item| item.id.as_str()).collect(),
+            _ => Vec::new(),
+        })
+        .collect();
+    assert_eq!(ids, [vec!["1", "2"], vec!["3"], vec![]]);
+    let kinds: Vec<&str> = batches.iter().map(Batch::kind).collect();
+    assert_eq!(
+        kinds,
+        ["items", "items", "labels"],
+        "empty lists are handed over, but missing ones aren't"
+    );
+    Ok(())
+}
+
+#[test]
+fn stops_when_asked() {
+    let mut batches = 0;
+    let result = parse(RESPONSE.as_bytes(), 1, |_| {
+        batches += 1;
+        batches < 2
+    });
+    assert!(matches!(result, Err(Error::Serialization(_))));
+    assert_eq!(batches, 2);
+}
@@ -0,0 +1,125 @@
+use chrono::{FixedOffset, Utc};
+
+use crate::{
+    import::Task,
+    taskwarrior::{parse_in, recurrence},
+};
+
+const EXPORT: &str = r#"[
+    {
+        "uuid": "a1",
+        "description": "Plant tulips",
+        "status": "pending",
+        "project": "Home.Garden",
+        "tags": ["errand"],
+        "priority": "H",
+        "due": "20240501T160000Z",
+        "annotations": [{"entry": "20240420T100000Z", "description": "The red ones"}],
+        "depends": "b2"
+    },
+    {
+        "uuid": "b2",
+        "description": "Buy bulbs",
+        "status": "completed",
+        "due": "20240430T000000Z"
+    },
+    {
+        "uuid": "c3",
+        "description": "Water plants",
+        "status": "recurring",
+        "project": "Home",
+        "recur": "2d",
+        "due": "20240501T000000Z"
+    },
+    {
+        "uuid": "d4",
+        "description": "Water plants",
+        "status": "pending",
+        "parent": "c3",
+        "due": "20240503T000000Z"
+    },
+    {
+        "uuid": "e5",
+        "description": "Old idea",
+        "status": "deleted"
+    },
+    {
+        "uuid": "f6",
+        "description": "Pay rent",
+        "status": "recurring",
+        "recur": "fortnightly-ish",
+        "priority": "L"
+    }
+]"#;
+
+#[test]
+fn maps_taskwarrior_tasks_onto_todoist() -> Result<(), Box<dyn std::error::Error>> {
+    let migration = parse_in(EXPORT, &Utc)?;
+    assert_eq!(
+        migration.tasks,
+        vec![
+            Task {
+                content: "Plant tulips".to_string(),
+                project: Some("Home".to_string()),
+                section: Some("Garden".to_string()),
+                due: Some("2024-05-01 16:00".to_string()),
+                priority: Some(1),
+                labels: vec!["errand".to_string()],
+                comments: vec!["The red ones".to_string()],
+                ..Default::default()
+            },
+            Task {
+                content: "Buy bulbs".to_string(),
+                due: Some("2024-04-30".to_string()),
+                completed: true,
+                ..Default::default()
+            },
+            Task {
+                content: "Water plants".to_string(),
+                project: Some("Home".to_string()),
+                due: Some("every 2 days starting 2024-05-01".to_string()),
+                ..Default::default()
+            },
+            Task {
+                content: "Pay rent".to_string(),
+                priority: Some(3),
+                ..Default::default()
+            },
+        ]
+    );
+    assert_eq!(
+        migration.notes,
+        [
+            "'Pay rent' repeats 'fortnightly-ish', which can't be brought over, so it's only due \
+             once.",
+            "1 deleted tasks are left out.",
+            "Which tasks depend on which others isn't brought over.",
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn puts_due_dates_in_local_time() -> Result<(), Box<dyn std::error::Error>> {
+    let zone = FixedOffset::west_opt(7 * 3600).ok_or("invalid offset")?;
+    let migration = parse_in(EXPORT, &zone)?;
+    assert_eq!(migration.tasks[0].due.as_deref(), Some("2024-05-01 09:00"));
+    assert_eq!(migration.tasks[1].due.as_deref(), Some("2024-04-29 17:00"));
+    Ok(())
+}
+
+#[test]
+fn translates_recurrences() {
+    assert_eq!(recurrence("weekly").as_deref(), Some("every week"));
+    assert_eq!(recurrence("weekdays").as_deref(), Some("every weekday"));
+    assert_eq!(recurrence("3wks").as_deref(), Some("every 3 weeks"));
+    assert_eq!(recurrence("1mo").as_deref(), Some("every month"));
+    assert_eq!(recurrence("q").as_deref(), Some("every 3 months"));
+    assert_eq!(recurrence("5min"), None);
+}
+
+#[test]
+fn rejects_other_json() {
+    assert!(parse_in(r#"{"items": []}"#, &Utc).is_err());
+    assert!(parse_in(r#"[{"description": " ", "status": "pending"}]"#, &Utc).is_err());
+}
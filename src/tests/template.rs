@@ -0,0 +1,100 @@
+use crate::{
+    sync::{Item, Section},
+    template::{Task, Template},
+    tests::utils::ItemBuilder,
+};
+
+fn item(id: &str, section_id: Option<&str>, child_order: i64) -> Item {
+    ItemBuilder::new(id)
+        .content(&id.to_lowercase())
+        .section(section_id)
+        .child_order(child_order)
+        .priority(1)
+        .build()
+}
+
+fn section(id: &str, name: &str, section_order: i64) -> Section {
+    Section {
+        id: id.to_string(),
+        name: name.to_string(),
+        section_order,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn snapshots_keep_sections_and_subtasks_in_order() {
+    let sections = [section("S2", "Toiletries", 2), section("S1", "Clothes", 1)];
+    let items = [
+        item("TOOTHBRUSH", Some("S2"), 1),
+        Item {
+            parent_id: Some("SHIRTS".to_string()),
+            ..item("LINEN", Some("S1"), 1)
+        },
+        item("SHIRTS", Some("S1"), 1),
+        Item {
+            priority: 4,
+            labels: vec!["errand".to_string()],
+            ..item("PASSPORT", None, 1)
+        },
+        Item {
+            checked: true,
+            ..item("SOCKS", Some("S1"), 2)
+        },
+    ];
+    let template = Template::snapshot("Packing", &sections, &items);
+
+    assert_eq!(template.project, "Packing");
+    assert_eq!(template.sections, ["Clothes", "Toiletries"]);
+    let contents: Vec<&str> = template
+        .tasks
+        .iter()
+        .map(|task| task.content.as_str())
+        .collect();
+    assert_eq!(contents, ["passport", "shirts", "linen", "toothbrush"]);
+    assert_eq!(
+        template.tasks[0],
+        Task {
+            content: "passport".to_string(),
+            priority: 1,
+            labels: vec!["errand".to_string()],
+            ..Default::default()
+        }
+    );
+    assert_eq!(template.tasks[2].parent, Some(1));
+    assert_eq!(template.tasks[2].section.as_deref(), Some("Clothes"));
+    assert_eq!(template.tasks[3].priority, 4);
+}
+
+#[test]
+fn templates_are_added_to_the_named_project() {
+    let template = Template {
+        project: "Packing".to_string(),
+        sections: vec!["Clothes".to_string()],
+        tasks: vec![
+            Task {
+                content: "Shirts".to_string(),
+                section: Some("Clothes".to_string()),
+                priority: 2,
+                ..Default::default()
+            },
+            Task {
+                content: "Linen".to_string(),
+                description: "For the hot days".to_string(),
+                section: Some("Clothes".to_string()),
+                priority: 4,
+                parent: Some(0),
+                ..Default::default()
+            },
+        ],
+    };
+    let tasks = template.tasks_for("Lisbon");
+
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].project.as_deref(), Some("Lisbon"));
+    assert_eq!(tasks[0].section.as_deref(), Some("Clothes"));
+    assert_eq!(tasks[0].priority, Some(2));
+    assert_eq!(tasks[0].description, None);
+    assert_eq!(tasks[1].description.as_deref(), Some("For the hot days"));
+    assert_eq!(tasks[1].parent, Some(0));
+}
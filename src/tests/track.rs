@@ -0,0 +1,105 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    storage::Store,
+    sync::Item,
+    tests::utils::ItemBuilder,
+    track::{project_totals, Timer, WorkSession, WorkTotal},
+};
+
+fn item(id: &str, content: &str, project_id: &str) -> Item {
+    ItemBuilder::new(id)
+        .content(content)
+        .project(project_id)
+        .build()
+}
+
+fn total(id: &str, content: &str, seconds: u64, sessions: usize) -> WorkTotal {
+    WorkTotal {
+        item_id: id.to_string(),
+        content: content.to_string(),
+        seconds,
+        sessions,
+    }
+}
+
+#[test]
+fn the_timer_tracks_the_time_since_it_started() -> Result<(), Box<dyn std::error::Error>> {
+    let start = DateTime::parse_from_rfc3339("2024-05-01T09:00:00Z")?.to_utc();
+    let mut store = Store::open_in_memory()?;
+    assert_eq!(store.timer()?, None);
+
+    store.set_timer(Some(&Timer::start(
+        &item("1", "Write report", "work"),
+        start,
+    )))?;
+    let timer = store.timer()?.ok_or("the timer isn't running")?;
+    let session = timer.stop(start + Duration::minutes(90));
+
+    assert_eq!(session.started_at, "2024-05-01T09:00:00Z");
+    assert_eq!(session.seconds, 90 * 60);
+    assert_eq!(session.project_id, "work");
+    store.set_timer(None)?;
+    assert_eq!(store.timer()?, None);
+    Ok(())
+}
+
+#[test]
+fn sessions_add_up_per_task() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let now = Utc::now();
+
+    store.record_work(&WorkSession::new(
+        &item("1", "Write report", "work"),
+        now,
+        1500,
+    ))?;
+    store.record_work(&WorkSession::new(
+        &item("2", "Plan trip", "home"),
+        now,
+        2000,
+    ))?;
+    store.record_work(&WorkSession::new(
+        &item("1", "Write the report", "work"),
+        now,
+        1500,
+    ))?;
+
+    assert_eq!(
+        store.work_totals()?,
+        [
+            total("1", "Write the report", 3000, 2),
+            total("2", "Plan trip", 2000, 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn sessions_add_up_per_project() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    let start = DateTime::parse_from_rfc3339("2024-05-06T00:00:00Z")?.to_utc();
+    let before = start - Duration::hours(1);
+    let after = start + Duration::hours(1);
+
+    store.record_work(&WorkSession::new(&item("1", "Old", "work"), before, 600))?;
+    store.record_work(&WorkSession::new(&item("2", "Report", "work"), after, 600))?;
+    store.record_work(&WorkSession::new(&item("3", "Slides", "work"), after, 300))?;
+    store.record_work(&WorkSession::new(&item("4", "Trip", "home"), after, 1200))?;
+    store.record_work(&WorkSession::new(&item("2", "Report", "work"), after, 600))?;
+
+    let totals = project_totals(&store.work_sessions(start)?);
+
+    assert_eq!(
+        totals,
+        [
+            (
+                "work".to_string(),
+                1500,
+                vec![total("2", "Report", 1200, 2), total("3", "Slides", 300, 1)]
+            ),
+            ("home".to_string(), 1200, vec![total("4", "Trip", 1200, 1)]),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::{
+    storage::Store,
+    sync::{Item, Project, Response, Section},
+    trash::restore_args,
+};
+
+fn store_with_project() -> Result<Store, Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "MOCK_SYNC_TOKEN".to_string(),
+        projects: Some(vec![Project {
+            id: "work".to_string(),
+            name: "Work".to_string(),
+            ..Default::default()
+        }]),
+        sections: Some(vec![Section {
+            id: "meetings".to_string(),
+            project_id: "work".to_string(),
+            ..Default::default()
+        }]),
+        ..Default::default()
+    })?;
+    Ok(store)
+}
+
+#[test]
+fn restored_items_keep_their_places_if_they_still_exist() -> Result<(), Box<dyn std::error::Error>>
+{
+    let store = store_with_project()?;
+    let item = Item {
+        content: "Prepare slides".to_string(),
+        project_id: "work".to_string(),
+        section_id: Some("meetings".to_string()),
+        parent_id: Some("deleted parent".to_string()),
+        labels: vec!["deep".to_string()],
+        ..Default::default()
+    };
+
+    let args = restore_args(&store, item, "inbox")?;
+    assert_eq!(args.project_id, "work");
+    assert_eq!(args.section_id.as_deref(), Some("meetings"));
+    assert_eq!(args.parent_id, None);
+    assert_eq!(args.labels, ["deep"]);
+    Ok(())
+}
+
+#[test]
+fn restored_items_go_to_the_inbox_without_their_project() -> Result<(), Box<dyn std::error::Error>>
+{
+    let store = store_with_project()?;
+    let item = Item {
+        content: "Old task".to_string(),
+        project_id: "gone".to_string(),
+        section_id: Some("meetings".to_string()),
+        ..Default::default()
+    };
+
+    let args = restore_args(&store, item, "inbox")?;
+    assert_eq!(args.project_id, "inbox");
+    assert_eq!(args.section_id, None);
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use crate::{
+    storage::Store,
+    sync::{CommandArgs, Due, Item, ItemIdArgs, LabelIdArgs, Response},
+    undo::{entry_for, Entry, JOURNAL_LENGTH},
+};
+
+fn store_with_item() -> Result<Store, Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    store.apply(&Response {
+        full_sync: true,
+        sync_token: "TOKEN".to_string(),
+        items: Some(vec![Item {
+            id: "I1".to_string(),
+            content: "Water plants".to_string(),
+            project_id: "P1".to_string(),
+            due: Some(Due {
+                date: "2024-01-01".to_string(),
+                string: Some("every monday".to_string()),
+                is_recurring: true,
+            }),
+            labels: vec!["home".to_string()],
+            priority: 3,
+            ..Default::default()
+        }]),
+        ..Default::default()
+    })?;
+    Ok(store)
+}
+
+#[test]
+fn deleted_items_are_restored_from_the_cache() -> Result<(), Box<dyn std::error::Error>> {
+    let store = store_with_item()?;
+    let entry = entry_for(
+        &store,
+        &CommandArgs::ItemDelete(ItemIdArgs {
+            id: "I1".to_string(),
+        }),
+    )?;
+
+    let Some(Entry {
+        action,
+        inverse: CommandArgs::ItemAdd(args),
+    }) = entry
+    else {
+        panic!("expected an item_add to undo the delete");
+    };
+    assert_eq!(action, "deleting 'Water plants'");
+    assert_eq!(args.project_id, "P1");
+    assert_eq!(args.content, "Water plants");
+    assert_eq!(
+        args.due.map(|due| due.string).as_deref(),
+        Some("every monday")
+    );
+    assert_eq!(args.priority, Some(3));
+    assert_eq!(args.labels, ["home"]);
+    Ok(())
+}
+
+#[test]
+fn some_commands_cannot_be_undone() -> Result<(), Box<dyn std::error::Error>> {
+    let store = store_with_item()?;
+
+    // the item isn't cached, so there's nothing to restore it from
+    let delete = CommandArgs::ItemDelete(ItemIdArgs {
+        id: "UNKNOWN".to_string(),
+    });
+    assert!(entry_for(&store, &delete)?.is_none());
+
+    let delete_label = CommandArgs::LabelDelete(LabelIdArgs {
+        id: "L1".to_string(),
+    });
+    assert!(entry_for(&store, &delete_label)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn journal_keeps_the_latest_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let mut store = Store::open_in_memory()?;
+    for index in 0..JOURNAL_LENGTH + 5 {
+        store.push_undo_entry(&Entry {
+            action: format!("action {index}"),
+            inverse: CommandArgs::ItemUncomplete(ItemIdArgs {
+                id: index.to_string(),
+            }),
+        })?;
+    }
+
+    let mut actions = Vec::new();
+    while let Some(entry) = store.pop_undo_entry()? {
+        actions.push(entry.action);
+    }
+    assert_eq!(actions.len(), JOURNAL_LENGTH);
+    assert_eq!(actions[0], format!("action {}", JOURNAL_LENGTH + 4));
+    assert_eq!(actions[JOURNAL_LENGTH - 1], "action 5");
+    Ok(())
+}
@@ -3,6 +3,8 @@ pub use wiremock_wrapper::ApiMockBuilder;
 
 #[cfg(test)]
 mod wiremock_wrapper {
+    use std::net::TcpListener;
+
     use serde::{Deserialize, Serialize};
     use wiremock::{matchers, Mock, MockServer, Request, ResponseTemplate};
 
@@ -17,6 +19,14 @@ mod wiremock_wrapper {
             }
         }
 
+        /// A server that takes connections on `listener`, for a test that needs
+        /// to know its address before it starts.
+        pub async fn listening_on(listener: TcpListener) -> Self {
+            ApiMockBuilder {
+                mock_server: MockServer::builder().listener(listener).start().await,
+            }
+        }
+
         // HACK: Not sure if the typing on `F` here is all necessary, or if there's a way around the `Clone` constraint
         pub async fn mock_response<F, T, R>(self, path: &str, condition: F, response: R) -> Self
         where
@@ -35,17 +45,325 @@ mod wiremock_wrapper {
             self
         }
 
+        /// Answers GET requests to `path` whose query has all of the given
+        /// parameters.
+        pub async fn mock_get<R: Serialize>(
+            self,
+            path: &str,
+            query: &[(&str, &str)],
+            response: R,
+        ) -> Self {
+            let mock = query.iter().fold(
+                Mock::given(matchers::method("GET")).and(matchers::path(path)),
+                |mock, (key, value)| mock.and(matchers::query_param(*key, *value)),
+            );
+            mock.respond_with(ResponseTemplate::new(200).set_body_json(response))
+                .mount(&self.mock_server)
+                .await;
+            self
+        }
+
+        /// Answers requests to `path` with `response`, but only after `delay`.
+        pub async fn mock_delayed<R: Serialize>(
+            self,
+            path: &str,
+            delay: std::time::Duration,
+            response: R,
+        ) -> Self {
+            Mock::given(matchers::path(path))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(response)
+                        .set_delay(delay),
+                )
+                .mount(&self.mock_server)
+                .await;
+            self
+        }
+
+        pub async fn mock_status(self, path: &str, status: u16) -> Self {
+            Mock::given(matchers::path(path))
+                .respond_with(ResponseTemplate::new(status))
+                .mount(&self.mock_server)
+                .await;
+            self
+        }
+
+        /// Responds to the first `times` requests with a 429 that asks the client
+        /// to retry immediately. Mount this before the mocks that should answer
+        /// once the rate limit is over.
+        pub async fn mock_rate_limited(self, path: &str, times: u64) -> Self {
+            Mock::given(matchers::path(path))
+                .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+                .up_to_n_times(times)
+                .mount(&self.mock_server)
+                .await;
+            self
+        }
+
         pub fn uri(&self) -> String {
             self.mock_server.uri()
         }
     }
 }
 
+#[cfg(test)]
+pub use fake_server::FakeSyncServer;
+
+#[cfg(test)]
+mod fake_server {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, PoisonError},
+        time::Duration,
+    };
+
+    use uuid::Uuid;
+
+    use wiremock::{matchers, Mock, MockServer, Request, Respond, ResponseTemplate};
+
+    use crate::{
+        storage::FULL_SYNC_TOKEN,
+        sync::{self, CommandArgs, CommandStatus, Item, Response, User},
+    };
+
+    /// A stand-in for the Sync API that keeps track of items, so that a test can
+    /// run several commands in a row and see each one's effects in the next.
+    /// Sync tokens count the changes made so far, and incremental syncs only
+    /// return the items that changed since the given token. Like Todoist, it
+    /// only carries out a command once for each `uuid`.
+    pub struct FakeSyncServer {
+        mock_server: MockServer,
+        state: Arc<Mutex<State>>,
+    }
+
+    #[derive(Default)]
+    struct State {
+        /// Each item, along with the version in which it last changed.
+        items: Vec<(Item, u64)>,
+        version: u64,
+
+        /// The status of each command carried out, by its `uuid`, with the ID
+        /// it gave its temp ID, if it has one.
+        answered: HashMap<Uuid, (CommandStatus, Option<(Uuid, String)>)>,
+
+        /// How long to wait before answering each request.
+        delay: Duration,
+    }
+
+    struct Responder {
+        state: Arc<Mutex<State>>,
+        inbox_project_id: String,
+    }
+
+    impl FakeSyncServer {
+        pub async fn start(inbox_project_id: &str) -> Self {
+            let mock_server = MockServer::start().await;
+            let state = Arc::new(Mutex::new(State::default()));
+            Mock::given(matchers::path("sync"))
+                .respond_with(Responder {
+                    state: Arc::clone(&state),
+                    inbox_project_id: inbox_project_id.to_string(),
+                })
+                .mount(&mock_server)
+                .await;
+            FakeSyncServer { mock_server, state }
+        }
+
+        pub fn uri(&self) -> String {
+            self.mock_server.uri()
+        }
+
+        /// Waits for `delay` before answering each request from now on, having
+        /// already carried out its commands, like a server that's slow to
+        /// respond.
+        pub fn delay_responses(&self, delay: Duration) {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.delay = delay;
+        }
+
+        /// The server's items, including completed and deleted ones.
+        pub fn items(&self) -> Vec<Item> {
+            let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.items.iter().map(|(item, _)| item.clone()).collect()
+        }
+    }
+
+    impl State {
+        fn item_mut(&mut self, id: &str) -> Option<&mut (Item, u64)> {
+            self.items.iter_mut().find(|(item, _)| item.id == id)
+        }
+
+        /// Applies a command, returning whether it worked.
+        fn apply(&mut self, args: CommandArgs) -> bool {
+            let version = self.version;
+            let change = |state: &mut State, id: &str, change: &dyn Fn(&mut Item)| {
+                state.item_mut(id).is_some_and(|(item, changed)| {
+                    change(item);
+                    *changed = version;
+                    true
+                })
+            };
+            match args {
+                CommandArgs::ItemClose(sync::ItemCloseArgs { id })
+                | CommandArgs::ItemComplete(sync::ItemIdArgs { id }) => {
+                    change(self, &id, &|item| item.checked = true)
+                }
+                CommandArgs::ItemUncomplete(sync::ItemIdArgs { id }) => {
+                    change(self, &id, &|item| item.checked = false)
+                }
+                CommandArgs::ItemDelete(sync::ItemIdArgs { id }) => {
+                    change(self, &id, &|item| item.is_deleted = true)
+                }
+                CommandArgs::ItemUpdate(args) => change(self, &args.id, &|item| {
+                    if let Some(content) = &args.content {
+                        item.content.clone_from(content);
+                    }
+                    if let Some(description) = &args.description {
+                        item.description.clone_from(description);
+                    }
+                    if let Some(duration) = args.duration {
+                        item.duration = duration;
+                    }
+                    if let Some(due) = &args.due {
+                        item.due = Some(fake_due(&due.string));
+                    }
+                    if let Some(priority) = args.priority {
+                        item.priority = priority;
+                    }
+                    if let Some(labels) = &args.labels {
+                        item.labels.clone_from(labels);
+                    }
+                    if let Some(responsible_uid) = &args.responsible_uid {
+                        item.responsible_uid.clone_from(responsible_uid);
+                    }
+                }),
+                _ => true,
+            }
+        }
+    }
+
+    /// Todoist would parse the date; here, it's taken to be one already.
+    fn fake_due(string: &str) -> sync::Due {
+        sync::Due {
+            date: string.to_string(),
+            string: Some(string.to_string()),
+            is_recurring: false,
+        }
+    }
+
+    impl Respond for Responder {
+        fn respond(&self, request: &Request) -> ResponseTemplate {
+            let Ok(request) = request.body_json::<sync::Request>() else {
+                return ResponseTemplate::new(400);
+            };
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+            let mut sync_status = HashMap::new();
+            let mut temp_id_mapping = HashMap::new();
+            for mut command in request.commands {
+                if let Some((status, temp_id)) = state.answered.get(&command.uuid) {
+                    sync_status.insert(command.uuid, status.clone());
+                    temp_id_mapping.extend(temp_id.clone());
+                    continue;
+                }
+                state.version += 1;
+                // like Todoist, commands can refer to things earlier ones added
+                if command.resolve_temp_ids(&temp_id_mapping).is_err() {
+                    return ResponseTemplate::new(400);
+                }
+                if let (CommandArgs::ProjectAdd(_) | CommandArgs::SectionAdd(_), Some(temp_id)) =
+                    (&command.args, command.temp_id)
+                {
+                    temp_id_mapping.insert(temp_id, format!("CONTAINER_{}", state.version));
+                }
+                let ok = if let CommandArgs::ItemAdd(args) = command.args {
+                    let id = format!("ITEM_{}", state.items.len() + 1);
+                    if let Some(temp_id) = command.temp_id {
+                        temp_id_mapping.insert(temp_id, id.clone());
+                    }
+                    let item = Item {
+                        id,
+                        content: args.content,
+                        description: args.description.unwrap_or_default(),
+                        project_id: args.project_id,
+                        section_id: args.section_id,
+                        parent_id: args.parent_id,
+                        labels: args.labels,
+                        priority: args.priority.unwrap_or(1),
+                        responsible_uid: args.responsible_uid,
+                        duration: args.duration,
+                        deadline: args.deadline,
+                        due: args.due.map(|due| fake_due(&due.string)),
+                        ..Default::default()
+                    };
+                    let version = state.version;
+                    state.items.push((item, version));
+                    true
+                } else {
+                    state.apply(command.args)
+                };
+                let status = if ok {
+                    CommandStatus::Ok("ok".to_string())
+                } else {
+                    CommandStatus::Error {
+                        error_code: 22,
+                        error: "Item not found".to_string(),
+                    }
+                };
+                let mapped = command
+                    .temp_id
+                    .and_then(|temp_id| Some((temp_id, temp_id_mapping.get(&temp_id)?.clone())));
+                state
+                    .answered
+                    .insert(command.uuid, (status.clone(), mapped));
+                sync_status.insert(command.uuid, status);
+            }
+
+            let full_sync = request.sync_token == FULL_SYNC_TOKEN;
+            let since: u64 = request.sync_token.parse().unwrap_or(0);
+            let wants = |resource: &str| request.resource_types.iter().any(|r| r == resource);
+            let items = wants("items").then(|| {
+                state
+                    .items
+                    .iter()
+                    .filter(|(item, changed)| {
+                        if full_sync {
+                            !item.is_deleted
+                        } else {
+                            *changed > since
+                        }
+                    })
+                    .map(|(item, _)| item.clone())
+                    .collect()
+            });
+            let user = wants("user").then(|| User {
+                full_name: "Drew".to_string(),
+                inbox_project_id: self.inbox_project_id.clone(),
+                ..Default::default()
+            });
+
+            ResponseTemplate::new(200)
+                .set_delay(state.delay)
+                .set_body_json(Response {
+                    full_sync,
+                    sync_status: Some(sync_status),
+                    sync_token: state.version.to_string(),
+                    temp_id_mapping,
+                    user,
+                    items,
+                    ..Default::default()
+                })
+        }
+    }
+}
+
 #[cfg(test)]
 pub use assert_fs_wrapper::FsMockBuilder;
 
 #[cfg(test)]
 mod assert_fs_wrapper {
+    use crate::storage::Store;
     use assert_fs::{
         prelude::{FileTouch, FileWriteStr, PathChild},
         TempDir,
@@ -72,8 +390,92 @@ mod assert_fs_wrapper {
             Ok(self)
         }
 
+        pub fn mock_store<F>(self, setup: F) -> Result<Self, Box<dyn Error>>
+        where
+            F: FnOnce(&mut Store) -> crate::Result<()>,
+        {
+            let mut store = Store::open(self.mock_dir.child("data/todoist.db").path())?;
+            setup(&mut store)?;
+            Ok(self)
+        }
+
         pub fn path(&self) -> &Path {
             self.mock_dir.path()
         }
     }
 }
+
+#[cfg(test)]
+pub use item_builder::ItemBuilder;
+
+#[cfg(test)]
+mod item_builder {
+    use crate::sync::{Due, Item, TaskDuration};
+
+    /// Builds the items that unit tests sort, filter, and store, starting
+    /// from one with just an ID and the content `Todo <id>`.
+    pub struct ItemBuilder {
+        item: Item,
+    }
+
+    impl ItemBuilder {
+        pub fn new(id: &str) -> Self {
+            ItemBuilder {
+                item: Item {
+                    id: id.to_string(),
+                    content: format!("Todo {id}"),
+                    ..Default::default()
+                },
+            }
+        }
+
+        pub fn content(mut self, content: &str) -> Self {
+            self.item.content = content.to_string();
+            self
+        }
+
+        pub fn description(mut self, description: &str) -> Self {
+            self.item.description = description.to_string();
+            self
+        }
+
+        pub fn project(mut self, project_id: &str) -> Self {
+            self.item.project_id = project_id.to_string();
+            self
+        }
+
+        pub fn section(mut self, section_id: Option<&str>) -> Self {
+            self.item.section_id = section_id.map(str::to_string);
+            self
+        }
+
+        /// Due on `date`, which is a date or a local date and time, or not
+        /// due at all for `None`.
+        pub fn due<'a>(mut self, date: impl Into<Option<&'a str>>) -> Self {
+            self.item.due = date.into().map(|date| Due {
+                date: date.to_string(),
+                ..Default::default()
+            });
+            self
+        }
+
+        pub fn priority(mut self, priority: u8) -> Self {
+            self.item.priority = priority;
+            self
+        }
+
+        pub fn duration(mut self, duration: Option<TaskDuration>) -> Self {
+            self.item.duration = duration;
+            self
+        }
+
+        pub fn child_order(mut self, child_order: i64) -> Self {
+            self.item.child_order = child_order;
+            self
+        }
+
+        pub fn build(self) -> Item {
+            self.item
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt::Write;
+
+use serde_json::json;
+
+use crate::webhook::{hmac_sha256, signature, verify, Event};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+#[test]
+fn computes_hmacs() {
+    // test cases 2 and 6 from RFC 4231
+    assert_eq!(
+        hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+        "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+    );
+    assert_eq!(
+        hex(&hmac_sha256(
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First"
+        )),
+        "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+    );
+}
+
+#[test]
+fn verifies_signatures() {
+    let body = br#"{"event_name":"item:added"}"#;
+    let signed = signature("secret", body);
+
+    assert!(verify("secret", body, &signed));
+    assert!(!verify("other secret", body, &signed));
+    assert!(!verify(
+        "secret",
+        br#"{"event_name":"item:deleted"}"#,
+        &signed
+    ));
+    assert!(!verify("secret", body, "not base64!"));
+    assert!(!verify("secret", body, ""));
+}
+
+#[test]
+fn events_about_tasks_have_items() -> Result<(), Box<dyn std::error::Error>> {
+    let event: Event = serde_json::from_value(json!({
+        "event_name": "item:completed",
+        "user_id": "2671355",
+        "event_data": {
+            "id": "6Jf8VQXxpwv56VQ7",
+            "content": "Buy milk",
+            "project_id": "inbox",
+            "section_id": null,
+            "parent_id": null,
+            "due": null,
+            "priority": 4,
+            "checked": false,
+        },
+    }))?;
+    let item = event.item().ok_or("no item")?;
+    assert_eq!(item.content, "Buy milk");
+    assert_eq!(item.priority, 4);
+    assert!(item.checked);
+
+    let event: Event = serde_json::from_value(json!({
+        "event_name": "project:added",
+        "event_data": { "id": "work", "name": "Work" },
+    }))?;
+    assert!(event.item().is_none());
+    Ok(())
+}
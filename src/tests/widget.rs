@@ -0,0 +1,64 @@
+use chrono::NaiveDate;
+
+use crate::{
+    sync::{Due, Item},
+    widget::{Format, Summary},
+};
+
+fn task(content: &str, date: &str) -> Item {
+    Item {
+        id: content.to_string(),
+        content: content.to_string(),
+        due: Some(Due {
+            date: date.to_string(),
+            string: None,
+            is_recurring: false,
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn summaries_count_what_is_due() -> Result<(), Box<dyn std::error::Error>> {
+    let today = NaiveDate::from_ymd_opt(2024, 5, 1).ok_or("bad date")?;
+    let items = vec![
+        task("Pay rent", "2024-04-30"),
+        task("Call mom", "2024-05-01T18:00:00"),
+        task("Water plants", "2024-05-01"),
+        task("Next week", "2024-05-08"),
+        Item {
+            checked: true,
+            ..task("Done already", "2024-05-01")
+        },
+    ];
+    let summary = Summary::new(&items, today);
+    assert_eq!(summary.text(), "2 due today, 1 overdue");
+    assert_eq!(
+        summary.render(Format::Tmux)?,
+        "#[fg=red]2 due today, 1 overdue#[default]"
+    );
+    assert_eq!(
+        summary.render(Format::Polybar)?,
+        "%{F#e44332}2 due today, 1 overdue%{F-}"
+    );
+    let waybar: serde_json::Value = serde_json::from_str(&summary.render(Format::Waybar)?)?;
+    assert_eq!(
+        waybar,
+        serde_json::json!({
+            "text": "2 due today, 1 overdue",
+            "tooltip": "Overdue: Pay rent\nWater plants\nCall mom",
+            "class": "overdue",
+        })
+    );
+
+    let summary = Summary::new(&items[1..], today);
+    assert_eq!(summary.render(Format::Tmux)?, "2 due today");
+    assert!(summary.render(Format::Waybar)?.contains(r#""class":"due""#));
+
+    let summary = Summary::new(&items[3..], today);
+    assert_eq!(summary.render(Format::Polybar)?, "Nothing due");
+    assert!(summary
+        .render(Format::Waybar)?
+        .contains(r#""class":"clear""#));
+    Ok(())
+}
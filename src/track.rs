@@ -0,0 +1,130 @@
+//! Tracking the time spent working on tasks, with a timer that's started and
+//! stopped by hand, or with [focus sessions](crate::focus). Both are kept in
+//! the store as work sessions, so the time adds up across them.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{focus::format_duration, sync::Item};
+
+/// The timer, while it's running on a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Timer {
+    pub item_id: String,
+    pub content: String,
+    pub project_id: String,
+
+    /// When the timer was started, as an RFC 3339 timestamp.
+    pub started_at: String,
+}
+
+/// A stretch of time spent working on a task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkSession {
+    pub item_id: String,
+
+    /// The task's content at the time.
+    pub content: String,
+
+    /// The task's project at the time. Empty for sessions recorded before
+    /// projects were.
+    pub project_id: String,
+
+    /// When the session started, as an RFC 3339 timestamp.
+    pub started_at: String,
+
+    pub seconds: u64,
+}
+
+impl Timer {
+    /// The timer, started on `item` at `now`.
+    #[must_use]
+    pub fn start(item: &Item, now: DateTime<Utc>) -> Self {
+        Timer {
+            item_id: item.id.clone(),
+            content: item.content.clone(),
+            project_id: item.project_id.clone(),
+            started_at: now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+
+    /// The session that the timer tracked, if it's stopped at `now`.
+    #[must_use]
+    pub fn stop(self, now: DateTime<Utc>) -> WorkSession {
+        let seconds = DateTime::parse_from_rfc3339(&self.started_at)
+            .map_or(0, |start| (now - start.to_utc()).num_seconds());
+        WorkSession {
+            item_id: self.item_id,
+            content: self.content,
+            project_id: self.project_id,
+            started_at: self.started_at,
+            seconds: u64::try_from(seconds).unwrap_or(0),
+        }
+    }
+}
+
+impl WorkSession {
+    /// A session on `item` that started at `started_at` and lasted `seconds`.
+    #[must_use]
+    pub fn new(item: &Item, started_at: DateTime<Utc>, seconds: u64) -> Self {
+        WorkSession {
+            seconds,
+            ..Timer::start(item, started_at).stop(started_at)
+        }
+    }
+}
+
+/// The time spent working on a task, over all its sessions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkTotal {
+    pub item_id: String,
+
+    /// The task's content when it was last worked on.
+    pub content: String,
+
+    pub seconds: u64,
+    pub sessions: usize,
+}
+
+/// Adds up the time spent on each project, and on each task within it. The
+/// projects with the most time come first, and so do the tasks within them.
+#[must_use]
+pub fn project_totals(sessions: &[WorkSession]) -> Vec<(String, u64, Vec<WorkTotal>)> {
+    let mut projects: HashMap<&str, HashMap<&str, WorkTotal>> = HashMap::new();
+    for session in sessions {
+        let total = projects
+            .entry(&session.project_id)
+            .or_default()
+            .entry(&session.item_id)
+            .or_insert_with(|| WorkTotal {
+                item_id: session.item_id.clone(),
+                content: session.content.clone(),
+                seconds: 0,
+                sessions: 0,
+            });
+        // sessions come oldest first, so this ends up as the latest content
+        total.content.clone_from(&session.content);
+        total.seconds += session.seconds;
+        total.sessions += 1;
+    }
+
+    let mut totals: Vec<(String, u64, Vec<WorkTotal>)> = projects
+        .into_iter()
+        .map(|(project_id, items)| {
+            let mut items: Vec<WorkTotal> = items.into_values().collect();
+            items.sort_by(|a, b| b.seconds.cmp(&a.seconds).then(a.item_id.cmp(&b.item_id)));
+            let seconds = items.iter().map(|item| item.seconds).sum();
+            (project_id.to_string(), seconds, items)
+        })
+        .collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    totals
+}
+
+/// The comment that notes the time worked on a task.
+#[must_use]
+pub fn note(seconds: u64) -> String {
+    format!("Worked on for {}.", format_duration(seconds))
+}
@@ -0,0 +1,80 @@
+//! The trash. Before a command that deletes a task is sent, the task is copied
+//! from the cache into the trash, as it was, so that it can be restored later
+//! with the same content, due date, labels, and so on. The trash keeps the
+//! most recent [`TRASH_LENGTH`] deleted tasks.
+//!
+//! Todoist has no way to bring back a deleted task, so restoring one adds a
+//! new task like it, with a new ID.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    storage::Store,
+    sync::{DueArgs, Item, ItemAddArgs},
+    Result,
+};
+
+/// How many deleted tasks the trash keeps, counting back from the latest.
+pub const TRASH_LENGTH: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedItem {
+    /// The task as it was cached just before it was deleted.
+    pub item: Item,
+
+    /// When it was deleted, as an RFC 3339 timestamp.
+    pub deleted_at: String,
+}
+
+/// The arguments for adding a task like `item` back.
+#[must_use]
+pub fn recreate(item: Item) -> ItemAddArgs {
+    ItemAddArgs {
+        project_id: item.project_id,
+        content: item.content,
+        description: Some(item.description).filter(|text| !text.is_empty()),
+        due: item.due.map(|due| DueArgs {
+            string: due.string.unwrap_or(due.date),
+            ..DueArgs::default()
+        }),
+        deadline: item.deadline,
+        duration: item.duration,
+        priority: Some(item.priority),
+        labels: item.labels,
+        responsible_uid: item.responsible_uid,
+        section_id: item.section_id,
+        parent_id: item.parent_id,
+    }
+}
+
+/// Like [`recreate`], but leaves out the project, section, and parent task if
+/// they're gone too, since adding the task to them would fail. Without its
+/// project, the task goes to `fallback_project_id`.
+///
+/// # Errors
+///
+/// Returns an error if the store can't be read.
+pub fn restore_args(store: &Store, item: Item, fallback_project_id: &str) -> Result<ItemAddArgs> {
+    let mut args = recreate(item);
+    let project_exists = store
+        .projects()?
+        .iter()
+        .any(|project| project.id == args.project_id && !project.is_archived);
+    if !project_exists {
+        args.project_id = fallback_project_id.to_string();
+        args.section_id = None;
+        args.parent_id = None;
+    }
+    if let Some(section_id) = &args.section_id {
+        let sections = store.sections_in_project(&args.project_id)?;
+        if !sections.iter().any(|section| &section.id == section_id) {
+            args.section_id = None;
+        }
+    }
+    if let Some(parent_id) = &args.parent_id {
+        if store.item(parent_id)?.is_none_or(|parent| parent.checked) {
+            args.parent_id = None;
+        }
+    }
+    Ok(args)
+}
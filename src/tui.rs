@@ -0,0 +1,410 @@
+//! An interactive terminal UI, with a sidebar of projects and a list of the
+//! selected project's tasks.
+//!
+//! Network requests happen on a background worker thread (with its own
+//! connection to the store) so that the UI never blocks on them. The worker
+//! syncs periodically, performs the actions sent to it by the UI, and reports
+//! back when the store has changed.
+
+use std::{
+    error::Error,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver, RecvTimeoutError, Sender},
+    thread,
+    time::Duration,
+};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style, Stylize},
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use todoist::{
+    client::SyncClient,
+    config::Output,
+    due, locale,
+    storage::{Store, FULL_SYNC_TOKEN},
+    sync::{
+        Command, CommandArgs, DueArgs, Item, ItemAddArgs, ItemCloseArgs, ItemUpdateArgs, Project,
+    },
+};
+
+const SYNC_INTERVAL: Duration = Duration::from_mins(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Something for the background worker to do.
+enum Action {
+    Sync,
+    Add { project_id: String, content: String },
+    Complete { id: String },
+    Reschedule { id: String, due: String },
+}
+
+/// What the background worker reports back after each action.
+enum Update {
+    Synced,
+    Offline,
+    Failed(String),
+}
+
+#[derive(PartialEq)]
+enum Focus {
+    Projects,
+    Tasks,
+}
+
+/// Text being typed at the bottom of the screen, and what it's for.
+enum Input {
+    Add(String),
+    Reschedule { id: String, due: String },
+}
+
+struct App {
+    store: Store,
+    inbox_project_id: String,
+    projects: Vec<Project>,
+    items: Vec<Item>,
+    project_state: ListState,
+    item_state: ListState,
+    focus: Focus,
+    input: Option<Input>,
+    status: String,
+    actions: Sender<Action>,
+}
+
+/// Runs the TUI until the user quits. Unless `sync_on_start` is false, the
+/// store is synced right away; either way, it's synced periodically after.
+pub fn run(
+    store_path: PathBuf,
+    client: SyncClient,
+    inbox_project_id: String,
+    sync_on_start: bool,
+) -> Result<(), Box<dyn Error>> {
+    let (actions, action_receiver) = mpsc::channel();
+    let (update_sender, updates) = mpsc::channel();
+    let store = Store::open(&store_path)?;
+    spawn_worker(store_path, client, action_receiver, update_sender);
+
+    let mut app = App {
+        store,
+        inbox_project_id,
+        projects: vec![],
+        items: vec![],
+        project_state: ListState::default().with_selected(Some(0)),
+        item_state: ListState::default().with_selected(Some(0)),
+        focus: Focus::Projects,
+        input: None,
+        status: String::new(),
+        actions,
+    };
+    app.reload()?;
+    if sync_on_start {
+        app.status = "Syncing...".to_string();
+        app.actions.send(Action::Sync)?;
+    }
+
+    let mut terminal = ratatui::init();
+    let result = app.run(&mut terminal, &updates);
+    ratatui::restore();
+    result
+}
+
+fn spawn_worker(
+    store_path: PathBuf,
+    client: SyncClient,
+    actions: Receiver<Action>,
+    updates: Sender<Update>,
+) {
+    thread::spawn(move || {
+        let Ok(runtime) = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        else {
+            return;
+        };
+
+        runtime.block_on(async {
+            let mut store = match Store::open(&store_path) {
+                Ok(store) => store,
+                Err(err) => {
+                    let _ = updates.send(Update::Failed(err.to_string()));
+                    return;
+                }
+            };
+
+            loop {
+                let action = match actions.recv_timeout(SYNC_INTERVAL) {
+                    Ok(action) => action,
+                    Err(RecvTimeoutError::Timeout) => Action::Sync,
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+
+                let update = match perform(&mut store, &client, action).await {
+                    Ok(true) => Update::Synced,
+                    Ok(false) => Update::Offline,
+                    Err(err) => Update::Failed(err.to_string()),
+                };
+                if updates.send(update).is_err() {
+                    break;
+                }
+            }
+        });
+    });
+}
+
+/// Performs an action, then syncs so that the store reflects it. Returns
+/// whether the server could be reached.
+async fn perform(
+    store: &mut Store,
+    client: &SyncClient,
+    action: Action,
+) -> Result<bool, Box<dyn Error>> {
+    let command = match action {
+        Action::Sync => None,
+        Action::Add {
+            project_id,
+            content,
+        } => Some(CommandArgs::ItemAdd(ItemAddArgs {
+            project_id,
+            content,
+            ..Default::default()
+        })),
+        Action::Complete { id } => Some(CommandArgs::ItemClose(ItemCloseArgs { id })),
+        Action::Reschedule { id, due } => Some(CommandArgs::ItemUpdate(ItemUpdateArgs {
+            id,
+            due: Some(DueArgs {
+                string: due,
+                lang: locale::lang().map(ToString::to_string),
+            }),
+            ..Default::default()
+        })),
+    };
+
+    if let Some(args) = command {
+        let commands = vec![Command::new(args)];
+        if crate::send_request(store, client, FULL_SYNC_TOKEN, &[], commands, Output::Table)
+            .await?
+            .is_none()
+        {
+            return Ok(false);
+        }
+    }
+
+    crate::sync(store, client, Output::Table).await
+}
+
+impl App {
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        updates: &Receiver<Update>,
+    ) -> Result<(), Box<dyn Error>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            while let Ok(update) = updates.try_recv() {
+                self.status = match update {
+                    Update::Synced => "Synced.".to_string(),
+                    Update::Offline => "Could not reach Todoist, showing cached data.".to_string(),
+                    Update::Failed(err) => format!("Error: {err}"),
+                };
+                self.reload()?;
+                // the worker may have printed to the terminal, so redraw everything
+                terminal.clear()?;
+            }
+
+            if event::poll(POLL_INTERVAL)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press && !self.handle_key(key)? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Re-reads projects and tasks from the store, keeping the selection in
+    /// bounds.
+    fn reload(&mut self) -> Result<(), Box<dyn Error>> {
+        let mut projects = self.store.projects()?;
+        // keep the inbox at the top, where the official apps put it
+        projects.sort_by_key(|project| project.id != self.inbox_project_id);
+        self.projects = projects;
+        clamp_selection(&mut self.project_state, self.projects.len());
+
+        self.items = match self.selected_project() {
+            Some(project) => self.store.open_items_in_project(&project.id)?,
+            None => vec![],
+        };
+        clamp_selection(&mut self.item_state, self.items.len());
+        Ok(())
+    }
+
+    fn selected_project(&self) -> Option<&Project> {
+        self.project_state
+            .selected()
+            .and_then(|index| self.projects.get(index))
+    }
+
+    fn selected_item(&self) -> Option<&Item> {
+        self.item_state
+            .selected()
+            .and_then(|index| self.items.get(index))
+    }
+
+    /// Returns `false` when the app should quit.
+    fn handle_key(&mut self, key: KeyEvent) -> Result<bool, Box<dyn Error>> {
+        if let Some(input) = &mut self.input {
+            let text = match input {
+                Input::Add(text) | Input::Reschedule { due: text, .. } => text,
+            };
+            match key.code {
+                KeyCode::Char(char) => text.push(char),
+                KeyCode::Backspace => {
+                    text.pop();
+                }
+                KeyCode::Esc => self.input = None,
+                KeyCode::Enter => self.submit_input()?,
+                _ => {}
+            }
+            return Ok(true);
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+            KeyCode::Tab | KeyCode::Char('h' | 'l') | KeyCode::Left | KeyCode::Right => {
+                self.focus = match self.focus {
+                    Focus::Projects => Focus::Tasks,
+                    Focus::Tasks => Focus::Projects,
+                };
+            }
+            KeyCode::Char('j') | KeyCode::Down => self.move_selection(true)?,
+            KeyCode::Char('k') | KeyCode::Up => self.move_selection(false)?,
+            KeyCode::Char('a') => self.input = Some(Input::Add(String::new())),
+            KeyCode::Char('c') => {
+                if let Some(item) = self.selected_item() {
+                    let id = item.id.clone();
+                    self.status = format!("Completing '{}'...", item.content);
+                    self.actions.send(Action::Complete { id })?;
+                }
+            }
+            KeyCode::Char('r') => {
+                if let Some(item) = self.selected_item() {
+                    self.input = Some(Input::Reschedule {
+                        id: item.id.clone(),
+                        due: String::new(),
+                    });
+                }
+            }
+            KeyCode::Char('s') => {
+                self.status = "Syncing...".to_string();
+                self.actions.send(Action::Sync)?;
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    fn move_selection(&mut self, down: bool) -> Result<(), Box<dyn Error>> {
+        let state = match self.focus {
+            Focus::Projects => &mut self.project_state,
+            Focus::Tasks => &mut self.item_state,
+        };
+        if down {
+            state.select_next();
+        } else {
+            state.select_previous();
+        }
+
+        if self.focus == Focus::Projects {
+            self.item_state.select(Some(0));
+        }
+        self.reload()
+    }
+
+    fn submit_input(&mut self) -> Result<(), Box<dyn Error>> {
+        match self.input.take() {
+            Some(Input::Add(content)) if !content.is_empty() => {
+                let project_id = self
+                    .selected_project()
+                    .map_or(self.inbox_project_id.clone(), |project| project.id.clone());
+                self.status = format!("Adding '{content}'...");
+                self.actions.send(Action::Add {
+                    project_id,
+                    content,
+                })?;
+            }
+            Some(Input::Reschedule { id, due }) if !due.is_empty() => {
+                if let Err(err) = due::validate_in(&due, locale::lang().unwrap_or("en")) {
+                    self.status = err.to_string();
+                } else {
+                    self.status = format!("Rescheduling to '{due}'...");
+                    self.actions.send(Action::Reschedule { id, due })?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [main_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [projects_area, items_area] =
+            Layout::horizontal([Constraint::Percentage(25), Constraint::Percentage(75)])
+                .areas(main_area);
+
+        let highlight = Style::default().add_modifier(Modifier::REVERSED);
+        let focused_block = |title: &str, focused: bool| {
+            let block = Block::bordered().title(title.to_string());
+            if focused {
+                block.bold()
+            } else {
+                block
+            }
+        };
+
+        let projects = List::new(
+            self.projects
+                .iter()
+                .map(|project| ListItem::new(project.name.clone())),
+        )
+        .block(focused_block("Projects", self.focus == Focus::Projects))
+        .highlight_style(highlight);
+        frame.render_stateful_widget(projects, projects_area, &mut self.project_state);
+
+        let items = List::new(self.items.iter().map(|item| {
+            let due = item
+                .due
+                .as_ref()
+                .map(|due| format!("  ({})", due.string.as_ref().unwrap_or(&due.date)))
+                .unwrap_or_default();
+            ListItem::new(format!("{}{due}", item.content))
+        }))
+        .block(focused_block("Tasks", self.focus == Focus::Tasks))
+        .highlight_style(highlight);
+        frame.render_stateful_widget(items, items_area, &mut self.item_state);
+
+        let status = match &self.input {
+            Some(Input::Add(text)) => Line::from(format!("New task: {text}_")),
+            Some(Input::Reschedule { due, .. }) => Line::from(format!("Due: {due}_")),
+            None => Line::from(format!(
+                "{}  [a]dd  [c]omplete  [r]eschedule  [s]ync  [q]uit",
+                self.status
+            )),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+}
+
+fn clamp_selection(state: &mut ListState, len: usize) {
+    match state.selected() {
+        _ if len == 0 => state.select(None),
+        Some(index) if index >= len => state.select(Some(len - 1)),
+        None => state.select(Some(0)),
+        Some(_) => {}
+    }
+}
@@ -0,0 +1,71 @@
+//! The journal behind `undo`. Before a command that can be undone is sent, the
+//! command that reverses it is worked out from the cached data, while that
+//! still reflects how things were. Once the original command has been sent (or
+//! queued), the reversing command is added to the journal, which keeps the most
+//! recent [`JOURNAL_LENGTH`] of them.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    storage::Store,
+    sync::{CommandArgs, ItemCloseArgs, ItemIdArgs, ProjectIdArgs},
+    trash, Result,
+};
+
+/// How many commands can be undone, counting back from the latest.
+pub const JOURNAL_LENGTH: usize = 20;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// What the original command did, like "completing 'Buy milk'".
+    pub action: String,
+
+    /// The command that reverses it.
+    pub inverse: CommandArgs,
+}
+
+/// Works out how to undo a command, if it can be undone. Deleted tasks can only
+/// be restored if they're cached, and they come back with a new ID.
+///
+/// # Errors
+///
+/// Returns an error if the store can't be read.
+pub fn entry_for(store: &Store, args: &CommandArgs) -> Result<Option<Entry>> {
+    let entry = match args {
+        CommandArgs::ItemClose(ItemCloseArgs { id })
+        | CommandArgs::ItemComplete(ItemIdArgs { id }) => Entry {
+            action: format!("completing {}", item_name(store, id)?),
+            inverse: CommandArgs::ItemUncomplete(ItemIdArgs { id: id.clone() }),
+        },
+        CommandArgs::ItemDelete(ItemIdArgs { id }) => {
+            let Some(item) = store.item(id)? else {
+                return Ok(None);
+            };
+            Entry {
+                action: format!("deleting '{}'", item.content),
+                inverse: CommandArgs::ItemAdd(trash::recreate(item)),
+            }
+        }
+        CommandArgs::ProjectArchive(ProjectIdArgs { id }) => {
+            let name = store
+                .projects()?
+                .into_iter()
+                .find(|project| &project.id == id)
+                .map_or(format!("project {id}"), |project| {
+                    format!("project '{}'", project.name)
+                });
+            Entry {
+                action: format!("archiving {name}"),
+                inverse: CommandArgs::ProjectUnarchive(ProjectIdArgs { id: id.clone() }),
+            }
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(entry))
+}
+
+fn item_name(store: &Store, id: &str) -> Result<String> {
+    Ok(store
+        .item(id)?
+        .map_or(format!("task {id}"), |item| format!("'{}'", item.content)))
+}
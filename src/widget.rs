@@ -0,0 +1,102 @@
+//! A one-line summary of what's due, for status bars like Waybar and Polybar
+//! and for tmux's status line, which run `todoist status` every so often. Each
+//! gets the summary in the form it reads, with overdue tasks standing out.
+
+use chrono::NaiveDate;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::{agenda, sync::Item};
+
+/// The status bar to write the summary for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// JSON for a `custom` module with `"return-type": "json"`, with the tasks
+    /// in the tooltip and a class to style by.
+    Waybar,
+
+    /// Text for a `custom/script` module, in red when tasks are overdue.
+    Polybar,
+
+    /// Text for `status-right` and the like, in red when tasks are overdue.
+    Tmux,
+}
+
+/// The open tasks that are due today, and the ones that are overdue.
+#[derive(Debug)]
+pub struct Summary<'a> {
+    pub overdue: Vec<&'a Item>,
+    pub today: Vec<&'a Item>,
+}
+
+/// What Waybar reads from a `custom` module.
+#[derive(Debug, Serialize)]
+struct Waybar<'a> {
+    text: String,
+    tooltip: String,
+
+    /// `overdue`, `due`, or `clear`.
+    class: &'a str,
+}
+
+impl<'a> Summary<'a> {
+    #[must_use]
+    pub fn new(items: &'a [Item], today: NaiveDate) -> Self {
+        let agenda = agenda::agenda(items, today, 1);
+        Summary {
+            overdue: agenda.overdue,
+            today: agenda
+                .days
+                .into_iter()
+                .flat_map(|(_, items)| items)
+                .collect(),
+        }
+    }
+
+    /// The summary, like "3 due today, 1 overdue".
+    #[must_use]
+    pub fn text(&self) -> String {
+        match (self.today.len(), self.overdue.len()) {
+            (0, 0) => "Nothing due".to_string(),
+            (today, 0) => format!("{today} due today"),
+            (0, overdue) => format!("{overdue} overdue"),
+            (today, overdue) => format!("{today} due today, {overdue} overdue"),
+        }
+    }
+
+    /// The summary as `format` has it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if Waybar's JSON can't be serialized.
+    pub fn render(&self, format: Format) -> crate::Result<String> {
+        let text = self.text();
+        let overdue = !self.overdue.is_empty();
+        Ok(match format {
+            Format::Waybar => serde_json::to_string(&Waybar {
+                text,
+                tooltip: self.tooltip(),
+                class: if overdue {
+                    "overdue"
+                } else if self.today.is_empty() {
+                    "clear"
+                } else {
+                    "due"
+                },
+            })?,
+            Format::Polybar if overdue => format!("%{{F#e44332}}{text}%{{F-}}"),
+            Format::Tmux if overdue => format!("#[fg=red]{text}#[default]"),
+            Format::Polybar | Format::Tmux => text,
+        })
+    }
+
+    /// The tasks, one on each line, the overdue ones first.
+    fn tooltip(&self) -> String {
+        let overdue = self
+            .overdue
+            .iter()
+            .map(|item| format!("Overdue: {}", item.content));
+        let today = self.today.iter().map(|item| item.content.clone());
+        overdue.chain(today).collect::<Vec<_>>().join("\n")
+    }
+}